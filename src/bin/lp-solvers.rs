@@ -0,0 +1,235 @@
+//! Solve an existing .lp or .mps file with an external solver,
+//! and print the solution as JSON.
+//!
+//! ```text
+//! lp-solvers [--solver auto|cbc|glpk|gurobi|cplex] [--time-limit SECONDS] [--gap GAP] [--threads N] FILE
+//! ```
+// solving is not used when no solver is compiled in
+#![cfg_attr(
+    not(any(
+        feature = "cbc",
+        feature = "glpk",
+        feature = "gurobi",
+        feature = "cplex"
+    )),
+    allow(dead_code, unused_imports, unused_variables)
+)]
+
+use std::path::PathBuf;
+use std::process::exit;
+use std::str::FromStr;
+
+#[cfg(feature = "cbc")]
+use lp_solvers::solvers::CbcSolver;
+#[cfg(feature = "cplex")]
+use lp_solvers::solvers::Cplex;
+#[cfg(feature = "glpk")]
+use lp_solvers::solvers::GlpkSolver;
+#[cfg(feature = "gurobi")]
+use lp_solvers::solvers::GurobiSolver;
+#[cfg(any(feature = "cbc", feature = "glpk"))]
+use lp_solvers::solvers::WithMaxSeconds;
+#[cfg(feature = "cbc")]
+use lp_solvers::solvers::WithNbThreads;
+use lp_solvers::solvers::{
+    solve_file, Solution, SolverProgram, SolverWithSolutionParsing, WithMipGap,
+};
+
+const USAGE: &str = "\
+usage: lp-solvers [OPTIONS] FILE
+
+Solve a .lp or .mps file and print the solution as JSON.
+
+options:
+    --solver NAME         auto (default), cbc, glpk, gurobi or cplex
+    --time-limit SECONDS  stop the solver after this time
+    --gap GAP             relative MIP gap at which the solver can stop
+    --threads N           number of threads the solver can use
+    -h, --help            print this message";
+
+/// The parsed command line
+#[derive(Debug, Default)]
+struct Args {
+    solver: Option<String>,
+    time_limit: Option<u32>,
+    gap: Option<f32>,
+    threads: Option<u32>,
+    file: Option<PathBuf>,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let mut parsed = Args::default();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                exit(0)
+            }
+            "--solver" => parsed.solver = Some(value(&mut args, &arg)?),
+            "--time-limit" => parsed.time_limit = Some(value(&mut args, &arg)?),
+            "--gap" => parsed.gap = Some(value(&mut args, &arg)?),
+            "--threads" => parsed.threads = Some(value(&mut args, &arg)?),
+            _ if arg.starts_with('-') => return Err(format!("unknown option {}", arg)),
+            _ if parsed.file.is_some() => return Err(format!("unexpected argument {}", arg)),
+            _ => parsed.file = Some(arg.into()),
+        }
+    }
+    Ok(parsed)
+}
+
+/// The value following an option
+fn value<T: FromStr>(args: &mut impl Iterator<Item = String>, option: &str) -> Result<T, String>
+where
+    T::Err: std::fmt::Display,
+{
+    let value = args
+        .next()
+        .ok_or_else(|| format!("missing value for {}", option))?;
+    value
+        .parse()
+        .map_err(|e| format!("invalid value {:?} for {}: {}", value, option, e))
+}
+
+/// Apply the options of the command line to a solver
+trait Configure: Sized {
+    fn configure(self, args: &Args) -> Result<Self, String>;
+}
+
+#[cfg(any(feature = "glpk", feature = "gurobi", feature = "cplex"))]
+fn unsupported(option: &str, solver: &str) -> String {
+    format!("{} is not supported by {}", option, solver)
+}
+
+#[cfg(feature = "cbc")]
+impl Configure for CbcSolver {
+    fn configure(mut self, args: &Args) -> Result<Self, String> {
+        if let Some(seconds) = args.time_limit {
+            self = self.with_max_seconds(seconds);
+        }
+        if let Some(gap) = args.gap {
+            self = self.with_mip_gap(gap)?;
+        }
+        if let Some(threads) = args.threads {
+            self = self.with_nb_threads(threads);
+        }
+        Ok(self)
+    }
+}
+
+#[cfg(feature = "glpk")]
+impl Configure for GlpkSolver {
+    fn configure(mut self, args: &Args) -> Result<Self, String> {
+        if let Some(seconds) = args.time_limit {
+            self = self.with_max_seconds(seconds);
+        }
+        if let Some(gap) = args.gap {
+            self = self.with_mip_gap(gap)?;
+        }
+        if args.threads.is_some() {
+            return Err(unsupported("--threads", "glpk"));
+        }
+        Ok(self)
+    }
+}
+
+#[cfg(feature = "gurobi")]
+impl Configure for GurobiSolver {
+    fn configure(mut self, args: &Args) -> Result<Self, String> {
+        if args.time_limit.is_some() {
+            return Err(unsupported("--time-limit", "gurobi"));
+        }
+        if let Some(gap) = args.gap {
+            self = self.with_mip_gap(gap)?;
+        }
+        if args.threads.is_some() {
+            return Err(unsupported("--threads", "gurobi"));
+        }
+        Ok(self)
+    }
+}
+
+#[cfg(feature = "cplex")]
+impl Configure for Cplex {
+    fn configure(mut self, args: &Args) -> Result<Self, String> {
+        if args.time_limit.is_some() {
+            return Err(unsupported("--time-limit", "cplex"));
+        }
+        if let Some(gap) = args.gap {
+            self = self.with_mip_gap(gap)?;
+        }
+        if args.threads.is_some() {
+            return Err(unsupported("--threads", "cplex"));
+        }
+        Ok(self)
+    }
+}
+
+fn solve<S>(solver: S, args: &Args) -> Result<Solution, String>
+where
+    S: Configure + SolverWithSolutionParsing + SolverProgram,
+{
+    let solver = solver.configure(args)?;
+    let file = args.file.as_ref().ok_or("missing problem file")?;
+    solve_file(&solver, file).map_err(|e| e.to_string())
+}
+
+/// Solve with the first installed solver, in the order of [lp_solvers::solvers::AllSolvers]
+fn solve_auto(args: &Args) -> Result<Solution, String> {
+    use lp_solvers::solvers::SolverTrait;
+    #[cfg(feature = "gurobi")]
+    if GurobiSolver::new().is_available() {
+        return solve(GurobiSolver::new(), args);
+    }
+    #[cfg(feature = "cplex")]
+    if Cplex::default().is_available() {
+        return solve(Cplex::default(), args);
+    }
+    #[cfg(feature = "cbc")]
+    if CbcSolver::new().is_available() {
+        return solve(CbcSolver::new(), args);
+    }
+    #[cfg(feature = "glpk")]
+    if GlpkSolver::new().is_available() {
+        return solve(GlpkSolver::new(), args);
+    }
+    Err("No solver available".to_string())
+}
+
+fn main() {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(args) if args.file.is_some() => args,
+        Ok(_) => {
+            eprintln!("missing problem file\n\n{}", USAGE);
+            exit(2)
+        }
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            exit(2)
+        }
+    };
+    let result = match args.solver.as_deref().unwrap_or("auto") {
+        "auto" => solve_auto(&args),
+        #[cfg(feature = "cbc")]
+        "cbc" => solve(CbcSolver::new(), &args),
+        #[cfg(feature = "glpk")]
+        "glpk" => solve(GlpkSolver::new(), &args),
+        #[cfg(feature = "gurobi")]
+        "gurobi" => solve(GurobiSolver::new(), &args),
+        #[cfg(feature = "cplex")]
+        "cplex" => solve(Cplex::default(), &args),
+        other if ["cbc", "glpk", "gurobi", "cplex"].contains(&other) => Err(format!(
+            "{} requires the {} feature of lp-solvers",
+            other, other
+        )),
+        other => Err(format!("unknown solver {:?}", other)),
+    };
+    match result
+        .and_then(|solution| serde_json::to_string_pretty(&solution).map_err(|e| e.to_string()))
+    {
+        Ok(json) => println!("{}", json),
+        Err(e) => {
+            eprintln!("error: {}", e);
+            exit(1)
+        }
+    }
+}
@@ -0,0 +1,279 @@
+//! Optimality certificates for the solutions of linear problems.
+//!
+//! An [OptimalityCertificate] stores the values of the variables and the dual values of the
+//! constraints of an optimal solution. [OptimalityCertificate::verify] checks, in exact
+//! rational arithmetic, that they prove the solution optimal: the values are feasible, the
+//! dual values are feasible for the dual problem, and the complementary slackness conditions
+//! hold. Only the conversion of the reported values to rationals is rounded, by the solver.
+//!
+//! Dual values follow the convention of cplex, glpk and gurobi: when minimizing, the dual value
+//! of a `>=` constraint is positive and the one of a `<=` constraint is negative, and the other
+//! way around when maximizing. The reduced cost of a variable is its objective coefficient
+//! minus the dual values weighted by its coefficients in the constraints.
+//!
+//! The basis of the solution is not part of the certificate: the solution files read by this
+//! crate do not contain it, and the checks do not need it.
+
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+
+use num_rational::BigRational;
+use num_traits::{Signed, ToPrimitive, Zero};
+
+use crate::lp_format::{ConsId, LpObjective};
+use crate::problem::{LinearExpression, Problem, Variable};
+use crate::solvers::{Solution, Status};
+
+/// The values and dual values of an optimal solution of a linear problem,
+/// to check that it is optimal, now or later
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OptimalityCertificate {
+    /// the value of every variable of the problem
+    pub values: BTreeMap<String, f64>,
+    /// the dual value of every constraint of the problem, in the order of the constraints
+    pub duals: Vec<f64>,
+}
+
+/// A reason why an [OptimalityCertificate] does not prove a solution optimal
+#[derive(Debug, Clone, PartialEq)]
+pub enum CertificateViolation {
+    /// the value of a variable is outside of its bounds, by the given amount
+    Bound {
+        /// the variable
+        variable: String,
+        /// how far the value is from the bound
+        amount: f64,
+    },
+    /// a constraint does not hold, by the given amount
+    Constraint {
+        /// the constraint
+        constraint: ConsId,
+        /// how far the left-hand side is from the right-hand side
+        amount: f64,
+    },
+    /// the dual value of an inequality has the wrong sign
+    DualSign {
+        /// the constraint
+        constraint: ConsId,
+        /// its dual value
+        dual: f64,
+    },
+    /// the reduced cost of a variable would improve the objective towards an infinite bound
+    ReducedCost {
+        /// the variable
+        variable: String,
+        /// its reduced cost
+        reduced_cost: f64,
+    },
+    /// a constraint that is not tight has a dual value
+    ConstraintSlackness {
+        /// the constraint
+        constraint: ConsId,
+        /// the product of its dual value and its slack
+        product: f64,
+    },
+    /// a variable that is not at a bound has a reduced cost
+    VariableSlackness {
+        /// the variable
+        variable: String,
+        /// the product of its reduced cost and its distance to the bound
+        product: f64,
+    },
+}
+
+impl OptimalityCertificate {
+    /// The certificate of an optimal solution of a linear problem.
+    /// Variables and constraints missing from the solution are zero.
+    ///
+    /// Fails when the problem has integer variables, when the solution is not optimal,
+    /// or when it has no dual values.
+    pub fn from_solution(
+        problem: &Problem<LinearExpression, Variable>,
+        solution: &Solution,
+    ) -> Result<Self, String> {
+        if let Some(variable) = problem.variables.iter().find(|v| v.is_integer) {
+            return Err(format!(
+                "Optimality certificates are for linear problems, but {} is integer",
+                variable.name
+            ));
+        }
+        if solution.status != Status::Optimal {
+            return Err(format!("The solution is {}, not optimal", solution.status));
+        }
+        if solution.dual_values.is_empty() && !problem.constraints.is_empty() {
+            return Err("The solution has no dual values".to_string());
+        }
+        Ok(OptimalityCertificate {
+            values: problem
+                .variables
+                .iter()
+                .map(|v| (v.name.clone(), solution.value(&v.name).unwrap_or_default()))
+                .collect(),
+            duals: (0..problem.constraints.len())
+                .map(|idx| {
+                    solution
+                        .dual(ConsId::from_index(idx))
+                        .map(f64::from)
+                        .unwrap_or_default()
+                })
+                .collect(),
+        })
+    }
+
+    /// Check the certificate against the problem, in exact arithmetic.
+    /// Values reported by solvers are rounded, so every condition may be violated by
+    /// `tolerance`, an absolute amount.
+    ///
+    /// Returns the violated conditions: the solution is proven optimal when there is none.
+    ///
+    /// ```
+    /// use std::cmp::Ordering;
+    /// use lp_solvers::certificate::OptimalityCertificate;
+    /// use lp_solvers::lp_format::{ConsId, LpObjective};
+    /// use lp_solvers::problem::{LinearExpression, ProblemBuilder, Variable};
+    ///
+    /// // minimize x subject to x >= 2
+    /// let mut builder = ProblemBuilder::new("pb", LpObjective::Minimize);
+    /// builder.add_variable(Variable {
+    ///     name: "x".to_string(),
+    ///     is_integer: false,
+    ///     lower_bound: 0.,
+    ///     upper_bound: f64::INFINITY,
+    /// });
+    /// let x = LinearExpression { coefficients: vec![("x".to_string(), 1.)] };
+    /// builder.set_objective(x.clone());
+    /// builder.add_constraint(x, Ordering::Greater, 2.);
+    /// let problem = builder.build();
+    ///
+    /// let certificate = OptimalityCertificate {
+    ///     values: vec![("x".to_string(), 2.)].into_iter().collect(),
+    ///     duals: vec![1.],
+    /// };
+    /// assert!(certificate.verify(&problem, 0.).is_empty());
+    ///
+    /// let wrong = OptimalityCertificate { duals: vec![0.], ..certificate };
+    /// assert_eq!(wrong.verify(&problem, 0.).len(), 1);
+    /// ```
+    pub fn verify(
+        &self,
+        problem: &Problem<LinearExpression, Variable>,
+        tolerance: f64,
+    ) -> Vec<CertificateViolation> {
+        let tolerance = exact(tolerance);
+        // maximizing is minimizing the opposite objective, whose dual values are opposite
+        let sign = exact(match problem.sense {
+            LpObjective::Minimize => 1.,
+            LpObjective::Maximize => -1.,
+        });
+        let value = |name: &str| exact(self.values.get(name).copied().unwrap_or_default());
+
+        let mut reduced_costs: HashMap<&str, BigRational> = HashMap::new();
+        for (name, coefficient) in problem.objective.coefficients.iter() {
+            *reduced_costs.entry(name).or_insert_with(BigRational::zero) +=
+                &sign * exact(*coefficient);
+        }
+        let mut violations = vec![];
+        for (idx, constraint) in problem.constraints.iter().enumerate() {
+            let id = ConsId::from_index(idx);
+            let dual = &sign * exact(self.duals.get(idx).copied().unwrap_or_default());
+            let mut slack = -exact(constraint.rhs);
+            for (name, coefficient) in constraint.lhs.coefficients.iter() {
+                let coefficient = exact(*coefficient);
+                slack += &coefficient * value(name);
+                *reduced_costs.entry(name).or_insert_with(BigRational::zero) -= &dual * coefficient;
+            }
+            let infeasibility = match constraint.operator {
+                Ordering::Less => slack.clone(),
+                Ordering::Greater => -slack.clone(),
+                Ordering::Equal => slack.abs(),
+            };
+            if infeasibility > tolerance {
+                violations.push(CertificateViolation::Constraint {
+                    constraint: id,
+                    amount: inexact(&infeasibility),
+                });
+            }
+            let wrong_sign = match constraint.operator {
+                Ordering::Less => dual.clone(),
+                Ordering::Greater => -dual.clone(),
+                Ordering::Equal => BigRational::zero(),
+            };
+            if wrong_sign > tolerance {
+                violations.push(CertificateViolation::DualSign {
+                    constraint: id,
+                    dual: inexact(&(&sign * &dual)),
+                });
+            }
+            let product = (&dual * &slack).abs();
+            if product > tolerance {
+                violations.push(CertificateViolation::ConstraintSlackness {
+                    constraint: id,
+                    product: inexact(&product),
+                });
+            }
+        }
+
+        for variable in problem.variables.iter() {
+            let x = value(&variable.name);
+            let lower = finite(variable.lower_bound);
+            let upper = finite(variable.upper_bound);
+            let below = lower.as_ref().map(|l| l - &x);
+            let above = upper.as_ref().map(|u| &x - u);
+            for amount in below.iter().chain(above.iter()) {
+                if amount > &tolerance {
+                    violations.push(CertificateViolation::Bound {
+                        variable: variable.name.clone(),
+                        amount: inexact(amount),
+                    });
+                }
+            }
+            let reduced_cost = reduced_costs
+                .remove(variable.name.as_str())
+                .unwrap_or_else(BigRational::zero);
+            // a positive reduced cost holds the variable at its lower bound,
+            // and a negative one at its upper bound
+            let distance = if reduced_cost.is_positive() {
+                below.map(|b| -b)
+            } else if reduced_cost.is_negative() {
+                above.map(|a| -a)
+            } else {
+                Some(BigRational::zero())
+            };
+            match distance {
+                None if reduced_cost.abs() > tolerance => {
+                    violations.push(CertificateViolation::ReducedCost {
+                        variable: variable.name.clone(),
+                        reduced_cost: inexact(&(&sign * &reduced_cost)),
+                    })
+                }
+                None => {}
+                Some(distance) => {
+                    let product = (&reduced_cost * distance).abs();
+                    if product > tolerance {
+                        violations.push(CertificateViolation::VariableSlackness {
+                            variable: variable.name.clone(),
+                            product: inexact(&product),
+                        });
+                    }
+                }
+            }
+        }
+        violations
+    }
+}
+
+/// The exact value of a finite float
+fn exact(value: f64) -> BigRational {
+    BigRational::from_float(value).unwrap_or_else(BigRational::zero)
+}
+
+/// The exact value of a bound, or None for an infinite bound
+fn finite(bound: f64) -> Option<BigRational> {
+    BigRational::from_float(bound)
+}
+
+/// The closest float to a rational, to report it
+fn inexact(value: &BigRational) -> f64 {
+    value.to_f64().unwrap_or(f64::NAN)
+}
@@ -1,8 +1,24 @@
 #![deny(missing_docs)]
 //! A library to write problems in the .lp file format
 //! and call external solvers to solve them.
+//!
+//! Running external solvers requires the `process` feature, enabled by default.
+//! Without it, problems can still be written and solutions parsed
+//! on targets that cannot spawn processes, such as `wasm32-wasi` or browsers.
 
+#[cfg(feature = "certificate")]
+pub mod certificate;
 pub mod lp_format;
+pub mod matrix;
+pub mod metadata;
+pub mod mps_format;
+pub mod presolve;
 pub mod problem;
+pub mod scaling;
+#[cfg(feature = "server")]
+pub mod server;
 pub mod solvers;
+pub mod template;
+#[cfg(feature = "testing")]
+pub mod testing;
 pub mod util;
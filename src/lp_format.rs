@@ -1,16 +1,28 @@
 //! Traits to be implemented by structures that can be dumped in the .lp format
 //!
 use std::cmp::Ordering;
+use std::collections::BTreeMap;
 use std::fmt;
 use std::fmt::Formatter;
 use std::io::prelude::*;
 use std::io::BufWriter;
 use std::io::Result;
+use std::iter::Sum;
+use std::ops::{Add, Mul, Neg, Sub};
+use std::path::Path;
 
 use tempfile::NamedTempFile;
 
+use crate::metadata::FileMetadata;
+use crate::problem::LinearExpression;
+
 /// Optimization sense
 #[derive(Clone, Copy, Eq, PartialEq, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
 pub enum LpObjective {
     /// min
     Minimize,
@@ -18,21 +30,128 @@ pub enum LpObjective {
     Maximize,
 }
 
+/// A floating point type that can be used for bounds, coefficients and constants:
+/// [f32] to save memory, or [f64] (the default everywhere) for precision.
+///
+/// Values are widened to [f64], which is lossless, where a common type is needed.
+pub trait LpFloat:
+    Copy
+    + Default
+    + PartialOrd
+    + fmt::Display
+    + fmt::Debug
+    + Into<f64>
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Neg<Output = Self>
+    + Sum
+    + Send
+    + Sync
+    + 'static
+{
+    /// Positive infinity
+    const INFINITY: Self;
+    /// Negative infinity
+    const NEG_INFINITY: Self;
+    /// Absolute value
+    fn abs(self) -> Self;
+    /// Whether the sign bit is set, including for -0.0
+    fn is_sign_negative(self) -> bool;
+    /// Write the shortest representation of the value that reads back to the same value.
+    ///
+    /// All the numbers of .lp and MPS files are written this way, by the same formatter:
+    /// the same problem is written to the same bytes on every platform and with every
+    /// version of Rust, so that files can be compared or hashed.
+    fn write_lp_value<W: Write>(self, writer: &mut W) -> Result<()>;
+    /// Format the value like [LpFloat::write_lp_value]
+    fn fmt_lp_value(self, f: &mut Formatter) -> fmt::Result;
+}
+
+macro_rules! impl_lp_float {
+    ($($t:ident)*) => {$(
+        impl LpFloat for $t {
+            const INFINITY: Self = $t::INFINITY;
+            const NEG_INFINITY: Self = $t::NEG_INFINITY;
+
+            fn abs(self) -> Self {
+                $t::abs(self)
+            }
+
+            fn is_sign_negative(self) -> bool {
+                $t::is_sign_negative(self)
+            }
+
+            fn write_lp_value<W: Write>(self, writer: &mut W) -> Result<()> {
+                writer.write_all(ryu::Buffer::new().format(self).as_bytes())
+            }
+
+            fn fmt_lp_value(self, f: &mut Formatter) -> fmt::Result {
+                f.write_str(ryu::Buffer::new().format(self))
+            }
+        }
+    )*};
+}
+
+impl_lp_float!(f32 f64);
+
+/// Display a value with [LpFloat::fmt_lp_value]
+pub(crate) struct LpValue<F>(pub F);
+
+impl<F: LpFloat> fmt::Display for LpValue<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.0.fmt_lp_value(f)
+    }
+}
+
 /// It's the user's responsibility to ensure
 /// that the variable names used by types implementing this trait
 /// follow the solver's requirements.
 pub trait WriteToLpFileFormat {
     /// Write the object to the given formatter in the .lp format
     fn to_lp_file_format(&self, f: &mut fmt::Formatter) -> fmt::Result;
+
+    /// Write the object to the given writer in the .lp format.
+    /// By default, this uses [WriteToLpFileFormat::to_lp_file_format].
+    /// Implement it to write large objects without the overhead of [fmt::Formatter].
+    fn write_lp_to<W: Write>(&self, writer: &mut W) -> Result<()> {
+        write!(writer, "{}", LpFormatted(self))
+    }
+
+    /// The (variable name, coefficient) pairs of the expression, if it is a linear expression.
+    /// Problems can only be written in the MPS format when their expressions implement this.
+    fn linear_terms(&self) -> Option<Vec<(&str, f64)>> {
+        None
+    }
 }
 
-impl<'a, T: WriteToLpFileFormat> WriteToLpFileFormat for &'a T {
+impl<T: WriteToLpFileFormat> WriteToLpFileFormat for &T {
     fn to_lp_file_format(&self, f: &mut Formatter) -> fmt::Result {
         (*self).to_lp_file_format(f)
     }
+
+    fn write_lp_to<W: Write>(&self, writer: &mut W) -> Result<()> {
+        (*self).write_lp_to(writer)
+    }
+
+    fn linear_terms(&self) -> Option<Vec<(&str, f64)>> {
+        (*self).linear_terms()
+    }
+}
+
+/// Display an object in the .lp format
+struct LpFormatted<'a, T: ?Sized>(&'a T);
+
+impl<'a, T: WriteToLpFileFormat + ?Sized> fmt::Display for LpFormatted<'a, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.0.to_lp_file_format(f)
+    }
 }
 
 /// A type that represents a variable. See [crate::problem::Variable].
+///
+/// Names are borrowed, and never copied when writing problems:
+/// they can be stored as [String], [std::borrow::Cow], [std::sync::Arc] or interned strings.
 pub trait AsVariable {
     /// Variable name. Needs to be unique. See [crate::util::UniqueNameGenerator]
     fn name(&self) -> &str;
@@ -44,7 +163,7 @@ pub trait AsVariable {
     fn upper_bound(&self) -> f64;
 }
 
-impl<'a, T: AsVariable> AsVariable for &'a T {
+impl<T: AsVariable> AsVariable for &T {
     fn name(&self) -> &str {
         (*self).name()
     }
@@ -62,17 +181,371 @@ impl<'a, T: AsVariable> AsVariable for &'a T {
     }
 }
 
+/// Handle to a constraint of a problem, identified by its position in
+/// [LpProblem::constraints]
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct ConsId(usize);
+
+impl ConsId {
+    /// Handle to the constraint at the given position
+    pub fn from_index(index: usize) -> Self {
+        ConsId(index)
+    }
+
+    /// Position of the constraint in the problem
+    pub fn index(&self) -> usize {
+        self.0
+    }
+
+    /// Name of the constraint in the .lp file
+    pub fn name(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl fmt::Display for ConsId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        ConstraintNaming::default().write_name(*self, f)
+    }
+}
+
+/// How constraints are named in the .lp file: a prefix followed by the position
+/// of the constraint, left-padded with zeroes to a minimum width.
+///
+/// The default is `c0`, `c1`, ... Names only depend on the position of the constraint,
+/// so they are the same across runs.
+///
+/// ```
+/// use lp_solvers::lp_format::{ConsId, ConstraintNaming};
+///
+/// let naming = ConstraintNaming::new("r", 3);
+/// assert_eq!(naming.name(ConsId::from_index(7)), "r007");
+/// assert_eq!(naming.constraint("r007"), Some(ConsId::from_index(7)));
+/// assert_eq!(naming.constraint("r7"), None);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ConstraintNaming {
+    /// Text before the constraint number
+    pub prefix: String,
+    /// Minimum number of digits. Shorter numbers are padded with zeroes
+    pub width: usize,
+}
+
+impl ConstraintNaming {
+    /// Name constraints with the given prefix and minimum number of digits
+    pub fn new(prefix: impl Into<String>, width: usize) -> Self {
+        ConstraintNaming {
+            prefix: prefix.into(),
+            width,
+        }
+    }
+
+    /// Name of the given constraint in the .lp file
+    pub fn name(&self, constraint: ConsId) -> String {
+        format!(
+            "{}{:0width$}",
+            self.prefix,
+            constraint.0,
+            width = self.width
+        )
+    }
+
+    /// The constraint that has the given name in the .lp file, if any
+    pub fn constraint(&self, name: &str) -> Option<ConsId> {
+        let digits = name.strip_prefix(self.prefix.as_str())?;
+        if !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let id = ConsId(digits.parse().ok()?);
+        if self.name(id) == name {
+            Some(id)
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn write_name_to<W: Write>(&self, constraint: ConsId, writer: &mut W) -> Result<()> {
+        let mut buffer = itoa::Buffer::new();
+        let digits = buffer.format(constraint.0);
+        writer.write_all(self.prefix.as_bytes())?;
+        for _ in digits.len()..self.width {
+            writer.write_all(b"0")?;
+        }
+        writer.write_all(digits.as_bytes())
+    }
+
+    fn write_name(&self, constraint: ConsId, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}{:0width$}",
+            self.prefix,
+            constraint.0,
+            width = self.width
+        )
+    }
+}
+
+impl Default for ConstraintNaming {
+    fn default() -> Self {
+        ConstraintNaming::new("c", 0)
+    }
+}
+
+/// Tags attached to the constraints of a problem, such as `capacity` or `plant 7`,
+/// to tell what a constraint is for without knowing its number.
+///
+/// They are written in .lp files as comments before the constraints,
+/// one `\ tag: ...` line per tag, and let the constraints found by an infeasibility analysis
+/// be reported by group. See [ProblemBuilder::tag_constraint](crate::problem::ProblemBuilder::tag_constraint).
+///
+/// ```
+/// use lp_solvers::lp_format::{ConsId, ConstraintTags};
+///
+/// let mut tags = ConstraintTags::default();
+/// tags.add(ConsId::from_index(0), "capacity");
+/// tags.add(ConsId::from_index(1), "capacity");
+/// tags.add(ConsId::from_index(1), "plant 7");
+/// let groups = tags.group((0..3).map(ConsId::from_index));
+/// assert_eq!(groups.tagged["capacity"].len(), 2);
+/// assert_eq!(groups.tagged["plant 7"], vec![ConsId::from_index(1)]);
+/// assert_eq!(groups.untagged, vec![ConsId::from_index(2)]);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ConstraintTags {
+    tags: BTreeMap<usize, Vec<String>>,
+}
+
+/// Constraints grouped by tag, see [ConstraintTags::group]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TaggedConstraints {
+    /// the constraints that have each tag. A constraint with several tags is in several groups
+    pub tagged: BTreeMap<String, Vec<ConsId>>,
+    /// the constraints without any tag
+    pub untagged: Vec<ConsId>,
+}
+
+impl ConstraintTags {
+    /// Attach a tag to a constraint. A tag is attached at most once to each constraint
+    pub fn add(&mut self, constraint: ConsId, tag: impl Into<String>) {
+        let tag = tag.into();
+        let tags = self.tags.entry(constraint.0).or_default();
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+
+    /// The tags of a constraint, in the order they were added
+    pub fn tags(&self, constraint: ConsId) -> &[String] {
+        self.tags
+            .get(&constraint.0)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Whether no constraint has a tag
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty()
+    }
+
+    /// Group the given constraints by tag
+    pub fn group(&self, constraints: impl IntoIterator<Item = ConsId>) -> TaggedConstraints {
+        let mut groups = TaggedConstraints::default();
+        for constraint in constraints {
+            let tags = self.tags(constraint);
+            if tags.is_empty() {
+                groups.untagged.push(constraint);
+            }
+            for tag in tags {
+                groups
+                    .tagged
+                    .entry(tag.clone())
+                    .or_default()
+                    .push(constraint);
+            }
+        }
+        groups
+    }
+
+    fn write_to<W: Write>(&self, constraint: ConsId, writer: &mut W) -> Result<()> {
+        for tag in self.tags(constraint) {
+            writer.write_all(b"  \\ tag: ")?;
+            writer.write_all(comment_line(tag).as_bytes())?;
+            writer.write_all(b"\n")?;
+        }
+        Ok(())
+    }
+
+    fn write(&self, constraint: ConsId, f: &mut Formatter) -> fmt::Result {
+        for tag in self.tags(constraint) {
+            writeln!(f, "  \\ tag: {}", comment_line(tag))?;
+        }
+        Ok(())
+    }
+}
+
+/// A comment ends at the end of the line
+fn comment_line(text: &str) -> std::borrow::Cow<'_, str> {
+    if text.contains(['\n', '\r']) {
+        text.replace(['\n', '\r'], " ").into()
+    } else {
+        text.into()
+    }
+}
+
+/// A file format in which problems can be written for solvers
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ProblemFormat {
+    /// The .lp format
+    Lp,
+    /// The free MPS format, which solvers read faster than .lp files for very large problems
+    FreeMps,
+}
+
+impl ProblemFormat {
+    /// Extension of files in this format, which solvers use to recognize it
+    pub fn suffix(&self) -> &'static str {
+        match self {
+            ProblemFormat::Lp => ".lp",
+            ProblemFormat::FreeMps => ".mps",
+        }
+    }
+}
+
+/// How to choose the format of the problem file given to a solver
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FormatSelection {
+    /// Always use this format when the solver supports it, and the .lp format otherwise
+    Fixed(ProblemFormat),
+    /// Use the free MPS format for problems with at least this many variables and constraints
+    /// when the solver supports it and the objective and all the constraints are linear
+    /// (see [WriteToLpFileFormat::linear_terms]), and the .lp format otherwise.
+    /// The variables and constraints are counted, up to the threshold.
+    BySize {
+        /// Minimum number of variables plus constraints for which MPS is used
+        mps_threshold: usize,
+    },
+}
+
+impl FormatSelection {
+    /// The format to use to give the problem to a solver that supports the given formats
+    pub fn select<'a, P: LpProblem<'a>>(
+        &self,
+        problem: &'a P,
+        supported: &[ProblemFormat],
+    ) -> ProblemFormat {
+        let format = match *self {
+            FormatSelection::Fixed(format) => format,
+            FormatSelection::BySize { mps_threshold } => {
+                // counting stops at the threshold
+                let variables = problem.variables().take(mps_threshold).count();
+                let constraints = problem
+                    .constraints()
+                    .take(mps_threshold - variables)
+                    .count();
+                let is_linear = || {
+                    problem.objective().linear_terms().is_some()
+                        && problem
+                            .constraints()
+                            .all(|constraint| constraint.lhs.linear_terms().is_some())
+                };
+                if variables + constraints >= mps_threshold && is_linear() {
+                    ProblemFormat::FreeMps
+                } else {
+                    ProblemFormat::Lp
+                }
+            }
+        };
+        if supported.contains(&format) {
+            format
+        } else {
+            ProblemFormat::Lp
+        }
+    }
+}
+
+impl Default for FormatSelection {
+    fn default() -> Self {
+        FormatSelection::BySize {
+            mps_threshold: 1_000_000,
+        }
+    }
+}
+
+/// Options controlling how a problem is written in the .lp format
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LpFormatOptions {
+    /// How constraints are named
+    pub constraint_naming: ConstraintNaming,
+    /// Do not write the bounds of variables that have the default bounds of the format:
+    /// between 0 and infinity
+    pub skip_default_bounds: bool,
+    /// Declare the integer variables between 0 and 1 in a `Binaries` section,
+    /// instead of writing their bounds and declaring them in the `Generals` section
+    pub binaries_section: bool,
+    /// Which format to write the problem in when solving it.
+    /// By default, very large linear problems are written in the free MPS format.
+    pub format: FormatSelection,
+    /// The name of the objective, `obj` by default. See [LpFormatOptions::objective_name]
+    pub objective_name: Option<String>,
+    /// Other objectives, with their names, written in MPS files as free rows after the
+    /// objective of the problem, for the tools that read several objectives.
+    /// The objective of the problem is the one selected, in the `OBJNAME` section:
+    /// solvers optimize it, and some of them read the other objectives as constraints
+    /// without bounds. They are not written in the .lp format.
+    pub other_objectives: Vec<(String, LinearExpression)>,
+    /// Tags of the constraints, written in .lp files as comments before them
+    pub constraint_tags: ConstraintTags,
+    /// Entries written as comments at the top of .lp and MPS files, see [crate::metadata]
+    pub metadata: FileMetadata,
+}
+
+impl LpFormatOptions {
+    /// The name of the objective in the files the problem is written to
+    ///
+    /// ```
+    /// use lp_solvers::lp_format::LpFormatOptions;
+    ///
+    /// assert_eq!(LpFormatOptions::default().objective_name(), "obj");
+    /// let options = LpFormatOptions {
+    ///     objective_name: Some("cost".to_string()),
+    ///     ..LpFormatOptions::default()
+    /// };
+    /// assert_eq!(options.objective_name(), "cost");
+    /// ```
+    pub fn objective_name(&self) -> &str {
+        self.objective_name.as_deref().unwrap_or("obj")
+    }
+
+    pub(crate) fn is_binary(&self, variable: &impl AsVariable) -> bool {
+        self.binaries_section
+            && variable.is_integer()
+            && variable.lower_bound() == 0.
+            && variable.upper_bound() == 1.
+    }
+
+    pub(crate) fn writes_bounds(&self, variable: &impl AsVariable) -> bool {
+        let default_bounds =
+            variable.lower_bound() == 0. && variable.upper_bound() == f64::INFINITY;
+        if self.skip_default_bounds && default_bounds {
+            return false;
+        }
+        !self.is_binary(variable)
+    }
+}
+
 /// A constraint expressing a relation between two expressions
-pub struct Constraint<E> {
+pub struct Constraint<E, F = f64> {
     /// left hand side of the constraint
     pub lhs: E,
     /// '<=' '=' or '>='
     pub operator: Ordering,
     /// Right-hand side of the constraint
-    pub rhs: f64,
+    pub rhs: F,
 }
 
-impl<E: WriteToLpFileFormat> WriteToLpFileFormat for Constraint<E> {
+impl<E: WriteToLpFileFormat, F: LpFloat> WriteToLpFileFormat for Constraint<E, F> {
     fn to_lp_file_format(&self, f: &mut Formatter) -> fmt::Result {
         self.lhs.to_lp_file_format(f)?;
         write!(
@@ -83,9 +556,19 @@ impl<E: WriteToLpFileFormat> WriteToLpFileFormat for Constraint<E> {
                 Ordering::Less => "<=",
                 Ordering::Greater => ">=",
             },
-            self.rhs
+            LpValue(self.rhs)
         )
     }
+
+    fn write_lp_to<W: Write>(&self, writer: &mut W) -> Result<()> {
+        self.lhs.write_lp_to(writer)?;
+        writer.write_all(match self.operator {
+            Ordering::Equal => b" = ",
+            Ordering::Less => b" <= ",
+            Ordering::Greater => b" >= ",
+        })?;
+        self.rhs.write_lp_value(writer)
+    }
 }
 
 /// Implemented by type that can be formatted as an lp problem
@@ -94,8 +577,10 @@ pub trait LpProblem<'a>: Sized {
     type Variable: AsVariable;
     /// expression type
     type Expression: WriteToLpFileFormat;
+    /// type of the right-hand sides of the constraints
+    type Float: LpFloat;
     /// Iterator over constraints
-    type ConstraintIterator: Iterator<Item = Constraint<Self::Expression>>;
+    type ConstraintIterator: Iterator<Item = Constraint<Self::Expression, Self::Float>>;
     /// Iterator over variables
     type VariableIterator: Iterator<Item = Self::Variable>;
 
@@ -111,46 +596,221 @@ pub trait LpProblem<'a>: Sized {
     fn sense(&'a self) -> LpObjective;
     /// List of constraints to apply
     fn constraints(&'a self) -> Self::ConstraintIterator;
+    /// Options controlling how the problem is written. Defaults to [LpFormatOptions::default].
+    /// See [LpProblem::with_format_options] to change them for an existing problem.
+    fn format_options(&self) -> LpFormatOptions {
+        LpFormatOptions::default()
+    }
+    /// Use the given format options when writing this problem
+    fn with_format_options(&'a self, options: LpFormatOptions) -> WithFormatOptions<'a, Self> {
+        WithFormatOptions {
+            problem: self,
+            options,
+        }
+    }
     /// Write the problem in the lp file format to the given formatter
     fn to_lp_file_format(&'a self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "\\ {}\n\n", self.name())?;
+        writeln!(f, "\\ {}", self.name())?;
+        for (key, value) in self.format_options().metadata.entries() {
+            writeln!(f, "\\ {}: {}", key, value)?;
+        }
+        writeln!(f)?;
         objective_lp_file_block(self, f)?;
         write_constraints_lp_file_block(self, f)?;
         write_bounds_lp_file_block(self, f)?;
         write!(f, "\nEnd\n")?;
         Ok(())
     }
+    /// Write the problem in the lp file format to the given writer.
+    ///
+    /// This is what solvers use. It is faster than [LpProblem::to_lp_file_format] for large problems,
+    /// and writes the same bytes.
+    /// Implementations that override [LpProblem::to_lp_file_format] should override this too.
+    fn write_lp_to<W: Write>(&'a self, writer: &mut W) -> Result<()> {
+        write_header_to(self, writer)?;
+        write_objective_to(self, writer)?;
+        write_constraints_to(self, writer)?;
+        write_bounds_to(self, writer)?;
+        writer.write_all(b"\nEnd\n")
+    }
+    /// Write the problem like [LpProblem::write_lp_to], formatting the constraints on all cores.
+    /// The output is the same. Use [LpProblem::in_parallel] to write problems this way when solving them.
+    #[cfg(feature = "parallel")]
+    fn write_lp_parallel_to<W: Write>(&'a self, writer: &mut W) -> Result<()>
+    where
+        Self::Expression: Sync,
+    {
+        use rayon::prelude::*;
+
+        write_header_to(self, writer)?;
+        write_objective_to(self, writer)?;
+        writer.write_all(b"\n\nSubject To\n")?;
+        let options = self.format_options();
+        let constraints: Vec<_> = self.constraints().collect();
+        // Format a limited number of chunks at once, to bound memory usage
+        for (batch_idx, batch) in constraints
+            .chunks(PARALLEL_CHUNK_SIZE * PARALLEL_CHUNKS_PER_BATCH)
+            .enumerate()
+        {
+            let first_idx = batch_idx * PARALLEL_CHUNK_SIZE * PARALLEL_CHUNKS_PER_BATCH;
+            let chunks = batch
+                .par_chunks(PARALLEL_CHUNK_SIZE)
+                .enumerate()
+                .map(|(chunk_idx, chunk)| {
+                    let mut buffer = vec![];
+                    let chunk_start = first_idx + chunk_idx * PARALLEL_CHUNK_SIZE;
+                    for (idx, constraint) in chunk.iter().enumerate() {
+                        write_constraint_to(
+                            &options,
+                            ConsId(chunk_start + idx),
+                            constraint,
+                            &mut buffer,
+                        )?;
+                    }
+                    Ok(buffer)
+                })
+                .collect::<Result<Vec<Vec<u8>>>>()?;
+            for chunk in chunks {
+                writer.write_all(&chunk)?;
+            }
+        }
+        write_bounds_to(self, writer)?;
+        writer.write_all(b"\nEnd\n")
+    }
+    /// Write this problem with [LpProblem::write_lp_parallel_to] when solving it
+    #[cfg(feature = "parallel")]
+    fn in_parallel(&'a self) -> Parallel<'a, Self> {
+        Parallel(self)
+    }
     /// Return an object whose [fmt::Display] implementation is the problem in the .lp format
-    fn display_lp(&'a self) -> DisplayedLp<'_, Self>
+    fn display_lp(&'a self) -> DisplayedLp<'a, Self>
     where
         Self: Sized,
     {
         DisplayedLp(self)
     }
 
+    /// Write the problem in the free MPS format to the given writer.
+    ///
+    /// Fails with [std::io::ErrorKind::InvalidInput] if an expression of the problem
+    /// does not implement [WriteToLpFileFormat::linear_terms].
+    fn write_free_mps_to<W: Write>(&'a self, writer: &mut W) -> Result<()> {
+        crate::mps_format::write_free_mps(self, writer)
+    }
+
     /// Write the problem to a temporary file
     fn to_tmp_file(&'a self) -> Result<NamedTempFile>
+    where
+        Self: Sized,
+    {
+        self.to_tmp_file_in(ProblemFormat::Lp)
+    }
+
+    /// Write the problem to a temporary file in the given format
+    fn to_tmp_file_in(&'a self, format: ProblemFormat) -> Result<NamedTempFile>
     where
         Self: Sized,
     {
         let mut f = tempfile::Builder::new()
             .prefix(self.name())
-            .suffix(".lp")
+            .suffix(format.suffix())
             .tempfile()?;
+        self.to_writer_in(format, &mut f)?;
+        Ok(f)
+    }
 
-        // Use a buffered writer to limit the number of syscalls
-        let mut buf_f = BufWriter::new(&mut f);
-        write!(buf_f, "{}", self.display_lp())?;
-        buf_f.flush()?;
+    /// Write the problem to the given path in the given format, to keep the problem
+    /// that was solved next to its results.
+    ///
+    /// The file is first written next to its destination, then renamed,
+    /// so that the path never holds a partly written problem.
+    /// With the `gzip` feature, a path ending in `.gz` is compressed with gzip.
+    ///
+    /// ```
+    /// use lp_solvers::lp_format::{LpObjective, LpProblem, ProblemFormat};
+    /// use lp_solvers::problem::{Problem, StrExpression};
+    ///
+    /// let problem: Problem = Problem {
+    ///     name: "archived".to_string(),
+    ///     sense: LpObjective::Minimize,
+    ///     objective: StrExpression("x".to_string()),
+    ///     variables: vec![],
+    ///     constraints: vec![],
+    /// };
+    /// let dir = tempfile::tempdir().unwrap();
+    /// let path = dir.path().join("archived.lp");
+    /// problem.write_to_path(&path, ProblemFormat::Lp).unwrap();
+    /// assert!(std::fs::read_to_string(&path).unwrap().starts_with("\\ archived"));
+    /// ```
+    fn write_to_path(&'a self, path: &Path, format: ProblemFormat) -> Result<()>
+    where
+        Self: Sized,
+    {
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        let mut f = tempfile::Builder::new()
+            .prefix(".lp-solvers")
+            .tempfile_in(dir)?;
+        if path.extension().is_some_and(|ext| ext == "gz") {
+            write_gzip(self, format, f.as_file_mut())?;
+        } else {
+            self.to_writer_in(format, f.as_file_mut())?;
+        }
+        f.as_file().sync_all()?;
+        f.persist(path)?;
+        Ok(())
+    }
 
-        // need to explicitly drop the buffered writer here,
-        // since it holds a reference to the actual file
-        drop(buf_f);
+    /// Write the problem in the .lp format to the given writer, using [LpProblem::write_lp_to].
+    /// The output is buffered, so there is no need to wrap the writer in a [BufWriter].
+    fn to_writer(&'a self, writer: &mut impl Write) -> Result<()>
+    where
+        Self: Sized,
+    {
+        self.to_writer_in(ProblemFormat::Lp, writer)
+    }
 
-        Ok(f)
+    /// Write the problem in the given format to the given writer, with buffering
+    fn to_writer_in(&'a self, format: ProblemFormat, writer: &mut impl Write) -> Result<()>
+    where
+        Self: Sized,
+    {
+        // Use a buffered writer to limit the number of syscalls
+        let mut buf_w = BufWriter::new(writer);
+        match format {
+            ProblemFormat::Lp => self.write_lp_to(&mut buf_w)?,
+            ProblemFormat::FreeMps => self.write_free_mps_to(&mut buf_w)?,
+        }
+        buf_w.flush()
     }
 }
 
+#[cfg(feature = "gzip")]
+fn write_gzip<'a, P: LpProblem<'a>>(
+    problem: &'a P,
+    format: ProblemFormat,
+    writer: &mut impl Write,
+) -> Result<()> {
+    let mut encoder = flate2::write::GzEncoder::new(writer, flate2::Compression::default());
+    problem.to_writer_in(format, &mut encoder)?;
+    encoder.finish()?;
+    Ok(())
+}
+
+#[cfg(not(feature = "gzip"))]
+fn write_gzip<'a, P: LpProblem<'a>>(
+    _problem: &'a P,
+    _format: ProblemFormat,
+    _writer: &mut impl Write,
+) -> Result<()> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::InvalidInput,
+        "Writing a compressed problem requires the gzip feature",
+    ))
+}
+
 /// A problem whose `Display` implementation outputs valid .lp syntax
 pub struct DisplayedLp<'a, P>(&'a P);
 
@@ -160,6 +820,99 @@ impl<'a, P: LpProblem<'a>> std::fmt::Display for DisplayedLp<'a, P> {
     }
 }
 
+/// A problem written with custom [LpFormatOptions]. See [LpProblem::with_format_options]
+pub struct WithFormatOptions<'a, P> {
+    problem: &'a P,
+    options: LpFormatOptions,
+}
+
+impl<'a, 'b: 'a, P: LpProblem<'b>> LpProblem<'a> for WithFormatOptions<'b, P> {
+    type Variable = P::Variable;
+    type Expression = P::Expression;
+    type Float = P::Float;
+    type ConstraintIterator = P::ConstraintIterator;
+    type VariableIterator = P::VariableIterator;
+
+    fn name(&self) -> &str {
+        self.problem.name()
+    }
+
+    fn variables(&'a self) -> Self::VariableIterator {
+        self.problem.variables()
+    }
+
+    fn objective(&'a self) -> Self::Expression {
+        self.problem.objective()
+    }
+
+    fn sense(&'a self) -> LpObjective {
+        self.problem.sense()
+    }
+
+    fn constraints(&'a self) -> Self::ConstraintIterator {
+        self.problem.constraints()
+    }
+
+    fn format_options(&self) -> LpFormatOptions {
+        self.options.clone()
+    }
+}
+
+/// A problem that is written using multiple threads. See [LpProblem::in_parallel]
+#[cfg(feature = "parallel")]
+pub struct Parallel<'a, P>(&'a P);
+
+#[cfg(feature = "parallel")]
+impl<'a, 'b: 'a, P: LpProblem<'b>> LpProblem<'a> for Parallel<'b, P>
+where
+    P::Expression: Sync,
+{
+    type Variable = P::Variable;
+    type Expression = P::Expression;
+    type Float = P::Float;
+    type ConstraintIterator = P::ConstraintIterator;
+    type VariableIterator = P::VariableIterator;
+
+    fn name(&self) -> &str {
+        self.0.name()
+    }
+
+    fn variables(&'a self) -> Self::VariableIterator {
+        self.0.variables()
+    }
+
+    fn objective(&'a self) -> Self::Expression {
+        self.0.objective()
+    }
+
+    fn sense(&'a self) -> LpObjective {
+        self.0.sense()
+    }
+
+    fn constraints(&'a self) -> Self::ConstraintIterator {
+        self.0.constraints()
+    }
+
+    fn format_options(&self) -> LpFormatOptions {
+        self.0.format_options()
+    }
+
+    fn to_lp_file_format(&'a self, f: &mut Formatter) -> fmt::Result {
+        self.0.to_lp_file_format(f)
+    }
+
+    fn write_lp_to<W: Write>(&'a self, writer: &mut W) -> Result<()> {
+        self.0.write_lp_parallel_to(writer)
+    }
+}
+
+/// Number of constraints formatted by each parallel task
+#[cfg(feature = "parallel")]
+const PARALLEL_CHUNK_SIZE: usize = 4096;
+/// Number of tasks whose output is kept in memory before being written
+#[cfg(feature = "parallel")]
+const PARALLEL_CHUNKS_PER_BATCH: usize = 64;
+
 fn objective_lp_file_block<'a>(
     prob: &'a impl LpProblem<'a>,
     f: &mut std::fmt::Formatter,
@@ -169,7 +922,8 @@ fn objective_lp_file_block<'a>(
         LpObjective::Maximize => "Maximize\n  ",
         LpObjective::Minimize => "Minimize\n  ",
     };
-    write!(f, "{}obj: ", obj_type)?;
+    let options = prob.format_options();
+    write!(f, "{}{}: ", obj_type, options.objective_name())?;
     prob.objective().to_lp_file_format(f)?;
     Ok(())
 }
@@ -178,9 +932,14 @@ fn write_constraints_lp_file_block<'a>(
     prob: &'a impl LpProblem<'a>,
     f: &mut std::fmt::Formatter,
 ) -> std::fmt::Result {
+    let options = prob.format_options();
+    let naming = options.constraint_naming;
     write!(f, "\n\nSubject To\n")?;
     for (idx, constraint) in prob.constraints().enumerate() {
-        write!(f, "  c{}: ", idx)?;
+        options.constraint_tags.write(ConsId(idx), f)?;
+        write!(f, "  ")?;
+        naming.write_name(ConsId(idx), f)?;
+        write!(f, ": ")?;
         constraint.to_lp_file_format(f)?;
         writeln!(f)?;
     }
@@ -188,32 +947,134 @@ fn write_constraints_lp_file_block<'a>(
 }
 
 fn write_bounds_lp_file_block<'a>(prob: &'a impl LpProblem<'a>, f: &mut Formatter) -> fmt::Result {
+    let options = prob.format_options();
     let mut integers = vec![];
+    let mut binaries = vec![];
     write!(f, "\nBounds\n")?;
     for variable in prob.variables() {
         let low: f64 = variable.lower_bound();
         let up: f64 = variable.upper_bound();
-        write!(f, "  ")?;
-        if low > f64::NEG_INFINITY {
-            write!(f, "{} <= ", low)?;
-        }
-        let name = variable.name().to_string();
-        write!(f, "{}", name)?;
-        if up < f64::INFINITY {
-            write!(f, " <= {}", up)?;
+        if options.writes_bounds(&variable) {
+            write!(f, "  ")?;
+            if low > f64::NEG_INFINITY {
+                write!(f, "{} <= ", LpValue(low))?;
+            }
+            f.write_str(variable.name())?;
+            if up < f64::INFINITY {
+                write!(f, " <= {}", LpValue(up))?;
+            }
+            if low.is_infinite() && up.is_infinite() {
+                write!(f, " free")?;
+            }
+            writeln!(f)?;
         }
-        if low.is_infinite() && up.is_infinite() {
-            write!(f, " free")?;
-        }
-        writeln!(f)?;
-        if variable.is_integer() {
-            integers.push(name);
+        if options.is_binary(&variable) {
+            binaries.push(variable);
+        } else if variable.is_integer() {
+            integers.push(variable);
         }
     }
     if !integers.is_empty() {
         writeln!(f, "\nGenerals")?;
-        for name in integers.iter() {
-            writeln!(f, "  {}", name)?;
+        for variable in integers.iter() {
+            writeln!(f, "  {}", variable.name())?;
+        }
+    }
+    if !binaries.is_empty() {
+        writeln!(f, "\nBinaries")?;
+        for variable in binaries.iter() {
+            writeln!(f, "  {}", variable.name())?;
+        }
+    }
+    Ok(())
+}
+
+fn write_header_to<'a, W: Write>(prob: &'a impl LpProblem<'a>, writer: &mut W) -> Result<()> {
+    writer.write_all(b"\\ ")?;
+    writer.write_all(prob.name().as_bytes())?;
+    writer.write_all(b"\n")?;
+    prob.format_options().metadata.write_to(b"\\ ", writer)?;
+    writer.write_all(b"\n")
+}
+
+fn write_objective_to<'a, W: Write>(prob: &'a impl LpProblem<'a>, writer: &mut W) -> Result<()> {
+    writer.write_all(match prob.sense() {
+        LpObjective::Maximize => b"Maximize\n  ",
+        LpObjective::Minimize => b"Minimize\n  ",
+    })?;
+    writer.write_all(prob.format_options().objective_name().as_bytes())?;
+    writer.write_all(b": ")?;
+    prob.objective().write_lp_to(writer)
+}
+
+fn write_constraints_to<'a, W: Write>(prob: &'a impl LpProblem<'a>, writer: &mut W) -> Result<()> {
+    let options = prob.format_options();
+    writer.write_all(b"\n\nSubject To\n")?;
+    for (idx, constraint) in prob.constraints().enumerate() {
+        write_constraint_to(&options, ConsId(idx), &constraint, writer)?;
+    }
+    Ok(())
+}
+
+fn write_constraint_to<E: WriteToLpFileFormat, F: LpFloat, W: Write>(
+    options: &LpFormatOptions,
+    id: ConsId,
+    constraint: &Constraint<E, F>,
+    writer: &mut W,
+) -> Result<()> {
+    options.constraint_tags.write_to(id, writer)?;
+    writer.write_all(b"  ")?;
+    options.constraint_naming.write_name_to(id, writer)?;
+    writer.write_all(b": ")?;
+    constraint.write_lp_to(writer)?;
+    writer.write_all(b"\n")
+}
+
+fn write_bounds_to<'a, W: Write>(prob: &'a impl LpProblem<'a>, writer: &mut W) -> Result<()> {
+    let options = prob.format_options();
+    let mut integers = vec![];
+    let mut binaries = vec![];
+    writer.write_all(b"\nBounds\n")?;
+    for variable in prob.variables() {
+        let low: f64 = variable.lower_bound();
+        let up: f64 = variable.upper_bound();
+        if options.writes_bounds(&variable) {
+            writer.write_all(b"  ")?;
+            if low > f64::NEG_INFINITY {
+                low.write_lp_value(writer)?;
+                writer.write_all(b" <= ")?;
+            }
+            writer.write_all(variable.name().as_bytes())?;
+            if up < f64::INFINITY {
+                writer.write_all(b" <= ")?;
+                up.write_lp_value(writer)?;
+            }
+            if low.is_infinite() && up.is_infinite() {
+                writer.write_all(b" free")?;
+            }
+            writer.write_all(b"\n")?;
+        }
+        if options.is_binary(&variable) {
+            binaries.push(variable);
+        } else if variable.is_integer() {
+            integers.push(variable);
+        }
+    }
+    write_names_section_to(b"\nGenerals\n", &integers, writer)?;
+    write_names_section_to(b"\nBinaries\n", &binaries, writer)
+}
+
+fn write_names_section_to<V: AsVariable, W: Write>(
+    header: &[u8],
+    variables: &[V],
+    writer: &mut W,
+) -> Result<()> {
+    if !variables.is_empty() {
+        writer.write_all(header)?;
+        for variable in variables {
+            writer.write_all(b"  ")?;
+            writer.write_all(variable.name().as_bytes())?;
+            writer.write_all(b"\n")?;
         }
     }
     Ok(())
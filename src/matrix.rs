@@ -0,0 +1,209 @@
+//! Building problems from a constraint matrix, a right-hand side and an objective vector,
+//! the way numerical code usually holds its data:
+//!
+//! > optimize `c·x` subject to `A x ⋚ b` and `x ≥ 0`
+//!
+//! The variables are named `x0`, `x1`, ... after their column in the matrix,
+//! and are continuous and non-negative. Change [Problem::variables] to set other bounds.
+//! Dense matrices are supported with the `ndarray` feature,
+//! and sparse matrices with the `sprs` feature.
+
+use std::cmp::Ordering;
+
+use crate::lp_format::{Constraint, LpFloat, LpObjective};
+use crate::problem::{LinearExpression, Problem, Variable};
+
+/// A problem built from matrices
+pub type MatrixProblem<F = f64> = Problem<LinearExpression<F>, Variable<F>, F>;
+
+/// The name of the variable of the given column
+pub fn column_name(column: usize) -> String {
+    format!("x{}", column)
+}
+
+impl<F: LpFloat> Problem<LinearExpression<F>, Variable<F>, F> {
+    /// Build a problem from the sparse rows of the constraint matrix.
+    /// Each row is a list of `(column, coefficient)` pairs.
+    ///
+    /// ```
+    /// use std::cmp::Ordering;
+    /// use lp_solvers::lp_format::LpObjective;
+    /// use lp_solvers::matrix::MatrixProblem;
+    ///
+    /// // maximize x0 + 2 x1 subject to x0 + x1 <= 4
+    /// let problem = MatrixProblem::from_sparse_rows(
+    ///     LpObjective::Maximize,
+    ///     &[1., 2.],
+    ///     vec![vec![(0, 1.), (1, 1.)]],
+    ///     &[Ordering::Less],
+    ///     &[4.],
+    /// )
+    /// .unwrap();
+    /// assert_eq!(problem.variables[1].name, "x1");
+    /// ```
+    pub fn from_sparse_rows<R>(
+        sense: LpObjective,
+        objective: &[F],
+        rows: R,
+        operators: &[Ordering],
+        rhs: &[F],
+    ) -> Result<Self, String>
+    where
+        R: IntoIterator<Item = Vec<(usize, F)>>,
+    {
+        if operators.len() != rhs.len() {
+            return Err(format!(
+                "{} constraint operators for a right-hand side of length {}",
+                operators.len(),
+                rhs.len()
+            ));
+        }
+        let columns = objective.len();
+        let mut constraints = Vec::with_capacity(rhs.len());
+        for (row, coefficients) in rows.into_iter().enumerate() {
+            if row >= rhs.len() {
+                return Err(format!(
+                    "The matrix has more rows than the right-hand side ({})",
+                    rhs.len()
+                ));
+            }
+            let lhs = coefficients
+                .into_iter()
+                .map(|(column, coefficient)| {
+                    if column < columns {
+                        Ok((column_name(column), coefficient))
+                    } else {
+                        Err(format!(
+                            "Column {} of row {} is out of the objective, of length {}",
+                            column, row, columns
+                        ))
+                    }
+                })
+                .collect::<Result<_, _>>()?;
+            constraints.push(Constraint {
+                lhs: LinearExpression { coefficients: lhs },
+                operator: operators[row],
+                rhs: rhs[row],
+            });
+        }
+        if constraints.len() != rhs.len() {
+            return Err(format!(
+                "The matrix has {} rows, but the right-hand side has {}",
+                constraints.len(),
+                rhs.len()
+            ));
+        }
+        Ok(Problem {
+            name: "lp_solvers_problem".to_string(),
+            sense,
+            objective: LinearExpression {
+                coefficients: objective
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, &c)| c != F::default())
+                    .map(|(column, &c)| (column_name(column), c))
+                    .collect(),
+            },
+            variables: (0..columns)
+                .map(|column| Variable {
+                    name: column_name(column),
+                    is_integer: false,
+                    lower_bound: F::default(),
+                    upper_bound: F::INFINITY,
+                })
+                .collect(),
+            constraints,
+        })
+    }
+
+    /// Build a problem from a dense constraint matrix, with one row per constraint
+    /// and one column per variable. Zero coefficients are left out.
+    ///
+    /// ```
+    /// use std::cmp::Ordering;
+    /// use ndarray::array;
+    /// use lp_solvers::lp_format::LpObjective;
+    /// use lp_solvers::matrix::MatrixProblem;
+    ///
+    /// let problem = MatrixProblem::from_ndarray(
+    ///     LpObjective::Minimize,
+    ///     array![1., 1.].view(),
+    ///     array![[1., 0.], [1., 2.]].view(),
+    ///     &[Ordering::Greater, Ordering::Greater],
+    ///     array![1., 3.].view(),
+    /// )
+    /// .unwrap();
+    /// assert_eq!(problem.constraints[0].lhs.coefficients.len(), 1);
+    /// ```
+    #[cfg(feature = "ndarray")]
+    pub fn from_ndarray(
+        sense: LpObjective,
+        objective: ndarray::ArrayView1<'_, F>,
+        matrix: ndarray::ArrayView2<'_, F>,
+        operators: &[Ordering],
+        rhs: ndarray::ArrayView1<'_, F>,
+    ) -> Result<Self, String> {
+        if matrix.ncols() != objective.len() {
+            return Err(format!(
+                "The matrix has {} columns, but the objective has {}",
+                matrix.ncols(),
+                objective.len()
+            ));
+        }
+        let rows = matrix.rows().into_iter().map(|row| {
+            row.iter()
+                .enumerate()
+                .filter(|(_, &c)| c != F::default())
+                .map(|(column, &c)| (column, c))
+                .collect()
+        });
+        Self::from_sparse_rows(sense, &objective.to_vec(), rows, operators, &rhs.to_vec())
+    }
+
+    /// Build a problem from a sparse constraint matrix, in any storage order,
+    /// with one row per constraint and one column per variable.
+    ///
+    /// ```
+    /// use std::cmp::Ordering;
+    /// use sprs::TriMat;
+    /// use lp_solvers::lp_format::LpObjective;
+    /// use lp_solvers::matrix::MatrixProblem;
+    ///
+    /// let mut matrix = TriMat::new((2, 3));
+    /// matrix.add_triplet(0, 0, 1.);
+    /// matrix.add_triplet(1, 2, 2.);
+    /// let problem = MatrixProblem::from_sprs(
+    ///     LpObjective::Maximize,
+    ///     &[1., 1., 1.],
+    ///     matrix.to_csc::<usize>().view(),
+    ///     &[Ordering::Less, Ordering::Less],
+    ///     &[1., 1.],
+    /// )
+    /// .unwrap();
+    /// assert_eq!(problem.constraints[1].lhs.coefficients, vec![("x2".to_string(), 2.)]);
+    /// ```
+    #[cfg(feature = "sprs")]
+    pub fn from_sprs(
+        sense: LpObjective,
+        objective: &[F],
+        matrix: sprs::CsMatView<'_, F>,
+        operators: &[Ordering],
+        rhs: &[F],
+    ) -> Result<Self, String> {
+        if matrix.cols() != objective.len() {
+            return Err(format!(
+                "The matrix has {} columns, but the objective has {}",
+                matrix.cols(),
+                objective.len()
+            ));
+        }
+        let mut rows = vec![vec![]; matrix.rows()];
+        for (&coefficient, (row, column)) in matrix.iter() {
+            rows[row].push((column, coefficient));
+        }
+        for row in rows.iter_mut() {
+            row.sort_by_key(|&(column, _)| column);
+        }
+        Self::from_sparse_rows(sense, objective, rows, operators, rhs)
+    }
+}
@@ -0,0 +1,205 @@
+//! Metadata written at the top of problem files, to trace archived models back to
+//! what produced them.
+//!
+//! [FileMetadata] is written as comments before the problem, one `key: value` line per entry:
+//! `\ model_version: 3` in .lp files and `* model_version: 3` in MPS files.
+//! Solvers ignore them, and [FileMetadata::read] reads them back from a file.
+//!
+//! ```
+//! use lp_solvers::lp_format::{LpFormatOptions, LpObjective, LpProblem};
+//! use lp_solvers::metadata::FileMetadata;
+//! use lp_solvers::problem::ProblemBuilder;
+//!
+//! let metadata = FileMetadata::new()
+//!     .with_model_version("3.1")
+//!     .with_git_hash("8f2c1e7");
+//! let problem = ProblemBuilder::<f64>::new("archived", LpObjective::Minimize).build();
+//! let options = LpFormatOptions { metadata, ..LpFormatOptions::default() };
+//! let lp = problem.with_format_options(options).display_lp().to_string();
+//! assert!(lp.starts_with("\\ archived\n\\ model_version: 3.1\n\\ git_hash: 8f2c1e7\n\n"));
+//!
+//! let read = FileMetadata::read(lp.as_bytes()).unwrap();
+//! assert_eq!(read.model_version(), Some("3.1"));
+//! assert_eq!(read.git_hash(), Some("8f2c1e7"));
+//! ```
+
+use std::io::{BufRead, Write};
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::solvers::SolverParams;
+
+const MODEL_VERSION: &str = "model_version";
+const GIT_HASH: &str = "git_hash";
+const TIMESTAMP: &str = "timestamp";
+/// Prefix of the keys of solver parameters
+const PARAM: &str = "param.";
+
+/// Entries written as comments at the top of problem files.
+/// See the [module documentation](self)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FileMetadata {
+    /// The keys and values of the entries, in the order they are written.
+    /// Only set by [FileMetadata::with], so that they cannot break out of their comment line
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "deserialize_entries"))]
+    entries: Vec<(String, String)>,
+}
+
+impl FileMetadata {
+    /// No metadata
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set an entry, replacing its previous value.
+    ///
+    /// Keys are made of letters, digits, `_`, `-` and `.`: other characters are replaced
+    /// with `_`. Line breaks in values are replaced with spaces, since comments end with the line.
+    pub fn with(mut self, key: impl Into<String>, value: impl ToString) -> Self {
+        let key: String = key
+            .into()
+            .chars()
+            .map(|c| if is_key_char(c) { c } else { '_' })
+            .collect();
+        let value = value.to_string().replace(['\n', '\r'], " ");
+        match self.entries.iter_mut().find(|(k, _)| *k == key) {
+            Some(entry) => entry.1 = value,
+            None => self.entries.push((key, value)),
+        }
+        self
+    }
+
+    /// The value of an entry
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// The keys and values of the entries, in the order they are written
+    pub fn entries(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.entries
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+    }
+
+    /// Whether there is no entry
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Set the version of the model that generated the problem
+    pub fn with_model_version(self, version: impl ToString) -> Self {
+        self.with(MODEL_VERSION, version)
+    }
+
+    /// The version of the model that generated the problem
+    pub fn model_version(&self) -> Option<&str> {
+        self.get(MODEL_VERSION)
+    }
+
+    /// Set the hash of the commit of the code that generated the problem
+    pub fn with_git_hash(self, hash: impl ToString) -> Self {
+        self.with(GIT_HASH, hash)
+    }
+
+    /// The hash of the commit of the code that generated the problem
+    pub fn git_hash(&self) -> Option<&str> {
+        self.get(GIT_HASH)
+    }
+
+    /// Set the time the problem was generated, written in seconds since the Unix epoch
+    pub fn with_timestamp(self, time: SystemTime) -> Self {
+        let seconds = time
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        self.with(TIMESTAMP, seconds)
+    }
+
+    /// The time the problem was generated, to the second
+    pub fn timestamp(&self) -> Option<SystemTime> {
+        let seconds = self.get(TIMESTAMP)?.parse().ok()?;
+        Some(UNIX_EPOCH + Duration::from_secs(seconds))
+    }
+
+    /// Record the parameters the problem is solved with, one `param.<name>` entry each
+    pub fn with_parameters(self, params: &SolverParams) -> Self {
+        params.iter().fold(self, |metadata, (name, value)| {
+            metadata.with(format!("{}{}", PARAM, name), value)
+        })
+    }
+
+    /// The parameters recorded with [FileMetadata::with_parameters]
+    pub fn parameters(&self) -> SolverParams {
+        self.entries
+            .iter()
+            .filter_map(|(key, value)| Some((key.strip_prefix(PARAM)?, value)))
+            .fold(SolverParams::new(), |params, (name, value)| {
+                params.with_param(name, value)
+            })
+    }
+
+    /// Read the metadata at the top of a .lp or MPS file: the `key: value` comments before
+    /// the first line that is not a comment. Other comments, such as the name of the problem
+    /// at the top of .lp files, are skipped.
+    pub fn read(reader: impl BufRead) -> Result<Self, String> {
+        let mut metadata = FileMetadata::new();
+        for line in reader.lines() {
+            let line = line.map_err(|e| format!("Unable to read the metadata: {}", e))?;
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let comment = match line.strip_prefix('\\').or_else(|| line.strip_prefix('*')) {
+                Some(comment) => comment.trim(),
+                None => break,
+            };
+            if let Some((key, value)) = comment.split_once(':') {
+                if !key.is_empty() && key.chars().all(is_key_char) {
+                    metadata = metadata.with(key, value.trim());
+                }
+            }
+        }
+        Ok(metadata)
+    }
+
+    /// Read the metadata at the top of a .lp or MPS file
+    pub fn read_file(path: &Path) -> Result<Self, String> {
+        let file = std::fs::File::open(path)
+            .map_err(|e| format!("Unable to open problem file {:?}: {}", path, e))?;
+        Self::read(std::io::BufReader::new(file))
+    }
+
+    /// Write the entries as comment lines starting with `comment`
+    pub(crate) fn write_to<W: Write>(&self, comment: &[u8], writer: &mut W) -> std::io::Result<()> {
+        for (key, value) in self.entries.iter() {
+            writer.write_all(comment)?;
+            writer.write_all(key.as_bytes())?;
+            writer.write_all(b": ")?;
+            writer.write_all(value.as_bytes())?;
+            writer.write_all(b"\n")?;
+        }
+        Ok(())
+    }
+}
+
+/// Read the entries like [FileMetadata::with] sets them
+#[cfg(feature = "serde")]
+fn deserialize_entries<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<(String, String)>, D::Error> {
+    let entries: Vec<(String, String)> = serde::Deserialize::deserialize(deserializer)?;
+    let metadata = entries
+        .into_iter()
+        .fold(FileMetadata::new(), |metadata, (key, value)| {
+            metadata.with(key, value)
+        });
+    Ok(metadata.entries)
+}
+
+fn is_key_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.')
+}
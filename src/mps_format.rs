@@ -0,0 +1,252 @@
+//! Writing problems in the free MPS format.
+//!
+//! MPS files list the coefficients of the problem column by column,
+//! so the whole coefficient matrix is kept in memory while writing.
+//! Solvers read them faster than .lp files for very large problems.
+//! See [crate::lp_format::FormatSelection] to choose when they are used.
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::io::{Error, ErrorKind, Result, Write};
+
+use crate::lp_format::{
+    AsVariable, ConsId, ConstraintNaming, LpFloat, LpObjective, LpProblem, WriteToLpFileFormat,
+};
+
+/// A row of the problem
+#[derive(Clone, Copy, PartialEq)]
+enum Row {
+    /// An objective: the one of the problem is 0, and the others follow
+    Objective(usize),
+    Constraint(ConsId),
+}
+
+/// A column of the problem: a variable, and its coefficients in each row
+struct Column {
+    name: String,
+    is_integer: bool,
+    bounds: Option<(f64, f64)>,
+    /// (row, coefficient) pairs
+    entries: Vec<(Row, f64)>,
+}
+
+/// The columns of the problem, in the order in which variables are declared
+/// and then in the order in which undeclared variables appear in expressions
+#[derive(Default)]
+struct Columns {
+    columns: Vec<Column>,
+    positions: HashMap<String, usize>,
+}
+
+impl Columns {
+    fn declare(&mut self, name: &str, is_integer: bool, bounds: Option<(f64, f64)>) -> usize {
+        if let Some(&position) = self.positions.get(name) {
+            return position;
+        }
+        let position = self.columns.len();
+        self.positions.insert(name.to_string(), position);
+        self.columns.push(Column {
+            name: name.to_string(),
+            is_integer,
+            bounds,
+            entries: vec![],
+        });
+        position
+    }
+
+    fn add_terms(&mut self, row: Row, terms: Vec<(&str, f64)>) {
+        for (name, coefficient) in terms {
+            let position = self.declare(name, false, None);
+            let entries = &mut self.columns[position].entries;
+            match entries.last_mut() {
+                // a variable that appears twice in the same expression
+                Some((last_row, value)) if *last_row == row => *value += coefficient,
+                _ => entries.push((row, coefficient)),
+            }
+        }
+    }
+}
+
+fn not_linear() -> Error {
+    Error::new(
+        ErrorKind::InvalidInput,
+        "only problems with linear expressions can be written in the MPS format",
+    )
+}
+
+/// Write the problem in the free MPS format. See [LpProblem::write_free_mps_to]
+pub(crate) fn write_free_mps<'a, P: LpProblem<'a>, W: Write>(
+    prob: &'a P,
+    writer: &mut W,
+) -> Result<()> {
+    let options = prob.format_options();
+    let naming = &options.constraint_naming;
+    let objective_names: Vec<&str> = std::iter::once(options.objective_name())
+        .chain(
+            options
+                .other_objectives
+                .iter()
+                .map(|(name, _)| name.as_str()),
+        )
+        .collect();
+    let mut columns = Columns::default();
+    for variable in prob.variables() {
+        let bounds = (variable.lower_bound(), variable.upper_bound());
+        columns.declare(variable.name(), variable.is_integer(), Some(bounds));
+    }
+    columns.add_terms(
+        Row::Objective(0),
+        prob.objective().linear_terms().ok_or_else(not_linear)?,
+    );
+    for (idx, (_, objective)) in options.other_objectives.iter().enumerate() {
+        let terms = objective.linear_terms().ok_or_else(not_linear)?;
+        columns.add_terms(Row::Objective(idx + 1), terms);
+    }
+
+    options.metadata.write_to(b"* ", writer)?;
+    writer.write_all(b"NAME ")?;
+    writer.write_all(prob.name().as_bytes())?;
+    if prob.sense() == LpObjective::Maximize {
+        writer.write_all(b"\nOBJSENSE\n    MAX")?;
+    }
+    if !options.other_objectives.is_empty() {
+        writer.write_all(b"\nOBJNAME\n    ")?;
+        writer.write_all(options.objective_name().as_bytes())?;
+    }
+    writer.write_all(b"\nROWS\n")?;
+    for name in objective_names.iter() {
+        writer.write_all(b" N ")?;
+        writer.write_all(name.as_bytes())?;
+        writer.write_all(b"\n")?;
+    }
+    let mut rhs = vec![];
+    for (idx, constraint) in prob.constraints().enumerate() {
+        let id = ConsId::from_index(idx);
+        writer.write_all(match constraint.operator {
+            Ordering::Equal => b" E ",
+            Ordering::Less => b" L ",
+            Ordering::Greater => b" G ",
+        })?;
+        naming.write_name_to(id, writer)?;
+        writer.write_all(b"\n")?;
+        columns.add_terms(
+            Row::Constraint(id),
+            constraint.lhs.linear_terms().ok_or_else(not_linear)?,
+        );
+        let value: f64 = constraint.rhs.into();
+        if value != 0. {
+            rhs.push((id, value));
+        }
+    }
+
+    let rows = RowNames {
+        objectives: &objective_names,
+        naming,
+    };
+    writer.write_all(b"COLUMNS\n")?;
+    let mut in_integers = false;
+    for column in columns.columns.iter() {
+        if column.is_integer != in_integers {
+            in_integers = column.is_integer;
+            writer.write_all(if in_integers {
+                b" MARKER 'MARKER' 'INTORG'\n"
+            } else {
+                b" MARKER 'MARKER' 'INTEND'\n"
+            })?;
+        }
+        if column.entries.is_empty() {
+            // declare the variable even if it appears nowhere
+            write_entry(writer, &rows, &column.name, Row::Objective(0), 0.)?;
+        }
+        for &(row, coefficient) in column.entries.iter() {
+            write_entry(writer, &rows, &column.name, row, coefficient)?;
+        }
+    }
+    if in_integers {
+        writer.write_all(b" MARKER 'MARKER' 'INTEND'\n")?;
+    }
+
+    writer.write_all(b"RHS\n")?;
+    for (id, value) in rhs {
+        writer.write_all(b" RHS ")?;
+        naming.write_name_to(id, writer)?;
+        writer.write_all(b" ")?;
+        value.write_lp_value(writer)?;
+        writer.write_all(b"\n")?;
+    }
+
+    writer.write_all(b"BOUNDS\n")?;
+    for column in columns.columns.iter() {
+        if let Some(bounds) = column.bounds {
+            write_bounds(writer, column, bounds)?;
+        }
+    }
+    writer.write_all(b"ENDATA\n")
+}
+
+/// The names of the rows
+struct RowNames<'a> {
+    objectives: &'a [&'a str],
+    naming: &'a ConstraintNaming,
+}
+
+/// Write the coefficient of a variable in a row
+fn write_entry<W: Write>(
+    writer: &mut W,
+    rows: &RowNames,
+    column: &str,
+    row: Row,
+    value: f64,
+) -> Result<()> {
+    writer.write_all(b" ")?;
+    writer.write_all(column.as_bytes())?;
+    writer.write_all(b" ")?;
+    match row {
+        Row::Constraint(id) => rows.naming.write_name_to(id, writer)?,
+        Row::Objective(idx) => writer.write_all(rows.objectives[idx].as_bytes())?,
+    }
+    writer.write_all(b" ")?;
+    value.write_lp_value(writer)?;
+    writer.write_all(b"\n")
+}
+
+/// Write the bounds of a declared variable.
+/// The bounds of integer variables are always written,
+/// because some solvers consider integer variables without bounds to be binary.
+fn write_bounds<W: Write>(writer: &mut W, column: &Column, (low, up): (f64, f64)) -> Result<()> {
+    let name = column.name.as_str();
+    if low == f64::NEG_INFINITY && up == f64::INFINITY {
+        return write_bound(writer, b"FR", name, None);
+    }
+    if low == up {
+        return write_bound(writer, b"FX", name, Some(low));
+    }
+    if low == f64::NEG_INFINITY {
+        write_bound(writer, b"MI", name, None)?;
+    } else if low != 0. || column.is_integer {
+        write_bound(writer, b"LO", name, Some(low))?;
+    }
+    if up < f64::INFINITY {
+        write_bound(writer, b"UP", name, Some(up))?;
+    } else if column.is_integer {
+        write_bound(writer, b"PL", name, None)?;
+    }
+    Ok(())
+}
+
+/// Write a line of the BOUNDS section
+fn write_bound<W: Write>(
+    writer: &mut W,
+    kind: &[u8],
+    column: &str,
+    value: Option<f64>,
+) -> Result<()> {
+    writer.write_all(b" ")?;
+    writer.write_all(kind)?;
+    writer.write_all(b" BND ")?;
+    writer.write_all(column.as_bytes())?;
+    if let Some(value) = value {
+        writer.write_all(b" ")?;
+        value.write_lp_value(writer)?;
+    }
+    writer.write_all(b"\n")
+}
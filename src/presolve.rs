@@ -0,0 +1,317 @@
+//! A light presolve, done in the crate before a problem is given to a solver.
+//!
+//! The bounds of the variables are tightened using the constraints, which finds
+//! problems that are obviously infeasible without starting a solver.
+//! This is useful when solving many small problems, where starting the solver
+//! costs more than solving.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::lp_format::{AsVariable, ConsId, LpProblem, WriteToLpFileFormat};
+use crate::problem::{LinearExpression, Problem, Variable};
+
+/// The number of passes over the constraints when tightening the bounds
+const PROPAGATION_PASSES: usize = 10;
+
+/// How much bounds and constraints can be violated, relative to their magnitude,
+/// before the problem is considered infeasible
+const TOLERANCE: f64 = 1e-9;
+
+/// Why a problem is infeasible, found by [propagate_bounds]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Infeasibility {
+    /// The lower bound of the variable is above its upper bound
+    CrossingBounds {
+        /// the variable
+        variable: String,
+        /// its lower bound, possibly tightened
+        lower: f64,
+        /// its upper bound, possibly tightened
+        upper: f64,
+    },
+    /// The constraint cannot hold, whatever the values of the variables within their bounds
+    Constraint(ConsId),
+}
+
+impl fmt::Display for Infeasibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Infeasibility::CrossingBounds {
+                variable,
+                lower,
+                upper,
+            } => write!(f, "The bounds of {} cross: {} > {}", variable, lower, upper),
+            Infeasibility::Constraint(id) => {
+                write!(f, "Constraint {} can never be satisfied", id.index())
+            }
+        }
+    }
+}
+
+impl std::error::Error for Infeasibility {}
+
+struct LinearConstraint {
+    terms: Vec<(usize, f64)>,
+    operator: Ordering,
+    rhs: f64,
+}
+
+/// The smallest and the largest value of the terms, with the given bounds.
+/// The number of infinite contributions is counted apart, so that the range without
+/// one of the terms can be computed.
+struct Activity {
+    finite_min: f64,
+    infinite_min: usize,
+    finite_max: f64,
+    infinite_max: usize,
+}
+
+fn term_range(coefficient: f64, (lower, upper): (f64, f64)) -> (f64, f64) {
+    if coefficient > 0. {
+        (coefficient * lower, coefficient * upper)
+    } else {
+        (coefficient * upper, coefficient * lower)
+    }
+}
+
+impl Activity {
+    fn new(terms: &[(usize, f64)], bounds: &[(f64, f64)]) -> Self {
+        let mut activity = Activity {
+            finite_min: 0.,
+            infinite_min: 0,
+            finite_max: 0.,
+            infinite_max: 0,
+        };
+        for &(variable, coefficient) in terms {
+            let (min, max) = term_range(coefficient, bounds[variable]);
+            if min.is_finite() {
+                activity.finite_min += min;
+            } else {
+                activity.infinite_min += 1;
+            }
+            if max.is_finite() {
+                activity.finite_max += max;
+            } else {
+                activity.infinite_max += 1;
+            }
+        }
+        activity
+    }
+
+    fn min(&self) -> f64 {
+        if self.infinite_min > 0 {
+            f64::NEG_INFINITY
+        } else {
+            self.finite_min
+        }
+    }
+
+    fn max(&self) -> f64 {
+        if self.infinite_max > 0 {
+            f64::INFINITY
+        } else {
+            self.finite_max
+        }
+    }
+
+    /// The smallest and the largest value of the other terms, given the range of one term
+    fn without(&self, (min, max): (f64, f64)) -> (f64, f64) {
+        let others_min = match (self.infinite_min, min.is_finite()) {
+            (0, _) => self.finite_min - min,
+            (1, false) => self.finite_min,
+            _ => f64::NEG_INFINITY,
+        };
+        let others_max = match (self.infinite_max, max.is_finite()) {
+            (0, _) => self.finite_max - max,
+            (1, false) => self.finite_max,
+            _ => f64::INFINITY,
+        };
+        (others_min, others_max)
+    }
+}
+
+fn tolerance(value: f64) -> f64 {
+    TOLERANCE * value.abs().max(1.)
+}
+
+/// Tighten the bounds of the variables of the problem using its constraints,
+/// and return the new bounds of each variable, by name.
+///
+/// Only the constraints whose left-hand side gives its
+/// [linear terms](WriteToLpFileFormat::linear_terms) are used.
+/// The bounds of integer variables are rounded to integers.
+/// Fails if the bounds of a variable cross, or if a constraint cannot be satisfied.
+///
+/// ```
+/// use std::cmp::Ordering;
+/// use lp_solvers::lp_format::{Constraint, LpObjective};
+/// use lp_solvers::presolve::propagate_bounds;
+/// use lp_solvers::problem::{LinearExpression, Problem, Variable};
+///
+/// let var = |name: &str| Variable {
+///     name: name.to_string(),
+///     is_integer: false,
+///     lower_bound: 0.,
+///     upper_bound: 10.,
+/// };
+/// let sum = |rhs| Constraint {
+///     lhs: LinearExpression { coefficients: vec![("x".to_string(), 1.), ("y".to_string(), 1.)] },
+///     operator: Ordering::Less,
+///     rhs,
+/// };
+/// let mut problem = Problem {
+///     name: "tiny".to_string(),
+///     sense: LpObjective::Maximize,
+///     objective: LinearExpression { coefficients: vec![("x".to_string(), 1.)] },
+///     variables: vec![var("x"), var("y")],
+///     constraints: vec![sum(4.)],
+/// };
+/// assert_eq!(propagate_bounds(&problem).unwrap()["x"], (0., 4.));
+/// problem.constraints = vec![sum(-1.)];
+/// assert!(propagate_bounds(&problem).is_err());
+/// ```
+pub fn propagate_bounds<'a, P: LpProblem<'a>>(
+    problem: &'a P,
+) -> Result<HashMap<String, (f64, f64)>, Infeasibility> {
+    let mut names = vec![];
+    let mut integers = vec![];
+    let mut bounds = vec![];
+    let mut index: HashMap<String, usize> = HashMap::new();
+    for variable in problem.variables() {
+        index.insert(variable.name().to_string(), names.len());
+        names.push(variable.name().to_string());
+        integers.push(variable.is_integer());
+        bounds.push((variable.lower_bound(), variable.upper_bound()));
+    }
+    let mut constraints = vec![];
+    for (id, constraint) in problem.constraints().enumerate() {
+        let terms = match constraint.lhs.linear_terms() {
+            Some(terms) => terms,
+            None => continue,
+        };
+        let mut linear = LinearConstraint {
+            terms: Vec::with_capacity(terms.len()),
+            operator: constraint.operator,
+            rhs: constraint.rhs.into(),
+        };
+        for (name, coefficient) in terms {
+            if coefficient == 0. {
+                continue;
+            }
+            let variable = *index.entry(name.to_string()).or_insert_with(|| {
+                // a variable without declared bounds is free
+                names.push(name.to_string());
+                integers.push(false);
+                bounds.push((f64::NEG_INFINITY, f64::INFINITY));
+                names.len() - 1
+            });
+            linear.terms.push((variable, coefficient));
+        }
+        constraints.push((ConsId::from_index(id), linear));
+    }
+
+    for variable in 0..names.len() {
+        check_bounds(&names, &integers, &mut bounds, variable)?;
+    }
+    for _ in 0..PROPAGATION_PASSES {
+        let mut changed = false;
+        for (id, constraint) in constraints.iter() {
+            let activity = Activity::new(&constraint.terms, &bounds);
+            let rhs = constraint.rhs;
+            let tol = tolerance(rhs);
+            let violated = match constraint.operator {
+                Ordering::Less => activity.min() > rhs + tol,
+                Ordering::Greater => activity.max() < rhs - tol,
+                Ordering::Equal => activity.min() > rhs + tol || activity.max() < rhs - tol,
+            };
+            if violated {
+                return Err(Infeasibility::Constraint(*id));
+            }
+            for &(variable, coefficient) in constraint.terms.iter() {
+                let (others_min, others_max) =
+                    activity.without(term_range(coefficient, bounds[variable]));
+                // the range of values of `coefficient * variable` allowed by the constraint
+                let (term_min, term_max) = match constraint.operator {
+                    Ordering::Less => (f64::NEG_INFINITY, rhs - others_min),
+                    Ordering::Greater => (rhs - others_max, f64::INFINITY),
+                    Ordering::Equal => (rhs - others_max, rhs - others_min),
+                };
+                let (lower, upper) = if coefficient > 0. {
+                    (term_min / coefficient, term_max / coefficient)
+                } else {
+                    (term_max / coefficient, term_min / coefficient)
+                };
+                let (current_lower, current_upper) = bounds[variable];
+                // only tighten by more than the tolerance, to stop on slow convergence.
+                // Any finite bound replaces an infinite one, whose tolerance is infinite
+                if lower > current_lower
+                    && (current_lower.is_infinite()
+                        || lower > current_lower + tolerance(current_lower))
+                {
+                    bounds[variable].0 = lower;
+                    changed = true;
+                }
+                if upper < current_upper
+                    && (current_upper.is_infinite()
+                        || upper < current_upper - tolerance(current_upper))
+                {
+                    bounds[variable].1 = upper;
+                    changed = true;
+                }
+                check_bounds(&names, &integers, &mut bounds, variable)?;
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+    Ok(names.into_iter().zip(bounds).collect())
+}
+
+/// Round the bounds of an integer variable, fix a variable whose bounds cross by less than
+/// the tolerance, and fail if they cross by more
+fn check_bounds(
+    names: &[String],
+    integers: &[bool],
+    bounds: &mut [(f64, f64)],
+    variable: usize,
+) -> Result<(), Infeasibility> {
+    let (mut lower, mut upper) = bounds[variable];
+    if integers[variable] {
+        lower = (lower - tolerance(lower)).ceil();
+        upper = (upper + tolerance(upper)).floor();
+    }
+    if lower > upper {
+        if lower - upper > tolerance(upper) {
+            return Err(Infeasibility::CrossingBounds {
+                variable: names[variable].clone(),
+                lower,
+                upper,
+            });
+        }
+        upper = lower;
+    }
+    bounds[variable] = (lower, upper);
+    Ok(())
+}
+
+impl Problem<LinearExpression, Variable> {
+    /// Replace the bounds of the variables by the ones found by [propagate_bounds],
+    /// and return the number of bounds that changed.
+    /// Variables whose bounds meet are fixed to that value.
+    pub fn tighten_bounds(&mut self) -> Result<usize, Infeasibility> {
+        let bounds = propagate_bounds(self)?;
+        let mut changed = 0;
+        for variable in self.variables.iter_mut() {
+            if let Some(&(lower, upper)) = bounds.get(&variable.name) {
+                changed += usize::from(lower != variable.lower_bound);
+                changed += usize::from(upper != variable.upper_bound);
+                variable.lower_bound = lower;
+                variable.upper_bound = upper;
+            }
+        }
+        Ok(changed)
+    }
+}
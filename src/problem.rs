@@ -1,31 +1,173 @@
 //! Concrete implementations for the traits in [crate::lp_format]
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt;
 use std::fmt::Formatter;
+use std::io;
+use std::io::Write;
 
-use crate::lp_format::{AsVariable, Constraint, LpObjective, LpProblem, WriteToLpFileFormat};
+use std::cmp::Ordering;
+
+use crate::lp_format::{
+    AsVariable, ConsId, Constraint, ConstraintTags, LpFloat, LpObjective, LpProblem, LpValue,
+    WriteToLpFileFormat,
+};
+use crate::solvers::Solution;
+use crate::util::UniqueNameGenerator;
 
 /// A string that is a valid expression in the .lp format for the solver you are using
 pub struct StrExpression(pub String);
 
+/// A linear combination of variables
+///
+/// ```
+/// use lp_solvers::problem::LinearExpression;
+///
+/// let expr = LinearExpression {
+///     coefficients: vec![("x".to_string(), 2.), ("y".to_string(), -1.)],
+/// };
+/// assert_eq!(expr.evaluate_with(|name| if name == "x" { 3. } else { 1. }), 5.);
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct LinearExpression<F = f64> {
+    /// (variable name, coefficient) pairs
+    pub coefficients: Vec<(String, F)>,
+}
+
+impl<F: LpFloat> LinearExpression<F> {
+    /// Multiply every coefficient by the given factor
+    pub fn scale(&mut self, factor: F) {
+        for (_, coefficient) in self.coefficients.iter_mut() {
+            *coefficient = *coefficient * factor;
+        }
+    }
+
+    /// Replace the variable names that appear as keys in `renames` by the associated value
+    pub fn rename_variables(&mut self, renames: &HashMap<String, String>) {
+        for (name, _) in self.coefficients.iter_mut() {
+            if let Some(new_name) = renames.get(name) {
+                name.clone_from(new_name);
+            }
+        }
+    }
+
+    /// Compute the value of the expression, given the value of each variable
+    pub fn evaluate_with(&self, mut value: impl FnMut(&str) -> F) -> F {
+        self.coefficients
+            .iter()
+            .map(|(name, coefficient)| *coefficient * value(name))
+            .sum()
+    }
+
+    /// Add up the coefficients of the variables that appear more than once,
+    /// remove the variables whose coefficient is zero, and sort the variables by name.
+    ///
+    /// The expression has the same value, but is shorter to write, and is written the same way
+    /// whatever order its terms were added in.
+    ///
+    /// ```
+    /// use lp_solvers::problem::LinearExpression;
+    ///
+    /// let terms = [("y", 1.), ("x", 2.), ("y", -1.), ("x", 1.)];
+    /// let mut expr = LinearExpression {
+    ///     coefficients: terms.iter().map(|&(name, c)| (name.to_string(), c)).collect(),
+    /// };
+    /// expr.normalize();
+    /// assert_eq!(expr.coefficients, vec![("x".to_string(), 3.)]);
+    /// ```
+    pub fn normalize(&mut self) {
+        let mut terms: BTreeMap<String, F> = BTreeMap::new();
+        for (name, coefficient) in self.coefficients.drain(..) {
+            let sum = terms.entry(name).or_default();
+            *sum = *sum + coefficient;
+        }
+        self.coefficients = terms
+            .into_iter()
+            .filter(|(_, coefficient)| *coefficient != F::default())
+            .collect();
+    }
+}
+
 /// A variable to optimize
-pub struct Variable {
+#[derive(Debug, Clone, PartialEq)]
+pub struct Variable<F = f64> {
     /// The variable name should be unique in the problem and have a name accepted by the solver
     pub name: String,
     /// Whether the variable is restricted to only integer values
     pub is_integer: bool,
     /// -INFINITY if there is no lower bound
-    pub lower_bound: f64,
+    pub lower_bound: F,
     /// INFINITY if there is no upper bound
-    pub upper_bound: f64,
+    pub upper_bound: F,
+}
+
+/// A new variable, with its coefficients in the objective and in the existing constraints,
+/// as priced out in column generation. See [Problem::add_column]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Column<F = f64> {
+    /// the new variable
+    pub variable: Variable<F>,
+    /// the coefficient of the variable in the objective
+    pub objective: F,
+    /// the coefficients of the variable in the left-hand sides of existing constraints
+    pub coefficients: Vec<(ConsId, F)>,
 }
 
 impl WriteToLpFileFormat for StrExpression {
     fn to_lp_file_format(&self, f: &mut Formatter) -> fmt::Result {
         f.write_str(&self.0)
     }
+
+    fn write_lp_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(self.0.as_bytes())
+    }
+}
+
+impl<F: LpFloat> WriteToLpFileFormat for LinearExpression<F> {
+    fn to_lp_file_format(&self, f: &mut Formatter) -> fmt::Result {
+        if self.coefficients.is_empty() {
+            return f.write_str("0");
+        }
+        for (idx, (name, coefficient)) in self.coefficients.iter().enumerate() {
+            match (idx, coefficient.is_sign_negative()) {
+                (0, true) => f.write_str("-")?,
+                (0, false) => {}
+                (_, true) => f.write_str(" - ")?,
+                (_, false) => f.write_str(" + ")?,
+            }
+            write!(f, "{} {}", LpValue(coefficient.abs()), name)?;
+        }
+        Ok(())
+    }
+
+    fn write_lp_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        if self.coefficients.is_empty() {
+            return writer.write_all(b"0");
+        }
+        for (idx, (name, coefficient)) in self.coefficients.iter().enumerate() {
+            writer.write_all(match (idx, coefficient.is_sign_negative()) {
+                (0, true) => b"-",
+                (0, false) => b"",
+                (_, true) => b" - ",
+                (_, false) => b" + ",
+            })?;
+            coefficient.abs().write_lp_value(writer)?;
+            writer.write_all(b" ")?;
+            writer.write_all(name.as_bytes())?;
+        }
+        Ok(())
+    }
+
+    fn linear_terms(&self) -> Option<Vec<(&str, f64)>> {
+        Some(
+            self.coefficients
+                .iter()
+                .map(|(name, coefficient)| (name.as_str(), (*coefficient).into()))
+                .collect(),
+        )
+    }
 }
 
-impl AsVariable for Variable {
+impl<F: LpFloat> AsVariable for Variable<F> {
     fn name(&self) -> &str {
         &self.name
     }
@@ -35,16 +177,16 @@ impl AsVariable for Variable {
     }
 
     fn lower_bound(&self) -> f64 {
-        self.lower_bound
+        self.lower_bound.into()
     }
 
     fn upper_bound(&self) -> f64 {
-        self.upper_bound
+        self.upper_bound.into()
     }
 }
 
-/// A concrete linear problem
-pub struct Problem<EXPR = StrExpression, VAR = Variable> {
+/// A concrete linear problem, whose constraints have right-hand sides of type `F`
+pub struct Problem<EXPR = StrExpression, VAR = Variable, F = f64> {
     /// problem name. "lp_solvers_problem" by default
     /// Write the problem in the lp file format to the given formatter
     pub name: String,
@@ -55,17 +197,672 @@ pub struct Problem<EXPR = StrExpression, VAR = Variable> {
     /// Variables of the problem
     pub variables: Vec<VAR>,
     /// List of constraints to apply
-    pub constraints: Vec<Constraint<EXPR>>,
+    pub constraints: Vec<Constraint<EXPR, F>>,
+}
+
+impl<F: LpFloat> Problem<LinearExpression<F>, Variable<F>, F> {
+    /// Add the variables and constraints of `other` to this problem.
+    ///
+    /// Variables of `other` whose name is already used in this problem are renamed
+    /// using a [UniqueNameGenerator]. The new objective is
+    /// `self_weight * self.objective + other_weight * other.objective`,
+    /// where the objective of `other` is negated first if its optimization sense differs.
+    ///
+    /// Returns the renamings that were applied to the variables of `other`.
+    ///
+    /// ```
+    /// use lp_solvers::lp_format::LpObjective;
+    /// use lp_solvers::problem::{LinearExpression, Problem, Variable};
+    ///
+    /// let scenario = |name: &str| Problem {
+    ///     name: name.to_string(),
+    ///     sense: LpObjective::Minimize,
+    ///     objective: LinearExpression { coefficients: vec![("x".to_string(), 1.)] },
+    ///     variables: vec![Variable {
+    ///         name: "x".to_string(),
+    ///         is_integer: false,
+    ///         lower_bound: 0.,
+    ///         upper_bound: 1.,
+    ///     }],
+    ///     constraints: vec![],
+    /// };
+    /// let mut merged = scenario("a");
+    /// let renames = merged.merge(scenario("b"), 0.5, 0.5);
+    /// assert_eq!(renames["x"], "x2");
+    /// assert_eq!(merged.variables.len(), 2);
+    /// ```
+    pub fn merge(
+        &mut self,
+        other: Self,
+        self_weight: F,
+        other_weight: F,
+    ) -> HashMap<String, String> {
+        let mut used: HashSet<String> = HashSet::with_capacity(self.variables.len());
+        let mut generator = UniqueNameGenerator::default();
+        for var in self.variables.iter() {
+            generator.add_variable(&var.name);
+            used.insert(var.name.clone());
+        }
+
+        let mut renames = HashMap::new();
+        self.variables.reserve(other.variables.len());
+        for mut var in other.variables {
+            if used.contains(&var.name) {
+                let new_name = loop {
+                    let candidate = generator.add_variable(&var.name).into_owned();
+                    if !used.contains(&candidate) {
+                        break candidate;
+                    }
+                };
+                let old_name = std::mem::replace(&mut var.name, new_name.clone());
+                renames.insert(old_name, new_name);
+            }
+            used.insert(var.name.clone());
+            self.variables.push(var);
+        }
+
+        let other_weight = if other.sense == self.sense {
+            other_weight
+        } else {
+            -other_weight
+        };
+        let mut other_objective = other.objective;
+        other_objective.rename_variables(&renames);
+        other_objective.scale(other_weight);
+        self.objective.scale(self_weight);
+        self.objective
+            .coefficients
+            .extend(other_objective.coefficients);
+
+        self.constraints
+            .extend(other.constraints.into_iter().map(|mut constraint| {
+                constraint.lhs.rename_variables(&renames);
+                constraint
+            }));
+        renames
+    }
+
+    /// Add a variable to the problem, with its coefficients in the objective and in
+    /// existing constraints, as in column generation: the master problem is solved,
+    /// new columns are priced out from its dual values, and it is solved again.
+    ///
+    /// Solvers run on a file, so the whole problem is written again for the next solve.
+    /// A [ResolvingSolver](crate::solvers::ResolvingSolver) starts it from the previous
+    /// solution, in which the new variables have no value.
+    ///
+    /// Fails, leaving the problem unchanged, when the name of the variable is already used
+    /// or a constraint does not exist.
+    ///
+    /// ```
+    /// use std::cmp::Ordering;
+    /// use lp_solvers::lp_format::LpObjective;
+    /// use lp_solvers::problem::{Column, LinearExpression, ProblemBuilder, Variable};
+    ///
+    /// let mut builder = ProblemBuilder::new("master", LpObjective::Minimize);
+    /// let demand = builder.add_constraint(LinearExpression::default(), Ordering::Greater, 4.);
+    /// let mut master = builder.build();
+    /// let pattern = Column {
+    ///     variable: Variable {
+    ///         name: "p".to_string(),
+    ///         is_integer: false,
+    ///         lower_bound: 0.,
+    ///         upper_bound: f64::INFINITY,
+    ///     },
+    ///     objective: 1.,
+    ///     coefficients: vec![(demand, 2.)],
+    /// };
+    /// master.add_column(pattern.clone()).unwrap();
+    /// assert_eq!(master.constraints[0].lhs.coefficients, vec![("p".to_string(), 2.)]);
+    /// assert!(master.add_column(pattern).is_err());
+    /// ```
+    pub fn add_column(&mut self, column: Column<F>) -> Result<(), String> {
+        let name = &column.variable.name;
+        if self.variables.iter().any(|v| &v.name == name) {
+            return Err(format!("The variable {} is already in the problem", name));
+        }
+        if let Some((missing, _)) = column
+            .coefficients
+            .iter()
+            .find(|(id, _)| id.index() >= self.constraints.len())
+        {
+            return Err(format!(
+                "The column {} has a coefficient in {}, which is not in the problem",
+                name,
+                missing.name()
+            ));
+        }
+        if column.objective != F::default() {
+            self.objective
+                .coefficients
+                .push((name.clone(), column.objective));
+        }
+        for (id, coefficient) in column.coefficients {
+            self.constraints[id.index()]
+                .lhs
+                .coefficients
+                .push((name.clone(), coefficient));
+        }
+        self.variables.push(column.variable);
+        Ok(())
+    }
+
+    /// [Normalize](LinearExpression::normalize) the objective and the left-hand sides of
+    /// all the constraints
+    pub fn normalize(&mut self) {
+        self.objective.normalize();
+        for constraint in self.constraints.iter_mut() {
+            constraint.lhs.normalize();
+        }
+    }
+
+    /// The value of the objective for the values of the variables in the solution,
+    /// computed in double precision. Variables that are not in the solution are zero,
+    /// since solvers usually leave them out.
+    ///
+    /// This does not rely on the objective value reported by the solver, which is not always
+    /// parsed, and can be rounded or be the value of the scaled problem.
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use lp_solvers::lp_format::LpObjective;
+    /// use lp_solvers::problem::{LinearExpression, Problem, Variable};
+    /// use lp_solvers::solvers::{Solution, Status};
+    ///
+    /// let problem: Problem<LinearExpression, Variable> = Problem {
+    ///     name: "evaluate".to_string(),
+    ///     sense: LpObjective::Maximize,
+    ///     objective: LinearExpression {
+    ///         coefficients: vec![("x".to_string(), 2.), ("y".to_string(), 3.)],
+    ///     },
+    ///     variables: vec![],
+    ///     constraints: vec![],
+    /// };
+    /// let mut results = HashMap::new();
+    /// results.insert("x".to_string(), 1.5);
+    /// assert_eq!(problem.evaluate(&Solution::new(Status::Optimal, results)), 3.);
+    /// ```
+    pub fn evaluate(&self, solution: &Solution) -> f64 {
+        evaluate_expression(&self.objective, solution)
+    }
+
+    /// The value of the left-hand side of the given constraint, for the values of the variables
+    /// in the solution, or None if the problem has no such constraint.
+    /// Variables that are not in the solution are zero.
+    pub fn constraint_activity(&self, constraint: ConsId, solution: &Solution) -> Option<f64> {
+        self.constraints
+            .get(constraint.index())
+            .map(|c| evaluate_expression(&c.lhs, solution))
+    }
+
+    /// The value of the left-hand side of every constraint, in the order of the constraints,
+    /// like [Problem::constraint_activity]
+    pub fn constraint_activities(&self, solution: &Solution) -> Vec<f64> {
+        self.constraints
+            .iter()
+            .map(|c| evaluate_expression(&c.lhs, solution))
+            .collect()
+    }
+
+    /// Remove the constraints that cannot change the solutions of the problem:
+    ///  - constraints that have the same left-hand side, operator and right-hand side
+    ///    as an earlier constraint
+    ///  - inequalities dominated by another one with the same left-hand side,
+    ///    like `x + y <= 5` when there is `x + y <= 3`
+    ///  - inequalities that always hold given the bounds of the variables,
+    ///    like `x + y <= 5` when `x` and `y` are between 0 and 2
+    ///
+    /// Left-hand sides are compared once [normalized](LinearExpression::normalize),
+    /// but they are not changed. The remaining constraints keep their order. Since their
+    /// indices change, the returned report tells which constraint each of them was.
+    ///
+    /// ```
+    /// use std::cmp::Ordering;
+    /// use lp_solvers::lp_format::{ConsId, LpObjective};
+    /// use lp_solvers::problem::{LinearExpression, ProblemBuilder, Redundancy, Variable};
+    ///
+    /// let x = || LinearExpression { coefficients: vec![("x".to_string(), 1.)] };
+    /// let mut builder = ProblemBuilder::new("redundant", LpObjective::Maximize);
+    /// builder.add_variable(Variable {
+    ///     name: "x".to_string(),
+    ///     is_integer: false,
+    ///     lower_bound: 0.,
+    ///     upper_bound: 10.,
+    /// });
+    /// builder.add_constraint(x(), Ordering::Less, 5.);
+    /// builder.add_constraint(x(), Ordering::Less, 5.);
+    /// builder.add_constraint(x(), Ordering::Less, 7.);
+    /// builder.add_constraint(x(), Ordering::Greater, -1.);
+    /// let mut problem = builder.build();
+    /// let report = problem.remove_redundant_constraints();
+    /// assert_eq!(problem.constraints.len(), 1);
+    /// assert_eq!(report.removed[0].reason, Redundancy::Duplicate(ConsId::from_index(0)));
+    /// assert_eq!(report.removed[1].reason, Redundancy::Dominated(ConsId::from_index(0)));
+    /// assert_eq!(report.removed[2].reason, Redundancy::ImpliedByBounds);
+    /// ```
+    pub fn remove_redundant_constraints(&mut self) -> RedundancyReport {
+        let bounds: HashMap<&str, (f64, f64)> = self
+            .variables
+            .iter()
+            .map(|v| {
+                (
+                    v.name.as_str(),
+                    (v.lower_bound.into(), v.upper_bound.into()),
+                )
+            })
+            .collect();
+        // the normalized left-hand side of each constraint that is not implied by the bounds
+        let mut keys: Vec<Option<TermsKey>> = Vec::with_capacity(self.constraints.len());
+        // the strongest constraint for each left-hand side and operator: the first one with
+        // the lowest right-hand side for <=, with the highest for >=.
+        // Equalities with different right-hand sides are all kept.
+        let mut strongest: HashMap<(TermsKey, Ordering, Option<u64>), usize> = HashMap::new();
+        for (index, constraint) in self.constraints.iter().enumerate() {
+            let mut lhs = constraint.lhs.clone();
+            lhs.normalize();
+            let (min, max) = lhs
+                .coefficients
+                .iter()
+                .fold((0., 0.), |(min, max), (name, c)| {
+                    let c: f64 = (*c).into();
+                    let (lower, upper) = bounds
+                        .get(name.as_str())
+                        .copied()
+                        .unwrap_or((f64::NEG_INFINITY, f64::INFINITY));
+                    let (low, high) = if c > 0. {
+                        (c * lower, c * upper)
+                    } else {
+                        (c * upper, c * lower)
+                    };
+                    (min + low, max + high)
+                });
+            let rhs: f64 = constraint.rhs.into();
+            let implied = match constraint.operator {
+                Ordering::Less => max <= rhs,
+                Ordering::Greater => min >= rhs,
+                Ordering::Equal => min == rhs && max == rhs,
+            };
+            if implied {
+                keys.push(None);
+                continue;
+            }
+            let key: TermsKey = lhs
+                .coefficients
+                .into_iter()
+                .map(|(name, c)| (name, Into::<f64>::into(c).to_bits()))
+                .collect();
+            let equal_rhs = (constraint.operator == Ordering::Equal).then(|| rhs.to_bits());
+            let best = strongest
+                .entry((key.clone(), constraint.operator, equal_rhs))
+                .or_insert(index);
+            let best_rhs: f64 = self.constraints[*best].rhs.into();
+            let stronger = match constraint.operator {
+                Ordering::Less => rhs < best_rhs,
+                Ordering::Greater => rhs > best_rhs,
+                Ordering::Equal => false,
+            };
+            if stronger {
+                *best = index;
+            }
+            keys.push(Some(key));
+        }
+        let reasons = keys
+            .into_iter()
+            .enumerate()
+            .map(|(index, key)| {
+                let key = match key {
+                    Some(key) => key,
+                    None => return Some(Redundancy::ImpliedByBounds),
+                };
+                let constraint = &self.constraints[index];
+                let equal_rhs = (constraint.operator == Ordering::Equal)
+                    .then(|| Into::<f64>::into(constraint.rhs).to_bits());
+                let best = strongest[&(key, constraint.operator, equal_rhs)];
+                if best == index {
+                    None
+                } else if self.constraints[best].rhs == constraint.rhs {
+                    Some(Redundancy::Duplicate(ConsId::from_index(best)))
+                } else {
+                    Some(Redundancy::Dominated(ConsId::from_index(best)))
+                }
+            })
+            .collect::<Vec<_>>();
+        let mut report = RedundancyReport::default();
+        let constraints = std::mem::take(&mut self.constraints);
+        for (index, (constraint, reason)) in constraints.into_iter().zip(reasons).enumerate() {
+            let id = ConsId::from_index(index);
+            match reason {
+                Some(reason) => report.removed.push(RemovedConstraint {
+                    constraint: id,
+                    reason,
+                }),
+                None => {
+                    report.kept.push(id);
+                    self.constraints.push(constraint);
+                }
+            }
+        }
+        report
+    }
+}
+
+/// The value of the expression in double precision, with zero for the variables
+/// that are not in the solution
+fn evaluate_expression<F: LpFloat>(expression: &LinearExpression<F>, solution: &Solution) -> f64 {
+    expression
+        .coefficients
+        .iter()
+        .map(|(name, coefficient)| {
+            let value = solution.results.get(name).copied().unwrap_or(0.);
+            (*coefficient).into() * f64::from(value)
+        })
+        .sum()
+}
+
+/// A normalized left-hand side, with the bits of its coefficients so that it can be hashed
+type TermsKey = Vec<(String, u64)>;
+
+/// The constraints removed by [Problem::remove_redundant_constraints]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RedundancyReport {
+    /// The constraints that were removed, in their original order
+    pub removed: Vec<RemovedConstraint>,
+    /// The original ids of the constraints that were kept, in their new order
+    pub kept: Vec<ConsId>,
+}
+
+impl RedundancyReport {
+    /// The id a constraint had before the redundant constraints were removed,
+    /// given its id in the problem that was left, to find its dual value for instance
+    pub fn original_id(&self, constraint: ConsId) -> Option<ConsId> {
+        self.kept.get(constraint.index()).copied()
+    }
+}
+
+/// A constraint removed by [Problem::remove_redundant_constraints]
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemovedConstraint {
+    /// The id the constraint had before it was removed
+    pub constraint: ConsId,
+    /// Why it was removed
+    pub reason: Redundancy,
+}
+
+/// Why a constraint was removed by [Problem::remove_redundant_constraints].
+/// The ids are the ones the constraints had before they were removed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Redundancy {
+    /// The same as the given constraint
+    Duplicate(ConsId),
+    /// Less restrictive than the given constraint, which has the same left-hand side
+    Dominated(ConsId),
+    /// Always satisfied, given the bounds of the variables
+    ImpliedByBounds,
+}
+
+/// Incrementally build a [Problem] with linear expressions,
+/// keeping handles to the constraints that are added.
+///
+/// ```
+/// use std::cmp::Ordering;
+/// use lp_solvers::lp_format::LpObjective;
+/// use lp_solvers::problem::{LinearExpression, ProblemBuilder, Variable};
+///
+/// let mut builder = ProblemBuilder::new("knapsack", LpObjective::Maximize);
+/// builder.add_variable(Variable {
+///     name: "x".to_string(),
+///     is_integer: false,
+///     lower_bound: 0.,
+///     upper_bound: f64::INFINITY,
+/// });
+/// builder.set_objective(LinearExpression { coefficients: vec![("x".to_string(), 1.)] });
+/// let capacity = builder.add_constraint(
+///     LinearExpression { coefficients: vec![("x".to_string(), 2.)] },
+///     Ordering::Less,
+///     10.,
+/// );
+/// assert_eq!(capacity.name(), "c0");
+/// let problem = builder.build();
+/// assert_eq!(problem.constraints.len(), 1);
+/// ```
+pub struct ProblemBuilder<F = f64> {
+    problem: Problem<LinearExpression<F>, Variable<F>, F>,
+    normalize: bool,
+    tags: ConstraintTags,
+}
+
+impl<F: LpFloat> ProblemBuilder<F> {
+    /// Start building a problem with no variables, no constraints and an empty objective
+    pub fn new(name: impl Into<String>, sense: LpObjective) -> Self {
+        ProblemBuilder {
+            problem: Problem {
+                name: name.into(),
+                sense,
+                objective: LinearExpression::default(),
+                variables: vec![],
+                constraints: vec![],
+            },
+            normalize: false,
+            tags: ConstraintTags::default(),
+        }
+    }
+
+    /// [Normalize](Problem::normalize) the expressions of the problem when it is built,
+    /// to write smaller files that do not depend on the order in which terms were added
+    pub fn normalize_expressions(&mut self) -> &mut Self {
+        self.normalize = true;
+        self
+    }
+
+    /// Add a variable to the problem
+    pub fn add_variable(&mut self, variable: Variable<F>) -> &mut Self {
+        self.problem.variables.push(variable);
+        self
+    }
+
+    /// Set the function to optimize
+    pub fn set_objective(&mut self, objective: LinearExpression<F>) -> &mut Self {
+        self.problem.objective = objective;
+        self
+    }
+
+    /// Add the constraint `lhs <operator> rhs`, and return a handle to it
+    pub fn add_constraint(
+        &mut self,
+        lhs: LinearExpression<F>,
+        operator: Ordering,
+        rhs: F,
+    ) -> ConsId {
+        let id = ConsId::from_index(self.problem.constraints.len());
+        self.problem
+            .constraints
+            .push(Constraint { lhs, operator, rhs });
+        id
+    }
+
+    /// Get the constraint associated to a handle returned by [ProblemBuilder::add_constraint]
+    pub fn constraint(&self, id: ConsId) -> Option<&Constraint<LinearExpression<F>, F>> {
+        self.problem.constraints.get(id.index())
+    }
+
+    /// Attach a tag, such as `capacity` or `plant 7`, to a constraint returned by
+    /// [ProblemBuilder::add_constraint]. A constraint can have several tags.
+    /// See [ConstraintTags]
+    pub fn tag_constraint(&mut self, id: ConsId, tag: impl Into<String>) -> &mut Self {
+        self.tags.add(id, tag);
+        self
+    }
+
+    /// The tags attached to the constraints so far
+    pub fn tags(&self) -> &ConstraintTags {
+        &self.tags
+    }
+
+    /// Finish building the problem
+    pub fn build(self) -> Problem<LinearExpression<F>, Variable<F>, F> {
+        self.build_with_tags().0
+    }
+
+    /// Finish building the problem, and return it with the tags of its constraints,
+    /// to write them with [crate::lp_format::LpFormatOptions::constraint_tags]
+    /// and group the results of an infeasibility analysis by tag.
+    ///
+    /// ```
+    /// use std::cmp::Ordering;
+    /// use lp_solvers::lp_format::{LpFormatOptions, LpObjective, LpProblem};
+    /// use lp_solvers::problem::{LinearExpression, ProblemBuilder};
+    ///
+    /// let mut builder = ProblemBuilder::new("plants", LpObjective::Minimize);
+    /// let x = LinearExpression { coefficients: vec![("x".to_string(), 1.)] };
+    /// let capacity = builder.add_constraint(x, Ordering::Less, 10.);
+    /// builder.tag_constraint(capacity, "capacity of plant 7");
+    /// let (problem, tags) = builder.build_with_tags();
+    /// let options = LpFormatOptions { constraint_tags: tags, ..LpFormatOptions::default() };
+    /// let lp = problem.with_format_options(options).display_lp().to_string();
+    /// assert!(lp.contains("  \\ tag: capacity of plant 7\n  c0: 1.0 x <= 10.0\n"));
+    /// ```
+    pub fn build_with_tags(self) -> (Problem<LinearExpression<F>, Variable<F>, F>, ConstraintTags) {
+        let mut problem = self.problem;
+        if self.normalize {
+            problem.normalize();
+        }
+        (problem, self.tags)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Problem<LinearExpression, Variable> {
+    /// Read a problem from its JSON definition.
+    ///
+    /// The expected document is an object with the following fields:
+    ///  - `name` (optional): the problem name
+    ///  - `sense`: `"minimize"` or `"maximize"`
+    ///  - `objective`: a list of `[variable_name, coefficient]` pairs
+    ///  - `variables`: a list of objects with a `name`, optional `lower_bound` and `upper_bound`
+    ///    (a missing or `null` bound means the variable is unbounded in that direction)
+    ///    and an optional `is_integer` flag (`false` by default)
+    ///  - `constraints`: a list of `[lhs, operator, rhs]` triplets, where `lhs` is a
+    ///    list of `[variable_name, coefficient]` pairs, and `operator` is `"<="`, `"="` or `">="`
+    ///
+    /// ```
+    /// use lp_solvers::problem::Problem;
+    ///
+    /// let definition = r#"{
+    ///     "name": "example",
+    ///     "sense": "maximize",
+    ///     "objective": [["x", 1], ["y", 2]],
+    ///     "variables": [
+    ///         {"name": "x", "lower_bound": 0, "upper_bound": 10},
+    ///         {"name": "y", "lower_bound": 0, "is_integer": true}
+    ///     ],
+    ///     "constraints": [[[["x", 1], ["y", 1]], "<=", 5]]
+    /// }"#;
+    /// let problem = Problem::from_reader(definition.as_bytes()).unwrap();
+    /// assert_eq!(problem.variables[1].upper_bound, f64::INFINITY);
+    /// ```
+    pub fn from_reader<R: std::io::Read>(reader: R) -> Result<Self, String> {
+        let definition: definition::ProblemDefinition = serde_json::from_reader(reader)
+            .map_err(|e| format!("Invalid problem definition: {}", e))?;
+        definition.into_problem()
+    }
+
+    /// Read a problem from its YAML definition. See [Problem::from_reader] for the schema.
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml_reader<R: std::io::Read>(reader: R) -> Result<Self, String> {
+        let definition: definition::ProblemDefinition = serde_yaml::from_reader(reader)
+            .map_err(|e| format!("Invalid problem definition: {}", e))?;
+        definition.into_problem()
+    }
+}
+
+#[cfg(feature = "serde")]
+mod definition {
+    use std::cmp::Ordering;
+
+    use serde::Deserialize;
+
+    use super::{LinearExpression, Problem, Variable};
+    use crate::lp_format::{Constraint, LpObjective};
+
+    type Terms = Vec<(String, f64)>;
+
+    #[derive(Deserialize)]
+    pub struct ProblemDefinition {
+        #[serde(default = "default_name")]
+        name: String,
+        sense: LpObjective,
+        #[serde(default)]
+        objective: Terms,
+        #[serde(default)]
+        variables: Vec<VariableDefinition>,
+        #[serde(default)]
+        constraints: Vec<(Terms, String, f64)>,
+    }
+
+    #[derive(Deserialize)]
+    struct VariableDefinition {
+        name: String,
+        #[serde(default)]
+        lower_bound: Option<f64>,
+        #[serde(default)]
+        upper_bound: Option<f64>,
+        #[serde(default)]
+        is_integer: bool,
+    }
+
+    fn default_name() -> String {
+        "lp_solvers_problem".to_string()
+    }
+
+    impl ProblemDefinition {
+        pub fn into_problem(self) -> Result<Problem<LinearExpression, Variable>, String> {
+            let constraints = self
+                .constraints
+                .into_iter()
+                .map(|(coefficients, operator, rhs)| {
+                    let operator = match operator.as_str() {
+                        "<=" | "=<" | "<" => Ordering::Less,
+                        "=" | "==" => Ordering::Equal,
+                        ">=" | "=>" | ">" => Ordering::Greater,
+                        _ => return Err(format!("Invalid constraint operator: {:?}", operator)),
+                    };
+                    Ok(Constraint {
+                        lhs: LinearExpression { coefficients },
+                        operator,
+                        rhs,
+                    })
+                })
+                .collect::<Result<_, String>>()?;
+            Ok(Problem {
+                name: self.name,
+                sense: self.sense,
+                objective: LinearExpression {
+                    coefficients: self.objective,
+                },
+                variables: self
+                    .variables
+                    .into_iter()
+                    .map(|v| Variable {
+                        name: v.name,
+                        is_integer: v.is_integer,
+                        lower_bound: v.lower_bound.unwrap_or(f64::NEG_INFINITY),
+                        upper_bound: v.upper_bound.unwrap_or(f64::INFINITY),
+                    })
+                    .collect(),
+                constraints,
+            })
+        }
+    }
 }
 
-impl<'a, EXPR: 'a, VAR: 'a> LpProblem<'a> for Problem<EXPR, VAR>
+impl<'a, EXPR: 'a, VAR: 'a, F: LpFloat> LpProblem<'a> for Problem<EXPR, VAR, F>
 where
     &'a VAR: AsVariable,
     &'a EXPR: WriteToLpFileFormat,
 {
     type Variable = &'a VAR;
     type Expression = &'a EXPR;
-    type ConstraintIterator = Box<dyn Iterator<Item = Constraint<&'a EXPR>> + 'a>;
+    type Float = F;
+    type ConstraintIterator = Box<dyn Iterator<Item = Constraint<&'a EXPR, F>> + 'a>;
     type VariableIterator = std::slice::Iter<'a, VAR>;
 
     fn name(&self) -> &str {
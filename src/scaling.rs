@@ -0,0 +1,172 @@
+//! Scaling of badly conditioned problems, whose coefficients span many orders of magnitude.
+//!
+//! The constraints and the variables of a problem are multiplied by factors that bring its
+//! coefficients closer to 1, so that solvers meet fewer numerical difficulties.
+//! The solution of the scaled problem is then turned back into a solution of the original one.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::lp_format::{ConsId, LpFloat};
+use crate::problem::{LinearExpression, Problem, Variable};
+use crate::solvers::Solution;
+
+/// The number of passes over the rows and the columns
+const SCALING_PASSES: usize = 4;
+
+/// The factors by which the constraints and the variables of a problem were scaled,
+/// returned by [Problem::scale]
+///
+/// ```
+/// use std::cmp::Ordering;
+/// use lp_solvers::lp_format::{Constraint, LpObjective};
+/// use lp_solvers::problem::{LinearExpression, Problem, Variable};
+/// use lp_solvers::solvers::{Solution, Status};
+///
+/// let mut problem = Problem {
+///     name: "unscaled".to_string(),
+///     sense: LpObjective::Maximize,
+///     objective: LinearExpression { coefficients: vec![("x".to_string(), 1.)] },
+///     variables: vec![Variable {
+///         name: "x".to_string(),
+///         is_integer: false,
+///         lower_bound: 0.,
+///         upper_bound: f64::INFINITY,
+///     }],
+///     constraints: vec![Constraint {
+///         lhs: LinearExpression { coefficients: vec![("x".to_string(), 1e6)] },
+///         operator: Ordering::Less,
+///         rhs: 2e6,
+///     }],
+/// };
+/// let scaling = problem.scale();
+/// let coefficient = problem.constraints[0].lhs.coefficients[0].1;
+/// assert!(0.5 < coefficient && coefficient < 2.);
+///
+/// // x = 2 is x' = 2 / c in the scaled problem
+/// let scaled_x = 2. / scaling.column_factor("x");
+/// let mut solution = Solution::new(Status::Optimal, Default::default());
+/// solution.results.insert("x".to_string(), scaled_x as f32);
+/// scaling.unscale(&mut solution);
+/// assert_eq!(solution.results["x"], 2.);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Scaling {
+    /// the factor of each constraint, by index
+    rows: Vec<f64>,
+    /// the factor of each variable that is scaled. The others have a factor of 1.
+    columns: HashMap<String, f64>,
+}
+
+impl Scaling {
+    /// The factor by which the given constraint was multiplied
+    pub fn row_factor(&self, constraint: ConsId) -> f64 {
+        self.rows.get(constraint.index()).copied().unwrap_or(1.)
+    }
+
+    /// The factor `c` such that the variable with the given name is `c` times the variable
+    /// of the scaled problem
+    pub fn column_factor(&self, variable: &str) -> f64 {
+        self.columns.get(variable).copied().unwrap_or(1.)
+    }
+
+    /// Turn a solution of the scaled problem into a solution of the original problem:
+    /// the values of the variables and the duals of the constraints are multiplied
+    /// by their factors. The objective value is the same in both problems.
+    pub fn unscale(&self, solution: &mut Solution) {
+        for (name, value) in solution.results.iter_mut() {
+            *value = (f64::from(*value) * self.column_factor(name)) as f32;
+        }
+        let naming = solution.constraint_naming.clone();
+        for (name, dual) in solution.dual_values.iter_mut() {
+            if let Some(constraint) = naming.constraint(name) {
+                *dual = (f64::from(*dual) * self.row_factor(constraint)) as f32;
+            }
+        }
+    }
+}
+
+/// The power of two nearest to `1 / sqrt(min * max)`, which brings `min` and `max` to either
+/// side of 1. Powers of two change the exponent of the coefficients, not their digits.
+fn geometric_factor(min: f64, max: f64) -> f64 {
+    if min.is_finite() && max.is_finite() && min > 0. {
+        2f64.powi(-(min * max).sqrt().log2().round() as i32)
+    } else {
+        1.
+    }
+}
+
+/// The smallest and the largest absolute value of the coefficients
+fn abs_range(coefficients: impl Iterator<Item = f64>) -> (f64, f64) {
+    coefficients.fold((f64::INFINITY, 0.), |(min, max), c| {
+        let c = c.abs();
+        (min.min(c), max.max(c))
+    })
+}
+
+impl<F: LpFloat + From<f32>> Problem<LinearExpression<F>, Variable<F>, F> {
+    /// Scale the constraints and the variables of the problem with geometric mean scaling,
+    /// and return the factors, to [unscale](Scaling::unscale) the solution.
+    ///
+    /// Each constraint is multiplied by a factor, and each continuous variable `x` is replaced
+    /// by `x'` such that `x = c x'`. The factors are powers of two, so that scaling does not
+    /// introduce rounding errors. Integer variables are not scaled.
+    /// The objective is expressed in the scaled variables, but keeps the same value.
+    pub fn scale(&mut self) -> Scaling {
+        let integers: HashSet<&str> = self
+            .variables
+            .iter()
+            .filter(|v| v.is_integer)
+            .map(|v| v.name.as_str())
+            .collect();
+        let mut rows = vec![1.; self.constraints.len()];
+        let mut columns: HashMap<String, f64> = HashMap::new();
+        let column =
+            |columns: &HashMap<String, f64>, name: &str| columns.get(name).copied().unwrap_or(1.);
+        for _ in 0..SCALING_PASSES {
+            for (row, constraint) in rows.iter_mut().zip(&self.constraints) {
+                let (min, max) = abs_range(
+                    constraint
+                        .lhs
+                        .coefficients
+                        .iter()
+                        .map(|(name, c)| (*c).into() * column(&columns, name)),
+                );
+                *row = geometric_factor(min, max);
+            }
+            let mut ranges: HashMap<&str, (f64, f64)> = HashMap::new();
+            for (row, constraint) in rows.iter().zip(&self.constraints) {
+                for (name, c) in constraint.lhs.coefficients.iter() {
+                    let c = ((*c).into() * row).abs();
+                    let (min, max) = ranges.entry(name.as_str()).or_insert((f64::INFINITY, 0.));
+                    *min = min.min(c);
+                    *max = max.max(c);
+                }
+            }
+            for (name, (min, max)) in ranges {
+                if !integers.contains(name) {
+                    columns.insert(name.to_string(), geometric_factor(min, max));
+                }
+            }
+        }
+        columns.retain(|_, factor| *factor != 1.);
+
+        // powers of two are exact in single precision, and multiplying by them is exact
+        let scale = |value: F, factor: f64| value * F::from(factor as f32);
+        let scale_expression = |expression: &mut LinearExpression<F>, row: f64| {
+            for (name, c) in expression.coefficients.iter_mut() {
+                *c = scale(*c, row * column(&columns, name));
+            }
+        };
+        scale_expression(&mut self.objective, 1.);
+        for (row, constraint) in rows.iter().zip(self.constraints.iter_mut()) {
+            scale_expression(&mut constraint.lhs, *row);
+            constraint.rhs = scale(constraint.rhs, *row);
+        }
+        for variable in self.variables.iter_mut() {
+            let factor = column(&columns, &variable.name);
+            variable.lower_bound = scale(variable.lower_bound, 1. / factor);
+            variable.upper_bound = scale(variable.upper_bound, 1. / factor);
+        }
+        Scaling { rows, columns }
+    }
+}
@@ -0,0 +1,121 @@
+//! Solving problems sent over HTTP, with the `server` feature.
+//!
+//! [router] turns a solver into an [axum] application, so that a machine with solvers installed
+//! can solve problems for others:
+//!
+//! ```no_run
+//! # #[cfg(feature = "cbc")] {
+//! # async fn serve() -> std::io::Result<()> {
+//! use lp_solvers::solvers::CbcSolver;
+//!
+//! let app = lp_solvers::server::router(CbcSolver::new());
+//! let listener = tokio::net::TcpListener::bind("0.0.0.0:3000").await?;
+//! axum::serve(listener, app).await
+//! # }
+//! # }
+//! ```
+//!
+//! Problems are POSTed to `/solve`, either as a JSON definition
+//! (see [crate::problem::Problem::from_reader]) with the `application/json` content type,
+//! or as the content of an .lp file with any other content type.
+//! The response is the [crate::solvers::Solution] as JSON, or an object with an `error` field.
+
+use std::io::Write;
+use std::sync::Arc;
+
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::{Json, Router};
+
+use crate::problem::Problem;
+use crate::solvers::{
+    solve_file, Solution, SolverError, SolverProgram, SolverTrait, SolverWithSolutionParsing,
+};
+
+/// An application that solves the problems POSTed to `/solve` with the given solver
+pub fn router<S>(solver: S) -> Router
+where
+    S: SolverProgram + SolverWithSolutionParsing + Send + Sync + 'static,
+{
+    Router::new()
+        .route("/solve", post(solve::<S>))
+        .with_state(Arc::new(solver))
+}
+
+/// Why a request could not be answered with a solution
+enum ServerError {
+    /// The problem in the request is invalid
+    BadRequest(String),
+    /// The solver could not solve the problem
+    Solver(SolverError),
+}
+
+impl IntoResponse for ServerError {
+    fn into_response(self) -> Response {
+        let (status, message) = match self {
+            ServerError::BadRequest(message) => (StatusCode::BAD_REQUEST, message),
+            ServerError::Solver(error @ SolverError::License(_)) => {
+                (StatusCode::SERVICE_UNAVAILABLE, error.to_string())
+            }
+            ServerError::Solver(error) => (StatusCode::INTERNAL_SERVER_ERROR, error.to_string()),
+        };
+        (status, Json(serde_json::json!({ "error": message }))).into_response()
+    }
+}
+
+/// The handler of `/solve`. The problem is solved on a blocking thread.
+///
+/// Responds with:
+///  - `200 OK` and the solution, even when the problem is infeasible or unbounded
+///  - `400 Bad Request` when the problem definition is invalid
+///  - `503 Service Unavailable` when the solver could not get a license
+///  - `500 Internal Server Error` when the solver failed for another reason
+pub async fn solve<S>(State(solver): State<Arc<S>>, headers: HeaderMap, body: Bytes) -> Response
+where
+    S: SolverProgram + SolverWithSolutionParsing + Send + Sync + 'static,
+{
+    let is_json = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.starts_with("application/json"))
+        .unwrap_or(false);
+    let solved = tokio::task::spawn_blocking(move || {
+        if is_json {
+            solve_json(&*solver, &body)
+        } else {
+            solve_lp(&*solver, &body)
+        }
+    })
+    .await;
+    match solved {
+        Ok(Ok(solution)) => Json(solution).into_response(),
+        Ok(Err(error)) => error.into_response(),
+        Err(e) => ServerError::Solver(format!("The solve panicked: {}", e).into()).into_response(),
+    }
+}
+
+fn solve_json<S>(solver: &S, body: &[u8]) -> Result<Solution, ServerError>
+where
+    S: SolverProgram + SolverWithSolutionParsing,
+{
+    let problem = Problem::from_reader(body).map_err(ServerError::BadRequest)?;
+    solver.run(&problem).map_err(ServerError::Solver)
+}
+
+fn solve_lp<S>(solver: &S, body: &[u8]) -> Result<Solution, ServerError>
+where
+    S: SolverProgram + SolverWithSolutionParsing,
+{
+    if body.iter().all(u8::is_ascii_whitespace) {
+        return Err(ServerError::BadRequest("Empty problem".to_string()));
+    }
+    let file = tempfile::Builder::new()
+        .suffix(".lp")
+        .tempfile()
+        .and_then(|mut file| file.write_all(body).map(|()| file))
+        .map_err(|e| ServerError::Solver(format!("Unable to write the problem: {}", e).into()))?;
+    solve_file(solver, file.path()).map_err(ServerError::Solver)
+}
@@ -1,11 +1,26 @@
 //! Auto solvers automatically find which of their child solvers is installed on
 //! the user's computer and uses it. The [AllSolvers] solvers tries all the supported solvers.
 
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
 use crate::lp_format::{LpObjective, LpProblem};
 use crate::problem::{Problem, StrExpression, Variable};
+#[cfg(feature = "cbc")]
+use crate::solvers::cbc::CbcSolver;
 #[cfg(feature = "cplex")]
 use crate::solvers::cplex::Cplex;
-use crate::solvers::{CbcSolver, GlpkSolver, GurobiSolver, Solution};
+#[cfg(feature = "glpk")]
+use crate::solvers::glpk::GlpkSolver;
+#[cfg(feature = "gurobi")]
+use crate::solvers::gurobi::GurobiSolver;
+use crate::solvers::{
+    ApplyOptions, CachedAutoSolver, DiscoveryCache, Solution, SolverError, SolverOptions,
+};
 
 use super::SolverTrait;
 
@@ -17,18 +32,376 @@ pub struct AutoSolver<SOLVER, NEXT>(SOLVER, NEXT);
 #[derive(Debug, Clone, Default)]
 pub struct NoSolver;
 
+// the solvers whose feature is disabled are never available
+#[cfg(not(feature = "cbc"))]
+type CbcSolver = NoSolver;
 #[cfg(not(feature = "cplex"))]
 type Cplex = NoSolver;
+#[cfg(not(feature = "glpk"))]
+type GlpkSolver = NoSolver;
+#[cfg(not(feature = "gurobi"))]
+type GurobiSolver = NoSolver;
 
-/// An [AutoSolver] that tries, in order: Gurobi, Cplex, Cbc and Glpk
+/// An [AutoSolver] that tries, in order: Gurobi, Cplex, Cbc and Glpk,
+/// skipping the ones whose feature is disabled
 pub type AllSolvers = AutoSolver<
     GurobiSolver,
     AutoSolver<Cplex, AutoSolver<CbcSolver, AutoSolver<GlpkSolver, NoSolver>>>,
 >;
 
 impl SolverTrait for NoSolver {
-    fn run<'a, P: LpProblem<'a>>(&self, _problem: &'a P) -> Result<Solution, String> {
-        Err("No solver available".to_string())
+    fn run<'a, P: LpProblem<'a>>(&self, _problem: &'a P) -> Result<Solution, SolverError> {
+        Err("No solver available".to_string().into())
+    }
+
+    fn run_file(&self, _problem_file: &Path) -> Result<Solution, SolverError> {
+        Err("No solver available".to_string().into())
+    }
+
+    fn is_available(&self) -> bool {
+        false
+    }
+}
+
+/// The solvers tried by an [AutoSolver], in order
+pub trait SolverList {
+    /// Check which solvers of the list are available, all at the same time
+    fn probe(&self) -> Vec<bool> {
+        self.probe_with(None)
+    }
+    /// Check which solvers of the list are available, all at the same time,
+    /// using and filling the cache if there is one
+    fn probe_with(&self, cache: Option<&DiscoveryCache>) -> Vec<bool>;
+    /// Check whether the solver at the given position in the list is available
+    fn probe_at(&self, index: usize) -> bool {
+        self.probe().get(index).copied().unwrap_or(false)
+    }
+    /// Run the solver at the given position in the list
+    fn run_at<'a, P: LpProblem<'a>>(
+        &self,
+        index: usize,
+        problem: &'a P,
+    ) -> Result<Solution, SolverError>;
+    /// Solve a problem file with the solver at the given position in the list
+    fn run_file_at(&self, index: usize, problem_file: &Path) -> Result<Solution, SolverError>;
+    /// The names of the solvers of the list, in order, for [SolverSelection].
+    /// `None` for a [NoSolver] in the list, which stands for a solver that is not compiled in.
+    fn solver_names(&self) -> Vec<Option<String>>;
+}
+
+/// Which solver of an [AutoSolver] or a [CachedAutoSolver] produced a solution,
+/// and why the solvers before it in the list were not used.
+/// See [Solution::solver_selection].
+///
+/// Solvers are named by their program, such as `gurobi_cl`, or by their type.
+///
+/// ```
+/// use lp_solvers::solvers::{SkipReason, SkippedSolver, SolverSelection};
+///
+/// let selection = SolverSelection {
+///     solver: "glpsol".to_string(),
+///     index: 1,
+///     skipped: vec![SkippedSolver {
+///         solver: "gurobi_cl".to_string(),
+///         reason: SkipReason::Unavailable,
+///     }],
+/// };
+/// assert_eq!(selection.to_string(), "glpsol (skipped gurobi_cl: not available)");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SolverSelection {
+    /// The solver that produced the solution
+    pub solver: String,
+    /// The position of the solver in the list
+    pub index: usize,
+    /// The solvers before it in the list, in order
+    pub skipped: Vec<SkippedSolver>,
+}
+
+/// A solver of an [AutoSolver] that was not used, see [SolverSelection]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SkippedSolver {
+    /// The name of the solver
+    pub solver: String,
+    /// Why it was not used
+    pub reason: SkipReason,
+}
+
+/// Why a solver of an [AutoSolver] was not used
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SkipReason {
+    /// The solver is not installed or does not work. See [diagnose_solver](crate::solvers::diagnose_solver)
+    /// to find out why.
+    Unavailable,
+    /// The solver is recorded as not available in the [DiscoveryCache] stored in the given file.
+    /// The cache must be cleared after installing the solver.
+    UnavailableInCache(PathBuf),
+    /// Checking whether the solver is available took longer than the [ProbeLimits]
+    /// of a [LimitedAutoSolver] allow, as when a license server does not answer
+    TimedOut,
+}
+
+impl fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SkipReason::Unavailable => f.write_str("not available"),
+            SkipReason::UnavailableInCache(path) => {
+                write!(f, "not available according to {}", path.display())
+            }
+            SkipReason::TimedOut => f.write_str("did not answer in time"),
+        }
+    }
+}
+
+impl fmt::Display for SolverSelection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.solver)?;
+        for (i, skipped) in self.skipped.iter().enumerate() {
+            let separator = if i == 0 { " (skipped " } else { ", " };
+            write!(f, "{}{}: {}", separator, skipped.solver, skipped.reason)?;
+        }
+        if !self.skipped.is_empty() {
+            f.write_str(")")?;
+        }
+        Ok(())
+    }
+}
+
+/// The name of a solver in a [SolverSelection]: its program, or the name of its type
+fn solver_name<S: SolverTrait>(solver: &S) -> Option<String> {
+    let type_name = std::any::type_name::<S>();
+    if type_name == std::any::type_name::<NoSolver>() {
+        return None;
+    }
+    Some(match solver.discovery_key() {
+        Some(key) => key.to_string(),
+        None => {
+            let path = type_name.split('<').next().unwrap_or(type_name);
+            path.rsplit("::").next().unwrap_or(path).to_string()
+        }
+    })
+}
+
+/// Probe the solvers of the list, and choose the first one that is available.
+/// Fails, naming the solvers, when none is.
+pub(crate) fn select_solver<L: SolverList + ?Sized>(
+    solvers: &L,
+    cache: Option<&DiscoveryCache>,
+) -> Result<SolverSelection, SolverError> {
+    let available = solvers.probe_with(cache);
+    let mut names = solvers.solver_names().into_iter();
+    let mut skipped = vec![];
+    for (index, available) in available.into_iter().enumerate() {
+        let solver = match names.next() {
+            Some(Some(name)) => name,
+            _ => continue,
+        };
+        if available {
+            return Ok(SolverSelection {
+                solver,
+                index,
+                skipped,
+            });
+        }
+        let reason = match cache {
+            Some(cache) => SkipReason::UnavailableInCache(cache.path().to_path_buf()),
+            None => SkipReason::Unavailable,
+        };
+        skipped.push(SkippedSolver { solver, reason });
+    }
+    Err(no_solver_available(&skipped))
+}
+
+/// The error when none of the solvers of a list can be used
+fn no_solver_available(skipped: &[SkippedSolver]) -> SolverError {
+    if skipped.is_empty() {
+        return "No solver available".to_string().into();
+    }
+    let names: Vec<&str> = skipped.iter().map(|s| s.solver.as_str()).collect();
+    format!("No solver available, tried: {}", names.join(", ")).into()
+}
+
+/// How often a [CancellationToken] is checked while waiting for probes
+const CANCELLATION_POLL: Duration = Duration::from_millis(10);
+
+/// Probe the solvers of the list, each in its own thread, and choose the first one
+/// that is available, without waiting for the probes longer than the limits allow.
+/// The probes that do not answer in time keep running in the background.
+pub(crate) fn select_solver_within<L>(
+    solvers: &L,
+    limits: &ProbeLimits,
+) -> Result<SolverSelection, SolverError>
+where
+    L: SolverList + Clone + Send + 'static,
+{
+    let deadline = limits.deadline_from(Instant::now());
+    let names = solvers.solver_names();
+    let (sender, receiver) = mpsc::channel();
+    for (index, _) in names.iter().enumerate().filter(|(_, name)| name.is_some()) {
+        let solvers = solvers.clone();
+        let sender = sender.clone();
+        std::thread::spawn(move || {
+            // the receiver is gone when a solver was chosen without waiting for this one
+            let _ = sender.send((index, solvers.probe_at(index)));
+        });
+    }
+    drop(sender);
+
+    let mut available: Vec<Option<bool>> = vec![None; names.len()];
+    let mut skipped = vec![];
+    for (index, name) in names.into_iter().enumerate() {
+        let solver = match name {
+            Some(name) => name,
+            None => continue,
+        };
+        let found = loop {
+            if let Some(found) = available[index] {
+                break Some(found);
+            }
+            if limits.is_cancelled() {
+                return Err("The selection of a solver was cancelled".to_string().into());
+            }
+            let remaining = match deadline {
+                Some(deadline) => match deadline.checked_duration_since(Instant::now()) {
+                    Some(remaining) if !remaining.is_zero() => Some(remaining),
+                    _ => break None,
+                },
+                None => None,
+            };
+            let wait = match (&limits.cancellation, remaining) {
+                (Some(_), Some(remaining)) => remaining.min(CANCELLATION_POLL),
+                (Some(_), None) => CANCELLATION_POLL,
+                (None, Some(remaining)) => remaining,
+                (None, None) => Duration::MAX,
+            };
+            match receiver.recv_timeout(wait) {
+                Ok((probed, found)) => available[probed] = Some(found),
+                Err(RecvTimeoutError::Timeout) => {}
+                // the probe panicked
+                Err(RecvTimeoutError::Disconnected) => break Some(false),
+            }
+        };
+        let reason = match found {
+            Some(true) => {
+                return Ok(SolverSelection {
+                    solver,
+                    index,
+                    skipped,
+                })
+            }
+            Some(false) => SkipReason::Unavailable,
+            None => SkipReason::TimedOut,
+        };
+        skipped.push(SkippedSolver { solver, reason });
+    }
+    Err(no_solver_available(&skipped))
+}
+
+impl SolverList for NoSolver {
+    fn probe_with(&self, _cache: Option<&DiscoveryCache>) -> Vec<bool> {
+        vec![]
+    }
+
+    fn run_at<'a, P: LpProblem<'a>>(
+        &self,
+        _index: usize,
+        problem: &'a P,
+    ) -> Result<Solution, SolverError> {
+        self.run(problem)
+    }
+
+    fn run_file_at(&self, _index: usize, problem_file: &Path) -> Result<Solution, SolverError> {
+        self.run_file(problem_file)
+    }
+
+    fn probe_at(&self, _index: usize) -> bool {
+        false
+    }
+
+    fn solver_names(&self) -> Vec<Option<String>> {
+        vec![]
+    }
+}
+
+impl<S: SolverTrait + Sync, T: SolverList + Sync> SolverList for AutoSolver<S, T> {
+    fn probe_with(&self, cache: Option<&DiscoveryCache>) -> Vec<bool> {
+        std::thread::scope(|scope| {
+            let next = scope.spawn(|| self.1.probe_with(cache));
+            let available = match cache {
+                Some(cache) => cache.discover(&self.0).available,
+                None => self.0.is_available(),
+            };
+            let mut available = vec![available];
+            available.extend(next.join().unwrap_or_default());
+            available
+        })
+    }
+
+    fn probe_at(&self, index: usize) -> bool {
+        match index {
+            0 => self.0.is_available(),
+            _ => self.1.probe_at(index - 1),
+        }
+    }
+
+    fn run_at<'a, P: LpProblem<'a>>(
+        &self,
+        index: usize,
+        problem: &'a P,
+    ) -> Result<Solution, SolverError> {
+        match index {
+            0 => self.0.run(problem),
+            _ => self.1.run_at(index - 1, problem),
+        }
+    }
+
+    fn run_file_at(&self, index: usize, problem_file: &Path) -> Result<Solution, SolverError> {
+        match index {
+            0 => self.0.run_file(problem_file),
+            _ => self.1.run_file_at(index - 1, problem_file),
+        }
+    }
+
+    fn solver_names(&self) -> Vec<Option<String>> {
+        let mut names = vec![solver_name(&self.0)];
+        names.extend(self.1.solver_names());
+        names
+    }
+}
+
+impl ApplyOptions for NoSolver {
+    fn apply_options(&self, _options: &SolverOptions) -> Result<NoSolver, String> {
+        Ok(NoSolver)
+    }
+}
+
+/// The options are applied to every solver of the list,
+/// so they configure whichever one is available
+impl<S: ApplyOptions, T: ApplyOptions> ApplyOptions for AutoSolver<S, T> {
+    fn apply_options(&self, options: &SolverOptions) -> Result<Self, String> {
+        Ok(AutoSolver(
+            self.0.apply_options(options)?,
+            self.1.apply_options(options)?,
+        ))
+    }
+}
+
+/// A tiny problem solved to check that a solver works
+pub(crate) fn probe_problem() -> Problem {
+    Problem {
+        name: "dummy".to_string(),
+        sense: LpObjective::Minimize,
+        objective: StrExpression("x".to_string()),
+        variables: vec![Variable {
+            name: "x".to_string(),
+            is_integer: false,
+            lower_bound: 0.0,
+            upper_bound: 1.0,
+        }],
+        constraints: vec![],
     }
 }
 
@@ -51,28 +424,163 @@ impl<SOLVER: Default, NEXT: Default> AutoSolver<SOLVER, NEXT> {
     }
 }
 
-impl<S: SolverTrait, T: SolverTrait> SolverTrait for AutoSolver<S, T> {
-    fn run<'a, P: LpProblem<'a>>(&self, problem: &'a P) -> Result<Solution, String> {
-        // Try solving a dummy problem (to avoid writing a large problem to disk if not necessary)
-        let works = self
-            .0
-            .run(&Problem {
-                name: "dummy".to_string(),
-                sense: LpObjective::Minimize,
-                objective: StrExpression("x".to_string()),
-                variables: vec![Variable {
-                    name: "x".to_string(),
-                    is_integer: false,
-                    lower_bound: 0.0,
-                    upper_bound: 1.0,
-                }],
-                constraints: vec![],
-            })
-            .is_ok();
-        if works {
-            self.0.run(problem)
-        } else {
-            self.1.run(problem)
+impl<SOLVER, NEXT> AutoSolver<SOLVER, NEXT> {
+    /// Remember which solvers are available in the given cache, instead of checking every time
+    pub fn with_discovery_cache(self, cache: DiscoveryCache) -> CachedAutoSolver<Self> {
+        CachedAutoSolver {
+            solvers: self,
+            cache,
+        }
+    }
+
+    /// Stop waiting for solvers to be probed when the given limits are reached,
+    /// and use the first available solver among the ones that answered in time
+    pub fn with_probe_limits(self, limits: ProbeLimits) -> LimitedAutoSolver<Self> {
+        LimitedAutoSolver {
+            solvers: self,
+            limits,
+        }
+    }
+}
+
+/// Lets the selection of a solver by a [LimitedAutoSolver] be stopped from another thread.
+/// Clones share the same state.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// A token that is not cancelled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel the selections that use this token, and the ones that will
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst)
+    }
+
+    /// Whether [CancellationToken::cancel] was called
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// How long a [LimitedAutoSolver] waits for its solvers to be probed
+#[derive(Debug, Clone, Default)]
+pub struct ProbeLimits {
+    timeout: Option<Duration>,
+    deadline: Option<Instant>,
+    cancellation: Option<CancellationToken>,
+}
+
+impl ProbeLimits {
+    /// Wait for the probes as long as they take
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Skip the solvers whose probe takes longer than the given duration
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Skip the solvers whose probe ends after the given instant
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Fail as soon as the given token is cancelled
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// The instant after which the probes that started at `start` are abandoned
+    fn deadline_from(&self, start: Instant) -> Option<Instant> {
+        let timeout = self.timeout.and_then(|t| start.checked_add(t));
+        match (timeout, self.deadline) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
         }
     }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancellation
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled)
+    }
+}
+
+/// A list of solvers, like [AutoSolver], that does not wait longer than its [ProbeLimits]
+/// to find out which solvers are available, so that a solver whose license server does not
+/// answer does not delay the fallback to the next ones.
+///
+/// Each solver is probed in its own thread. The probes that do not answer in time are
+/// skipped, with [SkipReason::TimedOut], and keep running in the background until the
+/// solver exits. The limits do not apply to the solve itself.
+///
+/// ```
+/// use std::time::Duration;
+/// use lp_solvers::solvers::{AllSolvers, CancellationToken, ProbeLimits};
+///
+/// let token = CancellationToken::new();
+/// let solver = AllSolvers::new().with_probe_limits(
+///     ProbeLimits::new()
+///         .with_timeout(Duration::from_secs(5))
+///         .with_cancellation(token.clone()),
+/// );
+/// // from another thread, when the user gives up
+/// token.cancel();
+/// ```
+#[derive(Debug, Clone)]
+pub struct LimitedAutoSolver<L> {
+    /// The solvers to try, in order
+    pub solvers: L,
+    /// How long to wait for the solvers to be probed
+    pub limits: ProbeLimits,
+}
+
+impl<L: SolverList + Clone + Send + 'static> SolverTrait for LimitedAutoSolver<L> {
+    fn run<'a, P: LpProblem<'a>>(&self, problem: &'a P) -> Result<Solution, SolverError> {
+        let selection = select_solver_within(&self.solvers, &self.limits)?;
+        let mut solution = self.solvers.run_at(selection.index, problem)?;
+        solution.solver_selection = Some(selection);
+        Ok(solution)
+    }
+
+    fn run_file(&self, problem_file: &Path) -> Result<Solution, SolverError> {
+        let selection = select_solver_within(&self.solvers, &self.limits)?;
+        let mut solution = self.solvers.run_file_at(selection.index, problem_file)?;
+        solution.solver_selection = Some(selection);
+        Ok(solution)
+    }
+
+    fn is_available(&self) -> bool {
+        select_solver_within(&self.solvers, &self.limits).is_ok()
+    }
+}
+
+/// All the solvers are probed concurrently (see [SolverTrait::is_available]),
+/// then the problem is given to the first one that is available.
+/// The solution records which one it was in [Solution::solver_selection].
+impl<S: SolverTrait + Sync, T: SolverList + Sync> SolverTrait for AutoSolver<S, T> {
+    fn run<'a, P: LpProblem<'a>>(&self, problem: &'a P) -> Result<Solution, SolverError> {
+        let selection = select_solver(self, None)?;
+        let mut solution = self.run_at(selection.index, problem)?;
+        solution.solver_selection = Some(selection);
+        Ok(solution)
+    }
+
+    fn run_file(&self, problem_file: &Path) -> Result<Solution, SolverError> {
+        let selection = select_solver(self, None)?;
+        let mut solution = self.run_file_at(selection.index, problem_file)?;
+        solution.solver_selection = Some(selection);
+        Ok(solution)
+    }
+
+    fn is_available(&self) -> bool {
+        self.probe().contains(&true)
+    }
 }
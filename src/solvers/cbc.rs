@@ -1,15 +1,20 @@
 //! The coin-or cbc solver.
 //! [https://github.com/coin-or/Cbc#cbc]
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::ffi::OsString;
 use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::{self, BufRead, BufReader, Write};
 use std::path::{Path, PathBuf};
 
 use crate::lp_format::*;
+#[cfg(feature = "memmap")]
+use crate::solvers::map_solution_file;
 use crate::solvers::{
-    Solution, SolverProgram, SolverWithSolutionParsing, Status, WithMaxSeconds, WithMipGap,
-    WithNbThreads,
+    open_solution_file, ApplyOptions, MissingVariables, ParseError, ParserMode, Solution,
+    SolutionValue, SolverCapabilities, SolverOptions, SolverParams, SolverProgram,
+    SolverWithSolutionParsing, Status, StdoutStatusRules, VariableFilter, WithMaxSeconds,
+    WithMipGap, WithMipStart, WithMissingVariables, WithNbThreads, WithParserMode, WithPresolve,
+    WithStatusRules,
 };
 
 /// The coin-or cbc solver
@@ -21,6 +26,15 @@ pub struct CbcSolver {
     threads: Option<u32>,
     seconds: Option<u32>,
     mipgap: Option<f32>,
+    presolve: Option<bool>,
+    parser_mode: ParserMode,
+    missing_variables: MissingVariables,
+    mip_start: Option<PathBuf>,
+    seed: Option<u32>,
+    log_level: Option<u32>,
+    options: CbcOptions,
+    params: SolverParams,
+    status_rules: StdoutStatusRules,
 }
 
 impl Default for CbcSolver {
@@ -39,6 +53,15 @@ impl CbcSolver {
             threads: None,
             seconds: None,
             mipgap: None,
+            presolve: None,
+            parser_mode: ParserMode::Strict,
+            missing_variables: MissingVariables::default(),
+            mip_start: None,
+            seed: None,
+            log_level: None,
+            options: CbcOptions::default(),
+            params: SolverParams::new(),
+            status_rules: StdoutStatusRules::new(),
         }
     }
 
@@ -51,10 +74,21 @@ impl CbcSolver {
             threads: self.threads,
             seconds: self.seconds,
             mipgap: self.mipgap,
+            presolve: self.presolve,
+            parser_mode: self.parser_mode,
+            missing_variables: self.missing_variables,
+            mip_start: self.mip_start.clone(),
+            seed: self.seed,
+            log_level: self.log_level,
+            options: self.options.clone(),
+            params: self.params.clone(),
+            status_rules: self.status_rules.clone(),
         }
     }
 
-    /// Set the temporary solution file to use
+    /// Set the temporary solution file to use.
+    /// The path can contain [RUN_ID_PLACEHOLDER](crate::solvers::RUN_ID_PLACEHOLDER), so that
+    /// solves running at the same time do not write to the same file.
     pub fn with_temp_solution_file(&self, temp_solution_file: String) -> CbcSolver {
         CbcSolver {
             name: self.name.clone(),
@@ -63,6 +97,277 @@ impl CbcSolver {
             threads: self.threads,
             seconds: self.seconds,
             mipgap: self.mipgap,
+            presolve: self.presolve,
+            parser_mode: self.parser_mode,
+            missing_variables: self.missing_variables,
+            mip_start: self.mip_start.clone(),
+            seed: self.seed,
+            log_level: self.log_level,
+            options: self.options.clone(),
+            params: self.params.clone(),
+            status_rules: self.status_rules.clone(),
+        }
+    }
+
+    /// Set the options of cbc that other solvers do not have.
+    /// Fails if one of them is out of the range cbc accepts.
+    ///
+    /// ```
+    /// use lp_solvers::solvers::{CbcOptions, CbcPreprocess, CbcSolver, CbcStrategy};
+    ///
+    /// let options = CbcOptions {
+    ///     strategy: Some(CbcStrategy::Aggressive),
+    ///     preprocess: Some(CbcPreprocess::Sos),
+    ///     max_solutions: Some(1),
+    ///     ..CbcOptions::default()
+    /// };
+    /// let solver = CbcSolver::new().with_cbc_options(options).unwrap();
+    /// assert!(CbcSolver::new().with_cbc_options(CbcOptions {
+    ///     max_solutions: Some(0),
+    ///     ..CbcOptions::default()
+    /// }).is_err());
+    /// ```
+    pub fn with_cbc_options(&self, options: CbcOptions) -> Result<CbcSolver, String> {
+        options.validate()?;
+        Ok(CbcSolver {
+            options,
+            ..(*self).clone()
+        })
+    }
+
+    /// The options of cbc that other solvers do not have
+    pub fn cbc_options(&self) -> &CbcOptions {
+        &self.options
+    }
+
+    /// Give other parameters to cbc, by name, such as `dextra3` to `dextra5`.
+    /// They are passed after the [options](Self::with_cbc_options), before solving,
+    /// without being checked.
+    ///
+    /// ```
+    /// use lp_solvers::solvers::{CbcSolver, SolverParams};
+    ///
+    /// let solver = CbcSolver::new().with_params(&SolverParams::new().with_param("dextra3", 0.5));
+    /// ```
+    pub fn with_params(&self, params: &SolverParams) -> CbcSolver {
+        CbcSolver {
+            params: params.clone(),
+            ..(*self).clone()
+        }
+    }
+}
+
+/// Options of the cbc command line that other solvers do not have.
+/// Options that are not set keep the defaults of cbc.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct CbcOptions {
+    /// How much work cbc puts into cuts and heuristics (`strategy`)
+    pub strategy: Option<CbcStrategy>,
+    /// The number of variables considered for strong branching, from 0 to 999999
+    /// (`strongBranching`)
+    pub strong_branching: Option<u32>,
+    /// The integer preprocessing of the problem (`preprocess`)
+    pub preprocess: Option<CbcPreprocess>,
+    /// The pivot choice of the dual simplex (`dualPivot`)
+    pub dual_pivot: Option<CbcDualPivot>,
+    /// The pivot choice of the primal simplex (`primalPivot`)
+    pub primal_pivot: Option<CbcPrimalPivot>,
+    /// Stop after finding this many feasible solutions, at least 1 (`maxSolutions`)
+    pub max_solutions: Option<u32>,
+    /// How the threads of cbc share the search, when the
+    /// [number of threads](crate::solvers::WithNbThreads) is set (`threads`)
+    pub parallel_mode: Option<CbcParallelMode>,
+}
+
+impl CbcOptions {
+    /// Check that the values are in the ranges cbc accepts
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(n) = self.strong_branching {
+            if n > 999_999 {
+                return Err(format!(
+                    "Invalid strong branching: {} is not between 0 and 999999",
+                    n
+                ));
+            }
+        }
+        if self.max_solutions == Some(0) {
+            return Err("Invalid max solutions: must be at least 1".to_string());
+        }
+        Ok(())
+    }
+
+    /// The command line arguments, as cbc parameter names followed by their values
+    fn arguments(&self) -> Vec<OsString> {
+        let parameters = [
+            ("strategy", self.strategy.map(|s| s.value().to_string())),
+            (
+                "strongBranching",
+                self.strong_branching.map(|n| n.to_string()),
+            ),
+            ("preprocess", self.preprocess.map(|p| p.value().to_string())),
+            ("dualPivot", self.dual_pivot.map(|p| p.value().to_string())),
+            (
+                "primalPivot",
+                self.primal_pivot.map(|p| p.value().to_string()),
+            ),
+            ("maxSolutions", self.max_solutions.map(|n| n.to_string())),
+        ];
+        let mut args = vec![];
+        for (name, value) in parameters.iter() {
+            if let Some(value) = value {
+                args.push(name.into());
+                args.push(value.into());
+            }
+        }
+        args
+    }
+}
+
+/// The strategy of cbc, see [CbcOptions::strategy]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum CbcStrategy {
+    /// Few cuts and heuristics (0)
+    Simple,
+    /// The default strategy (1)
+    Default,
+    /// More cuts and heuristics (2)
+    Aggressive,
+}
+
+impl CbcStrategy {
+    /// The value of the `strategy` parameter of cbc
+    pub fn value(&self) -> &'static str {
+        match self {
+            CbcStrategy::Simple => "0",
+            CbcStrategy::Default => "1",
+            CbcStrategy::Aggressive => "2",
+        }
+    }
+}
+
+/// How the threads of cbc share the search, see [CbcOptions::parallel_mode]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum CbcParallelMode {
+    /// The threads explore the tree as fast as they can: two solves of the same problem
+    /// may find different solutions. The default of cbc
+    Opportunistic,
+    /// The threads synchronize, so that solving the same problem again gives the same
+    /// solution, maybe more slowly
+    Deterministic,
+}
+
+impl CbcParallelMode {
+    /// The value of the `threads` parameter of cbc, to run the given number of threads
+    /// in this mode: cbc runs threads deterministically when 100 is added to their number
+    pub fn threads_value(&self, threads: u32) -> u32 {
+        match self {
+            CbcParallelMode::Opportunistic => threads,
+            CbcParallelMode::Deterministic => threads + 100,
+        }
+    }
+}
+
+/// The integer preprocessing of cbc, see [CbcOptions::preprocess]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum CbcPreprocess {
+    /// No preprocessing
+    Off,
+    /// The default preprocessing
+    On,
+    /// Also find special ordered sets
+    Sos,
+    /// Find special ordered sets, and only keep them if they look useful
+    TrySos,
+    /// Turn inequalities into equalities where possible
+    Equal,
+    /// Turn all the inequalities into equalities where possible
+    EqualAll,
+    /// Also aggregate variables
+    Aggregate,
+}
+
+impl CbcPreprocess {
+    /// The value of the `preprocess` parameter of cbc
+    pub fn value(&self) -> &'static str {
+        match self {
+            CbcPreprocess::Off => "off",
+            CbcPreprocess::On => "on",
+            CbcPreprocess::Sos => "sos",
+            CbcPreprocess::TrySos => "trysos",
+            CbcPreprocess::Equal => "equal",
+            CbcPreprocess::EqualAll => "equalall",
+            CbcPreprocess::Aggregate => "aggregate",
+        }
+    }
+}
+
+/// The pivot choice of the dual simplex of cbc, see [CbcOptions::dual_pivot]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum CbcDualPivot {
+    /// Let cbc choose
+    Automatic,
+    /// Dantzig's rule, the largest infeasibility
+    Dantzig,
+    /// Steepest edge on a part of the rows
+    Partial,
+    /// Steepest edge
+    Steepest,
+}
+
+impl CbcDualPivot {
+    /// The value of the `dualPivot` parameter of cbc
+    pub fn value(&self) -> &'static str {
+        match self {
+            CbcDualPivot::Automatic => "automatic",
+            CbcDualPivot::Dantzig => "dantzig",
+            CbcDualPivot::Partial => "partial",
+            CbcDualPivot::Steepest => "steepest",
+        }
+    }
+}
+
+/// The pivot choice of the primal simplex of cbc, see [CbcOptions::primal_pivot]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum CbcPrimalPivot {
+    /// Let cbc choose
+    Automatic,
+    /// Exact devex
+    Exact,
+    /// Dantzig's rule, the most negative reduced cost
+    Dantzig,
+    /// Steepest edge on a part of the columns
+    Partial,
+    /// Steepest edge
+    Steepest,
+    /// Start with devex, and switch to steepest edge
+    Change,
+    /// Solve a sequence of smaller problems
+    Sprint,
+}
+
+impl CbcPrimalPivot {
+    /// The value of the `primalPivot` parameter of cbc
+    pub fn value(&self) -> &'static str {
+        match self {
+            CbcPrimalPivot::Automatic => "automatic",
+            CbcPrimalPivot::Exact => "exact",
+            CbcPrimalPivot::Dantzig => "dantzig",
+            CbcPrimalPivot::Partial => "partial",
+            CbcPrimalPivot::Steepest => "steepest",
+            CbcPrimalPivot::Change => "change",
+            CbcPrimalPivot::Sprint => "sprint",
         }
     }
 }
@@ -73,66 +378,244 @@ impl SolverWithSolutionParsing for CbcSolver {
         f: &File,
         problem: Option<&'a P>,
     ) -> Result<Solution, String> {
-        let mut vars_value: HashMap<String, _> = HashMap::new();
+        self.read_filtered(f, problem, VariableFilter::default())
+    }
+
+    fn read_solution_filtered<'a, P: LpProblem<'a>>(
+        &self,
+        temp_solution_file: &Path,
+        problem: Option<&'a P>,
+        variables: &HashSet<String>,
+    ) -> Result<Solution, String> {
+        let f = open_solution_file(temp_solution_file)?;
+        self.read_filtered(&f, problem, VariableFilter::only(variables))
+    }
+}
+
+impl CbcSolver {
+    fn read_filtered<'a, P: LpProblem<'a>>(
+        &self,
+        f: &File,
+        problem: Option<&'a P>,
+        filter: VariableFilter,
+    ) -> Result<Solution, String> {
+        let variables_len = problem.map(|p| p.variables().size_hint().0);
+        #[cfg(feature = "memmap")]
+        let mut solution = read_solution_bytes(
+            &map_solution_file(f)?,
+            self.parser_mode,
+            variables_len.unwrap_or(0),
+            filter,
+        )?;
+        #[cfg(not(feature = "memmap"))]
+        let mut solution = read_solution(
+            BufReader::new(f),
+            self.parser_mode,
+            variables_len.unwrap_or(0),
+            filter,
+        )?;
 
-        // populate default values for all vars
         // CBC keeps only non-zero values from a number of variables
-        if let Some(p) = problem {
-            for var in p.variables() {
-                vars_value.insert(var.name().to_string(), 0.0);
-            }
-        }
+        self.missing_variables
+            .apply(&mut solution, problem, filter)?;
+        Ok(solution)
+    }
+}
+
+/// Parse a solution file written by cbc.
+///
+/// Cbc omits some variables whose value is zero:
+/// they are absent from the results of the returned solution.
+pub fn parse_cbc_solution(reader: impl BufRead, mode: ParserMode) -> Result<Solution, ParseError> {
+    read_solution(reader, mode, 0, VariableFilter::default())
+}
+
+fn read_solution(
+    mut reader: impl BufRead,
+    mode: ParserMode,
+    variables_len: usize,
+    filter: VariableFilter,
+) -> Result<Solution, ParseError> {
+    let mut vars_value: HashMap<String, _> = HashMap::with_capacity(filter.capacity(variables_len));
+    let mut buffer = String::new();
+    let _ = reader.read_line(&mut buffer);
 
-        let mut file = BufReader::new(f);
-        let mut buffer = String::new();
-        let _ = file.read_line(&mut buffer);
-
-        let mut buffer_split = buffer.split_whitespace();
-
-        let status = if let Some(status) = buffer_split.next() {
-            match status {
-                "Optimal" => {
-                    if let Some(substatus) = buffer_split.next() {
-                        match substatus {
-                            // MIP gap stops are "Optimal (within gap tolerance)"
-                            "(within" => Status::SubOptimal,
-                            _ => Status::Optimal,
-                        }
-                    } else {
-                        Status::Optimal
-                    }
+    let status = parse_status(&buffer)?;
+    let mut warnings = vec![];
+    for (idx, line) in reader.lines().enumerate() {
+        let l = line.map_err(|e| format!("Unable to read solution: {}", e))?;
+        match parse_line(&l) {
+            Ok((name, n)) => {
+                if filter.keeps(name) {
+                    vars_value.insert(name.to_string(), n);
                 }
-                // Infeasible status is either "Infeasible" or "Integer infeasible"
-                "Infeasible" | "Integer" => Status::Infeasible,
-                "Unbounded" => Status::Unbounded,
-                // "Stopped" can be "on time", "on iterations", "on difficulties" or "on ctrl-c"
-                "Stopped" => Status::SubOptimal,
-                _ => Status::NotSolved,
             }
-        } else {
-            return Err("Incorrect solution format".to_string());
-        };
-        for line in file.lines() {
-            let l = line.unwrap();
-            let mut result_line: Vec<_> = l.split_whitespace().collect();
-            if result_line[0] == "**" {
-                result_line.remove(0);
-            };
-            if result_line.len() == 4 {
-                match result_line[2].parse::<f32>() {
-                    Ok(n) => {
-                        vars_value.insert(result_line[1].to_string(), n);
-                    }
-                    Err(e) => return Err(e.to_string()),
+            // the first line is the status
+            Err(e) => mode.malformed(Some(idx + 2), e, &mut warnings)?,
+        }
+    }
+    let mut solution = Solution::new(status, vars_value);
+    solution.warnings = warnings;
+    Ok(solution)
+}
+
+/// Parse a solution file written by cbc, already in memory,
+/// without allocating a string for each line.
+///
+/// Cbc omits some variables whose value is zero:
+/// they are absent from the results of the returned solution.
+pub fn parse_cbc_solution_bytes(bytes: &[u8], mode: ParserMode) -> Result<Solution, ParseError> {
+    read_solution_bytes(bytes, mode, 0, VariableFilter::default())
+}
+
+fn read_solution_bytes(
+    bytes: &[u8],
+    mode: ParserMode,
+    variables_len: usize,
+    filter: VariableFilter,
+) -> Result<Solution, ParseError> {
+    let bytes = bytes.strip_suffix(b"\n").unwrap_or(bytes);
+    let mut lines = bytes.split(|&b| b == b'\n');
+    let status = parse_status(&String::from_utf8_lossy(lines.next().unwrap_or_default()))?;
+    let mut vars_value: HashMap<String, _> = HashMap::with_capacity(filter.capacity(variables_len));
+    let mut warnings = vec![];
+    for (idx, line) in lines.enumerate() {
+        match parse_line_bytes(line) {
+            Ok((name, n)) => {
+                if filter.keeps(name) {
+                    vars_value.insert(name.to_string(), n);
                 }
-            } else {
-                return Err("Incorrect solution format".to_string());
             }
+            // the first line is the status
+            Err(e) => mode.malformed(Some(idx + 2), e, &mut warnings)?,
+        }
+    }
+    let mut solution = Solution::new(status, vars_value);
+    solution.warnings = warnings;
+    Ok(solution)
+}
+
+/// Write a solution in the format of cbc solution files, which [parse_cbc_solution] reads.
+///
+/// [Status::InfeasibleOrUnbounded], which cbc does not report, is written as infeasible.
+pub fn write_cbc_solution<W: Write>(solution: &Solution, mut writer: W) -> io::Result<()> {
+    let status = match solution.status {
+        Status::Optimal => "Optimal",
+        Status::SubOptimal => "Stopped on time",
+        Status::Infeasible | Status::InfeasibleOrUnbounded => "Infeasible",
+        Status::Unbounded => "Unbounded",
+        Status::NotSolved => "Not solved",
+    };
+    writeln!(
+        writer,
+        "{} - objective value {}",
+        status,
+        solution.objective.unwrap_or_default()
+    )?;
+    for (idx, (name, value)) in solution.ordered_results().into_iter().enumerate() {
+        writeln!(writer, "{:>7} {} {} 0", idx, name, value)?;
+    }
+    Ok(())
+}
+
+/// The first words of the first line of cbc solution files, and the status they stand for.
+/// The first entry whose words start the line gives the status, see [cbc_solution_status].
+pub const CBC_SOLUTION_STATUSES: &[(&str, Status)] = &[
+    // MIP gap stops are "Optimal (within gap tolerance)"
+    ("Optimal (within", Status::SubOptimal),
+    ("Optimal", Status::Optimal),
+    // infeasible problems are either "Infeasible" or "Integer infeasible"
+    ("Infeasible", Status::Infeasible),
+    ("Integer", Status::Infeasible),
+    ("Unbounded", Status::Unbounded),
+    // "Stopped" can be "on time", "on iterations", "on difficulties" or "on ctrl-c"
+    ("Stopped", Status::SubOptimal),
+];
+
+/// The status given by the first line of a cbc solution file, as [parse_cbc_solution] reads it:
+/// the one of the first entry of [CBC_SOLUTION_STATUSES] whose words start the line,
+/// [Status::NotSolved] for other lines, and `None` for an empty line.
+///
+/// ```
+/// # #[cfg(feature = "cbc")] {
+/// use lp_solvers::solvers::{cbc_solution_status, Status};
+///
+/// let status = cbc_solution_status("Optimal (within gap tolerance) - objective value 3");
+/// assert_eq!(status, Some(Status::SubOptimal));
+/// assert_eq!(cbc_solution_status("Stopped on time - objective value 3"), Some(Status::SubOptimal));
+/// assert_eq!(cbc_solution_status("Something else"), Some(Status::NotSolved));
+/// # }
+/// ```
+pub fn cbc_solution_status(line: &str) -> Option<Status> {
+    line.split_whitespace().next()?;
+    let status = CBC_SOLUTION_STATUSES.iter().find(|(start, _)| {
+        let mut words = line.split_whitespace();
+        start
+            .split_whitespace()
+            .all(|word| words.next() == Some(word))
+    });
+    Some(status.map_or(Status::NotSolved, |(_, status)| status.clone()))
+}
+
+/// Parse the first line of the solution file
+fn parse_status(line: &str) -> Result<Status, ParseError> {
+    cbc_solution_status(line).ok_or_else(|| ParseError::at(1, "Incorrect solution format"))
+}
+
+/// Same as [parse_line], on bytes
+fn parse_line_bytes(line: &[u8]) -> Result<(&str, f32), String> {
+    let mut tokens = line
+        .split(u8::is_ascii_whitespace)
+        .filter(|t| !t.is_empty())
+        .peekable();
+    if tokens.peek() == Some(&&b"**"[..]) {
+        tokens.next();
+    }
+    match (
+        tokens.next(),
+        tokens.next(),
+        tokens.next(),
+        tokens.next(),
+        tokens.next(),
+    ) {
+        (Some(_), Some(name), Some(value), Some(_), None) => {
+            let name = std::str::from_utf8(name).map_err(|e| e.to_string())?;
+            Ok((name, f32::parse_value(value)?))
         }
-        Ok(Solution::new(status, vars_value))
+        _ => Err("Incorrect solution format".to_string()),
+    }
+}
+
+/// Parse a variable line: "index name value reduced_cost", prefixed by "**" when infeasible
+fn parse_line<T: SolutionValue>(line: &str) -> Result<(&str, T), String> {
+    let mut result_line: Vec<_> = line.split_whitespace().collect();
+    if result_line.first() == Some(&"**") {
+        result_line.remove(0);
+    };
+    if result_line.len() == 4 {
+        T::parse_value(result_line[2].as_bytes()).map(|n| (result_line[1], n))
+    } else {
+        Err("Incorrect solution format".to_string())
     }
 }
 
+/// Iterate over the variable values in a cbc solution file, without storing them.
+///
+/// Cbc omits some variables whose value is zero.
+pub fn read_cbc_solution_iter(
+    path: &Path,
+) -> Result<impl Iterator<Item = Result<(String, f64), String>>, String> {
+    let mut reader = BufReader::new(open_solution_file(path)?);
+    // the first line is the status
+    reader
+        .read_line(&mut String::new())
+        .map_err(|e| format!("Unable to read solution: {}", e))?;
+    Ok(reader.lines().map(|line| {
+        let line = line.map_err(|e| format!("Unable to read solution: {}", e))?;
+        parse_line(&line).map(|(name, value)| (name.to_string(), value))
+    }))
+}
+
 impl WithMaxSeconds<CbcSolver> for CbcSolver {
     fn max_seconds(&self) -> Option<u32> {
         self.seconds
@@ -174,6 +657,112 @@ impl WithNbThreads<CbcSolver> for CbcSolver {
     }
 }
 
+impl WithParserMode<CbcSolver> for CbcSolver {
+    fn parser_mode(&self) -> ParserMode {
+        self.parser_mode
+    }
+    fn with_parser_mode(&self, mode: ParserMode) -> CbcSolver {
+        CbcSolver {
+            parser_mode: mode,
+            ..(*self).clone()
+        }
+    }
+}
+
+impl WithMissingVariables<CbcSolver> for CbcSolver {
+    fn missing_variables(&self) -> MissingVariables {
+        self.missing_variables
+    }
+    fn with_missing_variables(&self, policy: MissingVariables) -> CbcSolver {
+        CbcSolver {
+            missing_variables: policy,
+            ..(*self).clone()
+        }
+    }
+}
+
+impl WithPresolve<CbcSolver> for CbcSolver {
+    fn presolve(&self) -> Option<bool> {
+        self.presolve
+    }
+    fn with_presolve(&self, presolve: bool) -> CbcSolver {
+        CbcSolver {
+            presolve: Some(presolve),
+            ..(*self).clone()
+        }
+    }
+}
+
+impl WithMipStart<CbcSolver> for CbcSolver {
+    fn mip_start(&self) -> Option<&Path> {
+        self.mip_start.as_deref()
+    }
+
+    fn with_mip_start(&self, solution_file: PathBuf) -> CbcSolver {
+        CbcSolver {
+            mip_start: Some(solution_file),
+            ..(*self).clone()
+        }
+    }
+
+    fn write_mip_start(&self, solution: &Solution, writer: &mut dyn Write) -> io::Result<()> {
+        write_cbc_solution(solution, writer)
+    }
+}
+
+impl WithStatusRules<CbcSolver> for CbcSolver {
+    fn status_rules(&self) -> &StdoutStatusRules {
+        &self.status_rules
+    }
+
+    fn with_status_rules(&self, rules: StdoutStatusRules) -> CbcSolver {
+        CbcSolver {
+            status_rules: rules,
+            ..(*self).clone()
+        }
+    }
+}
+
+/// The seed and the verbosity are given to cbc as `randomCbcSeed` and `log`
+impl ApplyOptions for CbcSolver {
+    fn apply_options(&self, options: &SolverOptions) -> Result<CbcSolver, String> {
+        let mut solver = self.clone();
+        if let Some(mipgap) = options.mip_gap {
+            solver = solver.with_mip_gap(mipgap)?;
+        }
+        solver.seconds = options.time_limit.or(solver.seconds);
+        solver.threads = options.threads.or(solver.threads);
+        solver.presolve = options.presolve.or(solver.presolve);
+        solver.seed = options.seed.or(solver.seed);
+        solver.log_level = options.verbosity.or(solver.log_level);
+        Ok(solver)
+    }
+}
+
+/// Cbc handles integer variables and special ordered sets,
+/// and can start from a known solution
+impl SolverCapabilities for CbcSolver {
+    fn supports_mip(&self) -> bool {
+        true
+    }
+
+    fn supports_quadratic(&self) -> bool {
+        false
+    }
+
+    fn supports_sos(&self) -> bool {
+        true
+    }
+
+    fn supports_indicators(&self) -> bool {
+        false
+    }
+
+    fn supports_warm_start(&self) -> bool {
+        true
+    }
+}
+
 impl SolverProgram for CbcSolver {
     fn command_name(&self) -> &str {
         &self.command_name
@@ -185,9 +774,15 @@ impl SolverProgram for CbcSolver {
             args.push("ratiogap".into());
             args.push(mipgap.to_string().into());
         }
+        let threads = match self.options.parallel_mode {
+            Some(mode) => self.nb_threads().map(|n| mode.threads_value(n)),
+            None => self.nb_threads(),
+        };
         for (name, value) in [
             ("seconds", self.max_seconds()),
-            ("threads", self.nb_threads()),
+            ("threads", threads),
+            ("randomCbcSeed", self.seed),
+            ("log", self.log_level),
         ]
         .iter()
         {
@@ -196,6 +791,20 @@ impl SolverProgram for CbcSolver {
                 args.push(val.to_string().into());
             }
         }
+        if let Some(presolve) = self.presolve() {
+            args.push("presolve".into());
+            args.push(if presolve { "on" } else { "off" }.into());
+        }
+        // cbc runs its arguments in order: the parameters must come before "solve"
+        args.extend(self.options.arguments());
+        for (name, value) in self.params.iter() {
+            args.push(name.into());
+            args.push(value.into());
+        }
+        if let Some(mip_start) = self.mip_start() {
+            args.push("mips".into());
+            args.push(mip_start.into());
+        }
         args.extend_from_slice(&["solve".into(), "solution".into(), solution_file.into()]);
         args
     }
@@ -203,11 +812,27 @@ impl SolverProgram for CbcSolver {
     fn preferred_temp_solution_file(&self) -> Option<&Path> {
         self.temp_solution_file.as_deref()
     }
+
+    fn parse_stdout_status(&self, stdout: &[u8]) -> Option<Status> {
+        self.status_rules.status(stdout)
+    }
+
+    fn version_arguments(&self) -> Option<&[&str]> {
+        Some(&["-quit"])
+    }
+
+    fn problem_formats(&self) -> &[ProblemFormat] {
+        &[ProblemFormat::Lp, ProblemFormat::FreeMps]
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::solvers::{CbcSolver, SolverProgram, WithMaxSeconds, WithMipGap, WithNbThreads};
+    use crate::solvers::{
+        CbcDualPivot, CbcOptions, CbcParallelMode, CbcPreprocess, CbcPrimalPivot, CbcSolver,
+        CbcStrategy, SolverParams, SolverProgram, WithMaxSeconds, WithMipGap, WithMipStart,
+        WithNbThreads, WithPresolve,
+    };
     use std::ffi::OsString;
     use std::path::Path;
 
@@ -292,6 +917,23 @@ mod tests {
         assert_eq!(args, expected);
     }
 
+    #[test]
+    fn cli_args_presolve() {
+        let solver = CbcSolver::new().with_presolve(false);
+        let args = solver.arguments(Path::new("test.lp"), Path::new("test.sol"));
+
+        let expected: Vec<OsString> = vec![
+            "test.lp".into(),
+            "presolve".into(),
+            "off".into(),
+            "solve".into(),
+            "solution".into(),
+            "test.sol".into(),
+        ];
+
+        assert_eq!(args, expected);
+    }
+
     #[test]
     fn cli_args_multiple() {
         let solver = CbcSolver::new()
@@ -317,4 +959,189 @@ mod tests {
 
         assert_eq!(args, expected);
     }
+
+    #[test]
+    fn cli_args_mip_start() {
+        let solver = CbcSolver::new().with_mip_start("start.sol".into());
+
+        let args = solver.arguments(Path::new("test.lp"), Path::new("test.sol"));
+
+        let expected: Vec<OsString> = vec![
+            "test.lp".into(),
+            "mips".into(),
+            "start.sol".into(),
+            "solve".into(),
+            "solution".into(),
+            "test.sol".into(),
+        ];
+
+        assert_eq!(args, expected);
+    }
+
+    #[test]
+    fn cli_args_deterministic_threads() {
+        let options = CbcOptions {
+            parallel_mode: Some(CbcParallelMode::Deterministic),
+            ..CbcOptions::default()
+        };
+        let solver = CbcSolver::new().with_cbc_options(options).unwrap();
+        let args = solver.arguments(Path::new("test.lp"), Path::new("test.sol"));
+        // without threads, cbc solves with one thread, which is deterministic
+        assert!(!args.contains(&"threads".into()));
+
+        let args = solver
+            .with_nb_threads(4)
+            .with_params(&SolverParams::new().with_param("dextra3", 0.5))
+            .arguments(Path::new("test.lp"), Path::new("test.sol"));
+        let expected: Vec<OsString> = vec![
+            "test.lp".into(),
+            "threads".into(),
+            "104".into(),
+            "dextra3".into(),
+            "0.5".into(),
+            "solve".into(),
+            "solution".into(),
+            "test.sol".into(),
+        ];
+        assert_eq!(args, expected);
+    }
+
+    fn cbc_options_args(options: CbcOptions) -> Vec<OsString> {
+        CbcSolver::new()
+            .with_cbc_options(options)
+            .expect("options should be valid")
+            .arguments(Path::new("test.lp"), Path::new("test.sol"))
+    }
+
+    fn expected_with(parameter: &str, value: &str) -> Vec<OsString> {
+        vec![
+            "test.lp".into(),
+            parameter.into(),
+            value.into(),
+            "solve".into(),
+            "solution".into(),
+            "test.sol".into(),
+        ]
+    }
+
+    #[test]
+    fn cli_args_strategy() {
+        let args = cbc_options_args(CbcOptions {
+            strategy: Some(CbcStrategy::Aggressive),
+            ..CbcOptions::default()
+        });
+        assert_eq!(args, expected_with("strategy", "2"));
+        let args = cbc_options_args(CbcOptions {
+            strategy: Some(CbcStrategy::Simple),
+            ..CbcOptions::default()
+        });
+        assert_eq!(args, expected_with("strategy", "0"));
+    }
+
+    #[test]
+    fn cli_args_strong_branching() {
+        let args = cbc_options_args(CbcOptions {
+            strong_branching: Some(10),
+            ..CbcOptions::default()
+        });
+        assert_eq!(args, expected_with("strongBranching", "10"));
+    }
+
+    #[test]
+    fn cli_args_strong_branching_too_large() {
+        let solver = CbcSolver::new().with_cbc_options(CbcOptions {
+            strong_branching: Some(1_000_000),
+            ..CbcOptions::default()
+        });
+        assert!(solver.is_err());
+    }
+
+    #[test]
+    fn cli_args_preprocess() {
+        let args = cbc_options_args(CbcOptions {
+            preprocess: Some(CbcPreprocess::TrySos),
+            ..CbcOptions::default()
+        });
+        assert_eq!(args, expected_with("preprocess", "trysos"));
+    }
+
+    #[test]
+    fn cli_args_dual_pivot() {
+        let args = cbc_options_args(CbcOptions {
+            dual_pivot: Some(CbcDualPivot::Steepest),
+            ..CbcOptions::default()
+        });
+        assert_eq!(args, expected_with("dualPivot", "steepest"));
+    }
+
+    #[test]
+    fn cli_args_primal_pivot() {
+        let args = cbc_options_args(CbcOptions {
+            primal_pivot: Some(CbcPrimalPivot::Sprint),
+            ..CbcOptions::default()
+        });
+        assert_eq!(args, expected_with("primalPivot", "sprint"));
+    }
+
+    #[test]
+    fn cli_args_max_solutions() {
+        let args = cbc_options_args(CbcOptions {
+            max_solutions: Some(1),
+            ..CbcOptions::default()
+        });
+        assert_eq!(args, expected_with("maxSolutions", "1"));
+    }
+
+    #[test]
+    fn cli_args_max_solutions_zero() {
+        let solver = CbcSolver::new().with_cbc_options(CbcOptions {
+            max_solutions: Some(0),
+            ..CbcOptions::default()
+        });
+        assert!(solver.is_err());
+    }
+
+    #[test]
+    fn cli_args_cbc_options_order() {
+        let solver = CbcSolver::new()
+            .with_presolve(true)
+            .with_mip_start("start.sol".into())
+            .with_cbc_options(CbcOptions {
+                strategy: Some(CbcStrategy::Default),
+                strong_branching: Some(5),
+                preprocess: Some(CbcPreprocess::Off),
+                dual_pivot: Some(CbcDualPivot::Dantzig),
+                primal_pivot: Some(CbcPrimalPivot::Exact),
+                max_solutions: Some(3),
+                parallel_mode: Some(CbcParallelMode::Opportunistic),
+            })
+            .expect("options should be valid");
+
+        let args = solver.arguments(Path::new("test.lp"), Path::new("test.sol"));
+
+        let expected: Vec<OsString> = vec![
+            "test.lp".into(),
+            "presolve".into(),
+            "on".into(),
+            "strategy".into(),
+            "1".into(),
+            "strongBranching".into(),
+            "5".into(),
+            "preprocess".into(),
+            "off".into(),
+            "dualPivot".into(),
+            "dantzig".into(),
+            "primalPivot".into(),
+            "exact".into(),
+            "maxSolutions".into(),
+            "3".into(),
+            "mips".into(),
+            "start.sol".into(),
+            "solve".into(),
+            "solution".into(),
+            "test.sol".into(),
+        ];
+
+        assert_eq!(args, expected);
+    }
 }
@@ -0,0 +1,378 @@
+//! Solvers built from a configuration document, so that deployments can change
+//! the solver, its path and its parameters without changing the code.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::lp_format::LpProblem;
+#[cfg(feature = "cbc")]
+use crate::solvers::CbcSolver;
+#[cfg(feature = "cplex")]
+use crate::solvers::Cplex;
+#[cfg(feature = "glpk")]
+use crate::solvers::GlpkSolver;
+#[cfg(any(
+    feature = "cbc",
+    feature = "cplex",
+    feature = "glpk",
+    feature = "gurobi"
+))]
+use crate::solvers::{auto::probe_problem, run_file_program, run_program, ApplyOptions};
+#[cfg(feature = "gurobi")]
+use crate::solvers::{GurobiSolver, SolverParams};
+use crate::solvers::{
+    NoSolver, PreparedSolve, Solution, SolverError, SolverHooks, SolverOptions, SolverTrait,
+    TempFilePool,
+};
+
+/// The environment variable that, when set, replaces the solver named by the configuration
+/// given to [from_config]
+pub const BACKEND_ENV_VAR: &str = "LP_SOLVERS_BACKEND";
+
+/// The configuration of a solver, read by [SolverConfig::parse] from a JSON document,
+/// or a TOML document with the `toml` feature.
+/// The options of [SolverOptions] are given at the top level.
+///
+/// ```toml
+/// solver = "gurobi"
+/// command = "/opt/gurobi/bin/gurobi_cl"
+/// time_limit = 60
+/// threads = 4
+/// temp_dir = "/scratch/lp"
+///
+/// [params]
+/// MIPFocus = 1
+///
+/// [env]
+/// GRB_LICENSE_FILE = "/etc/gurobi/gurobi.lic"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SolverConfig {
+    /// The solver: "cbc", "glpk", "gurobi", "cplex", or "auto" (the default) for the first
+    /// one that is installed, in the order of [AllSolvers](crate::solvers::AllSolvers)
+    pub solver: Option<String>,
+    /// The path of the solver program. Not used by "auto".
+    pub command: Option<String>,
+    /// The options understood by all the solvers
+    #[serde(flatten)]
+    pub options: SolverOptions,
+    /// Native gurobi parameters, such as `MIPFocus`. The other solvers ignore them.
+    pub params: BTreeMap<String, serde_json::Value>,
+    /// Where the problem and solution files are written,
+    /// instead of the temporary directory of the system
+    pub temp_dir: Option<PathBuf>,
+    /// Environment variables of the solver program, such as the location of its license
+    pub env: BTreeMap<String, String>,
+}
+
+impl SolverConfig {
+    /// Read a configuration from a JSON document (starting with `{`), or a TOML document
+    pub fn parse(text: &str) -> Result<Self, String> {
+        if text.trim_start().starts_with('{') {
+            serde_json::from_str(text).map_err(|e| format!("Invalid solver configuration: {}", e))
+        } else {
+            parse_toml(text)
+        }
+    }
+
+    /// Build the configured solver
+    pub fn build(&self) -> Result<ConfiguredSolver, String> {
+        let name = self.solver.as_deref().unwrap_or("auto");
+        let backend = match name {
+            "auto" => {
+                let names: &[&str] = &[
+                    #[cfg(feature = "gurobi")]
+                    "gurobi",
+                    #[cfg(feature = "cplex")]
+                    "cplex",
+                    #[cfg(feature = "cbc")]
+                    "cbc",
+                    #[cfg(feature = "glpk")]
+                    "glpk",
+                ];
+                let backends = names.iter().map(|name| self.backend(name, None));
+                Backend::Auto(backends.collect::<Result<_, _>>()?)
+            }
+            _ => self.backend(name, self.command.clone())?,
+        };
+        Ok(ConfiguredSolver {
+            name: name.to_string(),
+            backend,
+            env: Environment(self.env.clone()),
+            pool: self.temp_dir.as_ref().map(TempFilePool::in_dir),
+        })
+    }
+
+    // without any solver, every name is unknown
+    #[cfg_attr(
+        not(any(
+            feature = "cbc",
+            feature = "cplex",
+            feature = "glpk",
+            feature = "gurobi"
+        )),
+        allow(unused_variables)
+    )]
+    fn backend(&self, name: &str, command: Option<String>) -> Result<Backend, String> {
+        let options = &self.options;
+        match name {
+            #[cfg(feature = "cbc")]
+            "cbc" => {
+                let solver = match command {
+                    Some(command) => CbcSolver::new().command_name(command),
+                    None => CbcSolver::new(),
+                };
+                Ok(Backend::Cbc(solver.apply_options(options)?))
+            }
+            #[cfg(feature = "glpk")]
+            "glpk" => {
+                let solver = match command {
+                    Some(command) => GlpkSolver::new().command_name(command),
+                    None => GlpkSolver::new(),
+                };
+                Ok(Backend::Glpk(solver.apply_options(options)?))
+            }
+            #[cfg(feature = "gurobi")]
+            "gurobi" => {
+                let solver = match command {
+                    Some(command) => GurobiSolver::new().command_name(command),
+                    None => GurobiSolver::new(),
+                };
+                let mut params = SolverParams::new();
+                for (name, value) in &self.params {
+                    let value = match value {
+                        serde_json::Value::String(value) => value.clone(),
+                        value => value.to_string(),
+                    };
+                    params = params.with_param(name.as_str(), value);
+                }
+                Ok(Backend::Gurobi(
+                    solver.with_params(&params).apply_options(options)?,
+                ))
+            }
+            #[cfg(feature = "cplex")]
+            "cplex" => {
+                let solver = command.map_or_else(Cplex::default, Cplex::with_command);
+                Ok(Backend::Cplex(solver.apply_options(options)?))
+            }
+            // solvers of this crate whose feature is disabled
+            _ if ["cbc", "cplex", "glpk", "gurobi"].contains(&name) => Err(format!(
+                "The {} solver requires the {} feature of lp-solvers",
+                name, name
+            )),
+            _ => Err(format!("Unknown solver {:?}", name)),
+        }
+    }
+}
+
+#[cfg(feature = "toml")]
+fn parse_toml(text: &str) -> Result<SolverConfig, String> {
+    toml::from_str(text).map_err(|e| format!("Invalid solver configuration: {}", e))
+}
+
+#[cfg(not(feature = "toml"))]
+fn parse_toml(_text: &str) -> Result<SolverConfig, String> {
+    Err("Reading a TOML solver configuration requires the toml feature".to_string())
+}
+
+/// Build a solver from a configuration document, see [SolverConfig].
+///
+/// When the `LP_SOLVERS_BACKEND` environment variable is set, it replaces the solver of
+/// the configuration. The command of the configuration is then only used if the variable
+/// names the same solver, since it is the path of another program.
+///
+/// ```
+/// # #[cfg(feature = "cbc")] {
+/// use lp_solvers::solvers::from_config;
+///
+/// let solver = from_config(r#"{"solver": "cbc", "time_limit": 60}"#).unwrap();
+/// // solver.run(&problem)
+/// # }
+/// ```
+pub fn from_config(text: &str) -> Result<ConfiguredSolver, String> {
+    let mut config = SolverConfig::parse(text)?;
+    if let Ok(solver) = std::env::var(BACKEND_ENV_VAR) {
+        if !solver.is_empty() {
+            if config.solver.as_deref() != Some(solver.as_str()) {
+                config.command = None;
+            }
+            config.solver = Some(solver);
+        }
+    }
+    config.build()
+}
+
+/// A solver built by [SolverConfig::build]
+#[derive(Debug)]
+pub struct ConfiguredSolver {
+    name: String,
+    backend: Backend,
+    env: Environment,
+    pool: Option<TempFilePool>,
+}
+
+impl ConfiguredSolver {
+    /// The name of the configured solver, such as "cbc" or "auto"
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl SolverTrait for ConfiguredSolver {
+    fn run<'a, P: LpProblem<'a>>(&self, problem: &'a P) -> Result<Solution, SolverError> {
+        self.backend.run(problem, &self.env, self.pool.as_ref())
+    }
+
+    fn run_file(&self, problem_file: &Path) -> Result<Solution, SolverError> {
+        self.backend
+            .run_file(problem_file, &self.env, self.pool.as_ref())
+    }
+
+    fn is_available(&self) -> bool {
+        self.backend.is_available(&self.env, self.pool.as_ref())
+    }
+}
+
+// built once from the configuration, and not moved afterwards
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone)]
+enum Backend {
+    #[cfg(feature = "cbc")]
+    Cbc(CbcSolver),
+    #[cfg(feature = "glpk")]
+    Glpk(GlpkSolver),
+    #[cfg(feature = "gurobi")]
+    Gurobi(GurobiSolver),
+    #[cfg(feature = "cplex")]
+    Cplex(Cplex),
+    /// The first available solver, in order
+    Auto(Vec<Backend>),
+}
+
+impl Backend {
+    // without any solver, only the automatic backend remains
+    #[cfg_attr(
+        not(any(
+            feature = "cbc",
+            feature = "cplex",
+            feature = "glpk",
+            feature = "gurobi"
+        )),
+        allow(clippy::only_used_in_recursion)
+    )]
+    fn run<'a, P: LpProblem<'a>>(
+        &self,
+        problem: &'a P,
+        env: &Environment,
+        pool: Option<&TempFilePool>,
+    ) -> Result<Solution, SolverError> {
+        match self {
+            #[cfg(feature = "cbc")]
+            Backend::Cbc(solver) => run_program(solver, problem, env, pool),
+            #[cfg(feature = "glpk")]
+            Backend::Glpk(solver) => run_program(solver, problem, env, pool),
+            #[cfg(feature = "gurobi")]
+            Backend::Gurobi(solver) => run_program(solver, problem, env, pool),
+            #[cfg(feature = "cplex")]
+            Backend::Cplex(solver) => run_program(solver, problem, env, pool),
+            Backend::Auto(backends) => match Backend::first_available(backends, env, pool) {
+                Some(backend) => backend.run(problem, env, pool),
+                None => NoSolver.run(problem),
+            },
+        }
+    }
+
+    #[cfg_attr(
+        not(any(
+            feature = "cbc",
+            feature = "cplex",
+            feature = "glpk",
+            feature = "gurobi"
+        )),
+        allow(clippy::only_used_in_recursion)
+    )]
+    fn run_file(
+        &self,
+        problem_file: &Path,
+        env: &Environment,
+        pool: Option<&TempFilePool>,
+    ) -> Result<Solution, SolverError> {
+        match self {
+            #[cfg(feature = "cbc")]
+            Backend::Cbc(solver) => run_file_program(solver, problem_file, env, pool),
+            #[cfg(feature = "glpk")]
+            Backend::Glpk(solver) => run_file_program(solver, problem_file, env, pool),
+            #[cfg(feature = "gurobi")]
+            Backend::Gurobi(solver) => run_file_program(solver, problem_file, env, pool),
+            #[cfg(feature = "cplex")]
+            Backend::Cplex(solver) => run_file_program(solver, problem_file, env, pool),
+            Backend::Auto(backends) => match Backend::first_available(backends, env, pool) {
+                Some(backend) => backend.run_file(problem_file, env, pool),
+                None => NoSolver.run_file(problem_file),
+            },
+        }
+    }
+
+    /// Probe all the solvers at the same time, like AutoSolver, and return the first available
+    fn first_available<'b>(
+        backends: &'b [Backend],
+        env: &Environment,
+        pool: Option<&TempFilePool>,
+    ) -> Option<&'b Backend> {
+        let available: Vec<bool> = std::thread::scope(|scope| {
+            let probes: Vec<_> = backends
+                .iter()
+                .map(|backend| scope.spawn(move || backend.is_available(env, pool)))
+                .collect();
+            probes
+                .into_iter()
+                .map(|probe| probe.join().unwrap_or(false))
+                .collect()
+        });
+        backends
+            .iter()
+            .zip(available)
+            .find(|(_, available)| *available)
+            .map(|(backend, _)| backend)
+    }
+
+    /// Solve a tiny problem the way [Backend::run] would, with the environment of the
+    /// configuration: licenses, such as gurobi's, may only be given there
+    #[cfg_attr(
+        not(any(
+            feature = "cbc",
+            feature = "cplex",
+            feature = "glpk",
+            feature = "gurobi"
+        )),
+        allow(clippy::only_used_in_recursion)
+    )]
+    fn is_available(&self, env: &Environment, pool: Option<&TempFilePool>) -> bool {
+        match self {
+            #[cfg(feature = "cbc")]
+            Backend::Cbc(solver) => run_program(solver, &probe_problem(), env, pool).is_ok(),
+            #[cfg(feature = "glpk")]
+            Backend::Glpk(solver) => run_program(solver, &probe_problem(), env, pool).is_ok(),
+            #[cfg(feature = "gurobi")]
+            Backend::Gurobi(solver) => run_program(solver, &probe_problem(), env, pool).is_ok(),
+            #[cfg(feature = "cplex")]
+            Backend::Cplex(solver) => run_program(solver, &probe_problem(), env, pool).is_ok(),
+            Backend::Auto(backends) => backends
+                .iter()
+                .any(|backend| backend.is_available(env, pool)),
+        }
+    }
+}
+
+/// Environment variables set on the solver program
+#[derive(Debug, Clone, Default)]
+struct Environment(BTreeMap<String, String>);
+
+impl SolverHooks for Environment {
+    fn before_spawn(&self, solve: &mut PreparedSolve<'_>) {
+        solve.command.envs(&self.0);
+    }
+}
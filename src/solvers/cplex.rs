@@ -1,31 +1,69 @@
 //! The IBM CPLEX optimizer.
 //! You need to activate the "cplex" feature of this crate to use this solver.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::ffi::OsString;
 use std::fs::File;
-use std::io::BufReader;
-use std::path::Path;
+use std::io::{self, BufRead, BufReader, Write};
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+#[cfg(feature = "process")]
+use std::process::Command;
+#[cfg(feature = "process")]
+use std::time::Duration;
 
+use quick_xml::escape::escape;
 use quick_xml::events::{BytesStart, Event};
 use quick_xml::Reader;
 
-use crate::lp_format::LpProblem;
-use crate::solvers::{Solution, SolverProgram, SolverWithSolutionParsing, Status, WithMipGap};
-use crate::util::buf_contains;
+use crate::lp_format::{LpProblem, ProblemFormat};
+use crate::solvers::{
+    open_solution_file, ApplyOptions, MissingVariables, ParseError, Solution, SolutionValue,
+    SolverCapabilities, SolverOptions, SolverProgram, SolverWithSolutionParsing, Status,
+    StdoutStatusRules, VariableFilter, WithMaxSeconds, WithMipGap, WithMipStart,
+    WithMissingVariables, WithNbThreads, WithPresolve, WithStatusRules,
+};
+#[cfg(feature = "process")]
+use crate::solvers::{
+    run_license_check, run_tuning_tool, LicenseCheck, LicenseInfo, SolverError, SolverParams,
+    SolverTuning,
+};
+use crate::util::find_line;
 
 /// IBM cplex optimizer
 #[derive(Debug, Clone)]
 pub struct Cplex {
     command: String,
+    seconds: Option<u32>,
+    threads: Option<u32>,
+    seed: Option<u32>,
     mipgap: Option<f32>,
+    presolve: Option<bool>,
+    params_file: Option<PathBuf>,
+    mip_start: Option<PathBuf>,
+    log_file: Option<PathBuf>,
+    conflict_file: Option<PathBuf>,
+    populate: Option<CplexPopulate>,
+    missing_variables: MissingVariables,
+    status_rules: StdoutStatusRules,
 }
 
 impl Default for Cplex {
     fn default() -> Self {
         Self {
             command: "cplex".into(),
+            seconds: None,
+            threads: None,
+            seed: None,
             mipgap: None,
+            presolve: None,
+            params_file: None,
+            mip_start: None,
+            log_file: None,
+            conflict_file: None,
+            populate: None,
+            missing_variables: MissingVariables::default(),
+            status_rules: StdoutStatusRules::cplex(),
         }
     }
 }
@@ -35,11 +73,199 @@ impl Cplex {
     pub fn with_command(command: String) -> Self {
         Self {
             command,
-            mipgap: None,
+            ..Self::default()
+        }
+    }
+
+    /// Read the parameters from the given parameter file before solving,
+    /// such as one written with [crate::solvers::SolverParams::write_file]
+    /// after [crate::solvers::SolverTuning::tune]
+    pub fn with_params_file(&self, params_file: impl Into<PathBuf>) -> Self {
+        Self {
+            params_file: Some(params_file.into()),
+            ..(*self).clone()
+        }
+    }
+
+    /// Keep the log of cplex in the given file (`set logfile`), which cplex appends to.
+    /// The path can contain [RUN_ID_PLACEHOLDER](crate::solvers::RUN_ID_PLACEHOLDER), so that
+    /// solves running at the same time do not write to the same file.
+    pub fn with_log_file(&self, log_file: impl Into<PathBuf>) -> Self {
+        Self {
+            log_file: Some(log_file.into()),
+            ..(*self).clone()
+        }
+    }
+
+    /// Run the conflict refiner after solving, and write the conflict to the given file,
+    /// usually named `conflict.clp`. Cplex only finds a conflict when the problem is infeasible:
+    /// the file is not written otherwise.
+    ///
+    /// The files that were written are listed in the
+    /// [report](crate::solvers::SolveReport::output_files) of the solution.
+    pub fn with_conflict_file(&self, conflict_file: impl Into<PathBuf>) -> Self {
+        Self {
+            conflict_file: Some(conflict_file.into()),
+            ..(*self).clone()
+        }
+    }
+}
+
+/// How cplex fills its solution pool in [populate mode](Cplex::with_populate).
+/// The settings that are not given keep the defaults of cplex.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CplexPopulate {
+    /// how hard cplex searches for solutions, from 0 (automatic) to 4 (all the solutions)
+    /// (`set mip pool intensity`)
+    pub intensity: Option<u8>,
+    /// the number of solutions the pool keeps (`set mip pool capacity`)
+    pub capacity: Option<u32>,
+    /// the number of solutions after which cplex stops populating the pool
+    /// (`set mip limits populate`)
+    pub limit: Option<u32>,
+}
+
+impl CplexPopulate {
+    /// Populate the pool with the default settings of cplex
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set how hard cplex searches for solutions, from 0 (automatic) to 4 (all the solutions)
+    pub fn with_intensity(self, intensity: u8) -> Result<Self, String> {
+        if intensity <= 4 {
+            Ok(CplexPopulate {
+                intensity: Some(intensity),
+                ..self
+            })
+        } else {
+            Err(format!(
+                "Invalid pool intensity {}: must be between 0 and 4",
+                intensity
+            ))
+        }
+    }
+
+    /// Set the number of solutions the pool keeps
+    pub fn with_capacity(self, capacity: u32) -> Self {
+        CplexPopulate {
+            capacity: Some(capacity),
+            ..self
+        }
+    }
+
+    /// Set the number of solutions after which cplex stops populating the pool
+    pub fn with_limit(self, limit: u32) -> Self {
+        CplexPopulate {
+            limit: Some(limit),
+            ..self
+        }
+    }
+}
+
+impl Cplex {
+    /// Solve with the `populate` command instead of `optimize`, which fills the solution
+    /// pool of cplex with several solutions of a MIP.
+    /// The solution is the incumbent, the best solution found, and the members of the pool
+    /// are in its [solution_pool](Solution::solution_pool), in the order cplex writes them.
+    ///
+    /// ```
+    /// use std::path::Path;
+    /// # #[cfg(feature = "cplex")] {
+    /// use lp_solvers::solvers::{Cplex, CplexPopulate, SolverProgram};
+    ///
+    /// let populate = CplexPopulate::new().with_intensity(4).unwrap().with_capacity(10);
+    /// let solver = Cplex::default().with_populate(populate);
+    /// let args = solver.arguments(Path::new("problem.lp"), Path::new("problem.sol"));
+    /// assert_eq!(
+    ///     args,
+    ///     vec![
+    ///         "-c",
+    ///         "READ \"problem.lp\"",
+    ///         "set mip pool intensity 4",
+    ///         "set mip pool capacity 10",
+    ///         "populate",
+    ///         "WRITE \"problem.sol\" all",
+    ///     ]
+    /// );
+    /// # }
+    /// ```
+    pub fn with_populate(&self, populate: CplexPopulate) -> Self {
+        Self {
+            populate: Some(populate),
+            ..(*self).clone()
+        }
+    }
+}
+
+impl WithMissingVariables<Cplex> for Cplex {
+    fn missing_variables(&self) -> MissingVariables {
+        self.missing_variables
+    }
+    fn with_missing_variables(&self, policy: MissingVariables) -> Cplex {
+        Cplex {
+            missing_variables: policy,
+            ..(*self).clone()
+        }
+    }
+}
+
+impl WithPresolve<Cplex> for Cplex {
+    fn presolve(&self) -> Option<bool> {
+        self.presolve
+    }
+
+    fn with_presolve(&self, presolve: bool) -> Cplex {
+        Cplex {
+            presolve: Some(presolve),
+            ..(*self).clone()
         }
     }
 }
 
+impl WithMaxSeconds<Cplex> for Cplex {
+    fn max_seconds(&self) -> Option<u32> {
+        self.seconds
+    }
+
+    fn with_max_seconds(&self, seconds: u32) -> Cplex {
+        Cplex {
+            seconds: Some(seconds),
+            ..(*self).clone()
+        }
+    }
+}
+
+impl WithNbThreads<Cplex> for Cplex {
+    fn nb_threads(&self) -> Option<u32> {
+        self.threads
+    }
+
+    fn with_nb_threads(&self, threads: u32) -> Cplex {
+        Cplex {
+            threads: Some(threads),
+            ..(*self).clone()
+        }
+    }
+}
+
+impl WithMipStart<Cplex> for Cplex {
+    fn mip_start(&self) -> Option<&Path> {
+        self.mip_start.as_deref()
+    }
+
+    fn with_mip_start(&self, solution_file: PathBuf) -> Cplex {
+        Cplex {
+            mip_start: Some(solution_file),
+            ..(*self).clone()
+        }
+    }
+
+    fn write_mip_start(&self, solution: &Solution, writer: &mut dyn Write) -> io::Result<()> {
+        write_cplex_solution(solution, writer)
+    }
+}
+
 impl WithMipGap<Cplex> for Cplex {
     fn mip_gap(&self) -> Option<f32> {
         self.mipgap
@@ -57,6 +283,22 @@ impl WithMipGap<Cplex> for Cplex {
     }
 }
 
+/// The seed is set as `randomseed`. The verbosity is ignored: the status of the solve
+/// is read from the log of cplex, so it cannot be turned off.
+impl ApplyOptions for Cplex {
+    fn apply_options(&self, options: &SolverOptions) -> Result<Cplex, String> {
+        let mut solver = self.clone();
+        if let Some(mipgap) = options.mip_gap {
+            solver = solver.with_mip_gap(mipgap)?;
+        }
+        solver.seconds = options.time_limit.or(solver.seconds);
+        solver.threads = options.threads.or(solver.threads);
+        solver.seed = options.seed.or(solver.seed);
+        solver.presolve = options.presolve.or(solver.presolve);
+        Ok(solver)
+    }
+}
+
 macro_rules! format_osstr {
     ($($parts:expr)*) => {{
         let mut s = OsString::new();
@@ -65,77 +307,484 @@ macro_rules! format_osstr {
     }}
 }
 
+impl WithStatusRules<Cplex> for Cplex {
+    fn status_rules(&self) -> &StdoutStatusRules {
+        &self.status_rules
+    }
+
+    fn with_status_rules(&self, rules: StdoutStatusRules) -> Cplex {
+        Cplex {
+            status_rules: rules,
+            ..(*self).clone()
+        }
+    }
+}
+
+impl SolverCapabilities for Cplex {
+    fn supports_mip(&self) -> bool {
+        true
+    }
+
+    fn supports_quadratic(&self) -> bool {
+        true
+    }
+
+    fn supports_sos(&self) -> bool {
+        true
+    }
+
+    fn supports_indicators(&self) -> bool {
+        true
+    }
+
+    fn supports_warm_start(&self) -> bool {
+        true
+    }
+}
+
 impl SolverProgram for Cplex {
     fn command_name(&self) -> &str {
         &self.command
     }
 
     fn arguments(&self, lp_file: &Path, solution_file: &Path) -> Vec<OsString> {
-        let mut args = vec!["-c".into(), format_osstr!("READ \"" lp_file "\"")];
+        let mut args = vec!["-c".into()];
+
+        if let Some(log_file) = &self.log_file {
+            args.push(format_osstr!("set logfile \"" log_file "\""));
+        }
+
+        args.push(format_osstr!("READ \"" lp_file "\""));
+
+        if let Some(params_file) = &self.params_file {
+            args.push(format_osstr!("READ \"" params_file "\""));
+        }
+
+        if let Some(mip_start) = self.mip_start() {
+            args.push(format_osstr!("READ \"" mip_start "\""));
+        }
 
         if let Some(mipgap) = self.mip_gap() {
             args.push(format_osstr!("set mip tolerances mipgap " mipgap.to_string()));
         }
 
-        args.push("optimize".into());
-        args.push(format_osstr!("WRITE \"" solution_file "\""));
+        if let Some(presolve) = self.presolve() {
+            let value = if presolve { "y" } else { "n" };
+            args.push(format_osstr!("set preprocessing presolve " value));
+        }
+
+        for (name, value) in [
+            ("timelimit", self.seconds),
+            ("threads", self.threads),
+            ("randomseed", self.seed),
+        ]
+        .iter()
+        {
+            if let Some(value) = value {
+                args.push(format!("set {} {}", name, value).into());
+            }
+        }
+
+        match &self.populate {
+            Some(populate) => {
+                for (name, value) in [
+                    ("mip pool intensity", populate.intensity.map(u32::from)),
+                    ("mip pool capacity", populate.capacity),
+                    ("mip limits populate", populate.limit),
+                ]
+                .iter()
+                {
+                    if let Some(value) = value {
+                        args.push(format!("set {} {}", name, value).into());
+                    }
+                }
+                args.push("populate".into());
+                // the incumbent, followed by the members of the pool
+                args.push(format_osstr!("WRITE \"" solution_file "\" all"));
+            }
+            None => {
+                args.push("optimize".into());
+                args.push(format_osstr!("WRITE \"" solution_file "\""));
+            }
+        }
+
+        // the commands run whatever the outcome of the solve:
+        // the conflict refiner fails without writing anything when the problem is feasible
+        if let Some(conflict_file) = &self.conflict_file {
+            args.push("conflict".into());
+            args.push(format_osstr!("WRITE \"" conflict_file "\""));
+        }
 
         args
     }
 
     fn parse_stdout_status(&self, stdout: &[u8]) -> Option<Status> {
-        if buf_contains(stdout, "No solution exists") {
-            Some(Status::Infeasible)
-        } else {
-            None
-        }
+        self.status_rules.status(stdout)
     }
 
     fn solution_suffix(&self) -> Option<&str> {
         Some(".sol")
     }
+
+    fn problem_formats(&self) -> &[ProblemFormat] {
+        &[ProblemFormat::Lp, ProblemFormat::FreeMps]
+    }
+
+    fn output_files(&self) -> Vec<PathBuf> {
+        self.log_file
+            .iter()
+            .chain(&self.conflict_file)
+            .cloned()
+            .collect()
+    }
+
+    fn log_file(&self) -> Option<&Path> {
+        self.log_file.as_deref()
+    }
+
+    fn parse_license_error(&self, stdout: &[u8], stderr: &[u8]) -> Option<String> {
+        // 32201 to 32207 are licensing errors, and 1016 means that
+        // the problem exceeds the size limits of the community edition
+        const PATTERNS: [&str; 8] = [
+            "CPLEX Error  1016",
+            "CPLEX Error  32201",
+            "CPLEX Error  32202",
+            "CPLEX Error  32203",
+            "CPLEX Error  32204",
+            "CPLEX Error  32205",
+            "CPLEX Error  32206",
+            "CPLEX Error  32207",
+        ];
+        find_line(stdout, &PATTERNS).or_else(|| find_line(stderr, &PATTERNS))
+    }
 }
 
-fn extract_variable_name_and_value_from_event(
-    variable_event: BytesStart,
-) -> Result<(String, f32), String> {
+#[cfg(feature = "process")]
+impl SolverTuning for Cplex {
+    fn tune<'a, P: LpProblem<'a>>(
+        &self,
+        problem: &'a P,
+        time_limit: Option<Duration>,
+    ) -> Result<SolverParams, SolverError> {
+        let problem_file = problem
+            .to_tmp_file()
+            .map_err(|e| format!("Unable to write the problem: {}", e))?;
+        let dir = tempfile::tempdir().map_err(|e| e.to_string())?;
+        let params_file = dir.path().join("tuned.prm");
+        let mut command = Command::new(&self.command);
+        command.arg("-c");
+        command.arg(format_osstr!("READ \"" problem_file.path() "\""));
+        if let Some(limit) = time_limit {
+            command.arg(format!("set tune timelimit {}", limit.as_secs_f64()));
+        }
+        if let Some(mipgap) = self.mip_gap() {
+            command.arg(format!("set mip tolerances mipgap {}", mipgap));
+        }
+        command.arg("tools tune");
+        // the tuned parameters are the current ones once tuning is over
+        command.arg(format_osstr!("WRITE \"" params_file.as_path() "\""));
+        run_tuning_tool(self, command, dir.path(), &params_file)
+    }
+}
+
+/// Cplex has no license check of its own: it starts the interactive optimizer, and quits.
+#[cfg(feature = "process")]
+impl LicenseCheck for Cplex {
+    fn check_license(&self) -> Result<LicenseInfo, SolverError> {
+        let stdout = run_license_check(self, ["-c", "quit"])?;
+        let banner = stdout.lines().map(str::trim).find(|l| l.contains("CPLEX"));
+        Ok(LicenseInfo {
+            description: banner.map(str::to_string),
+            expires: None,
+            // the community edition is limited to 1000 variables and 1000 constraints
+            size_limited: banner.is_some_and(|l| l.contains("Community Edition")),
+        })
+    }
+}
+
+/// Extract the `name` attribute of an element, and the attribute with the given key parsed as a number
+fn extract_name_and_attribute_from_event<T: SolutionValue>(
+    event: BytesStart,
+    key: &[u8],
+) -> Result<(String, Option<T>), String> {
     let mut name = None;
     let mut value = None;
-    for attribute in variable_event.attributes() {
+    for attribute in event.attributes() {
         let attribute = attribute.map_err(|e| format!("attribute error: {}", e))?;
         match attribute.key.as_ref() {
             b"name" => name = Some(String::from_utf8_lossy(attribute.value.as_ref()).to_string()),
-            b"value" => {
+            k if k == key => {
                 value = Some(
-                    String::from_utf8_lossy(attribute.value.as_ref())
-                        .parse()
-                        .map_err(|e| format!("invalid variable value for {:?}: {}", name, e))?,
+                    T::parse_value(attribute.value.as_ref())
+                        .map_err(|e| format!("invalid value for {:?}: {}", name, e))?,
                 );
             }
             _ => {}
         }
     }
+    name.map(|name| (name, value))
+        .ok_or_else(|| "name not found".to_string())
+}
+
+fn extract_variable_name_and_value_from_event<T: SolutionValue>(
+    variable_event: BytesStart,
+) -> Result<(String, T), String> {
+    match extract_name_and_attribute_from_event(variable_event, b"value")? {
+        (name, Some(value)) => Ok((name, value)),
+        _ => Err("name and value not found for variable".to_string()),
+    }
+}
+
+/// Iterate over the variable values in a cplex solution file, without storing them
+pub fn read_cplex_solution_iter(
+    path: &Path,
+) -> Result<impl Iterator<Item = Result<(String, f64), String>>, String> {
+    let mut reader = Reader::from_reader(BufReader::new(open_solution_file(path)?));
+    let mut buf = Vec::new();
+    let mut in_variables = false;
+    let mut done = false;
+    Ok(std::iter::from_fn(move || {
+        while !done {
+            buf.clear();
+            let item = match reader.read_event_into(&mut buf) {
+                Err(e) => Err(format!(
+                    "Error at position {}: {:?}",
+                    reader.buffer_position(),
+                    e
+                )),
+                Ok(Event::Eof) if in_variables => Err(format!(
+                    "Error at position {}: Unterminated variables section",
+                    reader.buffer_position(),
+                )),
+                Ok(Event::Eof) => break,
+                Ok(Event::Start(e)) if e.local_name().as_ref() == b"variables" => {
+                    in_variables = true;
+                    continue;
+                }
+                Ok(Event::End(e)) if e.local_name().as_ref() == b"variables" => break,
+                Ok(Event::Empty(e)) | Ok(Event::Start(e))
+                    if in_variables && e.local_name().as_ref() == b"variable" =>
+                {
+                    extract_variable_name_and_value_from_event(e)
+                }
+                _ => continue,
+            };
+            done = item.is_err();
+            return Some(item);
+        }
+        done = true;
+        None
+    }))
+}
 
-    name.and_then(|name| value.map(|value| (name, value)))
-        .ok_or_else(|| "name and value not found for variable".to_string())
+/// The `solutionStatusValue` codes of cplex solution files (the `CPX_STAT_*` and `CPXMIP_*`
+/// constants of the CPLEX callable library), by range, and the status they stand for.
+/// The other codes mean that cplex stopped on a limit or on an error before finding a feasible
+/// solution, see [cplex_solution_status].
+pub const CPLEX_SOLUTION_STATUS_CODES: &[(RangeInclusive<u32>, Status)] = &[
+    // optimal, with or without an unbounded optimal face
+    (1..=1, Status::Optimal),
+    (20..=20, Status::Optimal),
+    (101..=101, Status::Optimal),
+    (129..=129, Status::Optimal),
+    // optimal within the mip gap, which is how cplex ends most MIP solves
+    (102..=102, Status::Optimal),
+    (130..=130, Status::Optimal),
+    (2..=2, Status::Unbounded),
+    (118..=118, Status::Unbounded),
+    (3..=3, Status::Infeasible),
+    (103..=103, Status::Infeasible),
+    (4..=4, Status::InfeasibleOrUnbounded),
+    (119..=119, Status::InfeasibleOrUnbounded),
+    // optimal within tolerances, with infeasibilities after unscaling,
+    // or stopped on a limit or on an error with a feasible solution
+    (5..=6, Status::SubOptimal),
+    (10..=19, Status::SubOptimal),
+    (21..=23, Status::SubOptimal),
+    (104..=105, Status::SubOptimal),
+    (107..=107, Status::SubOptimal),
+    (109..=109, Status::SubOptimal),
+    (111..=111, Status::SubOptimal),
+    (113..=113, Status::SubOptimal),
+    (115..=116, Status::SubOptimal),
+    (120..=128, Status::SubOptimal),
+];
+
+/// The status of a `solutionStatusValue` code of a cplex solution file, from
+/// [CPLEX_SOLUTION_STATUS_CODES], and [Status::NotSolved] for the other codes
+///
+/// ```
+/// # #[cfg(feature = "cplex")] {
+/// use lp_solvers::solvers::{cplex_solution_status, Status};
+///
+/// assert_eq!(cplex_solution_status(101), Status::Optimal);
+/// assert_eq!(cplex_solution_status(107), Status::SubOptimal);
+/// assert_eq!(cplex_solution_status(108), Status::NotSolved);
+/// # }
+/// ```
+pub fn cplex_solution_status(code: u32) -> Status {
+    CPLEX_SOLUTION_STATUS_CODES
+        .iter()
+        .find(|(codes, _)| codes.contains(&code))
+        .map_or(Status::NotSolved, |(_, status)| status.clone())
 }
 
 fn read_specific_solution(f: &File, variables_len: Option<usize>) -> Result<Solution, String> {
+    read_solution(BufReader::new(f), variables_len, VariableFilter::default()).map_err(String::from)
+}
+
+/// Parse a solution file written by cplex, in its xml format
+pub fn parse_cplex_solution(reader: impl BufRead) -> Result<Solution, ParseError> {
+    read_solution(reader, None, VariableFilter::default())
+}
+
+/// Parse a file with several solutions written by cplex (`write <file> all`),
+/// such as the members of its solution pool, in the order of the file
+///
+/// ```
+/// # #[cfg(feature = "cplex")] {
+/// use lp_solvers::solvers::parse_cplex_solution_pool;
+///
+/// let pool = r#"<CPLEXSolutions version="1.2">
+///  <CPLEXSolution version="1.2">
+///   <header objectiveValue="2" solutionStatusValue="101"/>
+///   <variables><variable name="x" index="0" value="2"/></variables>
+///  </CPLEXSolution>
+///  <CPLEXSolution version="1.2">
+///   <header objectiveValue="3" solutionStatusValue="101"/>
+///   <variables><variable name="x" index="0" value="3"/></variables>
+///  </CPLEXSolution>
+/// </CPLEXSolutions>"#;
+/// let solutions = parse_cplex_solution_pool(pool.as_bytes()).unwrap();
+/// assert_eq!(solutions.len(), 2);
+/// assert_eq!(solutions[1].value("x"), Some(3.));
+/// # }
+/// ```
+pub fn parse_cplex_solution_pool(reader: impl BufRead) -> Result<Vec<Solution>, ParseError> {
+    read_solution_pool(reader, VariableFilter::default())
+}
+
+/// Parse a solution file written by cplex, already in memory. See [parse_cplex_solution]
+pub fn parse_cplex_solution_bytes(bytes: &[u8]) -> Result<Solution, ParseError> {
+    read_solution(bytes, None, VariableFilter::default())
+}
+
+/// Parse a file with several solutions written by cplex, already in memory.
+/// See [parse_cplex_solution_pool]
+pub fn parse_cplex_solution_pool_bytes(bytes: &[u8]) -> Result<Vec<Solution>, ParseError> {
+    read_solution_pool(bytes, VariableFilter::default())
+}
+
+fn read_solution_pool(
+    f: impl BufRead,
+    filter: VariableFilter,
+) -> Result<Vec<Solution>, ParseError> {
+    let mut reader = Reader::from_reader(f);
+    let mut buf = Vec::new();
+    let mut solutions = vec![];
+    loop {
+        let mut solution = Solution::new(Status::NotSolved, HashMap::new());
+        if !read_next_solution(&mut reader, &mut buf, &mut solution, filter)? {
+            return Ok(solutions);
+        }
+        solutions.push(solution);
+    }
+}
+
+/// Read the incumbent of a file written in populate mode, with the other solutions as its pool
+fn read_populated_solution(f: impl BufRead, filter: VariableFilter) -> Result<Solution, String> {
+    let mut solutions = read_solution_pool(f, filter)?.into_iter();
+    let mut solution = solutions
+        .next()
+        .ok_or_else(|| "Incorrect solution format: no solution".to_string())?;
+    solution.solution_pool = solutions.collect();
+    Ok(solution)
+}
+
+/// Write a solution in the xml format of cplex, which [parse_cplex_solution] reads.
+/// Cplex can read these files as a MIP start.
+pub fn write_cplex_solution<W: Write>(solution: &Solution, mut writer: W) -> io::Result<()> {
+    // codes that cplex_solution_status reads back as the same status
+    let (code, description) = match solution.status {
+        Status::Optimal => (1, "optimal"),
+        Status::SubOptimal => (107, "time limit exceeded"),
+        Status::Infeasible => (3, "infeasible"),
+        Status::Unbounded => (2, "unbounded"),
+        Status::InfeasibleOrUnbounded => (4, "infeasible or unbounded"),
+        Status::NotSolved => (108, "time limit exceeded, no integer solution"),
+    };
+    writeln!(
+        writer,
+        "<?xml version = \"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>"
+    )?;
+    writeln!(writer, "<CPLEXSolution version=\"1.2\">")?;
+    write!(writer, " <header\n   solutionStatusValue=\"{}\"", code)?;
+    write!(writer, "\n   solutionStatusString=\"{}\"", description)?;
+    if let Some(objective) = solution.objective {
+        write!(writer, "\n   objectiveValue=\"{}\"", objective)?;
+    }
+    writeln!(writer, "/>")?;
+    if !solution.dual_values.is_empty() {
+        let mut duals: Vec<_> = solution.dual_values.iter().collect();
+        duals.sort_unstable_by(|a, b| a.0.cmp(b.0));
+        writeln!(writer, " <linearConstraints>")?;
+        for (idx, (name, dual)) in duals.into_iter().enumerate() {
+            writeln!(
+                writer,
+                "  <constraint name=\"{}\" index=\"{}\" dual=\"{}\"/>",
+                escape(name.as_str()),
+                idx,
+                dual
+            )?;
+        }
+        writeln!(writer, " </linearConstraints>")?;
+    }
+    writeln!(writer, " <variables>")?;
+    for (idx, (name, value)) in solution.ordered_results().into_iter().enumerate() {
+        writeln!(
+            writer,
+            "  <variable name=\"{}\" index=\"{}\" value=\"{}\"/>",
+            escape(name),
+            idx,
+            value
+        )?;
+    }
+    writeln!(writer, " </variables>")?;
+    writeln!(writer, "</CPLEXSolution>")
+}
+
+fn read_solution(
+    f: impl BufRead,
+    variables_len: Option<usize>,
+    filter: VariableFilter,
+) -> Result<Solution, ParseError> {
     let results = variables_len
-        .map(HashMap::with_capacity)
+        .map(|len| HashMap::with_capacity(filter.capacity(len)))
         .unwrap_or_default();
 
-    let mut solution = Solution {
-        status: Status::Optimal,
-        results,
-    };
+    // the header gives the status
+    let mut solution = Solution::new(Status::NotSolved, results);
 
-    let f = BufReader::new(f);
     let mut reader = Reader::from_reader(f);
     let mut buf = Vec::new();
+    if !read_next_solution(&mut reader, &mut buf, &mut solution, filter)? {
+        return Err("Incorrect solution format: no header or variables section".into());
+    }
+    Ok(solution)
+}
 
+/// Read the next solution of the file into `solution`, up to the end of its variables.
+/// Returns whether there was one before the end of the file.
+fn read_next_solution<R: BufRead>(
+    reader: &mut Reader<R>,
+    buf: &mut Vec<u8>,
+    solution: &mut Solution,
+    filter: VariableFilter,
+) -> Result<bool, String> {
+    let mut found = false;
     loop {
-        match reader.read_event_into(&mut buf) {
+        buf.clear();
+        match reader.read_event_into(buf) {
             Err(e) => {
                 return Err(format!(
                     "Error at position {}: {:?}",
@@ -147,21 +796,71 @@ fn read_specific_solution(f: &File, variables_len: Option<usize>) -> Result<Solu
             Ok(Event::Eof) => {
                 break;
             }
+            Ok(Event::Empty(e)) | Ok(Event::Start(e)) if e.local_name().as_ref() == b"header" => {
+                found = true;
+                for attribute in e.attributes() {
+                    let attribute = attribute.map_err(|e| format!("attribute error: {}", e))?;
+                    let value = String::from_utf8_lossy(attribute.value.as_ref());
+                    match attribute.key.as_ref() {
+                        b"solutionStatusValue" => {
+                            let code = value.parse().map_err(|e| {
+                                format!("invalid solution status {:?}: {}", value, e)
+                            })?;
+                            solution.status = cplex_solution_status(code);
+                        }
+                        b"objectiveValue" => solution.objective = value.parse().ok(),
+                        _ => {}
+                    }
+                }
+            }
+            // constraints come before variables. Their duals are only present for continuous problems
+            Ok(Event::Start(e)) if e.local_name().as_ref() == b"linearConstraints" => loop {
+                match reader.read_event_into(buf) {
+                    Ok(Event::Empty(e)) | Ok(Event::Start(e))
+                        if e.local_name().as_ref() == b"constraint" =>
+                    {
+                        if let (name, Some(dual)) =
+                            extract_name_and_attribute_from_event(e, b"dual")?
+                        {
+                            solution.dual_values.insert(name, dual);
+                        }
+                    }
+                    Ok(Event::End(e)) if e.local_name().as_ref() == b"linearConstraints" => {
+                        break;
+                    }
+                    Err(e) => {
+                        return Err(format!(
+                            "Error at position {}: {:?}",
+                            reader.buffer_position(),
+                            e
+                        ))
+                    }
+                    Ok(Event::Eof) => {
+                        return Err(format!(
+                            "Error at position {}: Unterminated linearConstraints section",
+                            reader.buffer_position(),
+                        ))
+                    }
+                    _ => {}
+                }
+            },
             // we reached the "variables" section, where the variables to parse are
             Ok(Event::Start(e)) if e.local_name().as_ref() == b"variables" => loop {
-                match reader.read_event_into(&mut buf) {
+                match reader.read_event_into(buf) {
                     // we matched either the start of a "variable" tag, or a "variable" tag without body
                     Ok(Event::Empty(e)) | Ok(Event::Start(e))
                         if e.local_name().as_ref() == b"variable" =>
                     {
                         // let's try to parse the variable name and value
                         let (name, value) = extract_variable_name_and_value_from_event(e)?;
-                        solution.results.insert(name, value);
+                        if filter.keeps(&name) {
+                            solution.results.insert(name, value);
+                        }
                     }
                     // we reached the end of the "variables" section, at this point all the variables should have been parsed.
                     // we can safely return
                     Ok(Event::End(e)) if e.local_name().as_ref() == b"variables" => {
-                        return Ok(solution);
+                        return Ok(true);
                     }
                     Err(e) => {
                         return Err(format!(
@@ -185,7 +884,7 @@ fn read_specific_solution(f: &File, variables_len: Option<usize>) -> Result<Solu
         }
     }
 
-    Ok(solution)
+    Ok(found)
 }
 
 impl SolverWithSolutionParsing for Cplex {
@@ -194,15 +893,61 @@ impl SolverWithSolutionParsing for Cplex {
         f: &File,
         problem: Option<&'a P>,
     ) -> Result<Solution, String> {
-        let len = problem.map(|p| p.variables().size_hint().0);
-        read_specific_solution(f, len)
+        let solution = if self.populate.is_some() {
+            read_populated_solution(BufReader::new(f), VariableFilter::default())?
+        } else {
+            let len = problem.map(|p| p.variables().size_hint().0);
+            read_specific_solution(f, len)?
+        };
+        self.complete(solution, problem, VariableFilter::default())
+    }
+
+    fn read_solution_filtered<'a, P: LpProblem<'a>>(
+        &self,
+        temp_solution_file: &Path,
+        problem: Option<&'a P>,
+        variables: &HashSet<String>,
+    ) -> Result<Solution, String> {
+        let f = open_solution_file(temp_solution_file)?;
+        let filter = VariableFilter::only(variables);
+        let solution = if self.populate.is_some() {
+            read_populated_solution(BufReader::new(f), filter)?
+        } else {
+            let len = problem.map(|p| p.variables().size_hint().0);
+            read_solution(BufReader::new(f), len, filter)?
+        };
+        self.complete(solution, problem, filter)
+    }
+}
+
+impl Cplex {
+    /// Apply the [MissingVariables] policy to the solution and to the members of its pool
+    fn complete<'a, P: LpProblem<'a>>(
+        &self,
+        mut solution: Solution,
+        problem: Option<&'a P>,
+        filter: VariableFilter,
+    ) -> Result<Solution, String> {
+        self.missing_variables
+            .apply(&mut solution, problem, filter)?;
+        for member in solution.solution_pool.iter_mut() {
+            self.missing_variables.apply(member, problem, filter)?;
+        }
+        Ok(solution)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::read_specific_solution;
-    use crate::solvers::{Cplex, SolverProgram, WithMipGap};
+    use super::{
+        cplex_solution_status, parse_cplex_solution, parse_cplex_solution_bytes,
+        read_cplex_solution_iter, read_populated_solution, read_specific_solution,
+        CPLEX_SOLUTION_STATUS_CODES,
+    };
+    use crate::lp_format::ConsId;
+    use crate::solvers::{
+        Cplex, CplexPopulate, SolverProgram, Status, VariableFilter, WithMipGap, WithPresolve,
+    };
     use std::collections::HashMap;
     use std::ffi::OsString;
     use std::io::{Seek, Write};
@@ -245,6 +990,61 @@ mod tests {
  </variables>
 </CPLEXSolution>"##;
 
+    #[test]
+    fn populated_solution() {
+        let pool = r#"<?xml version = "1.0" encoding="UTF-8" standalone="yes"?>
+<CPLEXSolutions version="1.2">
+ <CPLEXSolution version="1.2">
+  <header solutionName="incumbent" objectiveValue="2" solutionStatusValue="101"/>
+  <variables>
+   <variable name="x" index="0" value="2"/>
+   <variable name="y" index="1" value="0"/>
+  </variables>
+ </CPLEXSolution>
+ <CPLEXSolution version="1.2">
+  <header solutionName="p1" objectiveValue="2" solutionStatusValue="129"/>
+  <variables>
+   <variable name="x" index="0" value="0"/>
+   <variable name="y" index="1" value="2"/>
+  </variables>
+ </CPLEXSolution>
+</CPLEXSolutions>
+"#;
+        let solution = read_populated_solution(pool.as_bytes(), VariableFilter::default()).unwrap();
+        assert_eq!(solution.status, Status::Optimal);
+        assert_eq!(solution.value("x"), Some(2.));
+        assert_eq!(solution.solution_pool.len(), 1);
+        assert_eq!(solution.solution_pool[0].value("y"), Some(2.));
+        assert!(read_populated_solution(&b""[..], VariableFilter::default()).is_err());
+    }
+
+    #[test]
+    fn populate_intensity() {
+        assert!(CplexPopulate::new().with_intensity(5).is_err());
+        let solver = Cplex::default().with_populate(CplexPopulate::new().with_limit(20));
+        let args = solver.arguments(Path::new("test.lp"), Path::new("test.sol"));
+        assert!(args.contains(&OsString::from("set mip limits populate 20")));
+    }
+
+    #[test]
+    fn solution_status_codes() {
+        for (idx, (codes, _)) in CPLEX_SOLUTION_STATUS_CODES.iter().enumerate() {
+            for (other, _) in &CPLEX_SOLUTION_STATUS_CODES[idx + 1..] {
+                assert!(
+                    codes.end() < other.start() || other.end() < codes.start(),
+                    "{:?} and {:?} overlap",
+                    codes,
+                    other
+                );
+            }
+        }
+        assert_eq!(cplex_solution_status(1), Status::Optimal);
+        assert_eq!(cplex_solution_status(119), Status::InfeasibleOrUnbounded);
+        assert_eq!(cplex_solution_status(22), Status::SubOptimal);
+        assert_eq!(cplex_solution_status(106), Status::NotSolved);
+        assert_eq!(cplex_solution_status(7), Status::NotSolved);
+    }
+
     #[test]
     fn sol_file_parsing() {
         let mut tmpfile = tempfile::tempfile().expect("unable to create tempfile");
@@ -255,6 +1055,8 @@ mod tests {
 
         let solution = read_specific_solution(&tmpfile, None).expect("failed to read sol file");
 
+        assert_eq!(solution.status, Status::Optimal);
+        assert_eq!(solution.objective, Some(-122.5));
         assert_eq!(
             solution.results,
             HashMap::from([
@@ -266,6 +1068,97 @@ mod tests {
         );
     }
 
+    #[test]
+    fn sol_file_iter() {
+        let mut tmpfile = tempfile::NamedTempFile::new().expect("unable to create tempfile");
+        tmpfile
+            .write_all(SAMPLE_SOL_FILE.as_bytes())
+            .expect("unable to write sol file to tempfile");
+
+        let values: Result<Vec<_>, _> = read_cplex_solution_iter(tmpfile.path())
+            .expect("failed to open sol file")
+            .collect();
+        assert_eq!(
+            values.expect("failed to read sol file"),
+            vec![
+                ("x1".to_owned(), 40.0),
+                ("x2".to_owned(), 10.5),
+                ("x3".to_owned(), 19.5),
+                ("x4".to_owned(), 3.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn sol_file_status() {
+        for (code, status) in [
+            (102, Status::Optimal),
+            (103, Status::Infeasible),
+            (107, Status::SubOptimal),
+            (108, Status::NotSolved),
+            (118, Status::Unbounded),
+            (119, Status::InfeasibleOrUnbounded),
+        ] {
+            let sol_file = SAMPLE_SOL_FILE.replace(
+                r#"solutionStatusValue="101""#,
+                &format!(r#"solutionStatusValue="{}""#, code),
+            );
+            let mut tmpfile = tempfile::tempfile().expect("unable to create tempfile");
+            tmpfile.write_all(sol_file.as_bytes()).unwrap();
+            tmpfile.rewind().unwrap();
+            let solution = read_specific_solution(&tmpfile, None).unwrap();
+            assert_eq!(solution.status, status, "status code {}", code);
+        }
+    }
+
+    #[test]
+    fn sol_file_without_solution() {
+        for bytes in [
+            &b""[..],
+            b"hello world",
+            b"<CPLEXSolution version=\"1.2\"/>",
+        ] {
+            let error = parse_cplex_solution_bytes(bytes).unwrap_err();
+            assert!(
+                error.message.starts_with("Incorrect solution format"),
+                "{}",
+                error
+            );
+        }
+        assert!(parse_cplex_solution(&b"hello world"[..]).is_err());
+    }
+
+    #[test]
+    fn sol_file_duals() {
+        let lp_sol_file = SAMPLE_SOL_FILE
+            .replace(r#"slack="0"/>"#, r#"slack="0" dual="-1.5"/>"#)
+            .replace(r#"slack="2"/>"#, r#"slack="2" dual="0"/>"#);
+        let mut tmpfile = tempfile::tempfile().expect("unable to create tempfile");
+        tmpfile
+            .write_all(lp_sol_file.as_bytes())
+            .expect("unable to write sol file to tempfile");
+        tmpfile.rewind().expect("unable to rewind sol file");
+
+        let solution = read_specific_solution(&tmpfile, None).expect("failed to read sol file");
+
+        assert_eq!(solution.dual(ConsId::from_index(2)), Some(0.0));
+        assert_eq!(solution.dual(ConsId::from_index(0)), None);
+        assert_eq!(solution.dual_values["c1"], -1.5);
+        assert_eq!(solution.dual_values["c3"], -1.5);
+        assert_eq!(solution.results.len(), 4);
+    }
+
+    #[test]
+    fn license_error() {
+        let stdout = b"CPLEX> CPLEX Error  32201: ilm: CPLEX: no license found for this product.\n";
+        assert_eq!(
+            Cplex::default().parse_license_error(stdout, b""),
+            Some(
+                "CPLEX> CPLEX Error  32201: ilm: CPLEX: no license found for this product.".into()
+            )
+        );
+    }
+
     #[test]
     fn cli_args_default() {
         let solver = Cplex::default();
@@ -300,6 +1193,22 @@ mod tests {
         assert_eq!(args, expected);
     }
 
+    #[test]
+    fn cli_args_presolve() {
+        let solver = Cplex::default().with_presolve(false);
+        let args = solver.arguments(Path::new("test.lp"), Path::new("test.sol"));
+
+        let expected: Vec<OsString> = vec![
+            "-c".into(),
+            "READ \"test.lp\"".into(),
+            "set preprocessing presolve n".into(),
+            "optimize".into(),
+            "WRITE \"test.sol\"".into(),
+        ];
+
+        assert_eq!(args, expected);
+    }
+
     #[test]
     fn cli_args_mipgap_negative() {
         let solver = Cplex::default().with_mip_gap(-0.05);
@@ -311,4 +1220,28 @@ mod tests {
         let solver = Cplex::default().with_mip_gap(f32::INFINITY);
         assert!(solver.is_err());
     }
+
+    #[test]
+    fn cli_args_log_and_conflict_files() {
+        let solver = Cplex::default()
+            .with_log_file("cplex.log")
+            .with_conflict_file("conflict.clp");
+        let args = solver.arguments(Path::new("test.lp"), Path::new("test.sol"));
+
+        let expected: Vec<OsString> = vec![
+            "-c".into(),
+            "set logfile \"cplex.log\"".into(),
+            "READ \"test.lp\"".into(),
+            "optimize".into(),
+            "WRITE \"test.sol\"".into(),
+            "conflict".into(),
+            "WRITE \"conflict.clp\"".into(),
+        ];
+
+        assert_eq!(args, expected);
+        assert_eq!(
+            solver.output_files(),
+            vec![Path::new("cplex.log"), Path::new("conflict.clp")]
+        );
+    }
 }
@@ -0,0 +1,98 @@
+//! Lazy constraints, for solvers that are run on a file and have no callbacks.
+//!
+//! [solve_with_cuts] solves a problem without some of its constraints, asks a separator
+//! which of them the solution violates, adds them to the problem, and solves it again,
+//! until the separator finds no violated constraint.
+
+use crate::lp_format::{ConsId, Constraint};
+use crate::problem::{LinearExpression, Problem, Variable};
+use crate::solvers::{Solution, SolverError, SolverTrait, Status};
+
+/// The result of [solve_with_cuts]
+#[derive(Debug, Clone)]
+pub struct CutsSolution {
+    /// the last solution, which violates none of the cuts the separator knows of
+    /// when it is optimal or suboptimal
+    pub solution: Solution,
+    /// the number of times the problem was solved
+    pub rounds: usize,
+    /// the constraints that were added to the problem, in the order they were added
+    pub cuts: Vec<ConsId>,
+}
+
+/// Solve the problem, and as long as the separator returns constraints violated by the
+/// solution, add them to the problem and solve it again.
+///
+/// The loop stops when the separator returns no constraint, or when a solve does not
+/// find a solution: the separator is only called with optimal and suboptimal solutions.
+/// The cuts stay in the problem, so it can be solved again later with all of them.
+///
+/// Each round writes and solves the whole problem again. A
+/// [ResolvingSolver](crate::solvers::ResolvingSolver) starts each round from the solution
+/// of the previous one.
+///
+/// ```
+/// use std::cmp::Ordering;
+/// use std::collections::HashMap;
+/// use lp_solvers::lp_format::{Constraint, LpObjective};
+/// use lp_solvers::problem::{LinearExpression, ProblemBuilder};
+/// use lp_solvers::solvers::{solve_with_cuts, FnSolver, Solution, SolverError, Status};
+///
+/// // maximize x, and lazily x <= 2
+/// let mut problem = ProblemBuilder::new("lazy", LpObjective::Maximize).build();
+/// let solver = FnSolver(|lp: &str| -> Result<Solution, SolverError> {
+///     let x = if lp.contains("<= 2") { 2. } else { 10. };
+///     Ok(Solution::new(Status::Optimal, vec![("x".to_string(), x)].into_iter().collect()))
+/// });
+/// let result = solve_with_cuts(&solver, &mut problem, |solution: &Solution| {
+///     if solution.value("x") > Some(2.) {
+///         vec![Constraint {
+///             lhs: LinearExpression { coefficients: vec![("x".to_string(), 1.)] },
+///             operator: Ordering::Less,
+///             rhs: 2.,
+///         }]
+///     } else {
+///         vec![]
+///     }
+/// })
+/// .unwrap();
+/// assert_eq!(result.rounds, 2);
+/// assert_eq!(result.cuts.len(), 1);
+/// assert_eq!(result.solution.value("x"), Some(2.));
+/// assert_eq!(problem.constraints.len(), 1);
+/// ```
+pub fn solve_with_cuts<S, F>(
+    solver: &S,
+    problem: &mut Problem<LinearExpression, Variable>,
+    mut separator: F,
+) -> Result<CutsSolution, SolverError>
+where
+    S: SolverTrait,
+    F: FnMut(&Solution) -> Vec<Constraint<LinearExpression>>,
+{
+    let mut cuts = vec![];
+    let mut rounds = 0;
+    loop {
+        let solution = solver.run(&*problem)?;
+        rounds += 1;
+        if !matches!(solution.status, Status::Optimal | Status::SubOptimal) {
+            return Ok(CutsSolution {
+                solution,
+                rounds,
+                cuts,
+            });
+        }
+        let new_cuts = separator(&solution);
+        if new_cuts.is_empty() {
+            return Ok(CutsSolution {
+                solution,
+                rounds,
+                cuts,
+            });
+        }
+        for cut in new_cuts {
+            cuts.push(ConsId::from_index(problem.constraints.len()));
+            problem.constraints.push(cut);
+        }
+    }
+}
@@ -0,0 +1,191 @@
+//! Finding out why solvers cannot be used on this computer.
+//!
+//! [diagnose] checks every supported solver, so that applications can tell their users
+//! what to install or configure instead of just failing with "No solver available".
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::thread::ScopedJoinHandle;
+
+#[cfg(feature = "cbc")]
+use crate::solvers::CbcSolver;
+#[cfg(feature = "cplex")]
+use crate::solvers::Cplex;
+#[cfg(feature = "glpk")]
+use crate::solvers::GlpkSolver;
+#[cfg(feature = "gurobi")]
+use crate::solvers::GurobiSolver;
+use crate::solvers::{auto, SolverError, SolverProgram, SolverTrait, SolverWithSolutionParsing};
+
+/// The state of a solver program on this computer
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SolverDiagnosis {
+    /// The program that is run
+    pub command: String,
+    /// Where the program was found. `None` if it is not installed or not in `PATH`
+    pub path: Option<PathBuf>,
+    /// The version the program printed, for programs that can print it
+    pub version: Option<String>,
+    /// Why the program could not get a license, if it could not
+    pub license_error: Option<String>,
+    /// Why a trivial problem could not be solved, for reasons other than the license
+    pub solve_error: Option<String>,
+}
+
+impl SolverDiagnosis {
+    /// Whether the program was found
+    pub fn is_installed(&self) -> bool {
+        self.path.is_some()
+    }
+
+    /// Whether the program solved a trivial problem
+    pub fn is_usable(&self) -> bool {
+        self.is_installed() && self.license_error.is_none() && self.solve_error.is_none()
+    }
+
+    /// What the user should do for the solver to be usable, if anything
+    pub fn advice(&self) -> Option<String> {
+        if !self.is_installed() {
+            Some(format!(
+                "install {} and add its directory to the PATH environment variable",
+                self.command
+            ))
+        } else if let Some(error) = &self.license_error {
+            Some(format!("check the license of {}: {}", self.command, error))
+        } else {
+            self.solve_error
+                .as_ref()
+                .map(|error| format!("{} is installed but failed: {}", self.command, error))
+        }
+    }
+}
+
+impl fmt::Display for SolverDiagnosis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: ", self.command)?;
+        match (&self.path, self.advice()) {
+            (_, Some(advice)) => f.write_str(&advice),
+            (Some(path), None) => {
+                write!(f, "ok ({}", path.display())?;
+                if let Some(version) = &self.version {
+                    write!(f, ", {}", version)?;
+                }
+                f.write_str(")")
+            }
+            (None, None) => unreachable!("solvers that are not installed have advice"),
+        }
+    }
+}
+
+/// The state of all the supported solvers, returned by [diagnose]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Diagnosis {
+    /// One entry per solver, in the order in which [crate::solvers::AllSolvers] tries them
+    pub solvers: Vec<SolverDiagnosis>,
+}
+
+impl Diagnosis {
+    /// The solvers that can be used
+    pub fn usable(&self) -> impl Iterator<Item = &SolverDiagnosis> {
+        self.solvers.iter().filter(|s| s.is_usable())
+    }
+}
+
+/// One line per solver
+impl fmt::Display for Diagnosis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for solver in self.solvers.iter() {
+            writeln!(f, "{}", solver)?;
+        }
+        Ok(())
+    }
+}
+
+/// Check which of the supported solvers (whose feature is enabled) are installed, their version, their license,
+/// and whether they solve a trivial problem. The solvers are checked concurrently.
+///
+/// ```no_run
+/// let diagnosis = lp_solvers::solvers::diagnose();
+/// if diagnosis.usable().next().is_none() {
+///     eprintln!("No solver can be used:\n{}", diagnosis);
+/// }
+/// ```
+// without any solver, the list of solvers to check is empty
+#[cfg_attr(
+    not(any(
+        feature = "cbc",
+        feature = "cplex",
+        feature = "glpk",
+        feature = "gurobi"
+    )),
+    allow(unused_variables)
+)]
+pub fn diagnose() -> Diagnosis {
+    std::thread::scope(|scope| {
+        let handles: Vec<ScopedJoinHandle<SolverDiagnosis>> = vec![
+            #[cfg(feature = "gurobi")]
+            scope.spawn(|| diagnose_solver(&GurobiSolver::new())),
+            #[cfg(feature = "cplex")]
+            scope.spawn(|| diagnose_solver(&Cplex::default())),
+            #[cfg(feature = "cbc")]
+            scope.spawn(|| diagnose_solver(&CbcSolver::new())),
+            #[cfg(feature = "glpk")]
+            scope.spawn(|| diagnose_solver(&GlpkSolver::new())),
+        ];
+        Diagnosis {
+            solvers: handles
+                .into_iter()
+                .map(|handle| handle.join().expect("diagnosing a solver panicked"))
+                .collect(),
+        }
+    })
+}
+
+/// Check a single solver program, see [diagnose]
+pub fn diagnose_solver<S>(solver: &S) -> SolverDiagnosis
+where
+    S: SolverProgram + SolverWithSolutionParsing,
+{
+    let command = solver.command_name();
+    let mut diagnosis = SolverDiagnosis {
+        command: command.to_string(),
+        path: find_program(command),
+        version: None,
+        license_error: None,
+        solve_error: None,
+    };
+    if !diagnosis.is_installed() {
+        return diagnosis;
+    }
+    if solver.version_arguments().is_some() {
+        diagnosis.version = solver.discover().version;
+    }
+    match solver.run(&auto::probe_problem()) {
+        Ok(_) => {}
+        Err(SolverError::License(message)) => diagnosis.license_error = Some(message),
+        Err(SolverError::Other(message)) => diagnosis.solve_error = Some(message),
+    }
+    diagnosis
+}
+
+/// The path of the program that runs for the given command, like the `which` utility
+fn find_program(command: &str) -> Option<PathBuf> {
+    let command = Path::new(command);
+    let candidates: Vec<PathBuf> = if command.components().count() > 1 {
+        vec![command.to_path_buf()]
+    } else {
+        let path = std::env::var_os("PATH").unwrap_or_default();
+        std::env::split_paths(&path)
+            .map(|dir| dir.join(command))
+            .collect()
+    };
+    candidates.into_iter().find_map(|candidate| {
+        if candidate.is_file() {
+            return Some(candidate);
+        }
+        let exe = candidate.with_extension(std::env::consts::EXE_EXTENSION);
+        Some(exe).filter(|exe| cfg!(windows) && exe.is_file())
+    })
+}
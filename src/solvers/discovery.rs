@@ -0,0 +1,171 @@
+//! Remembering which solvers are installed, across runs.
+//!
+//! Checking which solvers are available means running them,
+//! which is slow compared to short-lived programs that solve a single small problem.
+//! A [DiscoveryCache] stores the result in a file, so that it is done only once.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::lp_format::LpProblem;
+use crate::solvers::auto::select_solver;
+use crate::solvers::{Solution, SolverError, SolverList, SolverTrait};
+
+/// What was found out about a solver on this computer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Discovery {
+    /// Whether the solver can be used
+    pub available: bool,
+    /// The version the solver printed, when it was checked by asking for its version
+    pub version: Option<String>,
+}
+
+/// A file where the [Discovery] of each solver program is stored.
+///
+/// Entries are keyed by the program name and a hash of the `PATH` environment variable,
+/// so changing `PATH` triggers a new discovery. Call [DiscoveryCache::clear]
+/// after installing or removing a solver without changing `PATH`.
+///
+/// ```
+/// use lp_solvers::solvers::{AllSolvers, DiscoveryCache};
+///
+/// let solver = AllSolvers::new().with_discovery_cache(DiscoveryCache::in_temp_dir());
+/// ```
+#[derive(Debug)]
+pub struct DiscoveryCache {
+    path: PathBuf,
+    // serializes the writes of the threads probing solvers
+    lock: Mutex<()>,
+}
+
+impl DiscoveryCache {
+    /// A cache stored in the given file, which is created when needed
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        DiscoveryCache {
+            path: path.into(),
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// A cache stored in the temporary directory of the system, shared by all programs
+    pub fn in_temp_dir() -> Self {
+        Self::new(std::env::temp_dir().join("lp-solvers-discovery.tsv"))
+    }
+
+    /// Path of the cache file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// What is known about the given program with the current `PATH`, if anything
+    pub fn get(&self, command_name: &str) -> Option<Discovery> {
+        self.read().remove(&(path_hash(), command_name.to_string()))
+    }
+
+    /// Remember what was found out about the given program with the current `PATH`
+    pub fn insert(&self, command_name: &str, discovery: &Discovery) -> io::Result<()> {
+        let _guard = self.lock.lock().unwrap();
+        let mut entries = self.read();
+        entries.insert((path_hash(), command_name.to_string()), discovery.clone());
+        // write to another file and rename it, so that readers never see a partial file
+        let dir = self.path.parent().unwrap_or_else(|| Path::new("."));
+        let mut file = tempfile::NamedTempFile::new_in(dir)?;
+        for ((hash, command), discovery) in entries.iter() {
+            writeln!(
+                file,
+                "{:016x}\t{}\t{}\t{}",
+                hash,
+                command,
+                discovery.available,
+                discovery.version.as_deref().unwrap_or_default()
+            )?;
+        }
+        file.persist(&self.path).map_err(|e| e.error)?;
+        Ok(())
+    }
+
+    /// Forget everything, for instance after installing a new solver
+    pub fn clear(&self) -> io::Result<()> {
+        match fs::remove_file(&self.path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    /// The discovery of the solver, from the cache if possible
+    pub(crate) fn discover<S: SolverTrait + ?Sized>(&self, solver: &S) -> Discovery {
+        let key = match solver.discovery_key() {
+            Some(key) => key,
+            None => return solver.discover(),
+        };
+        if let Some(discovery) = self.get(key) {
+            return discovery;
+        }
+        let discovery = solver.discover();
+        // failing to write the cache only means discovering again next time
+        let _ = self.insert(key, &discovery);
+        discovery
+    }
+
+    /// Read the entries of the cache file. A missing or invalid file is an empty cache
+    fn read(&self) -> HashMap<(u64, String), Discovery> {
+        let content = fs::read_to_string(&self.path).unwrap_or_default();
+        content
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.splitn(4, '\t');
+                let hash = u64::from_str_radix(fields.next()?, 16).ok()?;
+                let command = fields.next()?.to_string();
+                let available = fields.next()?.parse().ok()?;
+                let version = Some(fields.next()?)
+                    .filter(|v| !v.is_empty())
+                    .map(str::to_string);
+                Some(((hash, command), Discovery { available, version }))
+            })
+            .collect()
+    }
+}
+
+/// FNV-1a hash of the `PATH` environment variable,
+/// which does not change between versions of the compiler like [std::hash::Hash] can
+fn path_hash() -> u64 {
+    let path = std::env::var_os("PATH").unwrap_or_default();
+    path.to_string_lossy()
+        .bytes()
+        .fold(0xcbf29ce484222325, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+        })
+}
+
+/// A list of solvers, like [crate::solvers::AutoSolver],
+/// that remembers which solvers are available in a [DiscoveryCache]
+#[derive(Debug)]
+pub struct CachedAutoSolver<L> {
+    /// The solvers to try, in order
+    pub solvers: L,
+    /// Where to remember which solvers are available
+    pub cache: DiscoveryCache,
+}
+
+impl<L: SolverList> SolverTrait for CachedAutoSolver<L> {
+    fn run<'a, P: LpProblem<'a>>(&self, problem: &'a P) -> Result<Solution, SolverError> {
+        let selection = select_solver(&self.solvers, Some(&self.cache))?;
+        let mut solution = self.solvers.run_at(selection.index, problem)?;
+        solution.solver_selection = Some(selection);
+        Ok(solution)
+    }
+
+    fn run_file(&self, problem_file: &Path) -> Result<Solution, SolverError> {
+        let selection = select_solver(&self.solvers, Some(&self.cache))?;
+        let mut solution = self.solvers.run_file_at(selection.index, problem_file)?;
+        solution.solver_selection = Some(selection);
+        Ok(solution)
+    }
+
+    fn is_available(&self) -> bool {
+        self.solvers.probe_with(Some(&self.cache)).contains(&true)
+    }
+}
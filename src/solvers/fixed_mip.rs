@@ -0,0 +1,167 @@
+//! Dual values for the solutions of mixed integer problems.
+//!
+//! Solvers do not report dual values for MIPs. [FixedMipDualsSolver] solves the problem,
+//! then solves it again with its integer variables fixed to their values in the solution:
+//! what is left is a linear problem, whose dual values are the ones of the MIP solution.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::lp_format::{AsVariable, LpFormatOptions, LpObjective, LpProblem};
+use crate::solvers::{Discovery, Solution, SolverCapabilities, SolverError, SolverTrait, Status};
+
+/// A problem whose integer variables are fixed to given values, and made continuous.
+/// See [FixedMipDualsSolver]
+#[derive(Debug, Clone)]
+pub struct FixedIntegersProblem<'s, P> {
+    base: &'s P,
+    values: HashMap<String, f64>,
+}
+
+impl<'s, P> FixedIntegersProblem<'s, P> {
+    /// The base problem, with its integer variables fixed to the given values.
+    /// The integer variables that have no value are fixed to zero,
+    /// since solvers usually leave them out of solutions.
+    pub fn new(base: &'s P, values: HashMap<String, f64>) -> Self {
+        FixedIntegersProblem { base, values }
+    }
+}
+
+/// A variable of a [FixedIntegersProblem]
+#[derive(Debug, Clone)]
+pub struct FixedVariable<V> {
+    variable: V,
+    value: Option<f64>,
+}
+
+impl<V: AsVariable> AsVariable for FixedVariable<V> {
+    fn name(&self) -> &str {
+        self.variable.name()
+    }
+
+    fn is_integer(&self) -> bool {
+        false
+    }
+
+    fn lower_bound(&self) -> f64 {
+        self.value.unwrap_or_else(|| self.variable.lower_bound())
+    }
+
+    fn upper_bound(&self) -> f64 {
+        self.value.unwrap_or_else(|| self.variable.upper_bound())
+    }
+}
+
+impl<'a, 's: 'a, P: LpProblem<'s>> LpProblem<'a> for FixedIntegersProblem<'s, P> {
+    type Variable = FixedVariable<P::Variable>;
+    type Expression = P::Expression;
+    type Float = P::Float;
+    type ConstraintIterator = P::ConstraintIterator;
+    type VariableIterator = Box<dyn Iterator<Item = Self::Variable> + 'a>;
+
+    fn name(&self) -> &str {
+        self.base.name()
+    }
+
+    fn variables(&'a self) -> Self::VariableIterator {
+        let values = &self.values;
+        Box::new(self.base.variables().map(move |variable| {
+            let value = if variable.is_integer() {
+                Some(values.get(variable.name()).copied().unwrap_or_default())
+            } else {
+                None
+            };
+            FixedVariable { variable, value }
+        }))
+    }
+
+    fn objective(&'a self) -> Self::Expression {
+        self.base.objective()
+    }
+
+    fn sense(&'a self) -> LpObjective {
+        self.base.sense()
+    }
+
+    fn constraints(&'a self) -> Self::ConstraintIterator {
+        self.base.constraints()
+    }
+
+    fn format_options(&self) -> LpFormatOptions {
+        self.base.format_options()
+    }
+}
+
+/// A solver that also returns dual values for the solutions of mixed integer problems.
+///
+/// When the problem has integer variables and the solver finds a solution, the problem is
+/// solved a second time, with its integer variables fixed to their rounded values in the
+/// solution. The dual values of this linear problem are added to the solution.
+/// The solution itself, its status and its report are the ones of the first solve.
+///
+/// When the second solve fails, the solution has no dual values, and a warning tells why.
+///
+/// ```
+/// # #[cfg(feature = "cbc")] {
+/// use lp_solvers::solvers::{CbcSolver, FixedMipDualsSolver};
+/// let solver = FixedMipDualsSolver(CbcSolver::new());
+/// # }
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct FixedMipDualsSolver<S>(pub S);
+
+impl<S: SolverTrait> SolverTrait for FixedMipDualsSolver<S> {
+    fn run<'a, P: LpProblem<'a>>(&self, problem: &'a P) -> Result<Solution, SolverError> {
+        let mut solution = self.0.run(problem)?;
+        let has_solution = matches!(solution.status, Status::Optimal | Status::SubOptimal);
+        if !has_solution || !problem.variables().any(|v| v.is_integer()) {
+            return Ok(solution);
+        }
+        let values: HashMap<String, f64> = problem
+            .variables()
+            .filter(|v| v.is_integer())
+            .map(|v| {
+                let value = solution.value(v.name()).unwrap_or_default().round();
+                (v.name().to_string(), value)
+            })
+            .collect();
+        let fixed = FixedIntegersProblem::new(problem, values);
+        match self.0.run(&fixed) {
+            Ok(lp) if lp.status == Status::Optimal => solution.dual_values = lp.dual_values,
+            Ok(lp) => solution.warnings.push(
+                format!(
+                    "No dual values: the problem with fixed integer variables is {}",
+                    lp.status
+                )
+                .into(),
+            ),
+            Err(e) => solution.warnings.push(
+                format!(
+                    "No dual values: the problem with fixed integer variables could not be solved: {}",
+                    e
+                )
+                .into(),
+            ),
+        }
+        Ok(solution)
+    }
+
+    /// A problem file is given to the solver as it is, without dual values for MIPs
+    fn run_file(&self, problem_file: &Path) -> Result<Solution, SolverError> {
+        self.0.run_file(problem_file)
+    }
+
+    fn is_available(&self) -> bool {
+        self.0.is_available()
+    }
+
+    fn discover(&self) -> Discovery {
+        self.0.discover()
+    }
+
+    fn discovery_key(&self) -> Option<&str> {
+        self.0.discovery_key()
+    }
+}
+
+forward_capabilities!(FixedMipDualsSolver<S>, |this| &this.0);
@@ -1,15 +1,18 @@
 //! GNU's glpk solver
 //! [https://www.gnu.org/software/glpk/]
 //!
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::ffi::OsString;
 use std::fs::File;
-use std::io::{BufRead, BufReader, Error};
+use std::io::{BufRead, BufReader};
 use std::path::{Path, PathBuf};
 
 use crate::lp_format::*;
 use crate::solvers::{
-    Solution, SolverProgram, SolverWithSolutionParsing, Status, WithMaxSeconds, WithMipGap,
+    open_solution_file, ApplyOptions, MissingVariables, ParseError, ParserMode, Solution,
+    SolutionValue, SolverCapabilities, SolverOptions, SolverProgram, SolverWithSolutionParsing,
+    Status, StdoutStatusRules, VariableFilter, WithMaxSeconds, WithMipGap, WithMissingVariables,
+    WithParserMode, WithPresolve, WithStatusRules,
 };
 
 /// glpk solver
@@ -20,6 +23,50 @@ pub struct GlpkSolver {
     temp_solution_file: Option<PathBuf>,
     seconds: Option<u32>,
     mipgap: Option<f32>,
+    presolve: Option<bool>,
+    parser_mode: ParserMode,
+    missing_variables: MissingVariables,
+    output_files: Vec<(GlpkOutput, PathBuf)>,
+    solution_format: GlpkSolutionFormat,
+    status_rules: StdoutStatusRules,
+}
+
+/// A file that glpsol can write besides the solution, see [GlpkSolver::with_output_file]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GlpkOutput {
+    /// The problem, as glpk understood it, in the .lp format (`--wlp`)
+    Lp,
+    /// The problem in the fixed MPS format (`--wmps`)
+    Mps,
+    /// The problem in the free MPS format (`--wfreemps`)
+    FreeMps,
+    /// A copy of the terminal output (`--log`)
+    Log,
+}
+
+impl GlpkOutput {
+    /// The glpsol option that writes this file
+    pub fn option(&self) -> &'static str {
+        match self {
+            GlpkOutput::Lp => "--wlp",
+            GlpkOutput::Mps => "--wmps",
+            GlpkOutput::FreeMps => "--wfreemps",
+            GlpkOutput::Log => "--log",
+        }
+    }
+}
+
+/// The format of the solution file that glpsol writes, see [GlpkSolver::with_solution_format]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GlpkSolutionFormat {
+    /// The human-readable report (`-o`). Its layout changes across glpk versions,
+    /// and long names are cut, which the parser works around.
+    #[default]
+    Report,
+    /// The plain text solution (`--write`), made to be read by programs.
+    /// Rows and columns are numbered instead of named: reading it needs the problem,
+    /// whose .lp file gives their order. See [problem_columns]
+    Plain,
 }
 
 impl Default for GlpkSolver {
@@ -37,6 +84,12 @@ impl GlpkSolver {
             temp_solution_file: None,
             seconds: None,
             mipgap: None,
+            presolve: None,
+            parser_mode: ParserMode::Strict,
+            missing_variables: MissingVariables::default(),
+            output_files: vec![],
+            solution_format: GlpkSolutionFormat::default(),
+            status_rules: StdoutStatusRules::new(),
         }
     }
     /// Set the glpk command name
@@ -47,9 +100,17 @@ impl GlpkSolver {
             temp_solution_file: self.temp_solution_file.clone(),
             seconds: self.seconds,
             mipgap: self.mipgap,
+            presolve: self.presolve,
+            parser_mode: self.parser_mode,
+            missing_variables: self.missing_variables,
+            output_files: self.output_files.clone(),
+            solution_format: self.solution_format,
+            status_rules: self.status_rules.clone(),
         }
     }
-    /// Set the temporary solution file to use
+    /// Set the temporary solution file to use.
+    /// The path can contain [RUN_ID_PLACEHOLDER](crate::solvers::RUN_ID_PLACEHOLDER), so that
+    /// solves running at the same time do not write to the same file.
     pub fn with_temp_solution_file(&self, temp_solution_file: String) -> GlpkSolver {
         GlpkSolver {
             name: self.name.clone(),
@@ -57,78 +118,691 @@ impl GlpkSolver {
             temp_solution_file: Some(temp_solution_file.into()),
             seconds: self.seconds,
             mipgap: self.mipgap,
+            presolve: self.presolve,
+            parser_mode: self.parser_mode,
+            missing_variables: self.missing_variables,
+            output_files: self.output_files.clone(),
+            solution_format: self.solution_format,
+            status_rules: self.status_rules.clone(),
+        }
+    }
+
+    /// Also write the given file, such as the problem translated by glpk,
+    /// to compare it with what other solvers read. The files that were written
+    /// are listed in [crate::solvers::SolveReport::output_files].
+    ///
+    /// ```
+    /// use lp_solvers::solvers::{GlpkOutput, GlpkSolver};
+    ///
+    /// let solver = GlpkSolver::new()
+    ///     .with_output_file(GlpkOutput::Lp, "translated.lp")
+    ///     .with_output_file(GlpkOutput::Log, "glpk.log");
+    /// ```
+    pub fn with_output_file(&self, output: GlpkOutput, path: impl Into<PathBuf>) -> GlpkSolver {
+        let mut solver = self.clone();
+        solver.output_files.retain(|(o, _)| *o != output);
+        solver.output_files.push((output, path.into()));
+        solver
+    }
+
+    /// The path where the given file is written, if it is
+    pub fn output_file(&self, output: GlpkOutput) -> Option<&Path> {
+        self.output_files
+            .iter()
+            .find(|(o, _)| *o == output)
+            .map(|(_, path)| path.as_path())
+    }
+
+    /// The format of the solution file that glpsol writes
+    pub fn solution_format(&self) -> GlpkSolutionFormat {
+        self.solution_format
+    }
+
+    /// Choose the format of the solution file that glpsol writes.
+    /// A solution in the [plain](GlpkSolutionFormat::Plain) format can only be read
+    /// with the problem, which [SolverTrait::run](crate::solvers::SolverTrait::run) passes.
+    ///
+    /// ```
+    /// use lp_solvers::solvers::{GlpkSolutionFormat, GlpkSolver};
+    ///
+    /// let solver = GlpkSolver::new().with_solution_format(GlpkSolutionFormat::Plain);
+    /// ```
+    pub fn with_solution_format(&self, format: GlpkSolutionFormat) -> GlpkSolver {
+        GlpkSolver {
+            solution_format: format,
+            ..self.clone()
         }
     }
+
+    fn read_filtered<'a, P: LpProblem<'a>>(
+        &self,
+        f: &File,
+        problem: Option<&'a P>,
+        filter: VariableFilter,
+    ) -> Result<Solution, String> {
+        let mut solution = match self.solution_format {
+            GlpkSolutionFormat::Report => {
+                parse_report(&read_lines(BufReader::new(f))?, self.parser_mode, filter)?
+            }
+            GlpkSolutionFormat::Plain => {
+                let problem = problem.ok_or_else(|| {
+                    "The plain glpk solution format numbers the columns instead of naming them: \
+                     the problem is needed to read it"
+                        .to_string()
+                })?;
+                let columns = problem_columns(problem)?;
+                let naming = problem.format_options().constraint_naming;
+                parse_plain(
+                    BufReader::new(f),
+                    &columns,
+                    &naming,
+                    self.parser_mode,
+                    filter,
+                )?
+            }
+        };
+        self.missing_variables
+            .apply(&mut solution, problem, filter)?;
+        Ok(solution)
+    }
 }
 
 impl SolverWithSolutionParsing for GlpkSolver {
     fn read_specific_solution<'a, P: LpProblem<'a>>(
         &self,
         f: &File,
-        _problem: Option<&'a P>,
+        problem: Option<&'a P>,
+    ) -> Result<Solution, String> {
+        self.read_filtered(f, problem, VariableFilter::default())
+    }
+
+    fn read_solution_filtered<'a, P: LpProblem<'a>>(
+        &self,
+        temp_solution_file: &Path,
+        problem: Option<&'a P>,
+        variables: &HashSet<String>,
     ) -> Result<Solution, String> {
-        fn read_size(line: Option<Result<String, Error>>) -> Result<usize, String> {
-            match line {
-                Some(Ok(l)) => match l.split_whitespace().nth(1) {
-                    Some(value) => match value.parse::<usize>() {
-                        Ok(v) => Ok(v),
-                        _ => Err("Incorrect solution format".to_string()),
-                    },
-                    _ => Err("Incorrect solution format".to_string()),
-                },
-                _ => Err("Incorrect solution format".to_string()),
+        let f = open_solution_file(temp_solution_file)?;
+        self.read_filtered(&f, problem, VariableFilter::only(variables))
+    }
+}
+
+/// Parse a solution report written by glpk (`glpsol -o`)
+pub fn parse_glpk_solution(reader: impl BufRead, mode: ParserMode) -> Result<Solution, ParseError> {
+    parse_report(&read_lines(reader)?, mode, VariableFilter::default())
+}
+
+/// Parse a solution report written by glpk, already in memory.
+/// Bytes that are not valid UTF-8 are replaced, see [String::from_utf8_lossy]
+pub fn parse_glpk_solution_bytes(bytes: &[u8], mode: ParserMode) -> Result<Solution, ParseError> {
+    let bytes = bytes.strip_suffix(b"\n").unwrap_or(bytes);
+    let lines: Vec<String> = bytes
+        .split(|&b| b == b'\n')
+        .map(|line| {
+            let line = line.strip_suffix(b"\r").unwrap_or(line);
+            String::from_utf8_lossy(line).into_owned()
+        })
+        .collect();
+    parse_report(&lines, mode, VariableFilter::default())
+}
+
+/// The values of the `Status:` line of glpk solution reports, and the status they stand for
+pub const GLPK_SOLUTION_STATUSES: &[(&str, Status)] = &[
+    ("OPTIMAL", Status::Optimal),
+    ("INTEGER OPTIMAL", Status::Optimal),
+    ("FEASIBLE", Status::SubOptimal),
+    ("INTEGER NON-OPTIMAL", Status::SubOptimal),
+    ("INFEASIBLE (FINAL)", Status::Infeasible),
+    ("INTEGER EMPTY", Status::Infeasible),
+    ("UNBOUNDED", Status::Unbounded),
+    ("INTEGER UNDEFINED", Status::Unbounded),
+    ("UNDEFINED", Status::NotSolved),
+];
+
+/// The status of a value of the `Status:` line of a glpk solution report, from
+/// [GLPK_SOLUTION_STATUSES], or `None` if glpk does not write this value
+///
+/// ```
+/// # #[cfg(feature = "glpk")] {
+/// use lp_solvers::solvers::{glpk_solution_status, Status};
+///
+/// assert_eq!(glpk_solution_status("INTEGER OPTIMAL"), Some(Status::Optimal));
+/// assert_eq!(glpk_solution_status("SOMETHING ELSE"), None);
+/// # }
+/// ```
+pub fn glpk_solution_status(value: &str) -> Option<Status> {
+    GLPK_SOLUTION_STATUSES
+        .iter()
+        .find(|(text, _)| *text == value.trim())
+        .map(|(_, status)| status.clone())
+}
+
+fn read_lines(reader: impl BufRead) -> Result<Vec<String>, String> {
+    reader
+        .lines()
+        .collect::<Result<Vec<String>, _>>()
+        .map_err(|e| format!("Unable to read solution: {}", e))
+}
+
+/// Parse the human-readable report written by `glpsol -o`.
+///
+/// Sections and fields are located using their headers, and the separator line below them,
+/// so that the parser does not depend on the length of the problem and variable names.
+fn parse_report(
+    lines: &[String],
+    mode: ParserMode,
+    filter: VariableFilter,
+) -> Result<Solution, ParseError> {
+    let header_value = |key: &str| {
+        lines
+            .iter()
+            .find_map(|l| l.strip_prefix(key))
+            .map(|value| value.trim())
+    };
+    let status = match header_value("Status:") {
+        Some(value) => glpk_solution_status(value)
+            .ok_or_else(|| "Incorrect solution format: Unknown solution status".to_string())?,
+        None => return Err("Incorrect solution format: No solution status found".into()),
+    };
+    let col_count = header_value("Columns:")
+        .and_then(|v| v.split_whitespace().next())
+        .and_then(|v| v.parse::<usize>().ok())
+        .ok_or_else(|| "Incorrect solution format: No column count found".to_string())?;
+    // "Objective:  obj = 100 (MAXimum)"
+    let objective = header_value("Objective:")
+        .and_then(|v| v.split('=').nth(1))
+        .and_then(|v| v.split_whitespace().next())
+        .and_then(|v| v.parse::<f64>().ok());
+
+    let mut solution = Solution::new(status, HashMap::with_capacity(filter.capacity(col_count)));
+    solution.objective = objective;
+
+    let column_section = find_table(lines, "Column name", "Activity")
+        .ok_or_else(|| "Incorrect solution format: No column section found".to_string())?;
+    let mut warnings = vec![];
+    let mut columns_read = 0;
+    for row in column_section.rows() {
+        match row.and_then(|(name, value)| Ok((name, parse_value(&value)?))) {
+            Ok((name, value)) => {
+                columns_read += 1;
+                if filter.keeps(&name) {
+                    solution.results.insert(name, value);
+                }
+            }
+            Err(e) => mode.malformed(None, e, &mut warnings)?,
+        }
+    }
+    if columns_read < col_count {
+        mode.malformed(
+            None,
+            "Incorrect solution format: Not all columns are present".to_string(),
+            &mut warnings,
+        )?;
+    }
+
+    // Only continuous problems have a "Marginal" field
+    if let Some(marginals) = find_table(lines, "Row name", "Marginal") {
+        for row in marginals.rows() {
+            let dual = row.and_then(|(name, value)| match value.as_str() {
+                // basic rows have no marginal
+                "" => Ok(None),
+                "< eps" => Ok(Some((name, 0.))),
+                value => Ok(Some((name, parse_value(value)?))),
+            });
+            match dual {
+                Ok(Some((name, value))) => {
+                    solution.dual_values.insert(name, value);
+                }
+                Ok(None) => {}
+                Err(e) => mode.malformed(None, e, &mut warnings)?,
             }
         }
-        let mut vars_value: HashMap<_, _> = HashMap::new();
+    }
+    solution.warnings = warnings;
+    Ok(solution)
+}
 
-        let file = BufReader::new(f);
+/// Parse a solution written by glpk in the plain text format (`glpsol --write`), whose
+/// columns are named after `columns`, in the order glpk numbered them, and whose rows
+/// are named with `naming`. See [problem_columns] and [lp_file_columns]
+///
+/// ```
+/// # #[cfg(feature = "glpk")] {
+/// use lp_solvers::lp_format::ConstraintNaming;
+/// use lp_solvers::solvers::{lp_file_columns, parse_glpk_plain_solution, ParserMode, Status};
+///
+/// let lp = "\\ pb\nMaximize\n  obj: 3 y + 2 x\n\nSubject To\n  c0: x + y <= 4\n\nBounds\n  y <= 3\nEnd\n";
+/// let columns = lp_file_columns(lp);
+/// assert_eq!(columns, vec!["y", "x"]);
+///
+/// let sol = "c Status:     OPTIMAL\ns bas 1 2 f f 11\ni 1 u 4 2\nj 1 u 3 1\nj 2 b 1 0\ne o f\n";
+/// let naming = ConstraintNaming::default();
+/// let solution =
+///     parse_glpk_plain_solution(sol.as_bytes(), &columns, &naming, ParserMode::Strict).unwrap();
+/// assert_eq!(solution.status, Status::Optimal);
+/// assert_eq!(solution.objective, Some(11.));
+/// assert_eq!(solution.results["x"], 1.);
+/// assert_eq!(solution.dual_values["c0"], 2.);
+/// # }
+/// ```
+pub fn parse_glpk_plain_solution(
+    reader: impl BufRead,
+    columns: &[String],
+    naming: &ConstraintNaming,
+    mode: ParserMode,
+) -> Result<Solution, ParseError> {
+    parse_plain(reader, columns, naming, mode, VariableFilter::default())
+}
+
+/// Parse a solution written by glpk in the plain text format, already in memory.
+/// See [parse_glpk_plain_solution]. Bytes that are not valid UTF-8 are replaced,
+/// see [String::from_utf8_lossy]
+pub fn parse_glpk_plain_solution_bytes(
+    bytes: &[u8],
+    columns: &[String],
+    naming: &ConstraintNaming,
+    mode: ParserMode,
+) -> Result<Solution, ParseError> {
+    let text = String::from_utf8_lossy(bytes);
+    parse_plain(
+        text.as_bytes(),
+        columns,
+        naming,
+        mode,
+        VariableFilter::default(),
+    )
+}
 
-        let mut iter = file.lines();
-        let row = match read_size(iter.nth(1)) {
-            Ok(value) => value,
-            Err(e) => return Err(e),
+/// The names of the columns of a problem, in the order glpk numbers them when it reads
+/// the .lp file [LpProblem::write_lp_to] writes: the order in which they first appear in the
+/// objective, in the constraints, and then in the `Bounds`, `Generals` and `Binaries` sections.
+///
+/// Fails when an expression of the problem does not give its terms
+/// (see [WriteToLpFileFormat::linear_terms]), since the order cannot be known then.
+///
+/// ```
+/// # #[cfg(feature = "glpk")] {
+/// use lp_solvers::lp_format::LpObjective;
+/// use lp_solvers::problem::{LinearExpression, Problem, Variable};
+/// use lp_solvers::solvers::problem_columns;
+///
+/// let variable = |name: &str| Variable {
+///     name: name.to_string(),
+///     is_integer: false,
+///     lower_bound: 0.,
+///     upper_bound: 4.,
+/// };
+/// let problem: Problem<LinearExpression, Variable> = Problem {
+///     name: "pb".to_string(),
+///     sense: LpObjective::Maximize,
+///     objective: LinearExpression { coefficients: vec![("y[2]".to_string(), 3.)] },
+///     variables: vec![variable("x/1"), variable("y[2]"), variable("z")],
+///     constraints: vec![],
+/// };
+/// assert_eq!(problem_columns(&problem).unwrap(), vec!["y[2]", "x/1", "z"]);
+/// # }
+/// ```
+pub fn problem_columns<'a, P: LpProblem<'a>>(problem: &'a P) -> Result<Vec<String>, String> {
+    let unknown_order = || {
+        "The order of the columns is unknown, since an expression of the problem \
+         does not give its terms: the plain glpk solution format cannot be read"
+            .to_string()
+    };
+    let mut seen = HashSet::new();
+    let mut columns = vec![];
+    let mut add = |name: &str| {
+        if seen.insert(name.to_string()) {
+            columns.push(name.to_string());
+        }
+    };
+    let objective = problem.objective();
+    for (name, _) in objective.linear_terms().ok_or_else(unknown_order)? {
+        add(name);
+    }
+    for constraint in problem.constraints() {
+        for (name, _) in constraint.lhs.linear_terms().ok_or_else(unknown_order)? {
+            add(name);
+        }
+    }
+    // the variables that appear in no expression are numbered when they are first
+    // declared, in the sections written by `write_lp_to`
+    let options = problem.format_options();
+    let mut integers = vec![];
+    let mut binaries = vec![];
+    for variable in problem.variables() {
+        if options.writes_bounds(&variable) {
+            add(variable.name());
+        }
+        if options.is_binary(&variable) {
+            binaries.push(variable);
+        } else if variable.is_integer() {
+            integers.push(variable);
+        }
+    }
+    for variable in integers.iter().chain(binaries.iter()) {
+        add(variable.name());
+    }
+    Ok(columns)
+}
+
+/// The names of the columns of a problem in the .lp format, in the order glpk numbers them:
+/// the order in which they first appear in the file.
+///
+/// Names are found by splitting the text on operators, so names that contain `/`, `[`, `]`,
+/// `^` or `*` are not found correctly: prefer [problem_columns] when the problem is known.
+pub fn lp_file_columns(lp: &str) -> Vec<String> {
+    let mut seen = HashSet::new();
+    let mut columns = vec![];
+    for line in lp.lines() {
+        // comments start with a backslash, and names cannot contain ':', which ends the label
+        // of the objective and of the constraints
+        let line = line.split('\\').next().unwrap_or_default();
+        let terms = line.rsplit(':').next().unwrap_or_default();
+        let tokens = terms.split(|c: char| c.is_whitespace() || "+-*/^<>=[]".contains(c));
+        for token in tokens {
+            let is_name = token
+                .chars()
+                .next()
+                .is_some_and(|c| !c.is_ascii_digit() && c != '.');
+            let is_keyword = || {
+                crate::util::LP_KEYWORDS
+                    .iter()
+                    .any(|keyword| keyword.eq_ignore_ascii_case(token))
+            };
+            if is_name && !is_keyword() && seen.insert(token) {
+                columns.push(token.to_string());
+            }
+        }
+    }
+    columns
+}
+
+fn parse_plain(
+    reader: impl BufRead,
+    columns: &[String],
+    naming: &ConstraintNaming,
+    mode: ParserMode,
+    filter: VariableFilter,
+) -> Result<Solution, ParseError> {
+    let mut solution: Option<(PlainKind, Solution)> = None;
+    let mut warnings = vec![];
+    for (idx, line) in reader.lines().enumerate() {
+        let line = line.map_err(|e| format!("Unable to read solution: {}", e))?;
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        match fields.first().copied() {
+            None | Some("c") => {}
+            Some("e") => break,
+            Some("s") => {
+                let (kind, status, column_count, objective) =
+                    parse_plain_header(&fields).map_err(|e| ParseError::at(idx + 1, e))?;
+                if column_count != columns.len() {
+                    return Err(ParseError::at(
+                        idx + 1,
+                        format!(
+                            "Incorrect solution format: {} columns in the solution, but {} in the problem",
+                            column_count,
+                            columns.len()
+                        ),
+                    ));
+                }
+                let mut parsed = Solution::new(
+                    status,
+                    HashMap::with_capacity(filter.capacity(column_count)),
+                );
+                parsed.objective = Some(objective);
+                parsed.constraint_naming = naming.clone();
+                solution = Some((kind, parsed));
+            }
+            Some(line_type @ ("i" | "j")) => {
+                let (kind, solution) = solution.as_mut().ok_or_else(|| {
+                    ParseError::at(
+                        idx + 1,
+                        "Incorrect solution format: values before the solution line",
+                    )
+                })?;
+                let (number, value, dual) = match kind.parse_values(&fields) {
+                    Ok(values) => values,
+                    Err(e) => {
+                        mode.malformed(Some(idx + 1), e, &mut warnings)?;
+                        continue;
+                    }
+                };
+                if line_type == "i" {
+                    if let Some(dual) = dual {
+                        let name = naming.name(ConsId::from_index(number - 1));
+                        solution.dual_values.insert(name, dual);
+                    }
+                } else if let Some(name) = columns.get(number - 1) {
+                    if filter.keeps(name) {
+                        solution.results.insert(name.clone(), value);
+                    }
+                } else {
+                    let e = format!("Incorrect solution format: no column {}", number);
+                    mode.malformed(Some(idx + 1), e, &mut warnings)?;
+                }
+            }
+            Some(other) => {
+                let e = format!("Incorrect solution format: unknown line type {}", other);
+                mode.malformed(Some(idx + 1), e, &mut warnings)?
+            }
+        }
+    }
+    let (_, mut solution) = solution.ok_or("Incorrect solution format: No solution line found")?;
+    solution.warnings = warnings;
+    Ok(solution)
+}
+
+/// The kind of solution in a plain glpk solution file, which gives the fields of its lines
+#[derive(Debug, Clone, Copy)]
+enum PlainKind {
+    /// simplex: number, status, value, dual value
+    Basic,
+    /// interior point: number, value, dual value
+    Interior,
+    /// integer: number, value
+    Integer,
+}
+
+impl PlainKind {
+    /// The number of a row or a column, its value and its dual value,
+    /// from the fields of its line
+    fn parse_values(&self, fields: &[&str]) -> Result<(usize, f32, Option<f32>), String> {
+        let (value_idx, dual_idx) = match self {
+            PlainKind::Basic => (3, Some(4)),
+            PlainKind::Interior => (2, Some(3)),
+            PlainKind::Integer => (2, None),
         };
-        let col = match read_size(iter.next()) {
-            Ok(value) => value,
-            Err(e) => return Err(e),
+        let field = |idx: usize| {
+            fields
+                .get(idx)
+                .copied()
+                .ok_or_else(|| "Incorrect solution format: missing field".to_string())
         };
-        let status = match iter.nth(1) {
-            Some(Ok(status_line)) => match &status_line[12..] {
-                "INTEGER OPTIMAL" | "OPTIMAL" => Status::Optimal,
-                "INTEGER NON-OPTIMAL" | "FEASIBLE" => Status::SubOptimal,
-                "INFEASIBLE (FINAL)" | "INTEGER EMPTY" => Status::Infeasible,
-                "UNDEFINED" => Status::NotSolved,
-                "INTEGER UNDEFINED" | "UNBOUNDED" => Status::Unbounded,
-                _ => return Err("Incorrect solution format: Unknown solution status".to_string()),
-            },
-            _ => return Err("Incorrect solution format: No solution status found".to_string()),
+        let number = field(1)?
+            .parse::<usize>()
+            .ok()
+            .filter(|&n| n > 0)
+            .ok_or_else(|| "Incorrect solution format: invalid number".to_string())?;
+        let value = parse_value(field(value_idx)?)?;
+        let dual = match dual_idx {
+            Some(idx) => Some(parse_value(field(idx)?)?),
+            None => None,
         };
-        let mut result_lines = iter.skip(row + 7);
-        for _ in 0..col {
-            let line = match result_lines.next() {
-                Some(Ok(l)) => l,
-                _ => {
-                    return Err("Incorrect solution format: Not all columns are present".to_string())
-                }
+        Ok((number, value, dual))
+    }
+}
+
+/// The kind, status, number of columns and objective value of the `s` line of a plain solution
+fn parse_plain_header(fields: &[&str]) -> Result<(PlainKind, Status, usize, f64), String> {
+    let invalid = || format!("Incorrect solution format: {}", fields.join(" "));
+    let (kind, status, objective) = match fields {
+        ["s", "bas", _, _, primal, dual, objective] => {
+            let status = match (*primal, *dual) {
+                ("f", "f") => Status::Optimal,
+                ("f", "n") => Status::Unbounded,
+                ("f", _) => Status::SubOptimal,
+                ("n", _) => Status::Infeasible,
+                _ => Status::NotSolved,
             };
-            let result_line: Vec<_> = line.split_whitespace().collect();
-            if result_line.len() >= 4 {
-                match result_line[3].parse::<f32>() {
-                    Ok(n) => {
-                        vars_value.insert(result_line[1].to_string(), n);
-                    }
-                    Err(e) => return Err(e.to_string()),
-                }
+            (PlainKind::Basic, status, objective)
+        }
+        ["s", kind @ ("ipt" | "mip"), _, _, status, objective] => {
+            let status = match *status {
+                "o" => Status::Optimal,
+                "f" => Status::SubOptimal,
+                "n" => Status::Infeasible,
+                _ => Status::NotSolved,
+            };
+            let kind = if *kind == "ipt" {
+                PlainKind::Interior
             } else {
-                return Err(
-                    "Incorrect solution format: Column specification has to few fields".to_string(),
-                );
+                PlainKind::Integer
+            };
+            (kind, status, objective)
+        }
+        _ => return Err(invalid()),
+    };
+    let column_count = fields[3].parse().map_err(|_| invalid())?;
+    let objective = objective.parse().map_err(|_| invalid())?;
+    Ok((kind, status, column_count, objective))
+}
+
+fn parse_value(value: &str) -> Result<f32, String> {
+    f32::parse_value(value.as_bytes()).map_err(|e| format!("Incorrect solution format: {}", e))
+}
+
+/// A table in the glpsol report, such as the rows or the columns section
+struct Table<'a> {
+    /// lines following the separator
+    lines: &'a [String],
+    /// byte range of the field to read
+    value_span: (usize, usize),
+}
+
+/// Locate the table whose header contains `header_text`, to read the field titled `value_column`
+fn find_table<'a>(lines: &'a [String], header_text: &str, value_column: &str) -> Option<Table<'a>> {
+    let idx = lines.iter().position(|l| l.contains(header_text))?;
+    let spans = field_spans(lines.get(idx + 1)?);
+    let value_span = span_of(&lines[idx], &spans, value_column)?;
+    Some(Table {
+        lines: &lines[idx + 2..],
+        value_span,
+    })
+}
+
+/// Byte ranges of the fields, given the separator line ("------ ------------ -- ...")
+fn field_spans(separator: &str) -> Vec<(usize, usize)> {
+    let mut spans = vec![];
+    let mut start = None;
+    for (i, c) in separator
+        .char_indices()
+        .chain(std::iter::once((separator.len(), ' ')))
+    {
+        match (c, start) {
+            ('-', None) => start = Some(i),
+            ('-', Some(_)) => {}
+            (_, Some(s)) => {
+                spans.push((s, i));
+                start = None;
             }
+            (_, None) => {}
         }
-        Ok(Solution::new(status, vars_value))
+    }
+    spans
+}
+
+/// The span that contains the given header text
+fn span_of(header: &str, spans: &[(usize, usize)], text: &str) -> Option<(usize, usize)> {
+    let pos = header.find(text)?;
+    spans
+        .iter()
+        .copied()
+        .find(|&(start, end)| start <= pos && pos < end)
+}
+
+impl<'a> Table<'a> {
+    /// Iterate over the (name, value) pairs of the table, until its first empty line
+    fn rows(&self) -> impl Iterator<Item = Result<(String, String), String>> + '_ {
+        let mut lines = self.lines.iter().map(Ok);
+        std::iter::from_fn(move || next_row(&mut lines, self.value_span))
     }
 }
 
+/// Read the next (name, value) pair of a table, or None at the end of the table
+fn next_row<L: AsRef<str>>(
+    lines: &mut impl Iterator<Item = Result<L, String>>,
+    value_span: (usize, usize),
+) -> Option<Result<(String, String), String>> {
+    let line = match lines.next()? {
+        Ok(line) => line,
+        Err(e) => return Some(Err(e)),
+    };
+    let line = line.as_ref();
+    let mut tokens = line.split_whitespace();
+    let number = tokens.next()?;
+    number.parse::<usize>().ok()?;
+    let name = match tokens.next() {
+        Some(name) => name,
+        None => return Some(Err("Incorrect solution format: missing name".to_string())),
+    };
+    let number_end = line.find(number).unwrap_or(0) + number.len();
+    let name_end = number_end + line[number_end..].find(name).unwrap_or(0) + name.len();
+    // long names are printed alone on their line, and the fields on the next one
+    let next_line;
+    let fields_line = if line[name_end..].trim().is_empty() {
+        match lines.next() {
+            Some(Ok(l)) => {
+                next_line = l;
+                next_line.as_ref()
+            }
+            Some(Err(e)) => return Some(Err(e)),
+            None => {
+                return Some(Err(format!(
+                    "Incorrect solution format: no values for {}",
+                    name
+                )))
+            }
+        }
+    } else {
+        line
+    };
+    let (start, end) = value_span;
+    let value = fields_line
+        .get(start.min(fields_line.len())..end.min(fields_line.len()))
+        .unwrap_or("")
+        .trim();
+    Some(Ok((name.to_string(), value.to_string())))
+}
+
+/// Iterate over the variable values in a glpk solution report, without storing them
+pub fn read_glpk_solution_iter(
+    path: &Path,
+) -> Result<impl Iterator<Item = Result<(String, f64), String>>, String> {
+    let mut lines = BufReader::new(open_solution_file(path)?)
+        .lines()
+        .map(|l| l.map_err(|e| format!("Unable to read solution: {}", e)));
+    let header = loop {
+        match lines.next() {
+            Some(line) if line.as_ref().map_or(true, |l| l.contains("Column name")) => break line?,
+            Some(_) => {}
+            None => return Err("Incorrect solution format: No column section found".to_string()),
+        }
+    };
+    let separator = lines
+        .next()
+        .ok_or_else(|| "Incorrect solution format: No column section found".to_string())??;
+    let value_span = span_of(&header, &field_spans(&separator), "Activity")
+        .ok_or_else(|| "Incorrect solution format: No activity field found".to_string())?;
+    Ok(std::iter::from_fn(move || {
+        let row = next_row(&mut lines, value_span)?;
+        Some(row.and_then(|(name, value)| {
+            let value = f64::parse_value(value.as_bytes())
+                .map_err(|e| format!("Incorrect solution format: {}", e))?;
+            Ok((name, value))
+        }))
+    }))
+}
+
 impl WithMaxSeconds<GlpkSolver> for GlpkSolver {
     fn max_seconds(&self) -> Option<u32> {
         self.seconds
@@ -159,16 +833,111 @@ impl WithMipGap<GlpkSolver> for GlpkSolver {
     }
 }
 
+impl WithParserMode<GlpkSolver> for GlpkSolver {
+    fn parser_mode(&self) -> ParserMode {
+        self.parser_mode
+    }
+    fn with_parser_mode(&self, mode: ParserMode) -> GlpkSolver {
+        GlpkSolver {
+            parser_mode: mode,
+            ..(*self).clone()
+        }
+    }
+}
+
+impl WithMissingVariables<GlpkSolver> for GlpkSolver {
+    fn missing_variables(&self) -> MissingVariables {
+        self.missing_variables
+    }
+    fn with_missing_variables(&self, policy: MissingVariables) -> GlpkSolver {
+        GlpkSolver {
+            missing_variables: policy,
+            ..(*self).clone()
+        }
+    }
+}
+
+impl WithPresolve<GlpkSolver> for GlpkSolver {
+    fn presolve(&self) -> Option<bool> {
+        self.presolve
+    }
+
+    fn with_presolve(&self, presolve: bool) -> GlpkSolver {
+        GlpkSolver {
+            presolve: Some(presolve),
+            ..(*self).clone()
+        }
+    }
+}
+
+impl WithStatusRules<GlpkSolver> for GlpkSolver {
+    fn status_rules(&self) -> &StdoutStatusRules {
+        &self.status_rules
+    }
+
+    fn with_status_rules(&self, rules: StdoutStatusRules) -> GlpkSolver {
+        GlpkSolver {
+            status_rules: rules,
+            ..(*self).clone()
+        }
+    }
+}
+
+/// glpsol has no threads, no random seed and no log levels, so those options are ignored
+impl ApplyOptions for GlpkSolver {
+    fn apply_options(&self, options: &SolverOptions) -> Result<GlpkSolver, String> {
+        let mut solver = self.clone();
+        if let Some(mipgap) = options.mip_gap {
+            solver = solver.with_mip_gap(mipgap)?;
+        }
+        solver.seconds = options.time_limit.or(solver.seconds);
+        solver.presolve = options.presolve.or(solver.presolve);
+        Ok(solver)
+    }
+}
+
+/// Glpk only handles linear problems with integer variables
+impl SolverCapabilities for GlpkSolver {
+    fn supports_mip(&self) -> bool {
+        true
+    }
+
+    fn supports_quadratic(&self) -> bool {
+        false
+    }
+
+    fn supports_sos(&self) -> bool {
+        false
+    }
+
+    fn supports_indicators(&self) -> bool {
+        false
+    }
+
+    fn supports_warm_start(&self) -> bool {
+        false
+    }
+}
+
 impl SolverProgram for GlpkSolver {
     fn command_name(&self) -> &str {
         &self.command_name
     }
 
     fn arguments(&self, lp_file: &Path, solution_file: &Path) -> Vec<OsString> {
+        // glpsol does not look at the extension of the problem file
+        let format = match lp_file.extension() {
+            Some(ext) if ext == "mps" => "--freemps",
+            _ => "--lp",
+        };
+        let solution_option = match self.solution_format {
+            GlpkSolutionFormat::Report => "-o",
+            GlpkSolutionFormat::Plain => "--write",
+        };
         let mut args = vec![
-            "--lp".into(),
+            format.into(),
             lp_file.into(),
-            "-o".into(),
+            solution_option.into(),
             solution_file.into(),
         ];
 
@@ -182,17 +951,49 @@ impl SolverProgram for GlpkSolver {
             args.push(mipgap.to_string().into());
         }
 
+        if let Some(presolve) = self.presolve() {
+            args.push(if presolve { "--presol" } else { "--nopresol" }.into());
+        }
+
+        for (output, path) in &self.output_files {
+            args.push(output.option().into());
+            args.push(path.into());
+        }
+
         args
     }
 
+    fn output_files(&self) -> Vec<PathBuf> {
+        self.output_files
+            .iter()
+            .map(|(_, path)| path.clone())
+            .collect()
+    }
+
     fn preferred_temp_solution_file(&self) -> Option<&Path> {
         self.temp_solution_file.as_deref()
     }
+
+    fn parse_stdout_status(&self, stdout: &[u8]) -> Option<Status> {
+        self.status_rules.status(stdout)
+    }
+
+    fn version_arguments(&self) -> Option<&[&str]> {
+        Some(&["--version"])
+    }
+
+    fn reads_paths_without_extension(&self) -> bool {
+        // the format is given by the --lp flag
+        true
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::solvers::{GlpkSolver, SolverProgram, WithMaxSeconds, WithMipGap};
+    use crate::solvers::{
+        GlpkOutput, GlpkSolutionFormat, GlpkSolver, SolverProgram, WithMaxSeconds, WithMipGap,
+        WithPresolve,
+    };
     use std::ffi::OsString;
     use std::path::Path;
 
@@ -211,6 +1012,28 @@ mod tests {
         assert_eq!(args, expected);
     }
 
+    #[test]
+    fn cli_args_plain_solution() {
+        let solver = GlpkSolver::new().with_solution_format(GlpkSolutionFormat::Plain);
+        let args = solver.arguments(Path::new("test.lp"), Path::new("test.sol"));
+        assert_eq!(args[2], OsString::from("--write"));
+    }
+
+    #[test]
+    fn cli_args_mps() {
+        let solver = GlpkSolver::new();
+        let args = solver.arguments(Path::new("test.mps"), Path::new("test.sol"));
+
+        let expected: Vec<OsString> = vec![
+            "--freemps".into(),
+            "test.mps".into(),
+            "-o".into(),
+            "test.sol".into(),
+        ];
+
+        assert_eq!(args, expected);
+    }
+
     #[test]
     fn cli_args_seconds() {
         let solver = GlpkSolver::new().with_max_seconds(10);
@@ -248,6 +1071,33 @@ mod tests {
         assert_eq!(args, expected);
     }
 
+    #[test]
+    fn cli_args_output_files() {
+        let solver = GlpkSolver::new()
+            .with_output_file(GlpkOutput::Log, "old.log")
+            .with_output_file(GlpkOutput::FreeMps, "glpk.mps")
+            .with_output_file(GlpkOutput::Log, "glpk.log");
+        let args = solver.arguments(Path::new("test.lp"), Path::new("test.sol"));
+
+        let expected: Vec<OsString> = vec![
+            "--lp".into(),
+            "test.lp".into(),
+            "-o".into(),
+            "test.sol".into(),
+            "--wfreemps".into(),
+            "glpk.mps".into(),
+            "--log".into(),
+            "glpk.log".into(),
+        ];
+
+        assert_eq!(args, expected);
+        assert_eq!(
+            solver.output_file(GlpkOutput::Log),
+            Some(Path::new("glpk.log"))
+        );
+        assert_eq!(solver.output_file(GlpkOutput::Lp), None);
+    }
+
     #[test]
     fn cli_args_mipgap_negative() {
         let solver = GlpkSolver::new().with_mip_gap(-0.05);
@@ -260,6 +1110,22 @@ mod tests {
         assert!(solver.is_err());
     }
 
+    #[test]
+    fn cli_args_presolve() {
+        let solver = GlpkSolver::new().with_presolve(true);
+        let args = solver.arguments(Path::new("test.lp"), Path::new("test.sol"));
+
+        let expected: Vec<OsString> = vec![
+            "--lp".into(),
+            "test.lp".into(),
+            "-o".into(),
+            "test.sol".into(),
+            "--presol".into(),
+        ];
+
+        assert_eq!(args, expected);
+    }
+
     #[test]
     fn cli_args_multiple() {
         let solver = GlpkSolver::new()
@@ -1,13 +1,27 @@
 //! The proprietary gurobi solver
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::ffi::OsString;
 use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::{self, BufRead, BufReader, Write};
 use std::path::{Path, PathBuf};
+#[cfg(feature = "process")]
+use std::process::Command;
+use std::time::{Duration, SystemTime};
 
 use crate::lp_format::*;
-use crate::solvers::{Solution, SolverProgram, SolverWithSolutionParsing, Status, WithMipGap};
-use crate::util::buf_contains;
+#[cfg(feature = "memmap")]
+use crate::solvers::map_solution_file;
+use crate::solvers::{
+    open_solution_file, with_run_id, ApplyOptions, MissingVariables, ParseError, ParserMode,
+    Solution, SolutionValue, SolveReport, SolverCapabilities, SolverOptions, SolverParams,
+    SolverProgram, SolverWithSolutionParsing, Status, StdoutStatusRules, VariableFilter, Warning,
+    WithMipGap, WithMipStart, WithMissingVariables, WithParserMode, WithPresolve, WithStatusRules,
+};
+#[cfg(feature = "process")]
+use crate::solvers::{
+    run_license_check, run_tuning_tool, LicenseCheck, LicenseInfo, SolverError, SolverTuning,
+};
+use crate::util::find_line;
 
 /// The proprietary gurobi solver
 #[derive(Debug, Clone)]
@@ -16,6 +30,14 @@ pub struct GurobiSolver {
     command_name: String,
     temp_solution_file: Option<PathBuf>,
     mipgap: Option<f32>,
+    presolve: Option<bool>,
+    parser_mode: ParserMode,
+    missing_variables: MissingVariables,
+    params: SolverParams,
+    mip_start: Option<PathBuf>,
+    log_file: Option<PathBuf>,
+    result_files: Vec<PathBuf>,
+    status_rules: StdoutStatusRules,
 }
 
 impl Default for GurobiSolver {
@@ -32,6 +54,14 @@ impl GurobiSolver {
             command_name: "gurobi_cl".to_string(),
             temp_solution_file: None,
             mipgap: None,
+            presolve: None,
+            parser_mode: ParserMode::Strict,
+            missing_variables: MissingVariables::default(),
+            params: SolverParams::new(),
+            mip_start: None,
+            log_file: None,
+            result_files: vec![],
+            status_rules: StdoutStatusRules::gurobi(),
         }
     }
     /// set the name of the commandline gurobi executable to use
@@ -41,49 +71,360 @@ impl GurobiSolver {
             command_name,
             temp_solution_file: self.temp_solution_file.clone(),
             mipgap: self.mipgap,
+            presolve: self.presolve,
+            parser_mode: self.parser_mode,
+            missing_variables: self.missing_variables,
+            params: self.params.clone(),
+            mip_start: self.mip_start.clone(),
+            log_file: self.log_file.clone(),
+            result_files: self.result_files.clone(),
+            status_rules: self.status_rules.clone(),
         }
     }
+
+    /// Set the solution file that gurobi writes (`ResultFile=`), instead of a temporary file.
+    /// The file is kept after the solve, so that it can be given back to gurobi
+    /// as the starting solution of the next solve (`InputFile=`), with
+    /// [WithMipStart::with_mip_start], when the data of the problem changed a little.
+    /// Gurobi reads `.mst` starting solutions as well. The path can contain
+    /// [RUN_ID_PLACEHOLDER](crate::solvers::RUN_ID_PLACEHOLDER), so that
+    /// solves running at the same time do not write to the same file.
+    ///
+    /// ```
+    /// use lp_solvers::solvers::{GurobiSolver, WithMipStart};
+    ///
+    /// let solver = GurobiSolver::new().with_temp_solution_file("warm.sol".to_string());
+    /// // solve once with `solver`, then start the next solves from its solution
+    /// let resolver = solver.with_mip_start("warm.sol".into());
+    /// assert_eq!(resolver.mip_start(), Some(std::path::Path::new("warm.sol")));
+    /// ```
+    pub fn with_temp_solution_file(&self, temp_solution_file: String) -> GurobiSolver {
+        GurobiSolver {
+            temp_solution_file: Some(temp_solution_file.into()),
+            ..(*self).clone()
+        }
+    }
+
+    /// Solve with the given parameters, such as the ones found by [crate::solvers::SolverTuning::tune]
+    pub fn with_params(&self, params: &SolverParams) -> GurobiSolver {
+        GurobiSolver {
+            params: params.clone(),
+            ..(*self).clone()
+        }
+    }
+
+    /// Keep the log of gurobi in the given file (`LogFile=`), which gurobi appends to.
+    /// The status and the gap of a solve are read from it when the output of gurobi
+    /// does not have them, as when the console log is turned off with `LogToConsole=0`.
+    /// The path can contain [RUN_ID_PLACEHOLDER](crate::solvers::RUN_ID_PLACEHOLDER), so that
+    /// solves running at the same time do not write to the same file.
+    pub fn with_log_file(&self, path: impl Into<PathBuf>) -> GurobiSolver {
+        GurobiSolver {
+            log_file: Some(path.into()),
+            ..(*self).clone()
+        }
+    }
+
+    /// Make gurobi write another result file (`ResultFile=`), besides the solution.
+    /// Gurobi chooses what to write from the extension of the file,
+    /// and the files it writes during a solve are read into its [SolveReport]:
+    ///  - `.json` solutions, whose `SolutionInfo` (the `Runtime`, the `NodeCount`...) goes to
+    ///    [SolveReport::attributes]. `JSONSolDetail=1` in the [parameters](Self::with_params)
+    ///    makes gurobi write more of them. Needs the `serde` feature.
+    ///  - `.attr` attribute files, whose numeric model attributes go to
+    ///    [SolveReport::attributes]. Needs the `serde` feature.
+    ///  - `.ilp` files, written for infeasible problems, whose constraints go to
+    ///    [SolveReport::infeasible_constraints]
+    ///
+    /// The other files, such as `.bas` or `.mst` files, are only listed in
+    /// [SolveReport::output_files].
+    /// The path can contain [RUN_ID_PLACEHOLDER](crate::solvers::RUN_ID_PLACEHOLDER).
+    ///
+    /// ```
+    /// use std::path::Path;
+    /// # #[cfg(feature = "gurobi")] {
+    /// use lp_solvers::solvers::{GurobiSolver, SolverProgram};
+    ///
+    /// let solver = GurobiSolver::new()
+    ///     .with_result_file("details.json")
+    ///     .with_result_file("conflict.ilp");
+    /// let args = solver.arguments(Path::new("problem.lp"), Path::new("problem.sol"));
+    /// assert_eq!(
+    ///     args,
+    ///     vec![
+    ///         "ResultFile=problem.sol",
+    ///         "ResultFile=details.json",
+    ///         "ResultFile=conflict.ilp",
+    ///         "problem.lp"
+    ///     ]
+    /// );
+    /// # }
+    /// ```
+    pub fn with_result_file(&self, path: impl Into<PathBuf>) -> GurobiSolver {
+        let mut solver = self.clone();
+        solver.result_files.push(path.into());
+        solver
+    }
+
+    /// The path of grbtune, the tuning tool installed next to the gurobi executable
+    #[cfg(feature = "process")]
+    fn tune_command_name(&self) -> PathBuf {
+        Path::new(&self.command_name).with_file_name("grbtune")
+    }
 }
 
 impl SolverWithSolutionParsing for GurobiSolver {
     fn read_specific_solution<'a, P: LpProblem<'a>>(
         &self,
         f: &File,
-        _problem: Option<&'a P>,
+        problem: Option<&'a P>,
     ) -> Result<Solution, String> {
-        let mut vars_value: HashMap<_, _> = HashMap::new();
-        let mut file = BufReader::new(f);
-        let mut buffer = String::new();
-        let _ = file.read_line(&mut buffer);
-
-        if buffer.split(' ').next().is_some() {
-            for line in file.lines() {
-                let l = line.unwrap();
-
-                // Gurobi version 7 add comments on the header file
-                if let Some('#') = l.chars().next() {
-                    continue;
+        self.read_filtered(f, problem, VariableFilter::default())
+    }
+
+    fn read_solution_filtered<'a, P: LpProblem<'a>>(
+        &self,
+        temp_solution_file: &Path,
+        problem: Option<&'a P>,
+        variables: &HashSet<String>,
+    ) -> Result<Solution, String> {
+        let f = open_solution_file(temp_solution_file)?;
+        self.read_filtered(&f, problem, VariableFilter::only(variables))
+    }
+}
+
+impl GurobiSolver {
+    fn read_filtered<'a, P: LpProblem<'a>>(
+        &self,
+        f: &File,
+        problem: Option<&'a P>,
+        filter: VariableFilter,
+    ) -> Result<Solution, String> {
+        let variables_len = problem.map(|p| p.variables().size_hint().0);
+        #[cfg(feature = "memmap")]
+        let mut solution = read_solution_bytes(
+            &map_solution_file(f)?,
+            self.parser_mode,
+            variables_len.unwrap_or(0),
+            filter,
+        )?;
+        #[cfg(not(feature = "memmap"))]
+        let mut solution = read_solution(
+            BufReader::new(f),
+            self.parser_mode,
+            variables_len.unwrap_or(0),
+            filter,
+        )?;
+        self.missing_variables
+            .apply(&mut solution, problem, filter)?;
+        Ok(solution)
+    }
+}
+
+/// Parse a solution file written by gurobi (`ResultFile=*.sol`).
+///
+/// The file does not contain the solution status:
+/// the returned solution is always [Status::SubOptimal].
+/// The `# Objective value =` comment is read into [Solution::objective], and
+/// the `# Solution for model` comment into the [SolveReport::model_name] of the report.
+pub fn parse_gurobi_solution(
+    reader: impl BufRead,
+    mode: ParserMode,
+) -> Result<Solution, ParseError> {
+    read_solution(reader, mode, 0, VariableFilter::default())
+}
+
+fn read_solution(
+    reader: impl BufRead,
+    mode: ParserMode,
+    variables_len: usize,
+    filter: VariableFilter,
+) -> Result<Solution, ParseError> {
+    let mut vars_value: HashMap<_, _> = HashMap::with_capacity(filter.capacity(variables_len));
+    let mut warnings = vec![];
+    let mut header = SolutionHeader::default();
+    let mut is_empty = true;
+    for (idx, line) in reader.lines().enumerate() {
+        let l = line.map_err(|e| format!("Unable to read solution: {}", e))?;
+        is_empty = false;
+
+        // Gurobi version 7 add comments on the header file
+        if let Some('#') = l.chars().next() {
+            if let Err(e) = header.read(&l) {
+                mode.malformed(Some(idx + 1), e, &mut warnings)?;
+            }
+            continue;
+        }
+
+        match parse_line(&l) {
+            Ok((name, n)) => {
+                if filter.keeps(name) {
+                    vars_value.insert(name.to_string(), n);
                 }
+            }
+            Err(e) => malformed_line(idx, e, mode, &mut warnings)?,
+        }
+    }
+    if is_empty {
+        return Err(ParseError::at(1, "Incorrect solution format"));
+    }
+    // The solution file does not say whether the solution is optimal:
+    // the status is read from the output of gurobi when it is available
+    let mut solution = Solution::new(Status::SubOptimal, vars_value);
+    solution.warnings = warnings;
+    header.apply(&mut solution);
+    Ok(solution)
+}
 
-                let result_line: Vec<_> = l.split_whitespace().collect();
-                if result_line.len() == 2 {
-                    match result_line[1].parse::<f32>() {
-                        Ok(n) => {
-                            vars_value.insert(result_line[0].to_string(), n);
-                        }
-                        Err(e) => return Err(e.to_string()),
-                    }
-                } else {
-                    return Err("Incorrect solution format".to_string());
+/// A line that is neither a comment nor a value. Solution files start with one or the other:
+/// a file whose first line is malformed is not a gurobi solution, in any mode
+fn malformed_line(
+    idx: usize,
+    message: String,
+    mode: ParserMode,
+    warnings: &mut Vec<Warning>,
+) -> Result<(), ParseError> {
+    if idx == 0 {
+        return Err(ParseError::at(1, "Incorrect solution format"));
+    }
+    mode.malformed(Some(idx + 1), message, warnings)
+}
+
+/// What the comments at the top of a solution file say about the solution:
+/// `# Solution for model obj` and `# Objective value = 3`
+#[derive(Default)]
+struct SolutionHeader {
+    objective: Option<f64>,
+    model_name: Option<String>,
+}
+
+impl SolutionHeader {
+    /// Read a comment line, starting with `#`. Other comments are ignored
+    fn read(&mut self, line: &str) -> Result<(), String> {
+        let comment = line.trim_start_matches('#').trim();
+        if let Some(value) = comment.strip_prefix("Objective value =") {
+            let value = value.trim();
+            let objective = value
+                .parse()
+                .map_err(|_| format!("Invalid objective value {:?}", value))?;
+            self.objective = Some(objective);
+        } else if let Some(name) = comment.strip_prefix("Solution for model") {
+            let name = name.trim();
+            if !name.is_empty() {
+                self.model_name = Some(name.to_string());
+            }
+        }
+        Ok(())
+    }
+
+    fn apply(self, solution: &mut Solution) {
+        solution.objective = self.objective;
+        if let Some(model_name) = self.model_name {
+            solution.report = Some(SolveReport {
+                model_name: Some(model_name),
+                ..SolveReport::default()
+            });
+        }
+    }
+}
+
+/// Write a solution in the format of gurobi .sol files, which [parse_gurobi_solution] reads.
+/// Gurobi can read these files as a MIP start.
+pub fn write_gurobi_solution<W: Write>(solution: &Solution, mut writer: W) -> io::Result<()> {
+    writeln!(writer, "# Solution written by lp-solvers")?;
+    if let Some(objective) = solution.objective {
+        writeln!(writer, "# Objective value = {}", objective)?;
+    }
+    for (name, value) in solution.ordered_results() {
+        writeln!(writer, "{} {}", name, value)?;
+    }
+    Ok(())
+}
+
+/// Parse a solution file written by gurobi, already in memory,
+/// without allocating a string for each line.
+///
+/// The file does not contain the solution status:
+/// the returned solution is always [Status::SubOptimal].
+pub fn parse_gurobi_solution_bytes(bytes: &[u8], mode: ParserMode) -> Result<Solution, ParseError> {
+    read_solution_bytes(bytes, mode, 0, VariableFilter::default())
+}
+
+fn read_solution_bytes(
+    bytes: &[u8],
+    mode: ParserMode,
+    variables_len: usize,
+    filter: VariableFilter,
+) -> Result<Solution, ParseError> {
+    let bytes = bytes.strip_suffix(b"\n").unwrap_or(bytes);
+    let mut vars_value: HashMap<_, _> = HashMap::with_capacity(filter.capacity(variables_len));
+    let mut warnings = vec![];
+    let mut header = SolutionHeader::default();
+    for (idx, line) in bytes.split(|&b| b == b'\n').enumerate() {
+        // Gurobi version 7 add comments on the header file
+        if line.first() == Some(&b'#') {
+            if let Err(e) = header.read(&String::from_utf8_lossy(line)) {
+                mode.malformed(Some(idx + 1), e, &mut warnings)?;
+            }
+            continue;
+        }
+        match parse_line_bytes(line) {
+            Ok((name, n)) => {
+                if filter.keeps(name) {
+                    vars_value.insert(name.to_string(), n);
                 }
             }
-        } else {
-            return Err("Incorrect solution format".to_string());
+            Err(e) => malformed_line(idx, e, mode, &mut warnings)?,
+        }
+    }
+    let mut solution = Solution::new(Status::SubOptimal, vars_value);
+    solution.warnings = warnings;
+    header.apply(&mut solution);
+    Ok(solution)
+}
+
+/// Same as [parse_line], on bytes
+fn parse_line_bytes(line: &[u8]) -> Result<(&str, f32), String> {
+    let mut tokens = line
+        .split(u8::is_ascii_whitespace)
+        .filter(|t| !t.is_empty());
+    match (tokens.next(), tokens.next(), tokens.next()) {
+        (Some(name), Some(value), None) => {
+            let name = std::str::from_utf8(name).map_err(|e| e.to_string())?;
+            Ok((name, f32::parse_value(value)?))
         }
-        Ok(Solution::new(Status::Optimal, vars_value))
+        _ => Err("Incorrect solution format".to_string()),
+    }
+}
+
+/// Parse a variable line: "name value"
+fn parse_line<T: SolutionValue>(line: &str) -> Result<(&str, T), String> {
+    let result_line: Vec<_> = line.split_whitespace().collect();
+    if result_line.len() == 2 {
+        T::parse_value(result_line[1].as_bytes()).map(|n| (result_line[0], n))
+    } else {
+        Err("Incorrect solution format".to_string())
     }
 }
 
+/// Iterate over the variable values in a gurobi solution file, without storing them
+pub fn read_gurobi_solution_iter(
+    path: &Path,
+) -> Result<impl Iterator<Item = Result<(String, f64), String>>, String> {
+    let reader = BufReader::new(open_solution_file(path)?);
+    Ok(reader.lines().filter_map(|line| {
+        let line = match line {
+            Ok(line) => line,
+            Err(e) => return Some(Err(format!("Unable to read solution: {}", e))),
+        };
+        // Gurobi version 7 add comments on the header file
+        if line.starts_with('#') {
+            return None;
+        }
+        Some(parse_line(&line).map(|(name, value)| (name.to_string(), value)))
+    }))
+}
+
 impl WithMipGap<GurobiSolver> for GurobiSolver {
     fn mip_gap(&self) -> Option<f32> {
         self.mipgap
@@ -101,6 +442,173 @@ impl WithMipGap<GurobiSolver> for GurobiSolver {
     }
 }
 
+impl WithMipStart<GurobiSolver> for GurobiSolver {
+    fn mip_start(&self) -> Option<&Path> {
+        self.mip_start.as_deref()
+    }
+
+    fn with_mip_start(&self, solution_file: PathBuf) -> GurobiSolver {
+        GurobiSolver {
+            mip_start: Some(solution_file),
+            ..(*self).clone()
+        }
+    }
+
+    fn write_mip_start(&self, solution: &Solution, writer: &mut dyn Write) -> io::Result<()> {
+        write_gurobi_solution(solution, writer)
+    }
+}
+
+impl WithParserMode<GurobiSolver> for GurobiSolver {
+    fn parser_mode(&self) -> ParserMode {
+        self.parser_mode
+    }
+    fn with_parser_mode(&self, mode: ParserMode) -> GurobiSolver {
+        GurobiSolver {
+            parser_mode: mode,
+            ..(*self).clone()
+        }
+    }
+}
+
+impl WithMissingVariables<GurobiSolver> for GurobiSolver {
+    fn missing_variables(&self) -> MissingVariables {
+        self.missing_variables
+    }
+    fn with_missing_variables(&self, policy: MissingVariables) -> GurobiSolver {
+        GurobiSolver {
+            missing_variables: policy,
+            ..(*self).clone()
+        }
+    }
+}
+
+impl WithPresolve<GurobiSolver> for GurobiSolver {
+    fn presolve(&self) -> Option<bool> {
+        self.presolve
+    }
+
+    fn with_presolve(&self, presolve: bool) -> GurobiSolver {
+        GurobiSolver {
+            presolve: Some(presolve),
+            ..(*self).clone()
+        }
+    }
+}
+
+#[cfg(feature = "process")]
+impl SolverTuning for GurobiSolver {
+    fn tune<'a, P: LpProblem<'a>>(
+        &self,
+        problem: &'a P,
+        time_limit: Option<Duration>,
+    ) -> Result<SolverParams, SolverError> {
+        let problem_file = problem
+            .to_tmp_file()
+            .map_err(|e| format!("Unable to write the problem: {}", e))?;
+        // grbtune writes the best parameters to tune0.prm, in its working directory
+        let dir = tempfile::tempdir().map_err(|e| e.to_string())?;
+        let mut command = Command::new(self.tune_command_name());
+        command.arg("TuneResults=1");
+        if let Some(limit) = time_limit {
+            command.arg(format!("TuneTimeLimit={}", limit.as_secs_f64()));
+        }
+        if let Some(mipgap) = self.mip_gap() {
+            command.arg(format!("MIPGap={}", mipgap));
+        }
+        command.arg(problem_file.path());
+        run_tuning_tool(self, command, dir.path(), &dir.path().join("tune0.prm"))
+    }
+}
+
+#[cfg(feature = "process")]
+impl LicenseCheck for GurobiSolver {
+    fn check_license(&self) -> Result<LicenseInfo, SolverError> {
+        let stdout = run_license_check(self, ["--license"])?;
+        Ok(parse_gurobi_license(&stdout))
+    }
+}
+
+/// Parse the output of `gurobi_cl --license`, such as
+/// `Restricted license - for non-production use only - expires 2026-11-23`
+#[cfg(feature = "process")]
+fn parse_gurobi_license(stdout: &str) -> LicenseInfo {
+    let lines = stdout.lines().map(str::trim);
+    let description = lines
+        .clone()
+        .rfind(|l| l.contains("license") && !l.starts_with("Using license file"))
+        .or_else(|| lines.clone().find(|l| l.starts_with("Using license file")));
+    LicenseInfo {
+        description: description.map(str::to_string),
+        expires: lines
+            .clone()
+            .find_map(|l| l.split("expires ").nth(1))
+            .map(|date| date.trim().to_string()),
+        // pip installations come with a license limited to 2000 variables and constraints
+        size_limited: lines.clone().any(|l| l.starts_with("Restricted license")),
+    }
+}
+
+impl WithStatusRules<GurobiSolver> for GurobiSolver {
+    fn status_rules(&self) -> &StdoutStatusRules {
+        &self.status_rules
+    }
+
+    fn with_status_rules(&self, rules: StdoutStatusRules) -> GurobiSolver {
+        GurobiSolver {
+            status_rules: rules,
+            ..(*self).clone()
+        }
+    }
+}
+
+/// The time limit, the threads and the seed are set as the `TimeLimit`, `Threads` and `Seed`
+/// [parameters](GurobiSolver::with_params). The verbosity is ignored: the status of the solve
+/// is read from the log of gurobi, so it cannot be turned off.
+impl ApplyOptions for GurobiSolver {
+    fn apply_options(&self, options: &SolverOptions) -> Result<GurobiSolver, String> {
+        let mut solver = self.clone();
+        if let Some(mipgap) = options.mip_gap {
+            solver = solver.with_mip_gap(mipgap)?;
+        }
+        solver.presolve = options.presolve.or(solver.presolve);
+        for (name, value) in [
+            ("TimeLimit", options.time_limit),
+            ("Threads", options.threads),
+            ("Seed", options.seed),
+        ]
+        .iter()
+        {
+            if let Some(value) = value {
+                solver.params = solver.params.with_param(*name, value);
+            }
+        }
+        Ok(solver)
+    }
+}
+
+impl SolverCapabilities for GurobiSolver {
+    fn supports_mip(&self) -> bool {
+        true
+    }
+
+    fn supports_quadratic(&self) -> bool {
+        true
+    }
+
+    fn supports_sos(&self) -> bool {
+        true
+    }
+
+    fn supports_indicators(&self) -> bool {
+        true
+    }
+
+    fn supports_warm_start(&self) -> bool {
+        true
+    }
+}
+
 impl SolverProgram for GurobiSolver {
     fn command_name(&self) -> &str {
         &self.command_name
@@ -112,12 +620,41 @@ impl SolverProgram for GurobiSolver {
 
         let mut args = vec![arg0];
 
+        for result_file in &self.result_files {
+            let mut arg_result: OsString = "ResultFile=".into();
+            arg_result.push(result_file);
+            args.push(arg_result);
+        }
+
         if let Some(mipgap) = self.mip_gap() {
             let mut arg_mipgap: OsString = "MIPGap=".into();
             arg_mipgap.push::<OsString>(mipgap.to_string().into());
             args.push(arg_mipgap);
         }
 
+        for (name, value) in self.params.iter() {
+            args.push(format!("{}={}", name, value).into());
+        }
+
+        if let Some(log_file) = &self.log_file {
+            let mut arg_log: OsString = "LogFile=".into();
+            arg_log.push(log_file);
+            args.push(arg_log);
+        }
+
+        if let Some(mip_start) = self.mip_start() {
+            let mut arg_start: OsString = "InputFile=".into();
+            arg_start.push(mip_start);
+            args.push(arg_start);
+        }
+
+        match self.presolve() {
+            Some(true) => args.push("Presolve=-1".into()),
+            // Dual reductions are what prevents gurobi from telling infeasible and unbounded apart
+            Some(false) => args.extend(["Presolve=0".into(), "DualReductions=0".into()]),
+            None => {}
+        }
+
         args.push(lp_file.into());
 
         args
@@ -131,23 +668,214 @@ impl SolverProgram for GurobiSolver {
         Some(".sol")
     }
 
+    fn problem_formats(&self) -> &[ProblemFormat] {
+        &[ProblemFormat::Lp, ProblemFormat::FreeMps]
+    }
+
+    fn output_files(&self) -> Vec<PathBuf> {
+        self.log_file
+            .iter()
+            .chain(&self.result_files)
+            .cloned()
+            .collect()
+    }
+
+    fn read_output_files(&self, report: &mut SolveReport) -> Vec<Warning> {
+        let mut warnings = vec![];
+        for path in &self.result_files {
+            let path = &with_run_id(path, &report.run_id);
+            if !written_during_solve(path, report.solve_time) {
+                continue;
+            }
+            let read = match path.extension().and_then(|e| e.to_str()) {
+                Some("json") | Some("attr") => read_json_attributes(path, &mut report.attributes),
+                Some("ilp") => read_ilp_constraints(path).map(|constraints| {
+                    report.infeasible_constraints = constraints;
+                }),
+                _ => Ok(()),
+            };
+            if let Err(e) = read {
+                warnings.push(format!("result file {:?}: {}", path, e).into());
+            }
+        }
+        warnings
+    }
+
+    fn log_file(&self) -> Option<&Path> {
+        self.log_file.as_deref()
+    }
+
     fn parse_stdout_status(&self, stdout: &[u8]) -> Option<Status> {
-        if buf_contains(stdout, "Optimal solution found") {
-            Some(Status::Optimal)
-        } else if buf_contains(stdout, "infeasible") {
-            Some(Status::Infeasible)
-        } else {
-            None
+        self.status_rules.status(stdout)
+    }
+
+    fn parse_license_error(&self, stdout: &[u8], stderr: &[u8]) -> Option<String> {
+        // Error 10009 is GRB_ERROR_NO_LICENSE. Only the error lines are matched: a licensed
+        // gurobi also mentions its license, or the license server it uses, in its output
+        const PATTERNS: [&str; 2] = ["ERROR 10009", "No Gurobi license found"];
+        find_line(stdout, &PATTERNS).or_else(|| find_line(stderr, &PATTERNS))
+    }
+
+    fn parse_stdout_gap(&self, stdout: &[u8]) -> Option<f64> {
+        // "Best objective 1.0e+01, best bound 9.0e+00, gap 10.0000%"
+        let stdout = String::from_utf8_lossy(stdout);
+        let line = stdout
+            .lines()
+            .rev()
+            .find(|l| l.starts_with("Best objective"))?;
+        let gap = line.split("gap ").nth(1)?.trim().strip_suffix('%')?;
+        gap.parse::<f64>().ok().map(|percent| percent / 100.)
+    }
+}
+
+/// Whether the file was written by the solve that just ended after the given time,
+/// and not by a previous one. Gurobi only writes some result files, such as .ilp files,
+/// for some problems.
+fn written_during_solve(path: &Path, solve_time: Duration) -> bool {
+    // some filesystems only store modification times to the second
+    let start = SystemTime::now() - solve_time - Duration::from_secs(1);
+    std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .is_ok_and(|modified| modified >= start)
+}
+
+/// Add the numbers of a json result file to the attributes, by name
+#[cfg(feature = "serde")]
+fn read_json_attributes(
+    path: &Path,
+    attributes: &mut std::collections::BTreeMap<String, f64>,
+) -> Result<(), String> {
+    fn collect(
+        value: &serde_json::Value,
+        attributes: &mut std::collections::BTreeMap<String, f64>,
+    ) {
+        if let serde_json::Value::Object(map) = value {
+            for (name, value) in map {
+                match value {
+                    serde_json::Value::Number(n) => {
+                        if let Some(n) = n.as_f64() {
+                            attributes.insert(name.clone(), n);
+                        }
+                    }
+                    // the attributes of the variables and constraints are in arrays
+                    _ => collect(value, attributes),
+                }
+            }
+        }
+    }
+    let file = File::open(path).map_err(|e| e.to_string())?;
+    let value: serde_json::Value =
+        serde_json::from_reader(BufReader::new(file)).map_err(|e| e.to_string())?;
+    collect(&value, attributes);
+    Ok(())
+}
+
+#[cfg(not(feature = "serde"))]
+fn read_json_attributes(
+    _path: &Path,
+    _attributes: &mut std::collections::BTreeMap<String, f64>,
+) -> Result<(), String> {
+    Err("reading json result files requires the serde feature of lp-solvers".to_string())
+}
+
+/// The names of the constraints of an .ilp file,
+/// the irreducible infeasible subsystem that gurobi writes in the lp format
+fn read_ilp_constraints(path: &Path) -> Result<Vec<String>, String> {
+    let file = File::open(path).map_err(|e| e.to_string())?;
+    parse_ilp_constraints(BufReader::new(file))
+}
+
+fn parse_ilp_constraints(reader: impl BufRead) -> Result<Vec<String>, String> {
+    let mut constraints = vec![];
+    let mut in_constraints = false;
+    for line in reader.lines() {
+        let line = line.map_err(|e| e.to_string())?;
+        let line = line.trim();
+        let section = line.to_ascii_lowercase();
+        if ["subject to", "such that", "st", "s.t."].contains(&section.as_str()) {
+            in_constraints = true;
+        } else if ["bounds", "binaries", "generals", "general", "sos", "end"]
+            .contains(&section.as_str())
+        {
+            in_constraints = false;
+        } else if in_constraints && !line.starts_with('\\') {
+            // continuation lines of long constraints have no name
+            if let Some((name, _)) = line.split_once(':') {
+                constraints.push(name.trim().to_string());
+            }
         }
     }
+    Ok(constraints)
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::solvers::{GurobiSolver, SolverProgram, WithMipGap};
+    use crate::solvers::{GurobiSolver, SolverProgram, Status, WithMipGap, WithPresolve};
     use std::ffi::OsString;
     use std::path::Path;
 
+    #[test]
+    fn stdout_status_time_limit() {
+        let stdout = b"Explored 120 nodes (5012 simplex iterations) in 10.00 seconds
+Thread count was 8 (of 8 available processors)
+
+Solution count 3: 12 11 9
+
+Time limit reached
+Best objective 1.200000000000e+01, best bound 1.300000000000e+01, gap 8.3333%";
+        let solver = GurobiSolver::new();
+        assert_eq!(solver.parse_stdout_status(stdout), Some(Status::SubOptimal));
+        let gap = solver.parse_stdout_gap(stdout).unwrap();
+        assert!((gap - 0.083333).abs() < 1e-9);
+    }
+
+    #[test]
+    fn stdout_status_no_incumbent() {
+        let stdout = b"Solve interrupted
+Best objective -, best bound 1.300000000000e+01, gap -";
+        let solver = GurobiSolver::new();
+        assert_eq!(solver.parse_stdout_status(stdout), Some(Status::NotSolved));
+        assert_eq!(solver.parse_stdout_gap(stdout), None);
+    }
+
+    #[test]
+    fn license_error() {
+        let stdout = b"Set parameter ResultFile to value \"sol.sol\"
+
+ERROR 10009: No Gurobi license found (user x, host y, hostid z)
+";
+        let solver = GurobiSolver::new();
+        assert_eq!(
+            solver.parse_license_error(stdout, b""),
+            Some("ERROR 10009: No Gurobi license found (user x, host y, hostid z)".to_string())
+        );
+        assert_eq!(
+            solver.parse_license_error(b"Optimal solution found", b""),
+            None
+        );
+        let expired = b"ERROR 10009: License expired 2025-01-31\n";
+        assert_eq!(
+            solver.parse_license_error(b"", expired),
+            Some("ERROR 10009: License expired 2025-01-31".to_string())
+        );
+        let licensed = b"Set parameter TokenServer to value \"lic.example.com\"
+Using license server lic.example.com
+Academic license - for non-commercial use only - expires 2027-01-31
+
+Optimal solution found (tolerance 1.00e-04)
+";
+        assert_eq!(solver.parse_license_error(licensed, b""), None);
+    }
+
+    #[test]
+    fn stdout_status_optimal() {
+        let stdout = b"Optimal solution found (tolerance 1.00e-04)
+Best objective 9.000000000000e+00, best bound 9.000000000000e+00, gap 0.0000%";
+        let solver = GurobiSolver::new();
+        assert_eq!(solver.parse_stdout_status(stdout), Some(Status::Optimal));
+        assert_eq!(solver.parse_stdout_gap(stdout), Some(0.));
+    }
+
     #[test]
     fn cli_args_default() {
         let solver = GurobiSolver::new();
@@ -175,6 +903,58 @@ mod tests {
         assert_eq!(args, expected);
     }
 
+    #[test]
+    fn cli_args_presolve() {
+        let solver = GurobiSolver::new().with_presolve(false);
+        let args = solver.arguments(Path::new("test.lp"), Path::new("test.sol"));
+
+        let expected: Vec<OsString> = vec![
+            "ResultFile=test.sol".into(),
+            "Presolve=0".into(),
+            "DualReductions=0".into(),
+            "test.lp".into(),
+        ];
+
+        assert_eq!(args, expected);
+    }
+
+    #[test]
+    fn stdout_status_infeasible_or_unbounded() {
+        let solver = GurobiSolver::new();
+        let stdout = b"Infeasible or unbounded model";
+        assert_eq!(
+            solver.parse_stdout_status(stdout),
+            Some(Status::InfeasibleOrUnbounded)
+        );
+        let stdout = b"Model is infeasible";
+        assert_eq!(solver.parse_stdout_status(stdout), Some(Status::Infeasible));
+    }
+
+    #[test]
+    fn stdout_status_time_limit_with_node_log() {
+        let solver = GurobiSolver::new();
+        let stdout = b"Optimize a model with 120 rows, 80 columns and 640 nonzeros
+Variable types: 0 continuous, 80 integer (80 binary)
+
+    Nodes    |    Current Node    |     Objective Bounds      |     Work
+ Expl Unexpl |  Obj  Depth IntInf | Incumbent    BestBd   Gap | It/Node Time
+
+     0     0   10.50000    0   12          -   10.50000      -     -    0s
+H    0     0                      14.0000000   10.50000  25.0%     -    0s
+     0     2   10.50000    0   12   14.00000   10.50000  25.0%     -    0s
+  1234   567 infeasible   31        14.00000   12.10000  13.6%   8.2    5s
+  2001   890   12.75000   22    9   13.00000   12.20000  6.15%   8.0   10s
+
+Explored 2456 nodes (20000 simplex iterations) in 10.00 seconds (3.10 work units)
+Thread count was 8 (of 8 available processors)
+
+Solution count 2: 13 14
+
+Time limit reached
+Best objective 1.300000000000e+01, best bound 1.220000000000e+01, gap 6.1538%";
+        assert_eq!(solver.parse_stdout_status(stdout), Some(Status::SubOptimal));
+    }
+
     #[test]
     fn cli_args_mipgap_negative() {
         let solver = GurobiSolver::new().with_mip_gap(-0.05);
@@ -186,4 +966,40 @@ mod tests {
         let solver = GurobiSolver::new().with_mip_gap(f32::INFINITY);
         assert!(solver.is_err());
     }
+
+    #[test]
+    fn ilp_constraints() {
+        let ilp = "\\ Model problem_copy
+\\ LP format - for model browsing. Use MPS format to capture full model detail.
+Minimize
+
+Subject To
+ c1: x + y >= 12
+ long_constraint: 2 x + 3 y + 4 z
+   + 5 w <= 3
+Bounds
+ x <= 4
+ y <= 4
+End
+";
+        assert_eq!(
+            super::parse_ilp_constraints(ilp.as_bytes()).unwrap(),
+            vec!["c1", "long_constraint"]
+        );
+    }
+
+    #[test]
+    fn cli_args_log_file() {
+        let solver = GurobiSolver::new().with_log_file("gurobi.log");
+        let args = solver.arguments(Path::new("test.lp"), Path::new("test.sol"));
+
+        let expected: Vec<OsString> = vec![
+            "ResultFile=test.sol".into(),
+            "LogFile=gurobi.log".into(),
+            "test.lp".into(),
+        ];
+
+        assert_eq!(args, expected);
+        assert_eq!(solver.output_files(), vec![Path::new("gurobi.log")]);
+    }
 }
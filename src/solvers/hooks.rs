@@ -0,0 +1,101 @@
+//! Callbacks around the execution of external solvers,
+//! to inspect the generated files, change the command, or record metrics.
+
+use std::path::Path;
+use std::process::{Command, Output};
+
+use crate::lp_format::LpProblem;
+use crate::solvers::{
+    run_file_program, run_program, CommandSpec, Solution, SolveReport, SolverCapabilities,
+    SolverError, SolverProgram, SolverTrait, SolverWithSolutionParsing,
+};
+
+/// An external solver that is about to be started
+#[derive(Debug)]
+pub struct PreparedSolve<'a> {
+    /// the problem, written in the .lp format
+    pub problem_file: &'a Path,
+    /// where the solver is expected to write its solution
+    pub solution_file: &'a Path,
+    /// the command that will be run. It can be modified.
+    pub command: Command,
+}
+
+impl PreparedSolve<'_> {
+    /// The description of the command that will be run, to inspect it
+    pub fn spec(&self) -> CommandSpec {
+        CommandSpec::from_command(&self.command)
+    }
+}
+
+/// An external solver that exited, before its solution is read
+#[derive(Debug)]
+pub struct RawOutcome<'a> {
+    /// the problem, written in the .lp format
+    pub problem_file: &'a Path,
+    /// where the solver is expected to have written its solution
+    pub solution_file: &'a Path,
+    /// exit status and output of the solver
+    pub output: &'a Output,
+    /// measurements made so far
+    pub report: &'a SolveReport,
+}
+
+/// Functions called when running an external solver. All of them do nothing by default.
+pub trait SolverHooks {
+    /// Called before starting the solver
+    fn before_spawn(&self, _solve: &mut PreparedSolve<'_>) {}
+    /// Called when the solver exits, whether it succeeded or not
+    fn after_finish(&self, _outcome: &RawOutcome<'_>) {}
+}
+
+/// No hooks
+impl SolverHooks for () {}
+
+/// An external solver, with hooks called around its execution
+///
+/// ```
+/// # #[cfg(feature = "cbc")] {
+/// use lp_solvers::solvers::{CbcSolver, HookedSolver, PreparedSolve, SolverHooks};
+///
+/// struct KeepProblem;
+///
+/// impl SolverHooks for KeepProblem {
+///     fn before_spawn(&self, solve: &mut PreparedSolve<'_>) {
+///         let _ = std::fs::copy(solve.problem_file, "last_problem.lp");
+///     }
+/// }
+///
+/// let solver = HookedSolver::new(CbcSolver::new(), KeepProblem);
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct HookedSolver<S, H> {
+    /// the solver to run
+    pub solver: S,
+    /// the hooks to call
+    pub hooks: H,
+}
+
+impl<S, H> HookedSolver<S, H> {
+    /// Call the given hooks when running the solver
+    pub fn new(solver: S, hooks: H) -> Self {
+        HookedSolver { solver, hooks }
+    }
+}
+
+impl<S, H> SolverTrait for HookedSolver<S, H>
+where
+    S: SolverWithSolutionParsing + SolverProgram,
+    H: SolverHooks,
+{
+    fn run<'a, P: LpProblem<'a>>(&self, problem: &'a P) -> Result<Solution, SolverError> {
+        run_program(&self.solver, problem, &self.hooks, None)
+    }
+
+    fn run_file(&self, problem_file: &Path) -> Result<Solution, SolverError> {
+        run_file_program(&self.solver, problem_file, &self.hooks, None)
+    }
+}
+
+forward_capabilities!(HookedSolver<S, H>, |this| &this.solver);
@@ -0,0 +1,178 @@
+//! Finding the constraints that make a problem infeasible, with any solver.
+//!
+//! Gurobi and CPLEX compute irreducible infeasible subsystems themselves, CBC and GLPK do not.
+//! [find_infeasible_subset] finds one by deletion filtering: it removes constraints from the
+//! problem, first by halves and then one by one, and keeps the ones without which the problem
+//! becomes feasible.
+
+use crate::lp_format::{ConsId, Constraint, ConstraintTags, LpObjective, TaggedConstraints};
+use crate::problem::{LinearExpression, Problem, Variable};
+use crate::solvers::{SolverError, SolverTrait, Status};
+
+/// The result of [find_infeasible_subset]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InfeasibleSubset {
+    /// constraints of the problem that are infeasible together, with the variable bounds
+    pub constraints: Vec<ConsId>,
+    /// whether removing any one of the constraints makes them feasible.
+    /// It is not when the solve budget ran out, or when a solver could not tell
+    /// whether a subset was feasible.
+    pub minimal: bool,
+    /// the number of times the solver was run
+    pub solves: usize,
+}
+
+impl InfeasibleSubset {
+    /// The constraints of the subset, grouped by the tags given to them when building the
+    /// problem, to report which kinds of constraints conflict rather than their numbers
+    pub fn by_tag(&self, tags: &ConstraintTags) -> TaggedConstraints {
+        tags.group(self.constraints.iter().copied())
+    }
+}
+
+enum Feasibility {
+    Feasible,
+    Infeasible,
+    Unknown(Status),
+    OutOfBudget,
+}
+
+struct Search<'p, S> {
+    solver: &'p S,
+    problem: &'p Problem<LinearExpression, Variable>,
+    max_solves: Option<usize>,
+    solves: usize,
+}
+
+impl<S: SolverTrait> Search<'_, S> {
+    /// Whether the given constraints are feasible together.
+    /// The objective is dropped, so that the problem cannot be unbounded.
+    fn check(&mut self, constraints: &[usize]) -> Result<Feasibility, SolverError> {
+        if self.max_solves.is_some_and(|max| self.solves >= max) {
+            return Ok(Feasibility::OutOfBudget);
+        }
+        let subset = Problem {
+            name: format!("{}_subset", self.problem.name),
+            sense: LpObjective::Minimize,
+            objective: LinearExpression::default(),
+            variables: self.problem.variables.clone(),
+            constraints: constraints
+                .iter()
+                .map(|&idx| {
+                    let constraint = &self.problem.constraints[idx];
+                    Constraint {
+                        lhs: constraint.lhs.clone(),
+                        operator: constraint.operator,
+                        rhs: constraint.rhs,
+                    }
+                })
+                .collect(),
+        };
+        self.solves += 1;
+        Ok(match self.solver.run(&subset)?.status {
+            Status::Infeasible | Status::InfeasibleOrUnbounded => Feasibility::Infeasible,
+            Status::Optimal | Status::SubOptimal | Status::Unbounded => Feasibility::Feasible,
+            status @ Status::NotSolved => Feasibility::Unknown(status),
+        })
+    }
+}
+
+/// Find constraints of an infeasible problem that are infeasible together, and
+/// as few as possible: without any one of them, the others are feasible.
+/// The bounds of the variables are always kept.
+///
+/// The problem is solved once as it is, then once per removal attempt: `max_solves` limits
+/// the number of solves, for large problems. When it is reached, the constraints that were
+/// not removed yet are returned, and the subset is not [minimal](InfeasibleSubset::minimal).
+///
+/// Fails when the problem is feasible, or when the solver cannot tell whether it is.
+///
+/// ```
+/// use std::cmp::Ordering;
+/// use std::collections::HashMap;
+/// use lp_solvers::lp_format::{ConsId, LpObjective};
+/// use lp_solvers::problem::{LinearExpression, ProblemBuilder};
+/// use lp_solvers::solvers::{find_infeasible_subset, FnSolver, Solution, SolverError, Status};
+///
+/// let x = |coef: f64| LinearExpression { coefficients: vec![("x".to_string(), coef)] };
+/// let mut builder = ProblemBuilder::new("pb", LpObjective::Minimize);
+/// builder.add_constraint(x(1.), Ordering::Greater, 3.);
+/// builder.add_constraint(x(1.), Ordering::Greater, 0.);
+/// builder.add_constraint(x(1.), Ordering::Less, 2.);
+/// let problem = builder.build();
+///
+/// // x >= 3 and x <= 2 cannot both hold
+/// let solver = FnSolver(|lp: &str| -> Result<Solution, SolverError> {
+///     let infeasible = lp.contains(">= 3") && lp.contains("<= 2");
+///     let status = if infeasible { Status::Infeasible } else { Status::Optimal };
+///     Ok(Solution::new(status, HashMap::new()))
+/// });
+/// let subset = find_infeasible_subset(&solver, &problem, None).unwrap();
+/// assert_eq!(subset.constraints, vec![ConsId::from_index(0), ConsId::from_index(2)]);
+/// assert!(subset.minimal);
+/// ```
+pub fn find_infeasible_subset<S: SolverTrait>(
+    solver: &S,
+    problem: &Problem<LinearExpression, Variable>,
+    max_solves: Option<usize>,
+) -> Result<InfeasibleSubset, SolverError> {
+    let mut search = Search {
+        solver,
+        problem,
+        max_solves,
+        solves: 0,
+    };
+    let mut kept: Vec<usize> = (0..problem.constraints.len()).collect();
+    match search.check(&kept)? {
+        Feasibility::Infeasible => {}
+        Feasibility::Feasible => {
+            return Err(format!("The problem {} is feasible", problem.name).into());
+        }
+        Feasibility::Unknown(status) => {
+            return Err(format!(
+                "The feasibility of the problem {} is unknown: it is {}",
+                problem.name, status
+            )
+            .into());
+        }
+        Feasibility::OutOfBudget => {
+            return Err("No solve is allowed to find an infeasible subset"
+                .to_string()
+                .into());
+        }
+    }
+
+    let mut minimal = true;
+    let mut chunk = (kept.len() / 2).max(1);
+    'search: loop {
+        let mut start = 0;
+        while start < kept.len() {
+            let end = (start + chunk).min(kept.len());
+            let candidate: Vec<usize> = kept[..start].iter().chain(&kept[end..]).copied().collect();
+            match search.check(&candidate)? {
+                Feasibility::Infeasible => kept = candidate,
+                Feasibility::Feasible => start = end,
+                // one by one, the constraint may or may not be needed
+                Feasibility::Unknown(_) => {
+                    if chunk == 1 {
+                        minimal = false;
+                    }
+                    start = end
+                }
+                Feasibility::OutOfBudget => {
+                    minimal = false;
+                    break 'search;
+                }
+            }
+        }
+        if chunk == 1 {
+            break;
+        }
+        chunk = (chunk / 2).max(1);
+    }
+    Ok(InfeasibleSubset {
+        constraints: kept.into_iter().map(ConsId::from_index).collect(),
+        minimal,
+        solves: search.solves,
+    })
+}
@@ -0,0 +1,77 @@
+//! Checking the license of commercial solvers without solving a problem,
+//! so that services can fail at startup instead of on their first request.
+
+#[cfg(any(feature = "gurobi", feature = "cplex"))]
+use std::ffi::OsStr;
+use std::fmt;
+#[cfg(any(feature = "gurobi", feature = "cplex"))]
+use std::process::{Command, Stdio};
+
+use crate::solvers::SolverError;
+#[cfg(any(feature = "gurobi", feature = "cplex"))]
+use crate::solvers::SolverProgram;
+
+/// The license that a solver found, see [LicenseCheck::check_license]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LicenseInfo {
+    /// The line of the output of the solver that describes the license, if there is one
+    pub description: Option<String>,
+    /// The expiration date, as printed by the solver. `None` for licenses that do not expire
+    pub expires: Option<String>,
+    /// Whether the license limits the size of the problems that can be solved
+    pub size_limited: bool,
+}
+
+impl fmt::Display for LicenseInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.description.as_deref().unwrap_or("license available"))?;
+        if let Some(expires) = &self.expires {
+            write!(f, " (expires {})", expires)?;
+        }
+        if self.size_limited {
+            f.write_str(" (size-limited)")?;
+        }
+        Ok(())
+    }
+}
+
+/// A solver that needs a license
+pub trait LicenseCheck {
+    /// Check that the solver can get a license, without solving a problem.
+    /// Fails with [SolverError::License] when it cannot.
+    ///
+    /// ```no_run
+    /// # #[cfg(feature = "gurobi")] {
+    /// use lp_solvers::solvers::{GurobiSolver, LicenseCheck};
+    ///
+    /// let license = GurobiSolver::new().check_license().expect("no gurobi license");
+    /// println!("gurobi: {}", license);
+    /// # }
+    /// ```
+    fn check_license(&self) -> Result<LicenseInfo, SolverError>;
+}
+
+/// Run the solver program with the given arguments,
+/// and return its output if it did not report a license error
+#[cfg(any(feature = "gurobi", feature = "cplex"))]
+pub(crate) fn run_license_check<S, I>(solver: &S, args: I) -> Result<String, SolverError>
+where
+    S: SolverProgram,
+    I: IntoIterator,
+    I::Item: AsRef<OsStr>,
+{
+    let command_name = solver.command_name();
+    let output = Command::new(command_name)
+        .args(args)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| format!("Error while running {}: {}", command_name, e))?;
+    if let Some(message) = solver.parse_license_error(&output.stdout, &output.stderr) {
+        return Err(SolverError::License(message));
+    }
+    if !output.status.success() {
+        return Err(format!("{} exited with status {}", command_name, output.status).into());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
@@ -0,0 +1,147 @@
+//! Recording statistics about every solve, to monitor a fleet of solver workers.
+//!
+//! A [MeteredSolver] reports each solve to a [SolverMetrics] implementation.
+//! With the `metrics` feature, [MetricsRecorder] forwards them to the
+//! [metrics](https://docs.rs/metrics) facade, which has exporters for Prometheus, StatsD, and others.
+
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::lp_format::LpProblem;
+use crate::solvers::{Solution, SolveReport, SolverError, SolverTrait, Status};
+
+/// A finished solve, successful or not
+#[derive(Debug, Clone)]
+pub struct SolveEvent<'a> {
+    /// The label of the solver, see [MeteredSolver::new]
+    pub solver: &'a str,
+    /// The status of the solution. `None` if the solver failed
+    pub status: Option<Status>,
+    /// Time spent in [SolverTrait::run]
+    pub duration: Duration,
+    /// Number of declared variables in the problem
+    pub variables: usize,
+    /// Number of constraints in the problem
+    pub constraints: usize,
+    /// Measurements made by external solvers
+    pub report: Option<&'a SolveReport>,
+}
+
+impl SolveEvent<'_> {
+    /// The status, or `"error"` if the solver failed, to be used as a metric label
+    pub fn status_label(&self) -> &'static str {
+        match self.status {
+            Some(Status::Optimal) => "optimal",
+            Some(Status::SubOptimal) => "suboptimal",
+            Some(Status::Infeasible) => "infeasible",
+            Some(Status::Unbounded) => "unbounded",
+            Some(Status::InfeasibleOrUnbounded) => "infeasible_or_unbounded",
+            Some(Status::NotSolved) => "not_solved",
+            None => "error",
+        }
+    }
+}
+
+/// Where the statistics of solves are sent
+pub trait SolverMetrics {
+    /// Called after each solve
+    fn record(&self, event: &SolveEvent<'_>);
+}
+
+/// Functions can record statistics
+impl<F: Fn(&SolveEvent<'_>)> SolverMetrics for F {
+    fn record(&self, event: &SolveEvent<'_>) {
+        self(event)
+    }
+}
+
+/// A solver that records statistics about each of its solves
+///
+/// ```
+/// use lp_solvers::solvers::mock::MockSolver;
+/// use lp_solvers::solvers::{MeteredSolver, SolveEvent};
+///
+/// let solver = MeteredSolver::new("mock", MockSolver::new(), |event: &SolveEvent<'_>| {
+///     println!("{} solve in {:?}", event.status_label(), event.duration)
+/// });
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MeteredSolver<S, M> {
+    /// the solver to run
+    pub solver: S,
+    /// where statistics are recorded
+    pub metrics: M,
+    /// identifies the solver in the statistics
+    pub label: String,
+}
+
+impl<S, M> MeteredSolver<S, M> {
+    /// Record statistics about the solves of `solver`, labelled with `label`
+    pub fn new(label: impl Into<String>, solver: S, metrics: M) -> Self {
+        MeteredSolver {
+            solver,
+            metrics,
+            label: label.into(),
+        }
+    }
+}
+
+impl<S: SolverTrait, M: SolverMetrics> SolverTrait for MeteredSolver<S, M> {
+    fn run<'a, P: LpProblem<'a>>(&self, problem: &'a P) -> Result<Solution, SolverError> {
+        let start = Instant::now();
+        let result = self.solver.run(problem);
+        let solution = result.as_ref().ok();
+        self.metrics.record(&SolveEvent {
+            solver: &self.label,
+            status: solution.map(|s| s.status.clone()),
+            duration: start.elapsed(),
+            variables: problem.variables().count(),
+            constraints: problem.constraints().count(),
+            report: solution.and_then(|s| s.report.as_ref()),
+        });
+        result
+    }
+
+    /// Solves of problem files are not recorded, since the size of their problem is not known
+    fn run_file(&self, problem_file: &Path) -> Result<Solution, SolverError> {
+        self.solver.run_file(problem_file)
+    }
+
+    fn is_available(&self) -> bool {
+        self.solver.is_available()
+    }
+}
+
+/// Sends statistics to the [metrics](https://docs.rs/metrics) facade:
+///  - `lp_solvers_solves_total`: counter of solves, labelled by `solver` and `status`
+///  - `lp_solvers_solve_duration_seconds`: histogram of solve durations, labelled by `solver`
+///  - `lp_solvers_problem_variables` and `lp_solvers_problem_constraints`:
+///    histograms of the size of the problems, labelled by `solver`
+///  - `lp_solvers_problem_file_bytes`: histogram of the size of the problem files
+///    of external solvers, labelled by `solver`
+#[cfg(feature = "metrics")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MetricsRecorder;
+
+#[cfg(feature = "metrics")]
+impl SolverMetrics for MetricsRecorder {
+    fn record(&self, event: &SolveEvent<'_>) {
+        let solver = event.solver.to_string();
+        ::metrics::counter!(
+            "lp_solvers_solves_total",
+            "solver" => solver.clone(),
+            "status" => event.status_label()
+        )
+        .increment(1);
+        ::metrics::histogram!("lp_solvers_solve_duration_seconds", "solver" => solver.clone())
+            .record(event.duration.as_secs_f64());
+        ::metrics::histogram!("lp_solvers_problem_variables", "solver" => solver.clone())
+            .record(event.variables as f64);
+        ::metrics::histogram!("lp_solvers_problem_constraints", "solver" => solver.clone())
+            .record(event.constraints as f64);
+        if let Some(report) = event.report {
+            ::metrics::histogram!("lp_solvers_problem_file_bytes", "solver" => solver)
+                .record(report.problem_file_size as f64);
+        }
+    }
+}
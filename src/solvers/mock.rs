@@ -0,0 +1,94 @@
+//! A solver that does not run anything, to test code that uses solvers
+//! without having any solver installed.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::lp_format::LpProblem;
+use crate::solvers::{Solution, SolverError, SolverTrait};
+
+/// A solver that returns preprogrammed solutions,
+/// and records the problems it was asked to solve.
+///
+/// ```
+/// use std::collections::HashMap;
+/// use lp_solvers::lp_format::LpObjective;
+/// use lp_solvers::problem::{Problem, StrExpression};
+/// use lp_solvers::solvers::mock::MockSolver;
+/// use lp_solvers::solvers::{Solution, SolverTrait, Status};
+///
+/// let solver = MockSolver::new()
+///     .with_solution(Solution::new(Status::Infeasible, HashMap::new()))
+///     .with_solution_for("pb", Solution::new(Status::Optimal, HashMap::new()));
+/// let pb: Problem = Problem {
+///     name: "pb".to_string(),
+///     sense: LpObjective::Minimize,
+///     objective: StrExpression("0".to_string()),
+///     variables: vec![],
+///     constraints: vec![],
+/// };
+/// assert_eq!(solver.run(&pb).unwrap().status, Status::Optimal);
+/// assert!(solver.problems()[0].contains("Minimize"));
+/// ```
+#[derive(Debug, Default)]
+pub struct MockSolver {
+    default: Option<Result<Solution, SolverError>>,
+    by_name: HashMap<String, Result<Solution, SolverError>>,
+    problems: Mutex<Vec<String>>,
+}
+
+impl MockSolver {
+    /// A mock solver that fails on every problem
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the given solution for the problems that have no specific solution
+    pub fn with_solution(self, solution: Solution) -> Self {
+        self.with_result(Ok(solution))
+    }
+
+    /// Return the given result for the problems that have no specific result
+    pub fn with_result(self, result: Result<Solution, SolverError>) -> Self {
+        MockSolver {
+            default: Some(result),
+            ..self
+        }
+    }
+
+    /// Return the given solution for problems with the given name
+    pub fn with_solution_for(self, problem_name: impl Into<String>, solution: Solution) -> Self {
+        self.with_result_for(problem_name, Ok(solution))
+    }
+
+    /// Return the given result for problems with the given name
+    pub fn with_result_for(
+        mut self,
+        problem_name: impl Into<String>,
+        result: Result<Solution, SolverError>,
+    ) -> Self {
+        self.by_name.insert(problem_name.into(), result);
+        self
+    }
+
+    /// The problems the solver was asked to solve, in the .lp format, in order
+    pub fn problems(&self) -> Vec<String> {
+        self.problems.lock().unwrap().clone()
+    }
+}
+
+impl SolverTrait for MockSolver {
+    fn run<'a, P: LpProblem<'a>>(&self, problem: &'a P) -> Result<Solution, SolverError> {
+        self.problems
+            .lock()
+            .unwrap()
+            .push(problem.display_lp().to_string());
+        self.by_name
+            .get(problem.name())
+            .or(self.default.as_ref())
+            .cloned()
+            .unwrap_or_else(|| {
+                Err(format!("MockSolver: no solution for problem {:?}", problem.name()).into())
+            })
+    }
+}
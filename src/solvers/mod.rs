@@ -23,31 +23,146 @@
 //! The respective information is provided in the project's README in the section on
 //! [installing external solvers](https://github.com/jcavat/rust-lp-modeler#installing-external-solvers).
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
 use std::ffi::OsString;
+use std::fmt;
 use std::fs::File;
 use std::marker::PhantomData;
 use std::path::{Path, PathBuf};
-use std::process::Command;
+#[cfg(feature = "process")]
+use std::process::{Command, Stdio};
+#[cfg(feature = "process")]
+use std::time::Instant;
 
-use crate::lp_format::LpProblem;
+use crate::lp_format::{AsVariable, ConsId, ConstraintNaming, LpProblem, ProblemFormat};
+use crate::util::NamePolicy;
+
+/// Implement [SolverCapabilities] for a wrapper by forwarding to the solver it wraps
+macro_rules! forward_capabilities {
+    ($wrapper:ident<$inner:ident $(: $bound:path)? $(, $other:ident)*>, |$this:ident| $solver:expr) => {
+        impl<$inner: SolverCapabilities $(+ $bound)? $(, $other)*> SolverCapabilities
+            for $wrapper<$inner $(, $other)*>
+        {
+            fn supports_mip(&self) -> bool {
+                let $this = self;
+                let solver = $solver;
+                solver.supports_mip()
+            }
+
+            fn supports_quadratic(&self) -> bool {
+                let $this = self;
+                let solver = $solver;
+                solver.supports_quadratic()
+            }
+
+            fn supports_sos(&self) -> bool {
+                let $this = self;
+                let solver = $solver;
+                solver.supports_sos()
+            }
+
+            fn supports_indicators(&self) -> bool {
+                let $this = self;
+                let solver = $solver;
+                solver.supports_indicators()
+            }
+
+            fn supports_warm_start(&self) -> bool {
+                let $this = self;
+                let solver = $solver;
+                solver.supports_warm_start()
+            }
+
+            fn name_policy(&self) -> crate::util::NamePolicy {
+                let $this = self;
+                let solver = $solver;
+                solver.name_policy()
+            }
+        }
+    };
+}
 
 pub use self::auto::*;
+#[cfg(feature = "cbc")]
 pub use self::cbc::*;
+#[cfg(all(feature = "process", feature = "serde"))]
+pub use self::config::*;
 #[cfg(feature = "cplex")]
 pub use self::cplex::*;
+pub use self::cuts::*;
+#[cfg(feature = "process")]
+pub use self::diagnosis::*;
+pub use self::discovery::*;
+pub use self::fixed_mip::*;
+#[cfg(feature = "glpk")]
 pub use self::glpk::*;
+#[cfg(feature = "gurobi")]
 pub use self::gurobi::*;
+#[cfg(feature = "process")]
+pub use self::hooks::*;
+pub use self::iis::*;
+#[cfg(feature = "process")]
+pub use self::license::*;
+pub use self::metrics::*;
+pub use self::options::*;
+#[cfg(feature = "process")]
+pub use self::registry::*;
+pub use self::relaxation::*;
+#[cfg(feature = "remote")]
+pub use self::remote::*;
+pub use self::report::*;
+#[cfg(feature = "process")]
+pub use self::resolving::*;
+pub use self::scenarios::*;
+pub use self::shared::*;
+pub use self::status_rules::*;
+#[cfg(feature = "process")]
+pub use self::temp_files::*;
+pub use self::tuning::*;
 
 pub mod auto;
+#[cfg(feature = "cbc")]
 pub mod cbc;
+#[cfg(all(feature = "process", feature = "serde"))]
+pub mod config;
 #[cfg(feature = "cplex")]
 pub mod cplex;
+pub mod cuts;
+#[cfg(feature = "process")]
+pub mod diagnosis;
+pub mod discovery;
+pub mod fixed_mip;
+#[cfg(feature = "glpk")]
 pub mod glpk;
+#[cfg(feature = "gurobi")]
 pub mod gurobi;
+#[cfg(feature = "process")]
+pub mod hooks;
+pub mod iis;
+#[cfg(feature = "process")]
+pub mod license;
+pub mod metrics;
+pub mod mock;
+pub mod options;
+#[cfg(feature = "process")]
+pub mod registry;
+pub mod relaxation;
+#[cfg(feature = "remote")]
+pub mod remote;
+pub mod report;
+#[cfg(feature = "process")]
+pub mod resolving;
+pub mod scenarios;
+pub mod shared;
+pub mod status_rules;
+#[cfg(feature = "process")]
+pub mod temp_files;
+pub mod tuning;
 
 /// Solution status
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Status {
     /// the best possible solution was found
     Optimal,
@@ -57,30 +172,396 @@ pub enum Status {
     Infeasible,
     /// There is no single finite optimum for the problem
     Unbounded,
+    /// The problem is either infeasible or unbounded, but the solver did not determine which
+    InfeasibleOrUnbounded,
     /// Unable to solve
     NotSolved,
 }
 
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Status::Optimal => "optimal",
+            Status::SubOptimal => "suboptimal",
+            Status::Infeasible => "infeasible",
+            Status::Unbounded => "unbounded",
+            Status::InfeasibleOrUnbounded => "infeasible or unbounded",
+            Status::NotSolved => "not solved",
+        })
+    }
+}
+
 /// A solution to a problem
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Solution {
     /// solution state
     pub status: Status,
     /// map from variable name to variable value
+    #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_sorted"))]
     pub results: HashMap<String, f32>,
+    /// map from constraint name to the value of its dual,
+    /// for the solvers that report it
+    #[cfg_attr(feature = "serde", serde(default, serialize_with = "serialize_sorted"))]
+    pub dual_values: HashMap<String, f32>,
+    /// value of the objective function, for the solvers that report it
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub objective: Option<f64>,
+    /// relative gap between the solution and the best bound, for the MIP solvers that report it
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub mip_gap: Option<f64>,
+    /// how the solution was obtained, when it comes from an external solver
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub report: Option<SolveReport>,
+    /// how the constraints were named in the problem file, to map dual values back to constraints
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub constraint_naming: ConstraintNaming,
+    /// problems found while reading the solution, and changes made to it silently,
+    /// such as skipped lines in [ParserMode::Lenient] or values defaulted to zero
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub warnings: Vec<Warning>,
+    /// the names of the variables in the order of the problem, when they were recorded
+    /// with [Solution::set_variable_order] or by [OrderedSolver]. See [Solution::ordered_results]
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub variable_order: Vec<String>,
+    /// which solver of an [AutoSolver] produced the solution, and why the ones before it
+    /// were skipped
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub solver_selection: Option<SolverSelection>,
+    /// other solutions that the solver found besides this one,
+    /// such as the members of the solution pool of cplex in populate mode
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub solution_pool: Vec<Solution>,
+}
+
+/// Serialize a map sorted by key, so that serialized solutions can be compared
+#[cfg(feature = "serde")]
+fn serialize_sorted<S: serde::Serializer>(
+    map: &HashMap<String, f32>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let sorted: std::collections::BTreeMap<&String, &f32> = map.iter().collect();
+    serde::Serialize::serialize(&sorted, serializer)
 }
 
 impl Solution {
     /// Create a solution
     pub fn new(status: Status, results: HashMap<String, f32>) -> Solution {
-        Solution { status, results }
+        Solution {
+            status,
+            results,
+            dual_values: HashMap::new(),
+            objective: None,
+            mip_gap: None,
+            report: None,
+            constraint_naming: ConstraintNaming::default(),
+            warnings: vec![],
+            variable_order: vec![],
+            solver_selection: None,
+            solution_pool: vec![],
+        }
+    }
+
+    /// The value of the variable with the given name, if the solution has one
+    ///
+    /// ```
+    /// use lp_solvers::solvers::{Solution, Status};
+    ///
+    /// let results = vec![("x".to_string(), 2.), ("open".to_string(), 0.999_999_9)];
+    /// let solution = Solution::new(Status::Optimal, results.into_iter().collect());
+    /// assert_eq!(solution.value("x"), Some(2.));
+    /// assert_eq!(solution.int_value("x"), Some(2));
+    /// assert!(solution.is_one("open"));
+    /// assert_eq!(solution.value("y"), None);
+    /// ```
+    pub fn value(&self, name: &str) -> Option<f64> {
+        self.results.get(name).map(|&value| f64::from(value))
+    }
+
+    /// The value of the variable with the given name, rounded to the nearest integer.
+    /// `None` if the solution has no value for the variable, or if the value is further than
+    /// [DEFAULT_INTEGRALITY_TOLERANCE] from an integer.
+    pub fn int_value(&self, name: &str) -> Option<i64> {
+        let value = *self.results.get(name)?;
+        let rounded = value.round();
+        if (value - rounded).abs() <= DEFAULT_INTEGRALITY_TOLERANCE {
+            Some(rounded as i64)
+        } else {
+            None
+        }
+    }
+
+    /// Whether the binary variable with the given name is set, that is, whether its value
+    /// is within [DEFAULT_INTEGRALITY_TOLERANCE] of 1
+    pub fn is_one(&self, name: &str) -> bool {
+        self.int_value(name) == Some(1)
+    }
+
+    /// The values of the variables, sorted by variable name.
+    /// Unlike iterating over [Solution::results], the order does not change between runs.
+    pub fn sorted_results(&self) -> Vec<(&str, f32)> {
+        let mut results: Vec<_> = self
+            .results
+            .iter()
+            .map(|(name, &value)| (name.as_str(), value))
+            .collect();
+        results.sort_unstable_by(|a, b| a.0.cmp(b.0));
+        results
+    }
+
+    /// Remember the order of the variables of the problem, for [Solution::ordered_results]
+    pub fn set_variable_order<'a, P: LpProblem<'a>>(&mut self, problem: &'a P) {
+        self.variable_order = problem.variables().map(|v| v.name().to_string()).collect();
+    }
+
+    /// The values of the variables, in the order of the problem if it was recorded
+    /// (see [Solution::variable_order]), then the other values sorted by variable name.
+    /// Reports and diffs of solutions made from it do not depend on the order of a hash map.
+    ///
+    /// ```
+    /// use lp_solvers::solvers::{Solution, Status};
+    ///
+    /// let results = vec![("b".to_string(), 1.), ("a".to_string(), 2.), ("c".to_string(), 3.)];
+    /// let mut solution = Solution::new(Status::Optimal, results.into_iter().collect());
+    /// assert_eq!(solution.ordered_results(), vec![("a", 2.), ("b", 1.), ("c", 3.)]);
+    /// solution.variable_order = vec!["c".to_string(), "b".to_string()];
+    /// assert_eq!(solution.ordered_results(), vec![("c", 3.), ("b", 1.), ("a", 2.)]);
+    /// ```
+    pub fn ordered_results(&self) -> Vec<(&str, f32)> {
+        if self.variable_order.is_empty() {
+            return self.sorted_results();
+        }
+        let ordered: HashSet<&str> = self.variable_order.iter().map(String::as_str).collect();
+        let mut results: Vec<_> = self
+            .variable_order
+            .iter()
+            .filter_map(|name| Some((name.as_str(), *self.results.get(name)?)))
+            .collect();
+        results.extend(
+            self.sorted_results()
+                .into_iter()
+                .filter(|(name, _)| !ordered.contains(name)),
+        );
+        results
+    }
+
+    /// Value of the dual of the given constraint, if the solver reported it
+    pub fn dual(&self, constraint: ConsId) -> Option<f32> {
+        self.dual_values
+            .get(&self.constraint_naming.name(constraint))
+            .copied()
+    }
+
+    /// Dual values of the constraints, by constraint.
+    /// Dual values whose name does not match [Solution::constraint_naming] are skipped.
+    pub fn constraint_duals(&self) -> impl Iterator<Item = (ConsId, f32)> + '_ {
+        self.dual_values.iter().filter_map(move |(name, &value)| {
+            Some((self.constraint_naming.constraint(name)?, value))
+        })
+    }
+
+    /// Round the values of the integer variables of the problem to the nearest integer
+    /// when they are within `tolerance` of it.
+    /// Solvers return values such as `0.9999999` for variables that should be `1`.
+    ///
+    /// Returns the names of the integer variables whose value is further than `tolerance`
+    /// from any integer. These values are left unchanged.
+    /// See [DEFAULT_INTEGRALITY_TOLERANCE].
+    pub fn snap_integers<'a, P: LpProblem<'a>>(
+        &mut self,
+        problem: &'a P,
+        tolerance: f32,
+    ) -> Vec<String> {
+        let mut not_integral = vec![];
+        for variable in problem.variables().filter(|v| v.is_integer()) {
+            if let Some(value) = self.results.get_mut(variable.name()) {
+                let rounded = value.round();
+                if (*value - rounded).abs() <= tolerance {
+                    *value = rounded;
+                } else {
+                    not_integral.push(variable.name().to_string());
+                }
+            }
+        }
+        not_integral
+    }
+
+    /// The values of the variables of the problem, typed by the kind of the variable:
+    /// the values of binary variables (integer variables between 0 and 1) and of the other
+    /// integer variables are rounded to the nearest integer, and the values of continuous
+    /// variables are kept as they are.
+    /// The values are in the order of the problem. The variables without a value are skipped.
+    ///
+    /// Rounding hides values that are far from any integer, which solvers only return when
+    /// they failed: [Solution::snap_integers] finds them.
+    ///
+    /// ```
+    /// use lp_solvers::lp_format::LpObjective;
+    /// use lp_solvers::problem::{Problem, StrExpression, Variable};
+    /// use lp_solvers::solvers::{Solution, Status, TypedValue};
+    ///
+    /// let var = |name: &str, is_integer, upper_bound| Variable {
+    ///     name: name.to_string(),
+    ///     is_integer,
+    ///     lower_bound: 0.,
+    ///     upper_bound,
+    /// };
+    /// let problem: Problem = Problem {
+    ///     name: "typed".to_string(),
+    ///     sense: LpObjective::Minimize,
+    ///     objective: StrExpression("x".to_string()),
+    ///     variables: vec![var("open", true, 1.), var("count", true, 10.), var("x", false, 10.)],
+    ///     constraints: vec![],
+    /// };
+    /// let results = vec![("open", 0.999_999_9), ("count", 3.000_001), ("x", 0.5)];
+    /// let results = results.into_iter().map(|(n, v)| (n.to_string(), v)).collect();
+    /// let solution = Solution::new(Status::Optimal, results);
+    /// assert_eq!(
+    ///     solution.typed_results(&problem),
+    ///     vec![
+    ///         ("open", TypedValue::Bool(true)),
+    ///         ("count", TypedValue::Int(3)),
+    ///         ("x", TypedValue::Float(0.5)),
+    ///     ]
+    /// );
+    /// ```
+    pub fn typed_results<'a, P: LpProblem<'a>>(&self, problem: &'a P) -> Vec<(&str, TypedValue)> {
+        problem
+            .variables()
+            .filter_map(|variable| {
+                let (name, &value) = self.results.get_key_value(variable.name())?;
+                let value = f64::from(value);
+                let typed = if !variable.is_integer() {
+                    TypedValue::Float(value)
+                } else if variable.lower_bound() >= 0. && variable.upper_bound() <= 1. {
+                    TypedValue::Bool(value.round() >= 1.)
+                } else {
+                    TypedValue::Int(value.round() as i64)
+                };
+                Some((name.as_str(), typed))
+            })
+            .collect()
+    }
+}
+
+/// The value of a variable in a solution, typed by the kind of the variable.
+/// See [Solution::typed_results]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TypedValue {
+    /// The value of a binary variable
+    Bool(bool),
+    /// The rounded value of an integer variable
+    Int(i64),
+    /// The value of a continuous variable
+    Float(f64),
+}
+
+impl TypedValue {
+    /// The value as a number, 0 or 1 for binary variables
+    pub fn as_f64(&self) -> f64 {
+        match *self {
+            TypedValue::Bool(value) => f64::from(u8::from(value)),
+            TypedValue::Int(value) => value as f64,
+            TypedValue::Float(value) => value,
+        }
+    }
+}
+
+impl fmt::Display for TypedValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TypedValue::Bool(value) => value.fmt(f),
+            TypedValue::Int(value) => value.fmt(f),
+            TypedValue::Float(value) => value.fmt(f),
+        }
+    }
+}
+
+/// The default integrality tolerance of the supported solvers
+pub const DEFAULT_INTEGRALITY_TOLERANCE: f32 = 1e-5;
+
+/// An error that prevented a solver from returning a solution
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolverError {
+    /// The solver could not obtain a valid license. Contains the message of the solver.
+    License(String),
+    /// Any other error
+    Other(String),
+}
+
+impl fmt::Display for SolverError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolverError::License(message) => write!(f, "License error: {}", message),
+            SolverError::Other(message) => f.write_str(message),
+        }
+    }
+}
+
+impl Error for SolverError {}
+
+impl From<String> for SolverError {
+    fn from(message: String) -> Self {
+        SolverError::Other(message)
+    }
+}
+
+impl From<SolverError> for String {
+    fn from(error: SolverError) -> Self {
+        error.to_string()
     }
 }
 
 /// A solver that can take a problem and return a solution
+///
+/// The solvers of this crate are `Send + Sync`: one configured solver can be shared
+/// between threads, such as the handlers of a web server, and run on several problems
+/// at the same time. Each solve writes its own temporary files. Solves that write to the
+/// same fixed [solution file](SolverProgram::preferred_temp_solution_file) run one after
+/// the other, unless its path contains [RUN_ID_PLACEHOLDER].
 pub trait SolverTrait {
     /// Run the solver on the given problem
-    fn run<'a, P: LpProblem<'a>>(&self, problem: &'a P) -> Result<Solution, String>;
+    fn run<'a, P: LpProblem<'a>>(&self, problem: &'a P) -> Result<Solution, SolverError>;
+    /// Whether the solver can be used on this computer.
+    /// By default, this solves a tiny problem.
+    fn is_available(&self) -> bool {
+        self.run(&auto::probe_problem()).is_ok()
+    }
+    /// Find out whether the solver is available, and its version if it can tell it
+    fn discover(&self) -> Discovery {
+        Discovery {
+            available: self.is_available(),
+            version: None,
+        }
+    }
+    /// The name under which the discovery of this solver is stored in a [DiscoveryCache].
+    /// `None` if it should not be cached.
+    fn discovery_key(&self) -> Option<&str> {
+        None
+    }
+    /// Solve a problem file that already exists, such as a `.lp` or `.mps` file written by
+    /// another program, without reading it into an [LpProblem] first.
+    /// External solvers recognize the format of the file by its extension
+    /// (see [SolverProgram::problem_formats]), like [solve_file](crate::solvers::solve_file).
+    ///
+    /// By default, this fails: only solvers that read files can solve them.
+    fn run_file(&self, problem_file: &Path) -> Result<Solution, SolverError> {
+        Err(format!(
+            "This solver cannot solve the problem file {:?}",
+            problem_file
+        )
+        .into())
+    }
 }
 
 /// An external commandline solver
@@ -97,10 +578,138 @@ pub trait SolverProgram {
     fn parse_stdout_status(&self, _stdout: &[u8]) -> Option<Status> {
         None
     }
+    /// If the output of the program shows that it could not get a license, return the error message
+    fn parse_license_error(&self, _stdout: &[u8], _stderr: &[u8]) -> Option<String> {
+        None
+    }
+    /// Parse the relative MIP gap reported in the output of the program
+    fn parse_stdout_gap(&self, _stdout: &[u8]) -> Option<f64> {
+        None
+    }
     /// A suffix the solution file must have
     fn solution_suffix(&self) -> Option<&str> {
         None
     }
+    /// Arguments with which the program quickly exits successfully when it is installed,
+    /// such as `--version`, used by [SolverTrait::is_available] instead of solving a problem.
+    /// `None` for programs that must solve a problem to check their license.
+    fn version_arguments(&self) -> Option<&[&str]> {
+        None
+    }
+    /// Whether the program can read an .lp problem file from a path without extension,
+    /// such as `/proc/<pid>/fd/<fd>`. See [AnonymousFileSolver]
+    fn reads_paths_without_extension(&self) -> bool {
+        false
+    }
+    /// The formats of the problem files the program reads, recognized by their extension.
+    /// See [crate::lp_format::FormatSelection] for how the format is chosen.
+    fn problem_formats(&self) -> &[ProblemFormat] {
+        &[ProblemFormat::Lp]
+    }
+    /// Files the program writes besides the solution, such as logs.
+    /// The ones that exist after a solve are listed in [SolveReport::output_files].
+    fn output_files(&self) -> Vec<PathBuf> {
+        vec![]
+    }
+    /// The log file the program appends to, if it writes one.
+    /// When the status or the gap is not in the output of the program, as when it does not
+    /// log to the console, they are read from the lines it added to its log file.
+    fn log_file(&self) -> Option<&Path> {
+        None
+    }
+    /// Read the [output files](SolverProgram::output_files) that the program wrote into the
+    /// report of the solve, after it exited successfully.
+    /// Returns the problems found in them, which become warnings of the solution.
+    fn read_output_files(&self, _report: &mut SolveReport) -> Vec<Warning> {
+        vec![]
+    }
+    /// The command that solves the given problem file, without running it.
+    /// This is what the solver runs, unless [crate::solvers::SolverHooks::before_spawn] changes it.
+    ///
+    /// ```
+    /// # #[cfg(feature = "cbc")] {
+    /// use std::path::Path;
+    /// use lp_solvers::solvers::{CbcSolver, SolverProgram, WithNbThreads};
+    ///
+    /// let spec = CbcSolver::new()
+    ///     .with_nb_threads(2)
+    ///     .command_spec(Path::new("problem.lp"), Path::new("problem.sol"));
+    /// assert_eq!(
+    ///     spec.command_line(),
+    ///     vec!["cbc", "problem.lp", "threads", "2", "solve", "solution", "problem.sol"]
+    /// );
+    /// # }
+    /// ```
+    fn command_spec(&self, lp_file: &Path, solution_file: &Path) -> CommandSpec {
+        CommandSpec {
+            args: self.arguments(lp_file, solution_file),
+            ..CommandSpec::new(self.command_name())
+        }
+    }
+}
+
+/// How an external solver program is started: a description of a [Command]
+/// that can be inspected and compared without running anything
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommandSpec {
+    /// the program to run
+    pub program: OsString,
+    /// its arguments
+    pub args: Vec<OsString>,
+    /// the environment variables set for the program, in addition to the environment
+    /// of the current process. `None` removes the variable.
+    pub env: Vec<(OsString, Option<OsString>)>,
+    /// the working directory of the program, if it is not the current directory
+    pub cwd: Option<PathBuf>,
+}
+
+impl CommandSpec {
+    /// Run the given program without arguments
+    pub fn new(program: impl Into<OsString>) -> Self {
+        CommandSpec {
+            program: program.into(),
+            ..Self::default()
+        }
+    }
+
+    /// The description of the given command
+    #[cfg(feature = "process")]
+    pub fn from_command(command: &Command) -> Self {
+        CommandSpec {
+            program: command.get_program().to_owned(),
+            args: command.get_args().map(|arg| arg.to_owned()).collect(),
+            env: command
+                .get_envs()
+                .map(|(name, value)| (name.to_owned(), value.map(|v| v.to_owned())))
+                .collect(),
+            cwd: command.get_current_dir().map(Path::to_path_buf),
+        }
+    }
+
+    /// A command that can be run
+    #[cfg(feature = "process")]
+    pub fn to_command(&self) -> Command {
+        let mut command = Command::new(&self.program);
+        command.args(&self.args);
+        for (name, value) in &self.env {
+            match value {
+                Some(value) => command.env(name, value),
+                None => command.env_remove(name),
+            };
+        }
+        if let Some(cwd) = &self.cwd {
+            command.current_dir(cwd);
+        }
+        command
+    }
+
+    /// The program and its arguments, as text
+    pub fn command_line(&self) -> Vec<String> {
+        std::iter::once(&self.program)
+            .chain(&self.args)
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect()
+    }
 }
 
 /// A solver that can parse a solution file
@@ -120,16 +729,21 @@ pub trait SolverWithSolutionParsing {
         temp_solution_file: &Path,
         problem: Option<&'a P>,
     ) -> Result<Solution, String> {
-        match File::open(temp_solution_file) {
-            Ok(f) => {
-                let res = self.read_specific_solution(&f, problem)?;
-                Ok(res)
-            }
-            Err(e) => Err(format!(
-                "Cannot open solution file {:?}: {}",
-                temp_solution_file, e
-            )),
-        }
+        let f = open_solution_file(temp_solution_file)?;
+        self.read_specific_solution(&f, problem)
+    }
+    /// Read a solution, keeping only the values of the given variables.
+    /// The solvers of this crate skip the other variables while parsing, instead of storing them,
+    /// which saves memory when only a few of the variables of a large problem are needed.
+    fn read_solution_filtered<'a, P: LpProblem<'a>>(
+        &self,
+        temp_solution_file: &Path,
+        problem: Option<&'a P>,
+        variables: &HashSet<String>,
+    ) -> Result<Solution, String> {
+        let mut solution = self.read_solution_from_path(temp_solution_file, problem)?;
+        solution.results.retain(|name, _| variables.contains(name));
+        Ok(solution)
     }
     /// Read a solution from a file
     fn read_specific_solution<'a, P: LpProblem<'a>>(
@@ -139,55 +753,505 @@ pub trait SolverWithSolutionParsing {
     ) -> Result<Solution, String>;
 }
 
-impl<T: SolverWithSolutionParsing + SolverProgram> SolverTrait for T {
-    fn run<'a, P: LpProblem<'a>>(&self, problem: &'a P) -> Result<Solution, String> {
-        let command_name = self.command_name();
-        let file_model = problem
-            .to_tmp_file()
-            .map_err(|e| format!("Unable to create {} problem file: {}", command_name, e))?;
-
-        let temp_solution_file = if let Some(p) = self.preferred_temp_solution_file() {
-            PathBuf::from(p)
-        } else {
-            let mut builder = tempfile::Builder::new();
-            if let Some(suffix) = self.solution_suffix() {
-                builder.suffix(suffix);
+#[cfg(any(
+    feature = "cbc",
+    feature = "cplex",
+    feature = "glpk",
+    feature = "gurobi"
+))]
+/// The variables whose values are stored when reading a solution. All of them by default
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct VariableFilter<'f>(Option<&'f HashSet<String>>);
+
+#[cfg(any(
+    feature = "cbc",
+    feature = "cplex",
+    feature = "glpk",
+    feature = "gurobi"
+))]
+impl<'f> VariableFilter<'f> {
+    /// Only keep the given variables
+    pub(crate) fn only(variables: &'f HashSet<String>) -> Self {
+        VariableFilter(Some(variables))
+    }
+
+    /// Whether the value of the variable should be stored
+    pub(crate) fn keeps(&self, name: &str) -> bool {
+        match self.0 {
+            Some(variables) => variables.contains(name),
+            None => true,
+        }
+    }
+
+    /// Number of values to reserve room for, given the number of variables of the problem
+    pub(crate) fn capacity(&self, variables_len: usize) -> usize {
+        self.0.map_or(variables_len, HashSet::len)
+    }
+}
+
+/// Open a solution file, with a readable error message
+pub(crate) fn open_solution_file(path: &Path) -> Result<File, String> {
+    File::open(path).map_err(|e| format!("Cannot open solution file {:?}: {}", path, e))
+}
+
+/// Map a solution file in memory, to parse it without copying it
+#[cfg(all(feature = "memmap", any(feature = "cbc", feature = "gurobi")))]
+pub(crate) fn map_solution_file(f: &File) -> Result<memmap2::Mmap, String> {
+    // SAFETY: solution files are not modified once the solver has exited
+    unsafe { memmap2::Mmap::map(f) }.map_err(|e| format!("Unable to map solution file: {}", e))
+}
+
+#[cfg(any(
+    feature = "cbc",
+    feature = "cplex",
+    feature = "glpk",
+    feature = "gurobi"
+))]
+/// A number read from a solution file.
+///
+/// With the `fast-float` feature, values are parsed with `lexical`,
+/// which is noticeably faster than the standard library on very large solution files.
+pub(crate) trait SolutionValue: Sized {
+    /// Parse a value, with a readable error message
+    fn parse_value(value: &[u8]) -> Result<Self, String>;
+}
+
+#[cfg(any(
+    feature = "cbc",
+    feature = "cplex",
+    feature = "glpk",
+    feature = "gurobi"
+))]
+macro_rules! impl_solution_value {
+    ($($t:ty),*) => {$(
+        impl SolutionValue for $t {
+            #[cfg(feature = "fast-float")]
+            fn parse_value(value: &[u8]) -> Result<Self, String> {
+                lexical_parse_float::FromLexical::from_lexical(value).map_err(|e| {
+                    format!("invalid float {:?}: {}", String::from_utf8_lossy(value), e)
+                })
             }
-            PathBuf::from(builder.tempfile().map_err(|e| e.to_string())?.path())
-        };
-        let arguments = self.arguments(file_model.path(), &temp_solution_file);
-
-        let output = Command::new(command_name)
-            .args(arguments)
-            .output()
-            .map_err(|e| format!("Error while running {}: {}", command_name, e))?;
-
-        if !output.status.success() {
-            return Err(format!(
-                "{} exited with status {}",
-                command_name, output.status
-            ));
-        }
-        match self.parse_stdout_status(&output.stdout) {
-            Some(Status::Infeasible) => Ok(Solution::new(Status::Infeasible, Default::default())),
-            Some(Status::Unbounded) => Ok(Solution::new(Status::Unbounded, Default::default())),
-            status_hint => {
-                let mut solution = self
-                    .read_solution_from_path(&temp_solution_file, Some(problem))
+
+            #[cfg(not(feature = "fast-float"))]
+            fn parse_value(value: &[u8]) -> Result<Self, String> {
+                std::str::from_utf8(value)
+                    .map_err(|e| e.to_string())?
+                    .parse()
                     .map_err(|e| {
-                        format!(
-                            "{}. Solver output: {}",
-                            e,
-                            std::str::from_utf8(&output.stdout).unwrap_or("Invalid UTF8")
-                        )
-                    })?;
-                if let Some(status) = status_hint {
-                    solution.status = status;
-                }
-                Ok(solution)
+                        format!("invalid float {:?}: {}", String::from_utf8_lossy(value), e)
+                    })
+            }
+        }
+    )*};
+}
+
+#[cfg(any(
+    feature = "cbc",
+    feature = "cplex",
+    feature = "glpk",
+    feature = "gurobi"
+))]
+impl_solution_value!(f32, f64);
+
+#[cfg(feature = "process")]
+impl<T: SolverWithSolutionParsing + SolverProgram> SolverTrait for T {
+    fn run<'a, P: LpProblem<'a>>(&self, problem: &'a P) -> Result<Solution, SolverError> {
+        run_program(self, problem, &(), None)
+    }
+
+    fn run_file(&self, problem_file: &Path) -> Result<Solution, SolverError> {
+        solve_file(self, problem_file)
+    }
+
+    fn is_available(&self) -> bool {
+        self.discover().available
+    }
+
+    fn discover(&self) -> Discovery {
+        match self.version_arguments() {
+            Some(args) => match Command::new(self.command_name())
+                .args(args)
+                .stdin(Stdio::null())
+                .output()
+            {
+                Ok(output) if output.status.success() => Discovery {
+                    available: true,
+                    version: version_line(&output.stdout),
+                },
+                _ => Discovery {
+                    available: false,
+                    version: None,
+                },
+            },
+            None => Discovery {
+                available: self.run(&auto::probe_problem()).is_ok(),
+                version: None,
+            },
+        }
+    }
+
+    fn discovery_key(&self) -> Option<&str> {
+        Some(self.command_name())
+    }
+}
+
+/// A shared solver is a solver, so that one solver can be used by many threads
+/// and stored in the state of an application without cloning its configuration
+impl<S: SolverTrait> SolverTrait for std::sync::Arc<S> {
+    fn run<'a, P: LpProblem<'a>>(&self, problem: &'a P) -> Result<Solution, SolverError> {
+        (**self).run(problem)
+    }
+
+    fn run_file(&self, problem_file: &Path) -> Result<Solution, SolverError> {
+        (**self).run_file(problem_file)
+    }
+
+    fn is_available(&self) -> bool {
+        (**self).is_available()
+    }
+
+    fn discover(&self) -> Discovery {
+        (**self).discover()
+    }
+
+    fn discovery_key(&self) -> Option<&str> {
+        (**self).discovery_key()
+    }
+}
+
+/// Implement [SolverProgram] and [SolverWithSolutionParsing] for pointers to external solvers,
+/// which makes them implement [SolverTrait].
+/// [SolverTrait] itself cannot be implemented for `&S` and `Box<S>`, because it is already
+/// implemented for all the types that implement these two traits.
+macro_rules! forward_program {
+    ($($pointer:ty),*) => {$(
+        impl<S: SolverProgram> SolverProgram for $pointer {
+            fn command_name(&self) -> &str {
+                (**self).command_name()
+            }
+
+            fn arguments(&self, lp_file: &Path, solution_file: &Path) -> Vec<OsString> {
+                (**self).arguments(lp_file, solution_file)
+            }
+
+            fn preferred_temp_solution_file(&self) -> Option<&Path> {
+                (**self).preferred_temp_solution_file()
+            }
+
+            fn parse_stdout_status(&self, stdout: &[u8]) -> Option<Status> {
+                (**self).parse_stdout_status(stdout)
+            }
+
+            fn parse_license_error(&self, stdout: &[u8], stderr: &[u8]) -> Option<String> {
+                (**self).parse_license_error(stdout, stderr)
+            }
+
+            fn parse_stdout_gap(&self, stdout: &[u8]) -> Option<f64> {
+                (**self).parse_stdout_gap(stdout)
+            }
+
+            fn solution_suffix(&self) -> Option<&str> {
+                (**self).solution_suffix()
+            }
+
+            fn version_arguments(&self) -> Option<&[&str]> {
+                (**self).version_arguments()
+            }
+
+            fn reads_paths_without_extension(&self) -> bool {
+                (**self).reads_paths_without_extension()
+            }
+
+            fn problem_formats(&self) -> &[ProblemFormat] {
+                (**self).problem_formats()
             }
+
+            fn output_files(&self) -> Vec<PathBuf> {
+                (**self).output_files()
+            }
+
+            fn log_file(&self) -> Option<&Path> {
+                (**self).log_file()
+            }
+
+            fn read_output_files(&self, report: &mut SolveReport) -> Vec<Warning> {
+                (**self).read_output_files(report)
+            }
+
+            fn command_spec(&self, lp_file: &Path, solution_file: &Path) -> CommandSpec {
+                (**self).command_spec(lp_file, solution_file)
+            }
+        }
+
+        impl<S: SolverWithSolutionParsing> SolverWithSolutionParsing for $pointer {
+            fn read_solution_from_path<'a, P: LpProblem<'a>>(
+                &self,
+                temp_solution_file: &Path,
+                problem: Option<&'a P>,
+            ) -> Result<Solution, String> {
+                (**self).read_solution_from_path(temp_solution_file, problem)
+            }
+
+            fn read_solution_filtered<'a, P: LpProblem<'a>>(
+                &self,
+                temp_solution_file: &Path,
+                problem: Option<&'a P>,
+                variables: &HashSet<String>,
+            ) -> Result<Solution, String> {
+                (**self).read_solution_filtered(temp_solution_file, problem, variables)
+            }
+
+            fn read_specific_solution<'a, P: LpProblem<'a>>(
+                &self,
+                f: &File,
+                problem: Option<&'a P>,
+            ) -> Result<Solution, String> {
+                (**self).read_specific_solution(f, problem)
+            }
+        }
+    )*};
+}
+
+forward_program!(&S, Box<S>);
+
+#[cfg(feature = "process")]
+/// The line that mentions the version in the output of a program, or its first line
+fn version_line(stdout: &[u8]) -> Option<String> {
+    let stdout = String::from_utf8_lossy(stdout);
+    let mut lines = stdout.lines().map(str::trim).filter(|l| !l.is_empty());
+    let first = lines.clone().next()?;
+    let line = lines
+        .find(|l| l.to_ascii_lowercase().contains("version"))
+        .unwrap_or(first);
+    Some(line.to_string())
+}
+
+#[cfg(feature = "process")]
+/// The size of a log file, to read only what a solve adds to it
+fn log_file_size(path: &Path) -> u64 {
+    std::fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+}
+
+#[cfg(feature = "process")]
+/// What was added to a log file after it had the given size.
+/// The whole file is read if it is smaller, because it was replaced.
+fn read_log_since(path: &Path, size: u64) -> Vec<u8> {
+    let mut log = std::fs::read(path).unwrap_or_default();
+    if log.len() as u64 >= size {
+        log.drain(..size as usize);
+    }
+    log
+}
+
+#[cfg(feature = "process")]
+/// Run an external solver program on the problem, calling the given hooks,
+/// and taking the temporary files from the pool if there is one
+pub(crate) fn run_program<'a, S, P, H>(
+    solver: &S,
+    problem: &'a P,
+    hooks: &H,
+    pool: Option<&TempFilePool>,
+) -> Result<Solution, SolverError>
+where
+    S: SolverWithSolutionParsing + SolverProgram,
+    P: LpProblem<'a>,
+    H: SolverHooks + ?Sized,
+{
+    let command_name = solver.command_name();
+    let mut report = SolveReport::default();
+
+    let format = problem
+        .format_options()
+        .format
+        .select(problem, solver.problem_formats());
+    let start = Instant::now();
+    let file_model = match pool {
+        Some(pool) => pool.acquire(format.suffix()).and_then(|mut f| {
+            problem.to_writer_in(format, f.as_file_mut())?;
+            Ok(f)
+        }),
+        None => problem.to_tmp_file_in(format).map(PooledFile::unpooled),
+    }
+    .map_err(|e| format!("Unable to create {} problem file: {}", command_name, e))?;
+    report.write_time = start.elapsed();
+    report.problem_file_size = file_model
+        .as_file()
+        .metadata()
+        .map(|m| m.len())
+        .unwrap_or_default();
+    run_on_file(
+        solver,
+        Some(problem),
+        file_model.path(),
+        report,
+        hooks,
+        pool,
+    )
+}
+
+#[cfg(feature = "process")]
+/// Run an external solver program on a problem that was already written to `problem_file`.
+/// The report already contains the statistics about the problem file.
+/// The problem is `None` when the file does not come from an [LpProblem].
+pub(crate) fn run_on_file<'a, S, P, H>(
+    solver: &S,
+    problem: Option<&'a P>,
+    problem_file: &Path,
+    mut report: SolveReport,
+    hooks: &H,
+    pool: Option<&TempFilePool>,
+) -> Result<Solution, SolverError>
+where
+    S: SolverWithSolutionParsing + SolverProgram,
+    P: LpProblem<'a>,
+    H: SolverHooks + ?Sized,
+{
+    let command_name = solver.command_name();
+    report.run_id = new_run_id();
+    let run_id = report.run_id.clone();
+    // keeps the pooled solution file, or the fixed one, until the solution is read
+    let mut pooled_solution_file = None;
+    let mut _fixed_solution_file = None;
+    let temp_solution_file = if let Some(p) = solver.preferred_temp_solution_file() {
+        let path = with_run_id(p, &run_id);
+        _fixed_solution_file = Some(FixedFileLock::acquire(&path));
+        path
+    } else if let Some(pool) = pool {
+        let file = pool
+            .acquire(solver.solution_suffix().unwrap_or_default())
+            .map_err(|e| e.to_string())?;
+        pooled_solution_file.insert(file).path().to_path_buf()
+    } else {
+        let mut builder = tempfile::Builder::new();
+        if let Some(suffix) = solver.solution_suffix() {
+            builder.suffix(suffix);
         }
+        PathBuf::from(builder.tempfile().map_err(|e| e.to_string())?.path())
+    };
+    let mut spec = solver.command_spec(problem_file, &temp_solution_file);
+    spec.args = spec
+        .args
+        .into_iter()
+        .map(|arg| os_with_run_id(arg, &run_id))
+        .collect();
+    let mut prepared = PreparedSolve {
+        problem_file,
+        solution_file: &temp_solution_file,
+        command: spec.to_command(),
+    };
+    hooks.before_spawn(&mut prepared);
+    let mut command = prepared.command;
+    report.set_command_line(&command);
+
+    let log_file = solver.log_file().map(|path| with_run_id(path, &run_id));
+    let log_size = log_file.as_deref().map(log_file_size);
+    let start = Instant::now();
+    let output = command
+        .output()
+        .map_err(|e| format!("Error while running {}: {}", command_name, e))?;
+    report.solve_time = start.elapsed();
+    report.output_files = solver
+        .output_files()
+        .into_iter()
+        .map(|file| with_run_id(&file, &run_id))
+        .filter(|file| file.exists())
+        .collect();
+    hooks.after_finish(&RawOutcome {
+        problem_file,
+        solution_file: &temp_solution_file,
+        output: &output,
+        report: &report,
+    });
+
+    if let Some(message) = solver.parse_license_error(&output.stdout, &output.stderr) {
+        return Err(SolverError::License(message));
     }
+    if !output.status.success() {
+        return Err(format!("{} exited with status {}", command_name, output.status).into());
+    }
+    let output_file_warnings = solver.read_output_files(&mut report);
+    let log = match (log_file, log_size) {
+        (Some(path), Some(size)) => read_log_since(&path, size),
+        _ => vec![],
+    };
+    let status = solver
+        .parse_stdout_status(&output.stdout)
+        .or_else(|| solver.parse_stdout_status(&log));
+    let mut solution = match status {
+        Some(status @ Status::Infeasible)
+        | Some(status @ Status::Unbounded)
+        | Some(status @ Status::InfeasibleOrUnbounded)
+        | Some(status @ Status::NotSolved) => Solution::new(status, Default::default()),
+        status_hint => {
+            let start = Instant::now();
+            let mut solution = solver
+                .read_solution_from_path(&temp_solution_file, problem)
+                .map_err(|e| {
+                    format!(
+                        "{}. Solver output: {}",
+                        e,
+                        std::str::from_utf8(&output.stdout).unwrap_or("Invalid UTF8")
+                    )
+                })?;
+            report.parse_time = start.elapsed();
+            report.solution_file_size =
+                std::fs::metadata(&temp_solution_file).map(|m| m.len()).ok();
+            if let Some(status) = status_hint {
+                solution.status = status;
+            }
+            let gap = solver
+                .parse_stdout_gap(&output.stdout)
+                .or_else(|| solver.parse_stdout_gap(&log));
+            if let Some(gap) = gap {
+                solution.mip_gap = Some(gap);
+            }
+            solution
+        }
+    };
+    // keep what the parser read from the solution file
+    if let Some(parsed) = solution.report.take() {
+        report.model_name = parsed.model_name;
+    }
+    solution.report = Some(report);
+    solution.warnings.extend(output_file_warnings);
+    if let Some(problem) = problem {
+        solution.constraint_naming = problem.format_options().constraint_naming;
+    }
+    Ok(solution)
+}
+
+#[cfg(feature = "process")]
+/// Solve a problem file that already exists, instead of an [LpProblem].
+/// The solver recognizes the format of the file by its extension
+/// (see [SolverProgram::problem_formats]).
+///
+/// Constraints are expected to be named like [ConstraintNaming::default] for [Solution::dual].
+pub fn solve_file<S>(solver: &S, problem_file: &Path) -> Result<Solution, SolverError>
+where
+    S: SolverWithSolutionParsing + SolverProgram,
+{
+    run_file_program(solver, problem_file, &(), None)
+}
+
+#[cfg(feature = "process")]
+/// [solve_file], calling the given hooks, and taking the solution file from the pool
+pub(crate) fn run_file_program<S, H>(
+    solver: &S,
+    problem_file: &Path,
+    hooks: &H,
+    pool: Option<&TempFilePool>,
+) -> Result<Solution, SolverError>
+where
+    S: SolverWithSolutionParsing + SolverProgram,
+    H: SolverHooks + ?Sized,
+{
+    let report = SolveReport {
+        problem_file_size: std::fs::metadata(problem_file)
+            .map_err(|e| format!("Cannot open problem file {:?}: {}", problem_file, e))?
+            .len(),
+        ..SolveReport::default()
+    };
+    run_on_file::<_, crate::problem::Problem, _>(solver, None, problem_file, report, hooks, pool)
 }
 
 /// Configure the max allowed runtime
@@ -214,11 +1278,449 @@ pub trait WithMipGap<T> {
     fn with_mip_gap(&self, mipgap: f32) -> Result<T, String>;
 }
 
+/// Configure a starting solution, for solvers that support warm starts
+pub trait WithMipStart<T> {
+    /// get the file of the starting solution
+    fn mip_start(&self) -> Option<&Path>;
+    /// start from the solution in the given file, written by [WithMipStart::write_mip_start]
+    fn with_mip_start(&self, solution_file: PathBuf) -> T;
+    /// Write a solution in the format of the starting solutions of the solver.
+    /// Files are given a `.sol` suffix.
+    fn write_mip_start(
+        &self,
+        solution: &Solution,
+        writer: &mut dyn std::io::Write,
+    ) -> std::io::Result<()>;
+}
+
+/// The kinds of problems a solver can handle, to check a problem against the chosen solver
+/// before writing a file it would reject
+pub trait SolverCapabilities {
+    /// Whether the solver handles integer variables
+    fn supports_mip(&self) -> bool;
+    /// Whether the solver handles quadratic objectives and constraints
+    fn supports_quadratic(&self) -> bool;
+    /// Whether the solver handles special ordered sets
+    fn supports_sos(&self) -> bool;
+    /// Whether the solver handles indicator constraints
+    fn supports_indicators(&self) -> bool;
+    /// Whether the solver can start from a known solution
+    fn supports_warm_start(&self) -> bool;
+    /// The rules that variable names must follow for the solver to read them.
+    /// See [crate::util::UniqueNameGenerator::for_solver]
+    fn name_policy(&self) -> NamePolicy {
+        NamePolicy::lp()
+    }
+
+    /// Check that the solver can handle the problem
+    fn check_problem<'a, P: LpProblem<'a>>(&self, problem: &'a P) -> Result<(), String> {
+        if !self.supports_mip() {
+            if let Some(v) = problem.variables().find(|v| v.is_integer()) {
+                return Err(format!(
+                    "The solver does not support integer variables such as {}",
+                    v.name()
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A solver where presolve can be enabled or disabled
+pub trait WithPresolve<T> {
+    /// whether presolve is enabled, if it was configured
+    fn presolve(&self) -> Option<bool>;
+    /// enable or disable presolve
+    fn with_presolve(&self, presolve: bool) -> T;
+}
+
+/// A problem found while reading a solution, or a change made to it silently.
+/// See [Solution::warnings]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Warning {
+    /// A malformed line of the solution file was skipped, in [ParserMode::Lenient]
+    SkippedLine {
+        /// the number of the line in the file, starting at 1, when the parser knows it
+        line: Option<usize>,
+        /// why the line could not be read
+        message: String,
+    },
+    /// Variables of the problem were absent from the solution file, and were given the value 0.
+    /// See [MissingVariables::ZeroWithWarning]
+    DefaultedToZero {
+        /// the names of the variables
+        variables: Vec<String>,
+    },
+    /// A variable had a name the solver does not accept, and was solved under another name.
+    /// See [crate::util::NameMap::restore]
+    Renamed {
+        /// the name of the variable in the problem
+        original: String,
+        /// the name the solver saw
+        renamed: String,
+    },
+    /// Any other problem
+    Other(String),
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Warning::SkippedLine {
+                line: Some(line),
+                message,
+            } => write!(f, "line {}: {}", line, message),
+            Warning::SkippedLine {
+                line: None,
+                message,
+            } => f.write_str(message),
+            Warning::DefaultedToZero { variables } => write!(
+                f,
+                "{} variables absent from the solution were set to 0",
+                variables.len()
+            ),
+            Warning::Renamed { original, renamed } => {
+                write!(f, "variable {} was solved as {}", original, renamed)
+            }
+            Warning::Other(message) => f.write_str(message),
+        }
+    }
+}
+
+impl From<String> for Warning {
+    fn from(message: String) -> Self {
+        Warning::Other(message)
+    }
+}
+
+/// An error found while reading a solution file.
+///
+/// The parsers of solutions in memory, such as [parse_cbc_solution_bytes], return it.
+/// They read any input without panicking, so they can be fuzzed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// the line of the file the error is on, counting from 1, when it is about one line
+    pub line: Option<usize>,
+    /// what is wrong
+    pub message: String,
+}
+
+impl ParseError {
+    /// An error on the given line
+    pub fn at(line: usize, message: impl Into<String>) -> Self {
+        ParseError {
+            line: Some(line),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(line) = self.line {
+            write!(f, "line {}: ", line)?;
+        }
+        f.write_str(&self.message)
+    }
+}
+
+impl Error for ParseError {}
+
+impl From<String> for ParseError {
+    fn from(message: String) -> Self {
+        ParseError {
+            line: None,
+            message,
+        }
+    }
+}
+
+impl From<&str> for ParseError {
+    fn from(message: &str) -> Self {
+        message.to_string().into()
+    }
+}
+
+impl From<ParseError> for String {
+    fn from(error: ParseError) -> Self {
+        error.to_string()
+    }
+}
+
+impl From<ParseError> for SolverError {
+    fn from(error: ParseError) -> Self {
+        SolverError::Other(error.to_string())
+    }
+}
+
+/// How solution parsers handle malformed lines in the solution file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ParserMode {
+    /// Fail on the first malformed line
+    #[default]
+    Strict,
+    /// Skip malformed lines, and report them in [Solution::warnings]
+    Lenient,
+}
+
+impl ParserMode {
+    /// Fail with the given error in strict mode, or record it as a warning in lenient mode
+    #[cfg(any(feature = "cbc", feature = "glpk", feature = "gurobi"))]
+    pub(crate) fn malformed(
+        &self,
+        line: Option<usize>,
+        message: String,
+        warnings: &mut Vec<Warning>,
+    ) -> Result<(), ParseError> {
+        match self {
+            ParserMode::Strict => Err(ParseError { line, message }),
+            ParserMode::Lenient => {
+                warnings.push(Warning::SkippedLine { line, message });
+                Ok(())
+            }
+        }
+    }
+}
+
+/// A solver whose solution parser can skip malformed lines
+pub trait WithParserMode<T> {
+    /// how malformed lines are handled
+    fn parser_mode(&self) -> ParserMode;
+    /// set how malformed lines are handled
+    fn with_parser_mode(&self, mode: ParserMode) -> T;
+}
+
+/// What solution parsers do with the variables of the problem that are absent from the
+/// solution file, when the problem is known. Cbc omits some variables whose value is zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MissingVariables {
+    /// Give them the value 0. Cbc leaves out zeros on every solve, so this is silent
+    #[default]
+    Zero,
+    /// Give them the value 0, and list them in a [Warning::DefaultedToZero]
+    ZeroWithWarning,
+    /// Fail, naming the first of them
+    Error,
+    /// Leave them out of [Solution::results]
+    Skip,
+}
+
+impl MissingVariables {
+    /// Apply the policy to the variables of the problem that the filter keeps
+    #[cfg(any(
+        feature = "cbc",
+        feature = "cplex",
+        feature = "glpk",
+        feature = "gurobi"
+    ))]
+    pub(crate) fn apply<'a, P: LpProblem<'a>>(
+        self,
+        solution: &mut Solution,
+        problem: Option<&'a P>,
+        filter: VariableFilter,
+    ) -> Result<(), String> {
+        let problem = match problem {
+            Some(problem) if self != MissingVariables::Skip => problem,
+            _ => return Ok(()),
+        };
+        let missing: Vec<String> = problem
+            .variables()
+            .filter(|v| filter.keeps(v.name()) && !solution.results.contains_key(v.name()))
+            .map(|v| v.name().to_string())
+            .collect();
+        match missing.first() {
+            None => Ok(()),
+            Some(first) if self == MissingVariables::Error => Err(format!(
+                "{} variables are absent from the solution, such as {}",
+                missing.len(),
+                first
+            )),
+            Some(_) => {
+                for name in missing.iter() {
+                    solution.results.insert(name.clone(), 0.);
+                }
+                if self == MissingVariables::ZeroWithWarning {
+                    solution
+                        .warnings
+                        .push(Warning::DefaultedToZero { variables: missing });
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// A solver whose solution parser can be told what to do with missing variables
+pub trait WithMissingVariables<T> {
+    /// what is done with the variables absent from the solution file
+    fn missing_variables(&self) -> MissingVariables;
+    /// set what is done with the variables absent from the solution file
+    fn with_missing_variables(&self, policy: MissingVariables) -> T;
+}
+
+/// A solver that, when a problem is reported as [Status::InfeasibleOrUnbounded],
+/// solves it again with presolve disabled to return a definitive
+/// [Status::Infeasible] or [Status::Unbounded].
+///
+/// ```
+/// # #[cfg(feature = "gurobi")] {
+/// use lp_solvers::solvers::{DisambiguatingSolver, GurobiSolver};
+/// let solver = DisambiguatingSolver(GurobiSolver::new());
+/// # }
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct DisambiguatingSolver<S>(pub S);
+
+impl<S: SolverTrait + WithPresolve<S>> SolverTrait for DisambiguatingSolver<S> {
+    fn run<'a, P: LpProblem<'a>>(&self, problem: &'a P) -> Result<Solution, SolverError> {
+        let solution = self.0.run(problem)?;
+        if solution.status == Status::InfeasibleOrUnbounded {
+            self.0.with_presolve(false).run(problem)
+        } else {
+            Ok(solution)
+        }
+    }
+
+    fn run_file(&self, problem_file: &Path) -> Result<Solution, SolverError> {
+        let solution = self.0.run_file(problem_file)?;
+        if solution.status == Status::InfeasibleOrUnbounded {
+            self.0.with_presolve(false).run_file(problem_file)
+        } else {
+            Ok(solution)
+        }
+    }
+
+    fn is_available(&self) -> bool {
+        self.0.is_available()
+    }
+
+    fn discover(&self) -> Discovery {
+        self.0.discover()
+    }
+
+    fn discovery_key(&self) -> Option<&str> {
+        self.0.discovery_key()
+    }
+}
+
+forward_capabilities!(DisambiguatingSolver<S>, |this| &this.0);
+
+/// A solver that records the order of the variables of the problem in its solutions,
+/// so that [Solution::ordered_results] lists them in that order
+///
+/// ```
+/// # #[cfg(feature = "cbc")] {
+/// use lp_solvers::solvers::{CbcSolver, OrderedSolver};
+/// let solver = OrderedSolver(CbcSolver::new());
+/// # }
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct OrderedSolver<S>(pub S);
+
+impl<S: SolverTrait> SolverTrait for OrderedSolver<S> {
+    fn run<'a, P: LpProblem<'a>>(&self, problem: &'a P) -> Result<Solution, SolverError> {
+        let mut solution = self.0.run(problem)?;
+        solution.set_variable_order(problem);
+        Ok(solution)
+    }
+
+    /// The variables of a problem file are not known: they are in the order of the solution file
+    fn run_file(&self, problem_file: &Path) -> Result<Solution, SolverError> {
+        self.0.run_file(problem_file)
+    }
+
+    fn is_available(&self) -> bool {
+        self.0.is_available()
+    }
+
+    fn discover(&self) -> Discovery {
+        self.0.discover()
+    }
+
+    fn discovery_key(&self) -> Option<&str> {
+        self.0.discovery_key()
+    }
+}
+
+forward_capabilities!(OrderedSolver<S>, |this| &this.0);
+
+/// A solver that first tightens the bounds of the variables with
+/// [propagate_bounds](crate::presolve::propagate_bounds), and returns [Status::Infeasible]
+/// without running the solver when that shows the problem is infeasible.
+/// Useful when solving many small problems, many of which are infeasible.
+///
+/// The problem given to the solver is not changed.
+///
+/// ```
+/// # #[cfg(feature = "cbc")] {
+/// use lp_solvers::solvers::{CbcSolver, PresolvingSolver};
+/// let solver = PresolvingSolver(CbcSolver::new());
+/// # }
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct PresolvingSolver<S>(pub S);
+
+impl<S: SolverTrait> SolverTrait for PresolvingSolver<S> {
+    fn run<'a, P: LpProblem<'a>>(&self, problem: &'a P) -> Result<Solution, SolverError> {
+        if crate::presolve::propagate_bounds(problem).is_err() {
+            let mut solution = Solution::new(Status::Infeasible, HashMap::new());
+            solution.constraint_naming = problem.format_options().constraint_naming;
+            return Ok(solution);
+        }
+        self.0.run(problem)
+    }
+
+    /// A problem file is given to the solver as it is
+    fn run_file(&self, problem_file: &Path) -> Result<Solution, SolverError> {
+        self.0.run_file(problem_file)
+    }
+
+    fn is_available(&self) -> bool {
+        self.0.is_available()
+    }
+
+    fn discover(&self) -> Discovery {
+        self.0.discover()
+    }
+
+    fn discovery_key(&self) -> Option<&str> {
+        self.0.discovery_key()
+    }
+}
+
+forward_capabilities!(PresolvingSolver<S>, |this| &this.0);
+
+/// A solver that passes the problem, in the .lp format, to a function.
+/// Useful to plug in solving logic that is not an external program.
+///
+/// ```
+/// use std::collections::HashMap;
+/// use lp_solvers::solvers::{FnSolver, Solution, SolverError, Status};
+///
+/// let solver = FnSolver(|lp: &str| -> Result<Solution, SolverError> {
+///     assert!(lp.contains("Subject To"));
+///     Ok(Solution::new(Status::Optimal, HashMap::new()))
+/// });
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct FnSolver<F>(pub F);
+
+impl<F: Fn(&str) -> Result<Solution, SolverError>> SolverTrait for FnSolver<F> {
+    fn run<'a, P: LpProblem<'a>>(&self, problem: &'a P) -> Result<Solution, SolverError> {
+        let mut solution = (self.0)(&problem.display_lp().to_string())?;
+        solution.constraint_naming = problem.format_options().constraint_naming;
+        Ok(solution)
+    }
+}
+
 /// A static version of a solver, where the solver itself doesn't hold any data
 ///
 /// ```
+/// # #[cfg(feature = "cbc")] {
 /// use lp_solvers::solvers::{StaticSolver, CbcSolver};
 /// const STATIC_SOLVER : StaticSolver<CbcSolver> = StaticSolver::new();
+/// # }
 /// ```
 #[derive(Default, Copy, Clone)]
 pub struct StaticSolver<T>(PhantomData<T>);
@@ -231,8 +1733,18 @@ impl<T> StaticSolver<T> {
 }
 
 impl<T: SolverTrait + Default> SolverTrait for StaticSolver<T> {
-    fn run<'a, P: LpProblem<'a>>(&self, problem: &'a P) -> Result<Solution, String> {
+    fn run<'a, P: LpProblem<'a>>(&self, problem: &'a P) -> Result<Solution, SolverError> {
         let solver = T::default();
         SolverTrait::run(&solver, problem)
     }
+
+    fn run_file(&self, problem_file: &Path) -> Result<Solution, SolverError> {
+        T::default().run_file(problem_file)
+    }
+
+    fn is_available(&self) -> bool {
+        T::default().is_available()
+    }
 }
+
+forward_capabilities!(StaticSolver<T: Default>, |_this| T::default());
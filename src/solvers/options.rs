@@ -0,0 +1,88 @@
+//! Options that most solvers understand, to configure a solver
+//! without knowing which one it is.
+//!
+//! A [SolverOptions] can be loaded from a configuration file (with the `serde` feature),
+//! and applied with [ApplyOptions] to a single solver or to all the solvers of an
+//! [AutoSolver](crate::solvers::AutoSolver), whichever one ends up solving the problem.
+
+/// Options shared by the solvers. Options that are not set keep the defaults of the solver.
+///
+/// ```
+/// use lp_solvers::solvers::{AllSolvers, ApplyOptions, SolverOptions};
+///
+/// let options = SolverOptions::new().with_time_limit(60).with_threads(4);
+/// let solver = AllSolvers::new().apply_options(&options).unwrap();
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct SolverOptions {
+    /// The maximum solving time, in seconds
+    pub time_limit: Option<u32>,
+    /// The number of threads the solver uses
+    pub threads: Option<u32>,
+    /// The relative MIP gap at which the solver stops
+    pub mip_gap: Option<f32>,
+    /// Whether the solver simplifies the problem before solving it
+    pub presolve: Option<bool>,
+    /// The seed of the random choices of the solver, to reproduce a solve
+    pub seed: Option<u32>,
+    /// How much the solver logs, from 0 (nothing) upwards
+    pub verbosity: Option<u32>,
+}
+
+impl SolverOptions {
+    /// No options: every solver keeps its defaults
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the maximum solving time, in seconds
+    pub fn with_time_limit(mut self, seconds: u32) -> Self {
+        self.time_limit = Some(seconds);
+        self
+    }
+
+    /// Set the number of threads
+    pub fn with_threads(mut self, threads: u32) -> Self {
+        self.threads = Some(threads);
+        self
+    }
+
+    /// Set the relative MIP gap
+    pub fn with_mip_gap(mut self, mip_gap: f32) -> Self {
+        self.mip_gap = Some(mip_gap);
+        self
+    }
+
+    /// Enable or disable presolve
+    pub fn with_presolve(mut self, presolve: bool) -> Self {
+        self.presolve = Some(presolve);
+        self
+    }
+
+    /// Set the random seed
+    pub fn with_seed(mut self, seed: u32) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Set the log level
+    pub fn with_verbosity(mut self, verbosity: u32) -> Self {
+        self.verbosity = Some(verbosity);
+        self
+    }
+}
+
+/// A solver that can be configured with [SolverOptions]
+pub trait ApplyOptions: Sized {
+    /// A copy of the solver with the given options set.
+    ///
+    /// Options that the solver has no setting for are ignored, so that the same options
+    /// can be given to any solver. Fails if an option has a value the solver rejects,
+    /// such as a negative MIP gap.
+    fn apply_options(&self, options: &SolverOptions) -> Result<Self, String>;
+}
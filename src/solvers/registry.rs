@@ -0,0 +1,187 @@
+//! Solvers chosen by name at runtime, for command line tools and services
+//! that let their users pick the solver, as in `--solver NAME`.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+
+use crate::lp_format::LpProblem;
+#[cfg(feature = "cbc")]
+use crate::solvers::CbcSolver;
+#[cfg(feature = "cplex")]
+use crate::solvers::Cplex;
+#[cfg(feature = "glpk")]
+use crate::solvers::GlpkSolver;
+#[cfg(feature = "gurobi")]
+use crate::solvers::GurobiSolver;
+use crate::solvers::{
+    AllSolvers, ApplyOptions, Discovery, Solution, SolverError, SolverOptions, SolverTrait,
+};
+use crate::template::ProblemTemplate;
+
+/// Any solver, behind a pointer so that solvers of different types can be stored together.
+///
+/// The problem is written in memory as a [ProblemTemplate] before being given to the solver,
+/// which costs a copy of its text and of its linear terms.
+pub struct BoxedSolver(Box<dyn ErasedSolver>);
+
+impl BoxedSolver {
+    /// Box the given solver
+    pub fn new<S: SolverTrait + Send + Sync + 'static>(solver: S) -> Self {
+        BoxedSolver(Box::new(solver))
+    }
+}
+
+impl fmt::Debug for BoxedSolver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BoxedSolver").finish_non_exhaustive()
+    }
+}
+
+impl SolverTrait for BoxedSolver {
+    fn run<'a, P: LpProblem<'a>>(&self, problem: &'a P) -> Result<Solution, SolverError> {
+        self.0.run_template(&ProblemTemplate::new(problem))
+    }
+
+    fn run_file(&self, problem_file: &Path) -> Result<Solution, SolverError> {
+        self.0.run_file_erased(problem_file)
+    }
+
+    fn is_available(&self) -> bool {
+        self.0.available()
+    }
+
+    fn discover(&self) -> Discovery {
+        self.0.discover_erased()
+    }
+
+    fn discovery_key(&self) -> Option<&str> {
+        self.0.discovery_key_erased()
+    }
+}
+
+/// [SolverTrait], with a problem of a known type so that it can be used as a trait object
+trait ErasedSolver: Send + Sync {
+    fn run_template(&self, problem: &ProblemTemplate) -> Result<Solution, SolverError>;
+    fn run_file_erased(&self, problem_file: &Path) -> Result<Solution, SolverError>;
+    fn available(&self) -> bool;
+    fn discover_erased(&self) -> Discovery;
+    fn discovery_key_erased(&self) -> Option<&str>;
+}
+
+impl<S: SolverTrait + Send + Sync> ErasedSolver for S {
+    fn run_template(&self, problem: &ProblemTemplate) -> Result<Solution, SolverError> {
+        self.run(problem)
+    }
+
+    fn run_file_erased(&self, problem_file: &Path) -> Result<Solution, SolverError> {
+        self.run_file(problem_file)
+    }
+
+    fn available(&self) -> bool {
+        self.is_available()
+    }
+
+    fn discover_erased(&self) -> Discovery {
+        self.discover()
+    }
+
+    fn discovery_key_erased(&self) -> Option<&str> {
+        self.discovery_key()
+    }
+}
+
+/// A function that builds a solver with the given options
+pub type SolverFactory = Box<dyn Fn(&SolverOptions) -> Result<BoxedSolver, String> + Send + Sync>;
+
+/// Solvers by name. See [registry] for the solvers of this crate.
+///
+/// ```
+/// # #[cfg(feature = "cbc")] {
+/// use lp_solvers::solvers::{registry, BoxedSolver, CbcSolver, SolverOptions};
+///
+/// let mut solvers = registry();
+/// solvers.register("cbc-nightly", |_options: &SolverOptions| {
+///     Ok(BoxedSolver::new(CbcSolver::new().command_name("/opt/cbc/bin/cbc".to_string())))
+/// });
+/// let names: Vec<&str> = solvers.names().collect();
+/// println!("--solver {}", names.join("|"));
+/// let solver = solvers.build("cbc-nightly", &SolverOptions::new()).unwrap();
+/// # }
+/// ```
+#[derive(Default)]
+pub struct SolverRegistry {
+    factories: BTreeMap<String, SolverFactory>,
+}
+
+impl SolverRegistry {
+    /// A registry without any solver
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a solver under the given name, replacing the one that had this name
+    pub fn register<F>(&mut self, name: impl Into<String>, factory: F)
+    where
+        F: Fn(&SolverOptions) -> Result<BoxedSolver, String> + Send + Sync + 'static,
+    {
+        self.factories.insert(name.into(), Box::new(factory));
+    }
+
+    /// Whether a solver has the given name
+    pub fn contains(&self, name: &str) -> bool {
+        self.factories.contains_key(name)
+    }
+
+    /// The names of the solvers, in alphabetical order
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.factories.keys().map(String::as_str)
+    }
+
+    /// Build the solver with the given name and options
+    pub fn build(&self, name: &str, options: &SolverOptions) -> Result<BoxedSolver, String> {
+        match self.factories.get(name) {
+            Some(factory) => factory(options),
+            None => Err(format!(
+                "Unknown solver {:?}, expected one of: {}",
+                name,
+                self.names().collect::<Vec<_>>().join(", ")
+            )),
+        }
+    }
+}
+
+impl fmt::Debug for SolverRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.names()).finish()
+    }
+}
+
+/// A registry of the solvers of this crate whose feature is enabled: "cbc", "glpk", "gurobi",
+/// "cplex", and "auto" for the first one that is installed, as [AllSolvers].
+/// More solvers can be [registered](SolverRegistry::register).
+pub fn registry() -> SolverRegistry {
+    let mut registry = SolverRegistry::new();
+    registry.register("auto", |options: &SolverOptions| {
+        Ok(BoxedSolver::new(AllSolvers::new().apply_options(options)?))
+    });
+    #[cfg(feature = "cbc")]
+    registry.register("cbc", |options: &SolverOptions| {
+        Ok(BoxedSolver::new(CbcSolver::new().apply_options(options)?))
+    });
+    #[cfg(feature = "cplex")]
+    registry.register("cplex", |options: &SolverOptions| {
+        Ok(BoxedSolver::new(Cplex::default().apply_options(options)?))
+    });
+    #[cfg(feature = "glpk")]
+    registry.register("glpk", |options: &SolverOptions| {
+        Ok(BoxedSolver::new(GlpkSolver::new().apply_options(options)?))
+    });
+    #[cfg(feature = "gurobi")]
+    registry.register("gurobi", |options: &SolverOptions| {
+        Ok(BoxedSolver::new(
+            GurobiSolver::new().apply_options(options)?,
+        ))
+    });
+    registry
+}
@@ -0,0 +1,280 @@
+//! Finding out why a problem is infeasible, by relaxing its constraints.
+//!
+//! [relax_infeasible] solves a problem, and when it is infeasible, solves an elastic version of
+//! it instead: the constraints chosen in a [Relaxation] get slack variables, and the elastic
+//! problem minimizes their penalized sum. Its solution tells which constraints had to be
+//! violated, and by how much, for the problem to have a solution.
+
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+use crate::lp_format::{ConsId, Constraint, LpObjective};
+use crate::problem::{LinearExpression, Problem, Variable};
+use crate::solvers::{Solution, SolverError, SolverTrait, Status};
+use crate::util::UniqueNameGenerator;
+
+/// The constraints that may be violated in the elastic version of a problem,
+/// and the penalty for each unit of their violation
+///
+/// ```
+/// use lp_solvers::lp_format::ConsId;
+/// use lp_solvers::solvers::Relaxation;
+///
+/// let relaxation = Relaxation::all(1.).with_constraint(ConsId::from_index(2), 10.);
+/// assert_eq!(relaxation.penalty(ConsId::from_index(0)), Some(1.));
+/// assert_eq!(relaxation.penalty(ConsId::from_index(2)), Some(10.));
+/// assert_eq!(Relaxation::new().penalty(ConsId::from_index(0)), None);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Relaxation {
+    penalties: HashMap<ConsId, f64>,
+    default_penalty: Option<f64>,
+}
+
+impl Relaxation {
+    /// No constraint may be violated
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Every constraint may be violated, with the given penalty
+    pub fn all(penalty: f64) -> Self {
+        Relaxation {
+            default_penalty: Some(penalty),
+            ..Self::default()
+        }
+    }
+
+    /// The given constraint may be violated, with the given penalty
+    pub fn with_constraint(mut self, constraint: ConsId, penalty: f64) -> Self {
+        self.penalties.insert(constraint, penalty);
+        self
+    }
+
+    /// The penalty for each unit of violation of the given constraint,
+    /// or `None` if it may not be violated
+    pub fn penalty(&self, constraint: ConsId) -> Option<f64> {
+        self.penalties
+            .get(&constraint)
+            .copied()
+            .or(self.default_penalty)
+    }
+
+    /// The elastic version of the problem: the relaxed constraints get slack variables,
+    /// and the objective is to minimize the penalized sum of the slacks.
+    /// The original objective is dropped.
+    ///
+    /// The constraints keep their order, so they keep their [ConsId].
+    pub fn elastic_problem(&self, problem: &Problem<LinearExpression, Variable>) -> ElasticProblem {
+        let mut generator = UniqueNameGenerator::default();
+        let mut used = HashSet::with_capacity(problem.variables.len());
+        for variable in problem.variables.iter() {
+            generator.add_variable(&variable.name);
+            used.insert(variable.name.clone());
+        }
+        let mut new_slack = |stem: &str| loop {
+            let candidate = generator.add_variable(stem).into_owned();
+            if used.insert(candidate.clone()) {
+                break candidate;
+            }
+        };
+
+        let mut variables = problem.variables.clone();
+        let mut objective = LinearExpression::default();
+        let mut constraints = Vec::with_capacity(problem.constraints.len());
+        let mut slacks = vec![];
+        for (idx, constraint) in problem.constraints.iter().enumerate() {
+            let id = ConsId::from_index(idx);
+            let mut lhs = constraint.lhs.clone();
+            if let Some(penalty) = self.penalty(id) {
+                let mut slack = ElasticSlack {
+                    constraint: id,
+                    above: None,
+                    below: None,
+                };
+                // the left-hand side can be above the right-hand side of a <= constraint,
+                // and below the right-hand side of a >= constraint
+                if constraint.operator != Ordering::Greater {
+                    let name = new_slack("slackAbove");
+                    lhs.coefficients.push((name.clone(), -1.));
+                    slack.above = Some(name);
+                }
+                if constraint.operator != Ordering::Less {
+                    let name = new_slack("slackBelow");
+                    lhs.coefficients.push((name.clone(), 1.));
+                    slack.below = Some(name);
+                }
+                for name in slack.above.iter().chain(&slack.below) {
+                    objective.coefficients.push((name.clone(), penalty));
+                    variables.push(Variable {
+                        name: name.clone(),
+                        is_integer: false,
+                        lower_bound: 0.,
+                        upper_bound: f64::INFINITY,
+                    });
+                }
+                slacks.push(slack);
+            }
+            constraints.push(Constraint {
+                lhs,
+                operator: constraint.operator,
+                rhs: constraint.rhs,
+            });
+        }
+        ElasticProblem {
+            problem: Problem {
+                name: format!("{}_elastic", problem.name),
+                sense: LpObjective::Minimize,
+                objective,
+                variables,
+                constraints,
+            },
+            slacks,
+        }
+    }
+}
+
+/// The elastic version of a problem, see [Relaxation::elastic_problem]
+pub struct ElasticProblem {
+    /// the problem with the slack variables
+    pub problem: Problem<LinearExpression, Variable>,
+    /// the slack variables of the relaxed constraints
+    pub slacks: Vec<ElasticSlack>,
+}
+
+impl ElasticProblem {
+    /// The constraints that are violated in a solution of the elastic problem
+    pub fn violations(&self, solution: &Solution) -> Vec<ConstraintViolation> {
+        let value = |name: &Option<String>| {
+            name.as_ref()
+                .and_then(|name| solution.value(name))
+                .unwrap_or_default()
+        };
+        self.slacks
+            .iter()
+            .map(|slack| ConstraintViolation {
+                constraint: slack.constraint,
+                violation: value(&slack.above) - value(&slack.below),
+            })
+            .filter(|violation| violation.violation != 0.)
+            .collect()
+    }
+
+    /// Remove the values of the slack variables from a solution of the elastic problem,
+    /// which leaves the values of the variables of the original problem
+    pub fn remove_slacks(&self, solution: &mut Solution) {
+        for slack in self.slacks.iter() {
+            for name in slack.above.iter().chain(&slack.below) {
+                solution.results.remove(name);
+            }
+        }
+        solution.variable_order.retain(|name| {
+            !self
+                .slacks
+                .iter()
+                .any(|s| s.above.as_ref() == Some(name) || s.below.as_ref() == Some(name))
+        });
+    }
+}
+
+/// The slack variables of a constraint of an [ElasticProblem]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ElasticSlack {
+    /// the relaxed constraint
+    pub constraint: ConsId,
+    /// the variable by which the left-hand side can exceed the right-hand side,
+    /// for `<=` and `=` constraints
+    pub above: Option<String>,
+    /// the variable by which the left-hand side can fall short of the right-hand side,
+    /// for `>=` and `=` constraints
+    pub below: Option<String>,
+}
+
+/// How much a constraint is violated
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConstraintViolation {
+    /// the violated constraint
+    pub constraint: ConsId,
+    /// how much the left-hand side exceeds the right-hand side (positive),
+    /// or falls short of it (negative)
+    pub violation: f64,
+}
+
+/// The result of [relax_infeasible]
+#[derive(Debug, Clone)]
+pub struct RelaxedSolution {
+    /// the solution of the problem, or when it is infeasible, the values of its variables
+    /// in the solution of the elastic problem, with the status of that solve
+    pub solution: Solution,
+    /// whether the problem was infeasible, and the solution is the one of the elastic problem
+    pub relaxed: bool,
+    /// the constraints that are violated in the solution of the elastic problem
+    pub violations: Vec<ConstraintViolation>,
+}
+
+/// Solve the problem, and when it is infeasible, solve its elastic version with the
+/// given [Relaxation] instead, to find which constraints must be violated for the problem
+/// to have a solution, and by how much. Like the `feasRelax` of gurobi, for any solver.
+///
+/// ```
+/// use std::collections::HashMap;
+/// use lp_solvers::lp_format::{ConsId, LpObjective};
+/// use lp_solvers::problem::{LinearExpression, Problem, Variable};
+/// use lp_solvers::solvers::mock::MockSolver;
+/// use lp_solvers::solvers::{relax_infeasible, Relaxation, Solution, Status};
+///
+/// // x >= 3, with x <= 2
+/// let problem: Problem<LinearExpression, Variable> = Problem {
+///     name: "pb".to_string(),
+///     sense: LpObjective::Maximize,
+///     objective: LinearExpression { coefficients: vec![("x".to_string(), 1.)] },
+///     variables: vec![Variable {
+///         name: "x".to_string(),
+///         is_integer: false,
+///         lower_bound: 0.,
+///         upper_bound: 2.,
+///     }],
+///     constraints: vec![lp_solvers::lp_format::Constraint {
+///         lhs: LinearExpression { coefficients: vec![("x".to_string(), 1.)] },
+///         operator: std::cmp::Ordering::Greater,
+///         rhs: 3.,
+///     }],
+/// };
+/// let elastic = vec![("x".to_string(), 2.), ("slackBelow".to_string(), 1.)];
+/// let solver = MockSolver::new()
+///     .with_solution_for("pb", Solution::new(Status::Infeasible, HashMap::new()))
+///     .with_solution_for("pb_elastic", Solution::new(Status::Optimal, elastic.into_iter().collect()));
+///
+/// let relaxed = relax_infeasible(&solver, &problem, &Relaxation::all(1.)).unwrap();
+/// assert!(relaxed.relaxed);
+/// assert_eq!(relaxed.violations[0].constraint, ConsId::from_index(0));
+/// assert_eq!(relaxed.violations[0].violation, -1.);
+/// assert_eq!(relaxed.solution.value("x"), Some(2.));
+/// assert_eq!(relaxed.solution.value("slackBelow"), None);
+/// ```
+pub fn relax_infeasible<S: SolverTrait>(
+    solver: &S,
+    problem: &Problem<LinearExpression, Variable>,
+    relaxation: &Relaxation,
+) -> Result<RelaxedSolution, SolverError> {
+    let solution = solver.run(problem)?;
+    if !matches!(
+        solution.status,
+        Status::Infeasible | Status::InfeasibleOrUnbounded
+    ) {
+        return Ok(RelaxedSolution {
+            solution,
+            relaxed: false,
+            violations: vec![],
+        });
+    }
+    let elastic = relaxation.elastic_problem(problem);
+    let mut solution = solver.run(&elastic.problem)?;
+    let violations = elastic.violations(&solution);
+    elastic.remove_slacks(&mut solution);
+    Ok(RelaxedSolution {
+        solution,
+        relaxed: true,
+        violations,
+    })
+}
@@ -0,0 +1,151 @@
+//! Solving problems on another machine, with the `remote` feature.
+//!
+//! A [RemoteSolver] sends problems to a solve service, such as the one of the `server` feature.
+
+use std::thread::sleep;
+use std::time::Duration;
+
+use crate::lp_format::LpProblem;
+use crate::solvers::{Solution, SolverError, SolverTrait};
+
+/// A solver that POSTs problems, in the .lp format, to a solve service
+/// and reads the solution it responds with.
+///
+/// ```
+/// use std::time::Duration;
+/// use lp_solvers::solvers::RemoteSolver;
+///
+/// let solver = RemoteSolver::new("https://solver.example.com/solve")
+///     .with_bearer_token("secret")
+///     .with_timeout(Duration::from_secs(600))
+///     .with_retries(3, Duration::from_secs(1));
+/// ```
+#[derive(Debug, Clone)]
+pub struct RemoteSolver {
+    url: String,
+    headers: Vec<(String, String)>,
+    timeout: Option<Duration>,
+    retries: u32,
+    retry_delay: Duration,
+}
+
+impl RemoteSolver {
+    /// A solver that sends problems to the given URL, without retries or timeout
+    pub fn new(url: impl Into<String>) -> Self {
+        RemoteSolver {
+            url: url.into(),
+            headers: vec![],
+            timeout: None,
+            retries: 0,
+            retry_delay: Duration::from_secs(1),
+        }
+    }
+
+    /// The URL problems are sent to
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Send the given header with every request
+    pub fn with_header(&self, name: &str, value: &str) -> RemoteSolver {
+        let mut solver = self.clone();
+        solver.headers.push((name.to_string(), value.to_string()));
+        solver
+    }
+
+    /// Authenticate with the given bearer token
+    pub fn with_bearer_token(&self, token: &str) -> RemoteSolver {
+        self.with_header("Authorization", &format!("Bearer {}", token))
+    }
+
+    /// Give up on a request after the given time, which includes the time to solve the problem
+    pub fn with_timeout(&self, timeout: Duration) -> RemoteSolver {
+        RemoteSolver {
+            timeout: Some(timeout),
+            ..(*self).clone()
+        }
+    }
+
+    /// Send the problem again, up to `retries` times, after waiting for `delay`,
+    /// when the service cannot be reached or is temporarily unavailable.
+    /// Problems that the service failed to solve are not retried.
+    pub fn with_retries(&self, retries: u32, delay: Duration) -> RemoteSolver {
+        RemoteSolver {
+            retries,
+            retry_delay: delay,
+            ..(*self).clone()
+        }
+    }
+
+    /// Send the problem once
+    fn send(&self, agent: &ureq::Agent, problem: &str) -> Result<Solution, Attempt> {
+        let mut request = agent
+            .post(&self.url)
+            .set("Content-Type", "text/plain")
+            .set("Accept", "application/json");
+        for (name, value) in self.headers.iter() {
+            request = request.set(name, value);
+        }
+        match request.send_string(problem) {
+            Ok(response) => serde_json::from_reader(response.into_reader()).map_err(|e| {
+                Attempt::Failed(format!("Invalid solution from {}: {}", self.url, e).into())
+            }),
+            Err(ureq::Error::Status(code, response)) => {
+                let message = response_error(response);
+                let error = format!("{} responded with status {}: {}", self.url, code, message);
+                match code {
+                    503 if message.starts_with("License error") => {
+                        Err(Attempt::Retry(SolverError::License(error)))
+                    }
+                    502..=504 | 429 => Err(Attempt::Retry(error.into())),
+                    _ => Err(Attempt::Failed(error.into())),
+                }
+            }
+            Err(ureq::Error::Transport(e)) => Err(Attempt::Retry(
+                format!("Unable to reach {}: {}", self.url, e).into(),
+            )),
+        }
+    }
+}
+
+/// The outcome of a request that did not return a solution
+enum Attempt {
+    /// The request may succeed if it is sent again
+    Retry(SolverError),
+    /// The request will fail again
+    Failed(SolverError),
+}
+
+/// The `error` field of a JSON error response, or the whole response
+fn response_error(response: ureq::Response) -> String {
+    let body = response.into_string().unwrap_or_default();
+    serde_json::from_str::<serde_json::Value>(&body)
+        .ok()
+        .and_then(|value| value.get("error")?.as_str().map(str::to_string))
+        .unwrap_or(body)
+}
+
+impl SolverTrait for RemoteSolver {
+    fn run<'a, P: LpProblem<'a>>(&self, problem: &'a P) -> Result<Solution, SolverError> {
+        let mut builder = ureq::AgentBuilder::new();
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        let agent = builder.build();
+        let lp = problem.display_lp().to_string();
+        let mut attempts = 0;
+        loop {
+            match self.send(&agent, &lp) {
+                Ok(mut solution) => {
+                    solution.constraint_naming = problem.format_options().constraint_naming;
+                    return Ok(solution);
+                }
+                Err(Attempt::Retry(_)) if attempts < self.retries => {
+                    attempts += 1;
+                    sleep(self.retry_delay);
+                }
+                Err(Attempt::Retry(error)) | Err(Attempt::Failed(error)) => return Err(error),
+            }
+        }
+    }
+}
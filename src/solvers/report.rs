@@ -0,0 +1,138 @@
+//! Information about how a solution was obtained, for monitoring and debugging
+
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+#[cfg(feature = "process")]
+use std::process::Command;
+#[cfg(feature = "process")]
+use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+use std::time::Duration;
+#[cfg(feature = "process")]
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::lp_format::{ConsId, ConstraintNaming};
+
+/// Measurements about a call to an external solver
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SolveReport {
+    /// identifies the solve among all the solves of all the processes on this computer.
+    /// It replaces [RUN_ID_PLACEHOLDER] in the files the solver writes.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "String::is_empty")
+    )]
+    pub run_id: String,
+    /// time spent writing the problem file
+    pub write_time: Duration,
+    /// wall time of the solver process
+    pub solve_time: Duration,
+    /// time spent reading the solution file
+    pub parse_time: Duration,
+    /// size of the problem file, in bytes
+    pub problem_file_size: u64,
+    /// size of the solution file, in bytes, if the solver wrote one
+    pub solution_file_size: Option<u64>,
+    /// program that was run, followed by its arguments
+    pub command_line: Vec<String>,
+    /// files the solver wrote besides the solution, such as logs,
+    /// see [crate::solvers::SolverProgram::output_files]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub output_files: Vec<PathBuf>,
+    /// attributes of the solve that the solver wrote to result files besides the solution,
+    /// such as the `Runtime` or the `NodeCount` of gurobi,
+    /// see [crate::solvers::SolverProgram::read_output_files]
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "BTreeMap::is_empty")
+    )]
+    pub attributes: BTreeMap<String, f64>,
+    /// the names of the constraints of an irreducible infeasible subsystem of the problem,
+    /// when the solver wrote one to a result file
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub infeasible_constraints: Vec<String>,
+    /// the name of the model, when the solution file gives it,
+    /// such as the `# Solution for model` comment of gurobi
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub model_name: Option<String>,
+}
+
+impl SolveReport {
+    /// Record the program and its arguments
+    #[cfg(feature = "process")]
+    pub(crate) fn set_command_line(&mut self, command: &Command) {
+        self.command_line = std::iter::once(command.get_program())
+            .chain(command.get_args())
+            .map(|a| a.to_string_lossy().into_owned())
+            .collect();
+    }
+
+    /// The constraints of [SolveReport::infeasible_constraints], for the naming the problem
+    /// was written with. Names that do not match it are skipped.
+    /// Group them by tag with [ConstraintTags::group](crate::lp_format::ConstraintTags::group)
+    pub fn infeasible_constraint_ids(&self, naming: &ConstraintNaming) -> Vec<ConsId> {
+        self.infeasible_constraints
+            .iter()
+            .filter_map(|name| naming.constraint(name))
+            .collect()
+    }
+
+    /// Total time spent in writing the problem, solving it, and reading the solution
+    pub fn total_time(&self) -> Duration {
+        self.write_time + self.solve_time + self.parse_time
+    }
+}
+
+/// Replaced by the [run ID](SolveReport::run_id) of each solve in the arguments of solver
+/// programs, and in the paths of the solution, log and result files they write.
+/// Solves that run at the same time with the same solver then write to different files.
+///
+/// ```
+/// # #[cfg(feature = "glpk")] {
+/// use lp_solvers::solvers::GlpkSolver;
+///
+/// let solver = GlpkSolver::new().with_temp_solution_file("solutions/{run}.sol".to_string());
+/// # }
+/// ```
+pub const RUN_ID_PLACEHOLDER: &str = "{run}";
+
+/// The path, with [RUN_ID_PLACEHOLDER] replaced by the given run ID
+///
+/// ```
+/// use std::path::Path;
+/// use lp_solvers::solvers::with_run_id;
+///
+/// assert_eq!(with_run_id(Path::new("logs/{run}.log"), "42"), Path::new("logs/42.log"));
+/// ```
+pub fn with_run_id(path: &Path, run_id: &str) -> PathBuf {
+    os_with_run_id(path.as_os_str().to_owned(), run_id).into()
+}
+
+/// The argument, with [RUN_ID_PLACEHOLDER] replaced by the given run ID
+pub(crate) fn os_with_run_id(arg: OsString, run_id: &str) -> OsString {
+    match arg.to_str() {
+        Some(text) if text.contains(RUN_ID_PLACEHOLDER) => {
+            text.replace(RUN_ID_PLACEHOLDER, run_id).into()
+        }
+        _ => arg,
+    }
+}
+
+/// A new [run ID](SolveReport::run_id): the time, the process, and a counter of its solves
+#[cfg(feature = "process")]
+pub(crate) fn new_run_id() -> String {
+    static RUNS: AtomicUsize = AtomicUsize::new(0);
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let run = RUNS.fetch_add(1, AtomicOrdering::Relaxed);
+    format!("{}-{}-{}", seconds, std::process::id(), run)
+}
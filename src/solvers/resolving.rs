@@ -0,0 +1,153 @@
+//! Warm starts between successive solves of a changing problem,
+//! as in rolling-horizon optimization.
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+
+use crate::lp_format::{AsVariable, LpProblem};
+use crate::solvers::{
+    Solution, SolverCapabilities, SolverError, SolverTrait, Status, WithMipStart,
+};
+
+/// A solver that remembers the last solution of each problem, by problem name,
+/// and starts the next solve of a problem with the same name from it.
+///
+/// The values of variables that are not in the new problem are left out of the start.
+/// Variables added since, like the columns of [column generation](crate::problem::Problem::add_column),
+/// have no starting value.
+/// Solvers that do not [support warm starts](SolverCapabilities::supports_warm_start)
+/// solve every problem from scratch.
+///
+/// ```no_run
+/// # #[cfg(feature = "cbc")] {
+/// use lp_solvers::lp_format::LpObjective;
+/// use lp_solvers::problem::{Problem, StrExpression};
+/// use lp_solvers::solvers::{CbcSolver, ResolvingSolver, SolverTrait};
+///
+/// # let mut problem: Problem = Problem {
+/// #     name: "planning".to_string(),
+/// #     sense: LpObjective::Minimize,
+/// #     objective: StrExpression("x".to_string()),
+/// #     variables: vec![],
+/// #     constraints: vec![],
+/// # };
+/// let solver = ResolvingSolver::new(CbcSolver::new());
+/// for _day in 0..7 {
+///     // each solve starts from the solution of the previous day
+///     let solution = solver.run(&problem).unwrap();
+///     // ... move the horizon forward in `problem`
+/// }
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct ResolvingSolver<S> {
+    solver: S,
+    previous: Mutex<HashMap<String, Solution>>,
+}
+
+impl<S> ResolvingSolver<S> {
+    /// Remember the solutions of the given solver
+    pub fn new(solver: S) -> Self {
+        ResolvingSolver {
+            solver,
+            previous: Mutex::default(),
+        }
+    }
+
+    /// The solver that solves the problems
+    pub fn solver(&self) -> &S {
+        &self.solver
+    }
+
+    /// The last solution of the problem with the given name
+    pub fn previous(&self, problem_name: &str) -> Option<Solution> {
+        self.lock().get(problem_name).cloned()
+    }
+
+    /// Solve the next problem with the given name from scratch
+    pub fn forget(&self, problem_name: &str) -> Option<Solution> {
+        self.lock().remove(problem_name)
+    }
+
+    /// Solve the next problems from scratch
+    pub fn clear(&self) {
+        self.lock().clear()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, Solution>> {
+        // a solution is replaced at once, so it cannot be left inconsistent by a panic
+        self.previous
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl<S> ResolvingSolver<S>
+where
+    S: SolverTrait + SolverCapabilities + WithMipStart<S>,
+{
+    /// Solve the problem, starting from the given solution
+    fn run_from<'a, P: LpProblem<'a>>(
+        &self,
+        problem: &'a P,
+        previous: &Solution,
+    ) -> Result<Solution, SolverError> {
+        let names: HashSet<String> = problem.variables().map(|v| v.name().to_string()).collect();
+        let mut start = Solution::new(
+            previous.status.clone(),
+            previous
+                .results
+                .iter()
+                .filter(|(name, _)| names.contains(*name))
+                .map(|(name, &value)| (name.clone(), value))
+                .collect(),
+        );
+        start.objective = previous.objective;
+        let mut file = tempfile::Builder::new()
+            .suffix(".sol")
+            .tempfile()
+            .map_err(|e| format!("Unable to create the starting solution file: {}", e))?;
+        self.solver
+            .write_mip_start(&start, file.as_file_mut())
+            .map_err(|e| format!("Unable to write the starting solution: {}", e))?;
+        self.solver
+            .with_mip_start(file.path().to_path_buf())
+            .run(problem)
+    }
+}
+
+impl<S> SolverTrait for ResolvingSolver<S>
+where
+    S: SolverTrait + SolverCapabilities + WithMipStart<S>,
+{
+    fn run<'a, P: LpProblem<'a>>(&self, problem: &'a P) -> Result<Solution, SolverError> {
+        let previous = self.previous(problem.name());
+        let result = match previous {
+            Some(previous) if self.solver.supports_warm_start() => {
+                self.run_from(problem, &previous)
+            }
+            _ => self.solver.run(problem),
+        };
+        if let Ok(solution) = &result {
+            if matches!(solution.status, Status::Optimal | Status::SubOptimal)
+                && !solution.results.is_empty()
+            {
+                self.lock()
+                    .insert(problem.name().to_string(), solution.clone());
+            }
+        }
+        result
+    }
+
+    /// Problem files have no name to remember their solution by: they are solved from scratch
+    fn run_file(&self, problem_file: &Path) -> Result<Solution, SolverError> {
+        self.solver.run_file(problem_file)
+    }
+
+    fn is_available(&self) -> bool {
+        self.solver.is_available()
+    }
+}
+
+forward_capabilities!(ResolvingSolver<S>, |this| &this.solver);
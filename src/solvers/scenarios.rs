@@ -0,0 +1,214 @@
+//! Solving many variations of the same problem,
+//! for Monte-Carlo simulations and what-if analyses.
+//!
+//! A [Scenario] changes right-hand sides of constraints and bounds of variables of a base problem,
+//! without copying it. With the `parallel` feature, [solve_scenarios] solves many scenarios
+//! concurrently.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::lp_format::{AsVariable, ConsId, Constraint, LpFormatOptions, LpObjective, LpProblem};
+#[cfg(feature = "parallel")]
+use crate::solvers::{Solution, SolverError, SolverTrait};
+
+/// Changes to a base problem
+///
+/// ```
+/// use lp_solvers::lp_format::ConsId;
+/// use lp_solvers::solvers::Scenario;
+///
+/// let scenario = Scenario::new()
+///     .with_rhs(ConsId::from_index(0), 12.)
+///     .with_bounds("x", 0., 5.);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Scenario {
+    rhs: HashMap<ConsId, f64>,
+    bounds: HashMap<String, (f64, f64)>,
+}
+
+impl Scenario {
+    /// A scenario that changes nothing
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the right-hand side of a constraint
+    pub fn with_rhs(mut self, constraint: ConsId, rhs: f64) -> Self {
+        self.rhs.insert(constraint, rhs);
+        self
+    }
+
+    /// Set the bounds of the variable with the given name
+    pub fn with_bounds(mut self, variable: impl Into<String>, lower: f64, upper: f64) -> Self {
+        self.bounds.insert(variable.into(), (lower, upper));
+        self
+    }
+
+    /// The base problem, with the changes of this scenario.
+    /// Fails if the scenario changes a constraint or a variable that the problem does not have.
+    pub fn apply_to<'s, P: LpProblem<'s>>(
+        &'s self,
+        base: &'s P,
+    ) -> Result<ScenarioProblem<'s, P>, String> {
+        let constraints = base.constraints().count();
+        if let Some(id) = self.rhs.keys().find(|id| id.index() >= constraints) {
+            return Err(format!(
+                "Scenario changes constraint {} of a problem with {} constraints",
+                id.index(),
+                constraints
+            ));
+        }
+        if !self.bounds.is_empty() {
+            let names: HashSet<String> = base.variables().map(|v| v.name().to_string()).collect();
+            if let Some(name) = self.bounds.keys().find(|name| !names.contains(*name)) {
+                return Err(format!("Scenario changes unknown variable {:?}", name));
+            }
+        }
+        Ok(ScenarioProblem {
+            base,
+            scenario: self,
+        })
+    }
+}
+
+/// A problem changed by a [Scenario]. See [Scenario::apply_to]
+#[derive(Debug, Clone, Copy)]
+pub struct ScenarioProblem<'s, P> {
+    base: &'s P,
+    scenario: &'s Scenario,
+}
+
+/// A variable of a [ScenarioProblem], with the bounds of the scenario
+#[derive(Debug, Clone)]
+pub struct ScenarioVariable<V> {
+    variable: V,
+    bounds: Option<(f64, f64)>,
+}
+
+impl<V: AsVariable> AsVariable for ScenarioVariable<V> {
+    fn name(&self) -> &str {
+        self.variable.name()
+    }
+
+    fn is_integer(&self) -> bool {
+        self.variable.is_integer()
+    }
+
+    fn lower_bound(&self) -> f64 {
+        match self.bounds {
+            Some((lower, _)) => lower,
+            None => self.variable.lower_bound(),
+        }
+    }
+
+    fn upper_bound(&self) -> f64 {
+        match self.bounds {
+            Some((_, upper)) => upper,
+            None => self.variable.upper_bound(),
+        }
+    }
+}
+
+impl<'a, P: LpProblem<'a>> LpProblem<'a> for ScenarioProblem<'a, P> {
+    type Variable = ScenarioVariable<P::Variable>;
+    type Expression = P::Expression;
+    // the right-hand sides of the scenarios are in double precision
+    type Float = f64;
+    type ConstraintIterator = Box<dyn Iterator<Item = Constraint<P::Expression>> + 'a>;
+    type VariableIterator = Box<dyn Iterator<Item = Self::Variable> + 'a>;
+
+    fn name(&self) -> &str {
+        self.base.name()
+    }
+
+    fn variables(&'a self) -> Self::VariableIterator {
+        let bounds = &self.scenario.bounds;
+        Box::new(self.base.variables().map(move |variable| ScenarioVariable {
+            bounds: bounds.get(variable.name()).copied(),
+            variable,
+        }))
+    }
+
+    fn objective(&'a self) -> Self::Expression {
+        self.base.objective()
+    }
+
+    fn sense(&'a self) -> LpObjective {
+        self.base.sense()
+    }
+
+    fn constraints(&'a self) -> Self::ConstraintIterator {
+        let rhs = &self.scenario.rhs;
+        Box::new(
+            self.base
+                .constraints()
+                .enumerate()
+                .map(move |(index, constraint)| Constraint {
+                    rhs: match rhs.get(&ConsId::from_index(index)) {
+                        Some(&value) => value,
+                        None => constraint.rhs.into(),
+                    },
+                    lhs: constraint.lhs,
+                    operator: constraint.operator,
+                }),
+        )
+    }
+
+    fn format_options(&self) -> LpFormatOptions {
+        self.base.format_options()
+    }
+}
+
+/// Solve each scenario of the base problem with the given solver,
+/// running at most `max_processes` solves at the same time.
+/// The results are in the order of the scenarios.
+///
+/// ```no_run
+/// # #[cfg(feature = "cbc")] {
+/// use lp_solvers::lp_format::{ConsId, LpObjective};
+/// use lp_solvers::problem::{Problem, StrExpression};
+/// use lp_solvers::solvers::{solve_scenarios, CbcSolver, Scenario};
+///
+/// # let base: Problem = Problem {
+/// #     name: "base".to_string(),
+/// #     sense: LpObjective::Minimize,
+/// #     objective: StrExpression("x".to_string()),
+/// #     variables: vec![],
+/// #     constraints: vec![],
+/// # };
+/// let demands = [10., 12., 15.];
+/// let scenarios = demands
+///     .iter()
+///     .map(|&demand| Scenario::new().with_rhs(ConsId::from_index(0), demand));
+/// let solutions = solve_scenarios(&CbcSolver::new(), &base, scenarios, 4);
+/// # }
+/// ```
+#[cfg(feature = "parallel")]
+pub fn solve_scenarios<S, P, I>(
+    solver: &S,
+    base: &P,
+    scenarios: I,
+    max_processes: usize,
+) -> Vec<Result<Solution, SolverError>>
+where
+    S: SolverTrait + Sync,
+    P: for<'b> LpProblem<'b> + Sync,
+    I: IntoIterator<Item = Scenario>,
+{
+    use rayon::prelude::*;
+
+    let scenarios: Vec<Scenario> = scenarios.into_iter().collect();
+    let solve = |scenario: &Scenario| {
+        let problem = scenario.apply_to(base)?;
+        solver.run(&problem)
+    };
+    match rayon::ThreadPoolBuilder::new()
+        .num_threads(max_processes.max(1))
+        .build()
+    {
+        Ok(pool) => pool.install(|| scenarios.par_iter().map(solve).collect()),
+        // without threads, solve the scenarios one after the other
+        Err(_) => scenarios.iter().map(solve).collect(),
+    }
+}
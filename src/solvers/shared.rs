@@ -0,0 +1,114 @@
+//! Solutions that share their variable names with the problem,
+//! to keep many solutions of the same problem in memory without copying every name.
+
+use std::collections::{HashMap, HashSet};
+use std::iter::FromIterator;
+use std::sync::Arc;
+
+use crate::lp_format::{AsVariable, LpProblem};
+use crate::solvers::{Solution, Status};
+
+/// The variable names of a problem, stored once and shared by [SharedSolution]s
+///
+/// ```
+/// use std::collections::HashMap;
+/// use lp_solvers::solvers::{Solution, Status, VariableTable};
+///
+/// let table: VariableTable = ["x", "y"].iter().copied().collect();
+/// let mut results = HashMap::new();
+/// results.insert("x".to_string(), 1.);
+/// let solution = Solution::new(Status::Optimal, results).into_shared(&table);
+/// assert_eq!(solution.value("x"), Some(1.));
+/// // the name is the one of the table, not a copy
+/// let (name, _) = solution.results.iter().next().unwrap();
+/// assert!(std::sync::Arc::ptr_eq(name, &table.get("x").unwrap()));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct VariableTable {
+    names: HashSet<Arc<str>>,
+}
+
+impl VariableTable {
+    /// An empty table
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The names of the variables of the problem
+    pub fn from_problem<'a, P: LpProblem<'a>>(problem: &'a P) -> Self {
+        problem.variables().map(|v| v.name().to_string()).collect()
+    }
+
+    /// The shared name equal to the given one, added to the table if it is not in it yet
+    pub fn intern(&mut self, name: &str) -> Arc<str> {
+        if let Some(shared) = self.get(name) {
+            return shared;
+        }
+        let shared: Arc<str> = Arc::from(name);
+        self.names.insert(shared.clone());
+        shared
+    }
+
+    /// The shared name equal to the given one, if it is in the table
+    pub fn get(&self, name: &str) -> Option<Arc<str>> {
+        self.names.get(name).cloned()
+    }
+
+    /// Number of names in the table
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Whether the table has no names
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+impl<S: AsRef<str>> FromIterator<S> for VariableTable {
+    fn from_iter<T: IntoIterator<Item = S>>(iter: T) -> Self {
+        let mut table = VariableTable::new();
+        for name in iter {
+            table.intern(name.as_ref());
+        }
+        table
+    }
+}
+
+/// A solution whose variable names are shared with a [VariableTable].
+/// See [Solution::into_shared]
+#[derive(Debug, Clone, PartialEq)]
+pub struct SharedSolution {
+    /// solution state
+    pub status: Status,
+    /// map from variable name to variable value
+    pub results: HashMap<Arc<str>, f32>,
+    /// value of the objective function, for the solvers that report it
+    pub objective: Option<f64>,
+}
+
+impl SharedSolution {
+    /// Value of the given variable, if it is in the solution, like [Solution::value]
+    pub fn value(&self, name: &str) -> Option<f64> {
+        self.results.get(name).map(|&value| f64::from(value))
+    }
+}
+
+impl Solution {
+    /// Keep the status, values and objective of the solution, with names shared with the table.
+    /// Names that are not in the table are allocated separately.
+    pub fn into_shared(self, table: &VariableTable) -> SharedSolution {
+        SharedSolution {
+            status: self.status,
+            results: self
+                .results
+                .into_iter()
+                .map(|(name, value)| {
+                    let shared = table.get(&name).unwrap_or_else(|| Arc::from(name));
+                    (shared, value)
+                })
+                .collect(),
+            objective: self.objective,
+        }
+    }
+}
@@ -0,0 +1,151 @@
+//! Finding the status of a solve in the output of a solver program.
+//!
+//! Each solver comes with [StdoutStatusRules] for the phrases of its official builds.
+//! Builds that print other phrases can be handled by adding rules.
+
+use crate::solvers::Status;
+use crate::util::buf_contains;
+
+/// The phrases of the output of gurobi_cl, and the status of the solves that print them.
+/// The first phrase found in the output gives the status.
+pub const GUROBI_OUTPUT_STATUSES: &[(&str, Status)] = &[
+    ("Optimal solution found", Status::Optimal),
+    ("Infeasible or unbounded", Status::InfeasibleOrUnbounded),
+    ("infeasible or unbounded", Status::InfeasibleOrUnbounded),
+    // the node log prints `infeasible` for pruned nodes: only the final lines are matched
+    ("Model is infeasible", Status::Infeasible),
+    ("Infeasible model", Status::Infeasible),
+    ("Unbounded model", Status::Unbounded),
+    ("is unbounded", Status::Unbounded),
+    // stopped before finding any feasible solution
+    ("Best objective -,", Status::NotSolved),
+    ("Time limit reached", Status::SubOptimal),
+    ("Solve interrupted", Status::SubOptimal),
+    ("Solution limit reached", Status::SubOptimal),
+    ("Node limit reached", Status::SubOptimal),
+    ("Iteration limit reached", Status::SubOptimal),
+    ("Work limit reached", Status::SubOptimal),
+];
+
+/// The phrases of the output of the cplex interactive optimizer, and the status of the solves
+/// that print them. The other statuses are read from the solution file.
+pub const CPLEX_OUTPUT_STATUSES: &[(&str, Status)] = &[("No solution exists", Status::Infeasible)];
+
+/// What a rule looks for in the output of a solver
+#[derive(Debug, Clone)]
+pub enum StatusPattern {
+    /// The output contains this text
+    Contains(String),
+    /// The output matches this regular expression, with the `regex` feature
+    #[cfg(feature = "regex")]
+    Regex(regex::bytes::Regex),
+}
+
+impl StatusPattern {
+    /// Whether the output matches the pattern
+    pub fn matches(&self, stdout: &[u8]) -> bool {
+        match self {
+            StatusPattern::Contains(text) => buf_contains(stdout, text),
+            #[cfg(feature = "regex")]
+            StatusPattern::Regex(regex) => regex.is_match(stdout),
+        }
+    }
+}
+
+/// An ordered list of patterns, and the status of solves whose output matches them.
+/// The first matching rule gives the status.
+///
+/// ```
+/// use lp_solvers::solvers::{Status, StdoutStatusRules};
+///
+/// let rules = StdoutStatusRules::gurobi()
+///     .with_first_rule("Abandoned by the site watchdog", Status::NotSolved);
+/// assert_eq!(rules.status(b"Abandoned by the site watchdog"), Some(Status::NotSolved));
+/// assert_eq!(rules.status(b"Optimal solution found"), Some(Status::Optimal));
+/// assert_eq!(rules.status(b"Reading model"), None);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct StdoutStatusRules {
+    rules: Vec<(StatusPattern, Status)>,
+}
+
+impl StdoutStatusRules {
+    /// No rules: the status is read from the solution file
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Rules that look for the texts of a table, in its order
+    ///
+    /// ```
+    /// use lp_solvers::solvers::{Status, StdoutStatusRules, GUROBI_OUTPUT_STATUSES};
+    ///
+    /// let rules = StdoutStatusRules::from_table(&GUROBI_OUTPUT_STATUSES[..1]);
+    /// assert_eq!(rules.status(b"Optimal solution found"), Some(Status::Optimal));
+    /// assert_eq!(rules.status(b"Time limit reached"), None);
+    /// ```
+    pub fn from_table(table: &[(&str, Status)]) -> Self {
+        table.iter().fold(Self::new(), |rules, (text, status)| {
+            rules.with_rule(*text, status.clone())
+        })
+    }
+
+    /// The rules for the output of gurobi_cl, from [GUROBI_OUTPUT_STATUSES]
+    pub fn gurobi() -> Self {
+        Self::from_table(GUROBI_OUTPUT_STATUSES)
+    }
+
+    /// The rules for the output of the cplex interactive optimizer, from [CPLEX_OUTPUT_STATUSES]
+    pub fn cplex() -> Self {
+        Self::from_table(CPLEX_OUTPUT_STATUSES)
+    }
+
+    /// Add a rule, checked after the existing ones
+    pub fn with_rule(mut self, text: impl Into<String>, status: Status) -> Self {
+        self.rules
+            .push((StatusPattern::Contains(text.into()), status));
+        self
+    }
+
+    /// Add a rule, checked before the existing ones
+    pub fn with_first_rule(mut self, text: impl Into<String>, status: Status) -> Self {
+        self.rules
+            .insert(0, (StatusPattern::Contains(text.into()), status));
+        self
+    }
+
+    /// Add a rule with a regular expression, checked after the existing ones
+    #[cfg(feature = "regex")]
+    pub fn with_regex_rule(mut self, regex: regex::bytes::Regex, status: Status) -> Self {
+        self.rules.push((StatusPattern::Regex(regex), status));
+        self
+    }
+
+    /// Add a rule with a regular expression, checked before the existing ones
+    #[cfg(feature = "regex")]
+    pub fn with_first_regex_rule(mut self, regex: regex::bytes::Regex, status: Status) -> Self {
+        self.rules.insert(0, (StatusPattern::Regex(regex), status));
+        self
+    }
+
+    /// The rules, in the order they are checked
+    pub fn rules(&self) -> &[(StatusPattern, Status)] {
+        &self.rules
+    }
+
+    /// The status given by the first rule that the output matches
+    pub fn status(&self, stdout: &[u8]) -> Option<Status> {
+        self.rules
+            .iter()
+            .find(|(pattern, _)| pattern.matches(stdout))
+            .map(|(_, status)| status.clone())
+    }
+}
+
+/// A solver whose status is found in its output with [StdoutStatusRules]
+pub trait WithStatusRules<T> {
+    /// get the rules
+    fn status_rules(&self) -> &StdoutStatusRules;
+    /// set the rules, usually the default ones of the solver with some rules added
+    fn with_status_rules(&self, rules: StdoutStatusRules) -> T;
+}
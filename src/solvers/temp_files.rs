@@ -0,0 +1,358 @@
+//! Reuse of temporary files between solves,
+//! to avoid creating and deleting files when solving many small problems,
+//! or writing the same problem once for every solver,
+//! and problem files that have no name at all.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, Seek};
+use std::path::{Path, PathBuf};
+use std::sync::{Condvar, Mutex};
+use std::time::Instant;
+
+use tempfile::NamedTempFile;
+
+use crate::lp_format::LpProblem;
+use crate::solvers::{
+    run_file_program, run_on_file, run_program, Discovery, Solution, SolveReport,
+    SolverCapabilities, SolverError, SolverProgram, SolverTrait, SolverWithSolutionParsing,
+};
+
+/// A set of temporary files that are truncated and reused instead of being deleted.
+/// The files are deleted when the pool is dropped.
+#[derive(Debug, Default)]
+pub struct TempFilePool {
+    dir: Option<PathBuf>,
+    free: Mutex<HashMap<String, Vec<NamedTempFile>>>,
+}
+
+impl TempFilePool {
+    /// An empty pool
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// An empty pool that creates its files in the given directory,
+    /// instead of the temporary directory of the system
+    pub fn in_dir(dir: impl Into<PathBuf>) -> Self {
+        TempFilePool {
+            dir: Some(dir.into()),
+            ..Self::default()
+        }
+    }
+
+    /// An empty temporary file with the given suffix, taken from the pool when one is free.
+    /// It returns to the pool when dropped.
+    pub fn acquire(&self, suffix: &str) -> io::Result<PooledFile<'_>> {
+        let free = self
+            .free
+            .lock()
+            .unwrap()
+            .get_mut(suffix)
+            .and_then(|files| files.pop());
+        let mut file = match free {
+            Some(file) => file,
+            None => {
+                let mut builder = tempfile::Builder::new();
+                builder.suffix(suffix);
+                match &self.dir {
+                    Some(dir) => builder.tempfile_in(dir)?,
+                    None => builder.tempfile()?,
+                }
+            }
+        };
+        file.as_file().set_len(0)?;
+        file.rewind()?;
+        Ok(PooledFile {
+            file: Some(file),
+            pool: Some((self, suffix.to_string())),
+        })
+    }
+
+    /// Number of files waiting to be reused
+    pub fn free_files(&self) -> usize {
+        self.free.lock().unwrap().values().map(Vec::len).sum()
+    }
+
+    fn release(&self, suffix: String, file: NamedTempFile) {
+        self.free
+            .lock()
+            .unwrap()
+            .entry(suffix)
+            .or_default()
+            .push(file);
+    }
+}
+
+/// A temporary file that returns to its [TempFilePool] when dropped,
+/// or is deleted if it does not belong to a pool
+#[derive(Debug)]
+pub struct PooledFile<'a> {
+    file: Option<NamedTempFile>,
+    pool: Option<(&'a TempFilePool, String)>,
+}
+
+impl<'a> PooledFile<'a> {
+    /// A file that is deleted when dropped
+    pub(crate) fn unpooled(file: NamedTempFile) -> Self {
+        PooledFile {
+            file: Some(file),
+            pool: None,
+        }
+    }
+
+    /// Path of the file
+    pub fn path(&self) -> &Path {
+        self.named().path()
+    }
+
+    /// The open file
+    pub fn as_file(&self) -> &File {
+        self.named().as_file()
+    }
+
+    /// The open file, to write to it
+    pub fn as_file_mut(&mut self) -> &mut File {
+        self.file.as_mut().expect("file taken").as_file_mut()
+    }
+
+    fn named(&self) -> &NamedTempFile {
+        self.file.as_ref().expect("file taken")
+    }
+}
+
+impl<'a> Drop for PooledFile<'a> {
+    fn drop(&mut self) {
+        if let (Some(file), Some((pool, suffix))) = (self.file.take(), self.pool.take()) {
+            pool.release(suffix, file);
+        }
+    }
+}
+
+/// An external solver that reuses its temporary files between solves
+///
+/// ```
+/// # #[cfg(feature = "cbc")] {
+/// use lp_solvers::solvers::{CbcSolver, PooledSolver};
+///
+/// let solver = PooledSolver::new(CbcSolver::new());
+/// // solver.run(&problem) for many problems...
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct PooledSolver<S> {
+    /// the solver to run
+    pub solver: S,
+    /// the files used by the solver
+    pub pool: TempFilePool,
+}
+
+impl<S> PooledSolver<S> {
+    /// Reuse temporary files when running the given solver
+    pub fn new(solver: S) -> Self {
+        PooledSolver {
+            solver,
+            pool: TempFilePool::new(),
+        }
+    }
+}
+
+impl<S: SolverWithSolutionParsing + SolverProgram> SolverTrait for PooledSolver<S> {
+    fn run<'a, P: LpProblem<'a>>(&self, problem: &'a P) -> Result<Solution, SolverError> {
+        run_program(&self.solver, problem, &(), Some(&self.pool))
+    }
+
+    fn run_file(&self, problem_file: &Path) -> Result<Solution, SolverError> {
+        run_file_program(&self.solver, problem_file, &(), Some(&self.pool))
+    }
+
+    fn is_available(&self) -> bool {
+        self.solver.is_available()
+    }
+
+    fn discover(&self) -> Discovery {
+        self.solver.discover()
+    }
+
+    fn discovery_key(&self) -> Option<&str> {
+        self.solver.discovery_key()
+    }
+}
+
+forward_capabilities!(PooledSolver<S>, |this| &this.solver);
+
+/// A problem written once to a temporary file, that can then be solved by several solvers.
+/// The file is deleted when this is dropped, so it outlives all the solves that use it.
+///
+/// ```
+/// # #[cfg(all(feature = "cbc", feature = "glpk"))] {
+/// use lp_solvers::lp_format::LpObjective;
+/// use lp_solvers::problem::{Problem, StrExpression, Variable};
+/// use lp_solvers::solvers::{CbcSolver, GlpkSolver, WrittenProblem};
+///
+/// let pb: Problem = Problem {
+///     name: "shared".to_string(),
+///     sense: LpObjective::Maximize,
+///     objective: StrExpression("x".to_string()),
+///     variables: vec![Variable {
+///         name: "x".to_string(),
+///         is_integer: false,
+///         lower_bound: 0.,
+///         upper_bound: 1.,
+///     }],
+///     constraints: vec![],
+/// };
+/// let written = WrittenProblem::new(&pb).unwrap();
+/// assert!(written.path().exists());
+/// // written.solve(&CbcSolver::new()) and written.solve(&GlpkSolver::new())
+/// // both read the same file
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct WrittenProblem<'a, P> {
+    problem: &'a P,
+    file: NamedTempFile,
+    report: SolveReport,
+}
+
+impl<'a, P: LpProblem<'a>> WrittenProblem<'a, P> {
+    /// Write the problem to a temporary file
+    pub fn new(problem: &'a P) -> io::Result<Self> {
+        let start = Instant::now();
+        let file = problem.to_tmp_file()?;
+        let report = SolveReport {
+            write_time: start.elapsed(),
+            problem_file_size: file.as_file().metadata()?.len(),
+            ..SolveReport::default()
+        };
+        Ok(WrittenProblem {
+            problem,
+            file,
+            report,
+        })
+    }
+
+    /// The problem that was written
+    pub fn problem(&self) -> &'a P {
+        self.problem
+    }
+
+    /// Path of the problem file
+    pub fn path(&self) -> &Path {
+        self.file.path()
+    }
+
+    /// Solve the problem with the given solver, without writing it again.
+    /// The write time in the report of the solution is the time it took to write the shared file.
+    pub fn solve<S: SolverWithSolutionParsing + SolverProgram>(
+        &self,
+        solver: &S,
+    ) -> Result<Solution, SolverError> {
+        run_on_file(
+            solver,
+            Some(self.problem),
+            self.path(),
+            self.report.clone(),
+            &(),
+            None,
+        )
+    }
+}
+
+/// A solver that, on Linux, writes problems to anonymous files that have no name
+/// (with `O_TMPFILE` when the file system supports it), and passes them to the solver
+/// as `/proc/<pid>/fd/<fd>`. There is no naming race, and nothing to delete:
+/// the file disappears when it is closed, even if the process is killed.
+///
+/// This is only done for solvers that can read such paths
+/// (see [SolverProgram::reads_paths_without_extension]);
+/// other solvers and other systems get usual temporary files.
+///
+/// ```
+/// # #[cfg(feature = "glpk")] {
+/// use lp_solvers::solvers::{AnonymousFileSolver, GlpkSolver};
+///
+/// let solver = AnonymousFileSolver(GlpkSolver::new());
+/// # }
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct AnonymousFileSolver<S>(pub S);
+
+impl<S: SolverWithSolutionParsing + SolverProgram> SolverTrait for AnonymousFileSolver<S> {
+    #[cfg(target_os = "linux")]
+    fn run<'a, P: LpProblem<'a>>(&self, problem: &'a P) -> Result<Solution, SolverError> {
+        use std::os::unix::io::AsRawFd;
+
+        if !self.0.reads_paths_without_extension() {
+            return run_program(&self.0, problem, &(), None);
+        }
+        let start = Instant::now();
+        // the file is deleted when it is closed, after the solver has run
+        let file = tempfile::tempfile()
+            .and_then(|mut f| problem.to_writer(&mut f).map(|()| f))
+            .map_err(|e| format!("Unable to create anonymous problem file: {}", e))?;
+        let report = SolveReport {
+            write_time: start.elapsed(),
+            problem_file_size: file.metadata().map(|m| m.len()).unwrap_or_default(),
+            ..SolveReport::default()
+        };
+        // the path of our file descriptor, that the solver process can open
+        let path = format!("/proc/{}/fd/{}", std::process::id(), file.as_raw_fd());
+        run_on_file(&self.0, Some(problem), Path::new(&path), report, &(), None)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn run<'a, P: LpProblem<'a>>(&self, problem: &'a P) -> Result<Solution, SolverError> {
+        run_program(&self.0, problem, &(), None)
+    }
+
+    /// The file already exists: it is given to the solver as it is
+    fn run_file(&self, problem_file: &Path) -> Result<Solution, SolverError> {
+        self.0.run_file(problem_file)
+    }
+
+    fn is_available(&self) -> bool {
+        self.0.is_available()
+    }
+}
+
+forward_capabilities!(AnonymousFileSolver<S>, |this| &this.0);
+
+/// The solution files with a fixed path that solves of this process are writing
+static FIXED_FILES: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+/// Notified when a file is removed from [FIXED_FILES]
+static FIXED_FILE_RELEASED: Condvar = Condvar::new();
+
+/// The exclusive use of a file with a fixed path by a solve, until it is dropped
+#[derive(Debug)]
+pub(crate) struct FixedFileLock(PathBuf);
+
+impl FixedFileLock {
+    /// Wait until no other solve of this process uses the file, then use it.
+    /// Solves of a solver shared between threads, with a fixed solution file,
+    /// then run one after the other instead of overwriting each other's solution.
+    pub(crate) fn acquire(path: &Path) -> Self {
+        let mut used = FIXED_FILES
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        while used.iter().any(|p| p == path) {
+            used = FIXED_FILE_RELEASED
+                .wait(used)
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+        }
+        used.push(path.to_path_buf());
+        FixedFileLock(path.to_path_buf())
+    }
+}
+
+impl Drop for FixedFileLock {
+    fn drop(&mut self) {
+        let mut used = FIXED_FILES
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(idx) = used.iter().position(|p| p == &self.0) {
+            used.swap_remove(idx);
+        }
+        FIXED_FILE_RELEASED.notify_all();
+    }
+}
@@ -0,0 +1,180 @@
+//! Automatic parameter tuning, with the tuning tools of gurobi (`grbtune`)
+//! and cplex (`tools tune`).
+//!
+//! [SolverTuning::tune] runs the tuning tool on a representative problem,
+//! and returns the parameters it recommends as [SolverParams].
+//! They can be saved, and used for the next solves of similar problems.
+
+use std::fmt;
+use std::path::Path;
+#[cfg(all(feature = "process", any(feature = "gurobi", feature = "cplex")))]
+use std::process::Command;
+#[cfg(feature = "process")]
+use std::time::Duration;
+
+#[cfg(feature = "process")]
+use crate::lp_format::LpProblem;
+#[cfg(feature = "process")]
+use crate::solvers::SolverError;
+#[cfg(all(feature = "process", any(feature = "gurobi", feature = "cplex")))]
+use crate::solvers::SolverProgram;
+
+/// Solver parameters, as names and values in the order of a parameter file (.prm)
+///
+/// ```
+/// use lp_solvers::solvers::SolverParams;
+///
+/// let params = SolverParams::parse("# Parameter settings\nMIPFocus 1\nCuts 2\n").unwrap();
+/// assert_eq!(params.get("Cuts"), Some("2"));
+/// assert_eq!(params.to_string(), "MIPFocus 1\nCuts 2\n");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SolverParams {
+    /// The first line of cplex parameter files, which tells the version of the file
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub header: Option<String>,
+    /// The names and values of the parameters
+    pub params: Vec<(String, String)>,
+}
+
+impl SolverParams {
+    /// No parameters
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set a parameter, replacing its previous value
+    pub fn with_param(mut self, name: impl Into<String>, value: impl ToString) -> Self {
+        let name = name.into();
+        let value = value.to_string();
+        match self.params.iter_mut().find(|(n, _)| *n == name) {
+            Some(param) => param.1 = value,
+            None => self.params.push((name, value)),
+        }
+        self
+    }
+
+    /// The value of a parameter
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// The names and values of the parameters
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.params.iter().map(|(n, v)| (n.as_str(), v.as_str()))
+    }
+
+    /// Whether no parameter is set
+    pub fn is_empty(&self) -> bool {
+        self.params.is_empty()
+    }
+
+    /// Parse a parameter file written by gurobi or cplex
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut params = SolverParams::new();
+        for (idx, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if line.starts_with("CPLEX Parameter File") {
+                params.header = Some(line.to_string());
+                continue;
+            }
+            match line.split_once(char::is_whitespace) {
+                Some((name, value)) => params = params.with_param(name, value.trim()),
+                None => return Err(format!("line {}: no value for {:?}", idx + 1, line)),
+            }
+        }
+        Ok(params)
+    }
+
+    /// Read a parameter file written by gurobi or cplex
+    pub fn read_file(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("Unable to read parameter file {:?}: {}", path, e))?;
+        Self::parse(&text)
+    }
+
+    /// Write the parameters to a parameter file that the solver they come from can read
+    pub fn write_file(&self, path: &Path) -> std::io::Result<()> {
+        std::fs::write(path, self.to_string())
+    }
+}
+
+/// The content of a parameter file
+impl fmt::Display for SolverParams {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(header) = &self.header {
+            writeln!(f, "{}", header)?;
+        }
+        for (name, value) in self.iter() {
+            writeln!(f, "{} {}", name, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// A solver with a tool that searches for the parameters that solve a problem the fastest
+#[cfg(feature = "process")]
+pub trait SolverTuning {
+    /// Run the tuning tool on the problem, for at most `time_limit` if it is given,
+    /// and return the best parameters it found.
+    /// The parameters are empty when the defaults are the best.
+    ///
+    /// ```no_run
+    /// # #[cfg(feature = "gurobi")] {
+    /// use std::time::Duration;
+    /// use lp_solvers::lp_format::LpObjective;
+    /// use lp_solvers::problem::{Problem, StrExpression};
+    /// use lp_solvers::solvers::{GurobiSolver, SolverTrait, SolverTuning};
+    ///
+    /// # let problem: Problem = Problem {
+    /// #     name: "problem".to_string(),
+    /// #     sense: LpObjective::Minimize,
+    /// #     objective: StrExpression("x".to_string()),
+    /// #     variables: vec![],
+    /// #     constraints: vec![],
+    /// # };
+    /// let solver = GurobiSolver::new();
+    /// let params = solver.tune(&problem, Some(Duration::from_secs(600))).unwrap();
+    /// let tuned = solver.with_params(&params);
+    /// let solution = tuned.run(&problem);
+    /// # }
+    /// ```
+    fn tune<'a, P: LpProblem<'a>>(
+        &self,
+        problem: &'a P,
+        time_limit: Option<Duration>,
+    ) -> Result<SolverParams, SolverError>;
+}
+
+/// Run a tuning tool in `dir`, and read the parameter file it writes
+#[cfg(all(feature = "process", any(feature = "gurobi", feature = "cplex")))]
+pub(crate) fn run_tuning_tool<S: SolverProgram>(
+    solver: &S,
+    mut command: Command,
+    dir: &Path,
+    params_file: &Path,
+) -> Result<SolverParams, SolverError> {
+    let program = command.get_program().to_string_lossy().into_owned();
+    let output = command
+        .current_dir(dir)
+        .output()
+        .map_err(|e| format!("Error while running {}: {}", program, e))?;
+    if let Some(message) = solver.parse_license_error(&output.stdout, &output.stderr) {
+        return Err(SolverError::License(message));
+    }
+    if !output.status.success() {
+        return Err(format!("{} exited with status {}", program, output.status).into());
+    }
+    if params_file.exists() {
+        Ok(SolverParams::read_file(params_file)?)
+    } else {
+        Ok(SolverParams::new())
+    }
+}
@@ -0,0 +1,244 @@
+//! Problems that are solved many times with different data,
+//! as in rolling-horizon optimization.
+//!
+//! Writing the expressions of a large problem is what takes the most time
+//! when giving it to a solver. A [ProblemTemplate] writes them once,
+//! and only the right-hand sides of the constraints and the bounds of the variables
+//! can then be changed before each solve.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{Result, Write};
+use std::ops::Range;
+
+use crate::lp_format::{
+    AsVariable, ConsId, Constraint, LpFormatOptions, LpObjective, LpProblem, WriteToLpFileFormat,
+};
+use crate::problem::Variable;
+
+/// A problem whose expressions are already written in the .lp format
+///
+/// ```
+/// use std::cmp::Ordering;
+/// use lp_solvers::lp_format::{ConsId, Constraint, LpObjective, LpProblem};
+/// use lp_solvers::problem::{Problem, StrExpression, Variable};
+/// use lp_solvers::template::ProblemTemplate;
+///
+/// let problem: Problem = Problem {
+///     name: "stock".to_string(),
+///     sense: LpObjective::Maximize,
+///     objective: StrExpression("x".to_string()),
+///     variables: vec![Variable { name: "x".to_string(), is_integer: false, lower_bound: 0., upper_bound: 5. }],
+///     constraints: vec![Constraint { lhs: StrExpression("2 x".to_string()), operator: Ordering::Less, rhs: 8. }],
+/// };
+/// let mut template = ProblemTemplate::new(&problem);
+/// for demand in [8., 9., 10.] {
+///     template.set_rhs(ConsId::from_index(0), demand).unwrap();
+///     assert!(template.display_lp().to_string().contains(&format!("<= {}", demand)));
+/// }
+/// template.set_bounds("x", 1., 4.).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct ProblemTemplate {
+    name: String,
+    sense: LpObjective,
+    options: LpFormatOptions,
+    /// The written objective and left-hand sides of constraints, one after the other
+    text: Vec<u8>,
+    /// Their linear terms, for the expressions that give them
+    terms: Vec<(String, f64)>,
+    objective: TemplateExpression,
+    constraints: Vec<TemplateConstraint>,
+    variables: Vec<Variable>,
+    variable_index: HashMap<String, usize>,
+}
+
+/// Where an expression is in the text and the terms of a [ProblemTemplate]
+#[derive(Debug, Clone)]
+struct TemplateExpression {
+    text: Range<usize>,
+    terms: Option<Range<usize>>,
+}
+
+#[derive(Debug, Clone)]
+struct TemplateConstraint {
+    lhs: TemplateExpression,
+    operator: Ordering,
+    rhs: f64,
+}
+
+impl ProblemTemplate {
+    /// Write the expressions of the problem, to solve it many times with different data
+    pub fn new<'a, P: LpProblem<'a>>(problem: &'a P) -> Self {
+        let mut text = vec![];
+        let mut terms = vec![];
+        let mut write_expression = |expression: &P::Expression| {
+            let start = text.len();
+            expression
+                .write_lp_to(&mut text)
+                .expect("writing to memory does not fail");
+            let linear = expression.linear_terms().map(|linear| {
+                let first = terms.len();
+                terms.extend(
+                    linear
+                        .into_iter()
+                        .map(|(name, coefficient)| (name.to_string(), coefficient)),
+                );
+                first..terms.len()
+            });
+            TemplateExpression {
+                text: start..text.len(),
+                terms: linear,
+            }
+        };
+        let objective = write_expression(&problem.objective());
+        let constraints = problem
+            .constraints()
+            .map(|constraint| TemplateConstraint {
+                lhs: write_expression(&constraint.lhs),
+                operator: constraint.operator,
+                rhs: constraint.rhs.into(),
+            })
+            .collect();
+        let variables: Vec<Variable> = problem
+            .variables()
+            .map(|v| Variable {
+                name: v.name().to_string(),
+                is_integer: v.is_integer(),
+                lower_bound: v.lower_bound(),
+                upper_bound: v.upper_bound(),
+            })
+            .collect();
+        let variable_index = variables
+            .iter()
+            .enumerate()
+            .map(|(index, v)| (v.name.clone(), index))
+            .collect();
+        ProblemTemplate {
+            name: problem.name().to_string(),
+            sense: problem.sense(),
+            options: problem.format_options(),
+            text,
+            terms,
+            objective,
+            constraints,
+            variables,
+            variable_index,
+        }
+    }
+
+    /// The right-hand side of a constraint
+    pub fn rhs(&self, constraint: ConsId) -> Option<f64> {
+        self.constraints.get(constraint.index()).map(|c| c.rhs)
+    }
+
+    /// Change the right-hand side of a constraint
+    pub fn set_rhs(&mut self, constraint: ConsId, rhs: f64) -> std::result::Result<(), String> {
+        let count = self.constraints.len();
+        let constraint = self
+            .constraints
+            .get_mut(constraint.index())
+            .ok_or_else(|| {
+                format!(
+                    "No constraint {} in a problem with {} constraints",
+                    constraint.index(),
+                    count
+                )
+            })?;
+        constraint.rhs = rhs;
+        Ok(())
+    }
+
+    /// The variable with the given name
+    pub fn variable(&self, name: &str) -> Option<&Variable> {
+        self.variable_index.get(name).map(|&i| &self.variables[i])
+    }
+
+    /// Change the bounds of the variable with the given name
+    pub fn set_bounds(
+        &mut self,
+        name: &str,
+        lower: f64,
+        upper: f64,
+    ) -> std::result::Result<(), String> {
+        let &index = self
+            .variable_index
+            .get(name)
+            .ok_or_else(|| format!("No variable named {:?}", name))?;
+        let variable = &mut self.variables[index];
+        variable.lower_bound = lower;
+        variable.upper_bound = upper;
+        Ok(())
+    }
+
+    fn expression(&self, expression: &TemplateExpression) -> WrittenExpression<'_> {
+        WrittenExpression {
+            text: &self.text[expression.text.clone()],
+            terms: expression.terms.clone().map(|terms| &self.terms[terms]),
+        }
+    }
+}
+
+/// An expression written in the .lp format by [ProblemTemplate::new],
+/// with its linear terms if it gave them
+#[derive(Debug, Clone, Copy)]
+pub struct WrittenExpression<'a> {
+    text: &'a [u8],
+    terms: Option<&'a [(String, f64)]>,
+}
+
+impl WriteToLpFileFormat for WrittenExpression<'_> {
+    fn to_lp_file_format(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&String::from_utf8_lossy(self.text))
+    }
+
+    fn write_lp_to<W: Write>(&self, writer: &mut W) -> Result<()> {
+        writer.write_all(self.text)
+    }
+
+    fn linear_terms(&self) -> Option<Vec<(&str, f64)>> {
+        self.terms.map(|terms| {
+            terms
+                .iter()
+                .map(|(name, coefficient)| (name.as_str(), *coefficient))
+                .collect()
+        })
+    }
+}
+
+impl<'a> LpProblem<'a> for ProblemTemplate {
+    type Variable = &'a Variable;
+    type Expression = WrittenExpression<'a>;
+    type Float = f64;
+    type ConstraintIterator = Box<dyn Iterator<Item = Constraint<WrittenExpression<'a>>> + 'a>;
+    type VariableIterator = std::slice::Iter<'a, Variable>;
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn variables(&'a self) -> Self::VariableIterator {
+        self.variables.iter()
+    }
+
+    fn objective(&'a self) -> Self::Expression {
+        self.expression(&self.objective)
+    }
+
+    fn sense(&'a self) -> LpObjective {
+        self.sense
+    }
+
+    fn constraints(&'a self) -> Self::ConstraintIterator {
+        Box::new(self.constraints.iter().map(move |c| Constraint {
+            lhs: self.expression(&c.lhs),
+            operator: c.operator,
+            rhs: c.rhs,
+        }))
+    }
+
+    fn format_options(&self) -> LpFormatOptions {
+        self.options.clone()
+    }
+}
@@ -0,0 +1,194 @@
+//! Helpers for the regression tests of crates that build problems with this one,
+//! enabled by the `testing` feature, usually in `[dev-dependencies]`.
+//!
+//! [assert_lp_snapshot] compares the .lp file written for a problem with a golden file,
+//! and [assert_solutions_close] compares solutions up to a [Tolerance].
+//!
+//! Golden files are written, instead of compared, when the `LP_SOLVERS_UPDATE_SNAPSHOTS`
+//! environment variable is set: run the tests once with it after an intended change,
+//! and review the differences of the golden files.
+//!
+//! ```
+//! use std::collections::HashMap;
+//! use lp_solvers::solvers::{Solution, Status};
+//! use lp_solvers::testing::{assert_solutions_close, Tolerance};
+//!
+//! let values = |x: f32| vec![("x".to_string(), x)].into_iter().collect::<HashMap<_, _>>();
+//! let expected = Solution::new(Status::Optimal, values(2.));
+//! let actual = Solution::new(Status::Optimal, values(2.000_000_5));
+//! assert_solutions_close(&actual, &expected, Tolerance::default());
+//! ```
+
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use crate::lp_format::LpProblem;
+use crate::solvers::Solution;
+
+/// The environment variable that makes [assert_lp_snapshot] write golden files
+pub const UPDATE_SNAPSHOTS_VAR: &str = "LP_SOLVERS_UPDATE_SNAPSHOTS";
+
+/// The problem in the .lp format, as it is written for solvers
+pub fn lp_snapshot<'a, P: LpProblem<'a>>(problem: &'a P) -> String {
+    let mut written = vec![];
+    problem
+        .write_lp_to(&mut written)
+        .expect("writing to memory does not fail");
+    String::from_utf8_lossy(&written).into_owned()
+}
+
+/// Compare a text with the content of a golden file, or write it to the file when `update`
+/// is true. Line endings of the file are ignored, for files checked out on Windows.
+///
+/// Fails when the file is missing or different, with the first line that differs.
+pub fn check_snapshot(actual: &str, path: &Path, update: bool) -> Result<(), String> {
+    if update {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Unable to create {:?}: {}", dir, e))?;
+        }
+        return std::fs::write(path, actual)
+            .map_err(|e| format!("Unable to write snapshot {:?}: {}", path, e));
+    }
+    let expected = std::fs::read_to_string(path).map_err(|e| {
+        format!(
+            "Unable to read snapshot {:?}: {}. Set {} to write it",
+            path, e, UPDATE_SNAPSHOTS_VAR
+        )
+    })?;
+    let expected = expected.replace("\r\n", "\n");
+    if expected == actual {
+        return Ok(());
+    }
+    let mut expected_lines = expected.lines();
+    let mut actual_lines = actual.lines();
+    let mut line = 1;
+    loop {
+        match (expected_lines.next(), actual_lines.next()) {
+            (Some(e), Some(a)) if e == a => line += 1,
+            // the lines are the same, but not what ends them
+            (None, None) => {
+                let ending = |text: &str| {
+                    if text.ends_with('\n') {
+                        "a final newline"
+                    } else {
+                        "no final newline"
+                    }
+                };
+                let difference = if expected.ends_with('\n') == actual.ends_with('\n') {
+                    "got \"\\r\\n\" line endings".to_string()
+                } else {
+                    format!("expected {}, got {}", ending(&expected), ending(actual))
+                };
+                return Err(format!(
+                    "{:?} differs at the end of its lines: {}. Set {} to update it",
+                    path, difference, UPDATE_SNAPSHOTS_VAR
+                ));
+            }
+            (e, a) => {
+                return Err(format!(
+                    "{:?} differs at line {}: expected {:?}, got {:?}. Set {} to update it",
+                    path,
+                    line,
+                    e.unwrap_or("end of file"),
+                    a.unwrap_or("end of file"),
+                    UPDATE_SNAPSHOTS_VAR
+                ))
+            }
+        }
+    }
+}
+
+/// Check that the problem is written like in the golden file at `path`, or write the file
+/// when the [UPDATE_SNAPSHOTS_VAR] environment variable is set. See [check_snapshot]
+#[track_caller]
+pub fn assert_lp_snapshot<'a, P: LpProblem<'a>>(problem: &'a P, path: impl AsRef<Path>) {
+    let update = std::env::var_os(UPDATE_SNAPSHOTS_VAR).is_some();
+    if let Err(e) = check_snapshot(&lp_snapshot(problem), path.as_ref(), update) {
+        panic!("{}", e)
+    }
+}
+
+/// How far apart two values can be to be considered equal:
+/// by `absolute`, or by `relative` times the largest of their magnitudes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tolerance {
+    /// the largest absolute difference
+    pub absolute: f64,
+    /// the largest difference relative to the values
+    pub relative: f64,
+}
+
+impl Tolerance {
+    /// Allow the given absolute and relative differences
+    pub fn new(absolute: f64, relative: f64) -> Self {
+        Tolerance { absolute, relative }
+    }
+
+    /// Whether the two values are equal within the tolerance
+    pub fn allows(&self, a: f64, b: f64) -> bool {
+        let allowed = self.absolute.max(self.relative * a.abs().max(b.abs()));
+        a == b || (a - b).abs() <= allowed
+    }
+}
+
+/// `1e-6`, absolute and relative: values are read in single precision
+impl Default for Tolerance {
+    fn default() -> Self {
+        Tolerance::new(1e-6, 1e-6)
+    }
+}
+
+/// The differences between two solutions: their status, the values of the variables,
+/// the objective values when both have one, and the dual values of the expected solution.
+///
+/// Variables and dual values missing from a solution are zero, since solvers leave them out.
+/// Returns an empty list when the solutions are equal within the tolerance.
+pub fn compare_solutions(
+    actual: &Solution,
+    expected: &Solution,
+    tolerance: Tolerance,
+) -> Vec<String> {
+    let mut differences = vec![];
+    if actual.status != expected.status {
+        differences.push(format!(
+            "the status is {}, not {}",
+            actual.status, expected.status
+        ));
+    }
+    let variables: BTreeSet<&String> = actual
+        .results
+        .keys()
+        .chain(expected.results.keys())
+        .collect();
+    for name in variables {
+        let a = f64::from(actual.results.get(name).copied().unwrap_or_default());
+        let e = f64::from(expected.results.get(name).copied().unwrap_or_default());
+        if !tolerance.allows(a, e) {
+            differences.push(format!("{} is {}, not {}", name, a, e));
+        }
+    }
+    if let (Some(a), Some(e)) = (actual.objective, expected.objective) {
+        if !tolerance.allows(a, e) {
+            differences.push(format!("the objective is {}, not {}", a, e));
+        }
+    }
+    let constraints: BTreeSet<&String> = expected.dual_values.keys().collect();
+    for name in constraints {
+        let a = f64::from(actual.dual_values.get(name).copied().unwrap_or_default());
+        let e = f64::from(expected.dual_values[name]);
+        if !tolerance.allows(a, e) {
+            differences.push(format!("the dual value of {} is {}, not {}", name, a, e));
+        }
+    }
+    differences
+}
+
+/// Check that two solutions are equal within the tolerance, see [compare_solutions]
+#[track_caller]
+pub fn assert_solutions_close(actual: &Solution, expected: &Solution, tolerance: Tolerance) {
+    let differences = compare_solutions(actual, expected, tolerance);
+    if !differences.is_empty() {
+        panic!("the solutions differ: {}", differences.join(", "))
+    }
+}
@@ -1,16 +1,169 @@
 //! Utilities to help with building problems
 use std::borrow::Cow;
-use std::collections::hash_map::DefaultHasher;
-use std::collections::HashMap;
-use std::hash::{Hash, Hasher};
+use std::collections::{HashMap, HashSet};
+
+use crate::lp_format::{AsVariable, LpFloat, LpProblem};
+use crate::problem::{LinearExpression, Problem, Variable};
+use crate::solvers::{Solution, SolverCapabilities, Warning};
+
+/// Words that have a special meaning in the .lp format, and cannot be used as variable names.
+/// The comparison is case-insensitive.
+pub const LP_KEYWORDS: &[&str] = &[
+    "bin", "binaries", "binary", "bound", "bounds", "end", "free", "gen", "general", "generals",
+    "inf", "infinity", "max", "maximise", "maximize", "maximum", "min", "minimise", "minimize",
+    "minimum", "semi", "semis", "st", "subject", "such", "that", "to",
+];
+
+/// Prefixes that make a name be read as the exponent of a number in the .lp format,
+/// as in `2e1`. The comparison is case-insensitive.
+pub const EXPONENT_PREFIXES: &[&str] = &[
+    "e0", "e1", "e2", "e3", "e4", "e5", "e6", "e7", "e8", "e9", "ee",
+];
+
+/// Rules that names must follow to be accepted by a solver or a file format.
+/// [NamePolicy::default] follows the rules of the .lp format, conservatively.
+///
+/// ```
+/// use lp_solvers::util::{NamePolicy, UniqueNameGenerator};
+///
+/// let mut gen = UniqueNameGenerator::with_policy(NamePolicy::fixed_mps());
+/// assert_eq!(gen.add_variable("production_1"), "producti");
+/// assert_eq!(gen.add_variable("production_2"), "product2");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NamePolicy {
+    /// Maximum length of names
+    pub max_len: usize,
+    /// Whether names can contain digits. They never start with one
+    pub digits: bool,
+    /// Symbols that names can contain, in addition to ASCII letters
+    pub symbols: String,
+    /// Names cannot start with these prefixes, compared case-insensitively
+    pub forbidden_prefixes: Vec<String>,
+    /// Names that cannot be used, compared case-insensitively
+    pub keywords: Vec<String>,
+}
+
+impl Default for NamePolicy {
+    /// Only letters, and no name that could be read as an exponent
+    fn default() -> Self {
+        NamePolicy {
+            max_len: 255,
+            digits: false,
+            symbols: String::new(),
+            forbidden_prefixes: EXPONENT_PREFIXES.iter().map(|p| p.to_string()).collect(),
+            keywords: LP_KEYWORDS.iter().map(|k| k.to_string()).collect(),
+        }
+    }
+}
+
+impl NamePolicy {
+    /// Names of at most 255 letters, digits, and underscores, that are not read as a number
+    /// or a keyword: the names [validate_name] accepts, without its other symbols
+    pub fn lp() -> Self {
+        NamePolicy {
+            digits: true,
+            symbols: "_".to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Names of at most 8 characters, for the fixed MPS format
+    pub fn fixed_mps() -> Self {
+        NamePolicy {
+            max_len: 8,
+            digits: true,
+            symbols: "_".to_string(),
+            forbidden_prefixes: vec![],
+            keywords: vec![],
+        }
+    }
+
+    /// Whether the name follows the policy
+    pub fn accepts(&self, name: &str) -> bool {
+        !name.is_empty()
+            && name.len() <= self.max_len
+            && name.chars().all(|c| self.allows(c))
+            && !name.starts_with(|c: char| c.is_ascii_digit())
+            && !self.is_reserved(name)
+    }
+
+    fn allows(&self, c: char) -> bool {
+        c.is_ascii_alphabetic() || (self.digits && c.is_ascii_digit()) || self.symbols.contains(c)
+    }
+
+    fn is_reserved(&self, name: &str) -> bool {
+        let starts_with = |prefix: &String| {
+            name.get(..prefix.len())
+                .is_some_and(|start| start.eq_ignore_ascii_case(prefix))
+        };
+        self.forbidden_prefixes.iter().any(starts_with)
+            || self.keywords.iter().any(|k| k.eq_ignore_ascii_case(name))
+    }
+
+    /// The name without the characters that are not allowed, prefixed with `v` if it
+    /// would be reserved, be reserved once numbered (as `e2`), or start with a digit
+    fn stem<'a>(&self, name: &'a str) -> Cow<'a, str> {
+        let mut stem = if name.chars().all(|c| self.allows(c)) {
+            Cow::Borrowed(name)
+        } else {
+            Cow::Owned(name.replace(|c: char| !self.allows(c), ""))
+        };
+        if stem.is_empty() {
+            stem = Cow::Borrowed("v");
+        } else if self.is_reserved(&stem)
+            || self.is_reserved(&format!("{}2", stem))
+            || stem.starts_with(|c: char| c.is_ascii_digit())
+        {
+            stem = Cow::Owned(format!("v{}", stem));
+        }
+        if stem.len() > self.max_len {
+            // allowed characters are ASCII
+            stem = Cow::Owned(stem[..self.max_len].to_string());
+        }
+        stem
+    }
+}
 
 /// Useful to generate a list of unique valid variable names
 #[derive(Debug, Default)]
 pub struct UniqueNameGenerator {
-    names: HashMap<u64, usize>,
+    policy: NamePolicy,
+    /// The number of names generated from each stem
+    stems: HashMap<String, usize>,
+    names: HashSet<String>,
 }
 
 impl UniqueNameGenerator {
+    /// Generate names that follow the given policy
+    pub fn with_policy(policy: NamePolicy) -> Self {
+        UniqueNameGenerator {
+            policy,
+            ..Self::default()
+        }
+    }
+
+    /// Generate names that the given solver accepts. See [SolverCapabilities::name_policy]
+    ///
+    /// ```
+    /// # #[cfg(feature = "glpk")] {
+    /// use lp_solvers::solvers::GlpkSolver;
+    /// use lp_solvers::util::UniqueNameGenerator;
+    ///
+    /// let mut gen = UniqueNameGenerator::for_solver(&GlpkSolver::new());
+    /// assert_eq!(gen.add_variable("x_1"), "x_1");
+    /// assert_eq!(gen.add_variable("2x"), "v2x");
+    /// # }
+    /// ```
+    pub fn for_solver(solver: &impl SolverCapabilities) -> Self {
+        Self::with_policy(solver.name_policy())
+    }
+
+    /// The rules followed by the generated names
+    pub fn policy(&self) -> &NamePolicy {
+        &self.policy
+    }
+
     /// Create a new variable. Returns a valid variable name, never returned before by this generator.
     ///
     /// ```
@@ -22,35 +175,212 @@ impl UniqueNameGenerator {
     /// assert_eq!(gen.add_variable("z"), "z");
     /// assert_eq!(gen.add_variable("!#?/"), "v"); // "!#?/" is not a valid variable name
     /// assert_eq!(gen.add_variable("x"), "x2"); // A variable with name x is already present
+    /// assert_eq!(gen.add_variable("free"), "vfree"); // "free" is a keyword
+    /// assert_eq!(gen.add_variable("e"), "ve"); // "e2" would be read as an exponent
+    /// assert_eq!(gen.add_variable("energy"), "energy");
     /// ```
     pub fn add_variable<'a>(&mut self, name: &'a str) -> Cow<'a, str> {
-        let mut stem = stem(name);
-        let hash = calculate_hash(&stem);
-        let n = self.names.entry(hash).or_insert(0);
-        *n += 1;
-        if *n >= 2 {
-            stem = Cow::Owned(stem.into_owned() + &n.to_string());
+        let stem = self.policy.stem(name);
+        let n = self.stems.entry(stem.to_string()).or_insert(0);
+        loop {
+            *n += 1;
+            let candidate = if *n == 1 {
+                stem.clone()
+            } else {
+                let suffix = n.to_string();
+                let len = stem
+                    .len()
+                    .min(self.policy.max_len.saturating_sub(suffix.len()));
+                Cow::Owned(format!("{}{}", &stem[..len], suffix))
+            };
+            if self.names.insert(candidate.to_string()) {
+                return candidate;
+            }
         }
-        stem
     }
+
+    /// Replace the name of every variable of the problem, in its variables and expressions,
+    /// with a valid unique name, and return the correspondence between the old and new names.
+    /// Constraints need no renaming: their names are generated when the problem is written
+    /// (see [crate::lp_format::ConstraintNaming]).
+    ///
+    /// ```
+    /// use lp_solvers::lp_format::LpObjective;
+    /// use lp_solvers::problem::{LinearExpression, Problem, Variable};
+    /// use lp_solvers::solvers::{Solution, Status};
+    /// use lp_solvers::util::UniqueNameGenerator;
+    ///
+    /// let mut problem = Problem {
+    ///     name: "renamed".to_string(),
+    ///     sense: LpObjective::Maximize,
+    ///     objective: LinearExpression { coefficients: vec![("stock[paris]".to_string(), 1.)] },
+    ///     variables: vec![Variable {
+    ///         name: "stock[paris]".to_string(),
+    ///         is_integer: false,
+    ///         lower_bound: 0.,
+    ///         upper_bound: 1.,
+    ///     }],
+    ///     constraints: vec![],
+    /// };
+    /// let names = UniqueNameGenerator::default().rename_problem(&mut problem);
+    /// assert_eq!(problem.variables[0].name, "stockparis");
+    /// assert_eq!(names.original("stockparis"), Some("stock[paris]"));
+    ///
+    /// let results = vec![("stockparis".to_string(), 1.)].into_iter().collect();
+    /// let solution = names.restore(Solution::new(Status::Optimal, results));
+    /// assert_eq!(solution.results["stock[paris]"], 1.);
+    /// ```
+    pub fn rename_problem<F: LpFloat>(
+        &mut self,
+        problem: &mut Problem<LinearExpression<F>, Variable<F>, F>,
+    ) -> NameMap {
+        let mut names = NameMap::default();
+        for variable in problem.variables.iter_mut() {
+            variable.name = names.rename(self, &variable.name);
+        }
+        let expressions = std::iter::once(&mut problem.objective)
+            .chain(problem.constraints.iter_mut().map(|c| &mut c.lhs));
+        for expression in expressions {
+            for (name, _) in expression.coefficients.iter_mut() {
+                *name = names.rename(self, name);
+            }
+        }
+        names
+    }
+}
+
+/// The correspondence between original and sanitized variable names,
+/// returned by [UniqueNameGenerator::rename_problem]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NameMap {
+    sanitized: HashMap<String, String>,
+    original: HashMap<String, String>,
 }
 
-fn stem(name: &str) -> Cow<str> {
-    if name.contains(|c: char| !c.is_ascii_alphabetic()) || name.is_empty() {
-        let mut owned = name.replace(|c: char| !c.is_ascii_alphabetic(), "");
-        if owned.is_empty() {
-            owned.push('v');
+impl NameMap {
+    /// The sanitized name given to the variable with the given original name
+    pub fn sanitized(&self, original: &str) -> Option<&str> {
+        self.sanitized.get(original).map(String::as_str)
+    }
+
+    /// The original name of the variable with the given sanitized name
+    pub fn original(&self, sanitized: &str) -> Option<&str> {
+        self.original.get(sanitized).map(String::as_str)
+    }
+
+    /// Number of renamed variables
+    pub fn len(&self) -> usize {
+        self.sanitized.len()
+    }
+
+    /// Whether no variable was renamed
+    pub fn is_empty(&self) -> bool {
+        self.sanitized.is_empty()
+    }
+
+    /// Use the original names in the results of a solution of the renamed problem.
+    /// Every variable whose name changed gets a [Warning::Renamed] in the solution.
+    pub fn restore(&self, mut solution: Solution) -> Solution {
+        let mut renamed = vec![];
+        solution.results = solution
+            .results
+            .into_iter()
+            .map(|(name, value)| match self.original.get(&name) {
+                Some(original) if *original != name => {
+                    renamed.push((original.clone(), name));
+                    (original.clone(), value)
+                }
+                _ => (name, value),
+            })
+            .collect();
+        renamed.sort();
+        let warnings = renamed
+            .into_iter()
+            .map(|(original, renamed)| Warning::Renamed { original, renamed });
+        solution.warnings.extend(warnings);
+        solution
+    }
+
+    /// The sanitized name of `name`, generated the first time it is seen
+    fn rename(&mut self, generator: &mut UniqueNameGenerator, name: &str) -> String {
+        if let Some(sanitized) = self.sanitized.get(name) {
+            return sanitized.clone();
         }
-        Cow::Owned(owned)
+        let sanitized = generator.add_variable(name).into_owned();
+        self.sanitized.insert(name.to_string(), sanitized.clone());
+        self.original.insert(sanitized.clone(), name.to_string());
+        sanitized
+    }
+}
+
+fn is_keyword(name: &str) -> bool {
+    LP_KEYWORDS
+        .iter()
+        .any(|keyword| keyword.eq_ignore_ascii_case(name))
+}
+
+/// Check that a name can be used for a variable in the .lp format.
+///
+/// Names must be non-empty, at most 255 characters long,
+/// made of letters, digits, and the symbols `!"#$%&()/,.;?@_`'{}|~`,
+/// and not start with a digit, a period, or one of the [EXPONENT_PREFIXES],
+/// which would be parsed as a number. They must not be one of the [LP_KEYWORDS].
+///
+/// ```
+/// use lp_solvers::util::validate_name;
+///
+/// assert!(validate_name("x_1").is_ok());
+/// assert!(validate_name("1x").is_err());
+/// assert!(validate_name("e1").is_err());
+/// assert!(validate_name("energy").is_ok());
+/// assert!(validate_name("Bounds").is_err());
+/// assert!(validate_name("a-b").is_err());
+/// ```
+pub fn validate_name(name: &str) -> Result<(), String> {
+    const SYMBOLS: &str = "!\"#$%&()/,.;?@_`'{}|~";
+    let first = name
+        .chars()
+        .next()
+        .ok_or_else(|| "Invalid variable name: empty name".to_string())?;
+    if name.len() > 255 {
+        Err(format!("Invalid variable name {:?}: too long", name))
+    } else if let Some(c) = name
+        .chars()
+        .find(|&c| !c.is_ascii_alphanumeric() && !SYMBOLS.contains(c))
+    {
+        Err(format!(
+            "Invalid variable name {:?}: invalid character {:?}",
+            name, c
+        ))
+    } else if first.is_ascii_digit() || first == '.' {
+        Err(format!(
+            "Invalid variable name {:?}: starts with a number",
+            name
+        ))
+    } else if EXPONENT_PREFIXES.iter().any(|prefix| {
+        name.get(..prefix.len())
+            .is_some_and(|start| start.eq_ignore_ascii_case(prefix))
+    }) {
+        Err(format!(
+            "Invalid variable name {:?}: parsed as an exponent",
+            name
+        ))
+    } else if is_keyword(name) {
+        Err(format!(
+            "Invalid variable name {:?}: reserved keyword",
+            name
+        ))
     } else {
-        Cow::Borrowed(name)
+        Ok(())
     }
 }
 
-fn calculate_hash(t: &str) -> u64 {
-    let mut s = DefaultHasher::new();
-    t.hash(&mut s);
-    s.finish()
+/// Check the names of all the variables of a problem with [validate_name].
+/// Returns the error for the first invalid name.
+pub fn validate_variable_names<'a, P: LpProblem<'a>>(problem: &'a P) -> Result<(), String> {
+    problem
+        .variables()
+        .try_for_each(|variable| validate_name(variable.name()))
 }
 
 pub(crate) fn buf_contains(haystack: &[u8], needle: &str) -> bool {
@@ -59,3 +389,12 @@ pub(crate) fn buf_contains(haystack: &[u8], needle: &str) -> bool {
         .windows(needle.len())
         .any(|window| window == needle)
 }
+
+/// The first line of `haystack` that contains one of the `needles`
+#[cfg(any(feature = "gurobi", feature = "cplex"))]
+pub(crate) fn find_line(haystack: &[u8], needles: &[&str]) -> Option<String> {
+    haystack
+        .split(|&b| b == b'\n')
+        .find(|line| needles.iter().any(|needle| buf_contains(line, needle)))
+        .map(|line| String::from_utf8_lossy(line).trim().to_string())
+}
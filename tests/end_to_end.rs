@@ -1,3 +1,5 @@
+#![cfg(all(feature = "process", feature = "cbc"))]
+
 use std::cmp::Ordering;
 use std::collections::HashMap;
 
@@ -62,6 +64,9 @@ fn solve_integer_problem_with_solver<S: SolverTrait>(solver: &S) {
             .into_iter()
             .collect();
     assert_eq!(solution.results, expected_results);
+    let report = solution.report.expect("missing solve report");
+    assert!(report.problem_file_size > 0);
+    assert!(report.solution_file_size.is_some());
 }
 
 fn infeasible<S: SolverTrait>(solver: &S) {
@@ -0,0 +1,97 @@
+#![cfg(all(unix, feature = "process", feature = "cbc"))]
+
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::Mutex;
+
+use lp_solvers::lp_format::LpObjective;
+use lp_solvers::problem::{Problem, StrExpression, Variable};
+use lp_solvers::solvers::{
+    CbcSolver, CommandSpec, HookedSolver, PreparedSolve, RawOutcome, SolverHooks, SolverTrait,
+    Status, WithMaxSeconds,
+};
+
+/// Replaces cbc by a copy of a solution file, and records what it sees
+#[derive(Default)]
+struct FakeCbc {
+    problem: Mutex<String>,
+    success: Mutex<Option<bool>>,
+}
+
+impl SolverHooks for FakeCbc {
+    fn before_spawn(&self, solve: &mut PreparedSolve<'_>) {
+        *self.problem.lock().unwrap() = std::fs::read_to_string(solve.problem_file).unwrap();
+        let fixture = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("solution_files")
+            .join("cbc_optimal.sol");
+        let mut command = Command::new("cp");
+        command.arg(fixture).arg(solve.solution_file);
+        solve.command = command;
+    }
+
+    fn after_finish(&self, outcome: &RawOutcome<'_>) {
+        *self.success.lock().unwrap() = Some(outcome.output.status.success());
+    }
+}
+
+#[test]
+fn hooks_replace_the_command() {
+    let pb: Problem = Problem {
+        name: "hooked".to_string(),
+        sense: LpObjective::Maximize,
+        objective: StrExpression("a + b".to_string()),
+        variables: vec![Variable {
+            name: "a".to_string(),
+            is_integer: false,
+            lower_bound: 0.,
+            upper_bound: 5.,
+        }],
+        constraints: vec![],
+    };
+    let solver = HookedSolver::new(CbcSolver::new(), FakeCbc::default());
+    let solution = solver.run(&pb).unwrap();
+    assert_eq!(solution.status, Status::Optimal);
+    assert_eq!(solution.results.get("a"), Some(&5.));
+    assert!(solver.hooks.problem.lock().unwrap().contains("obj: a + b"));
+    assert_eq!(*solver.hooks.success.lock().unwrap(), Some(true));
+    assert_eq!(solution.report.unwrap().command_line[0], "cp");
+}
+
+/// Records the command it is about to run, and runs `true` instead
+#[derive(Default)]
+struct DryRun {
+    spec: Mutex<Option<CommandSpec>>,
+}
+
+impl SolverHooks for DryRun {
+    fn before_spawn(&self, solve: &mut PreparedSolve<'_>) {
+        solve.command.env("CBC_LOG", "1");
+        *self.spec.lock().unwrap() = Some(solve.spec());
+        solve.command = Command::new("true");
+    }
+}
+
+#[test]
+fn hooks_see_the_command_spec() {
+    let pb: Problem = Problem {
+        name: "dry".to_string(),
+        sense: LpObjective::Minimize,
+        objective: StrExpression("a".to_string()),
+        variables: vec![],
+        constraints: vec![],
+    };
+    let solver = HookedSolver::new(CbcSolver::new().with_max_seconds(5), DryRun::default());
+    // `true` writes no solution
+    assert!(solver.run(&pb).is_err());
+    let spec = solver.hooks.spec.lock().unwrap().take().unwrap();
+    assert_eq!(spec.program, "cbc");
+    assert_eq!(spec.args[1..3], ["seconds", "5"]);
+    assert_eq!(spec.env, vec![("CBC_LOG".into(), Some("1".into()))]);
+    assert_eq!(spec.cwd, None);
+    let mut command = spec.to_command();
+    assert_eq!(
+        CommandSpec::from_command(command.current_dir("/")).cwd,
+        Some("/".into())
+    );
+}
@@ -1,7 +1,13 @@
 use std::cmp::Ordering;
+use std::time::{Duration, UNIX_EPOCH};
 
-use lp_solvers::lp_format::{Constraint, LpObjective, LpProblem};
-use lp_solvers::problem::{Problem, StrExpression, Variable};
+use lp_solvers::lp_format::{
+    AsVariable, ConsId, Constraint, ConstraintNaming, ConstraintTags, FormatSelection,
+    LpFormatOptions, LpObjective, LpProblem, ProblemFormat, WriteToLpFileFormat,
+};
+use lp_solvers::metadata::FileMetadata;
+use lp_solvers::problem::{LinearExpression, Problem, StrExpression, Variable};
+use lp_solvers::solvers::SolverParams;
 
 #[test]
 fn simple_problem() {
@@ -41,12 +47,12 @@ Minimize
   obj: 2 x + y
 
 Subject To
-  c0: x + y + z >= 5
+  c0: x + y + z >= 5.0
 
 Bounds
   x free
-  0 <= y
-  1 <= z <= 10
+  0.0 <= y
+  1.0 <= z <= 10.0
 
 End
 ";
@@ -85,10 +91,10 @@ Maximize
   obj: x - y
 
 Subject To
-  c0: x - y <= -5
+  c0: x - y <= -5.0
 
 Bounds
-  -10 <= x <= 10
+  -10.0 <= x <= 10.0
   y <= 16.5
 
 Generals
@@ -102,7 +108,7 @@ End
 
 #[test]
 fn without_constraints() {
-    let pb = Problem {
+    let pb: Problem = Problem {
         name: "int_problem".to_string(),
         sense: LpObjective::Maximize,
         objective: StrExpression("x".to_string()),
@@ -122,7 +128,190 @@ Maximize
 Subject To
 
 Bounds
-  0 <= x <= 2.5
+  0.0 <= x <= 2.5
+
+Generals
+  x
+
+End
+";
+    assert_eq!(pb.display_lp().to_string(), expected_str);
+}
+
+#[test]
+fn custom_constraint_naming() {
+    let pb = Problem {
+        name: "named".to_string(),
+        sense: LpObjective::Minimize,
+        objective: StrExpression("x".to_string()),
+        variables: vec![Variable {
+            name: "x".to_string(),
+            is_integer: false,
+            lower_bound: 0.,
+            upper_bound: f64::INFINITY,
+        }],
+        constraints: vec![
+            Constraint {
+                lhs: StrExpression("x".to_string()),
+                operator: Ordering::Greater,
+                rhs: 1.0,
+            },
+            Constraint {
+                lhs: StrExpression("x".to_string()),
+                operator: Ordering::Less,
+                rhs: 3.0,
+            },
+        ],
+    };
+    let options = LpFormatOptions {
+        constraint_naming: ConstraintNaming::new("row_", 2),
+        ..Default::default()
+    };
+    let expected_str = "\\ named
+
+Minimize
+  obj: x
+
+Subject To
+  row_00: x >= 1.0
+  row_01: x <= 3.0
+
+Bounds
+  0.0 <= x
+
+End
+";
+    assert_eq!(
+        pb.with_format_options(options).display_lp().to_string(),
+        expected_str
+    );
+}
+
+#[test]
+fn write_to_writer() {
+    let pb = Problem {
+        name: "written".to_string(),
+        sense: LpObjective::Maximize,
+        objective: LinearExpression {
+            coefficients: vec![("x".to_string(), 2.), ("y".to_string(), -0.5)],
+        },
+        variables: vec![
+            Variable {
+                name: "x".to_string(),
+                is_integer: true,
+                lower_bound: 0.,
+                upper_bound: 1.,
+            },
+            Variable {
+                name: "y".to_string(),
+                is_integer: false,
+                lower_bound: f64::NEG_INFINITY,
+                upper_bound: f64::INFINITY,
+            },
+        ],
+        constraints: vec![Constraint {
+            lhs: LinearExpression {
+                coefficients: vec![("x".to_string(), 1.), ("y".to_string(), 1.)],
+            },
+            operator: Ordering::Less,
+            rhs: 1e-7,
+        }],
+    };
+    let options = LpFormatOptions {
+        constraint_naming: ConstraintNaming::new("r", 2),
+        ..Default::default()
+    };
+    let mut written = vec![];
+    pb.with_format_options(options)
+        .to_writer(&mut written)
+        .unwrap();
+    let expected_str = "\\ written
+
+Maximize
+  obj: 2.0 x - 0.5 y
+
+Subject To
+  r00: 1.0 x + 1.0 y <= 1e-7
+
+Bounds
+  0.0 <= x <= 1.0
+  y free
+
+Generals
+  x
+
+End
+";
+    assert_eq!(String::from_utf8(written).unwrap(), expected_str);
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn parallel_writing_is_identical() {
+    let constraints = (0..10_000)
+        .map(|i| Constraint {
+            lhs: LinearExpression {
+                coefficients: vec![("x".to_string(), i as f64), ("y".to_string(), -1.)],
+            },
+            operator: Ordering::Less,
+            rhs: i as f64 / 3.,
+        })
+        .collect();
+    let pb: Problem<LinearExpression, Variable> = Problem {
+        name: "large".to_string(),
+        sense: LpObjective::Minimize,
+        objective: LinearExpression {
+            coefficients: vec![("x".to_string(), 1.)],
+        },
+        variables: vec![],
+        constraints,
+    };
+    let mut sequential = vec![];
+    pb.write_lp_to(&mut sequential).unwrap();
+    let mut parallel = vec![];
+    pb.in_parallel().to_writer(&mut parallel).unwrap();
+    assert_eq!(sequential, parallel);
+}
+
+/// A variable whose name is shared with other data structures
+struct SharedNameVariable(std::sync::Arc<str>);
+
+impl AsVariable for SharedNameVariable {
+    fn name(&self) -> &str {
+        &self.0
+    }
+
+    fn is_integer(&self) -> bool {
+        true
+    }
+
+    fn lower_bound(&self) -> f64 {
+        0.
+    }
+
+    fn upper_bound(&self) -> f64 {
+        1.
+    }
+}
+
+#[test]
+fn shared_variable_names() {
+    let pb: Problem<StrExpression, SharedNameVariable> = Problem {
+        name: "shared".to_string(),
+        sense: LpObjective::Minimize,
+        objective: StrExpression("x".to_string()),
+        variables: vec![SharedNameVariable("x".into())],
+        constraints: vec![],
+    };
+    let expected_str = "\\ shared
+
+Minimize
+  obj: x
+
+Subject To
+
+Bounds
+  0.0 <= x <= 1.0
 
 Generals
   x
@@ -131,3 +320,545 @@ End
 ";
     assert_eq!(pb.display_lp().to_string(), expected_str);
 }
+
+#[test]
+fn skip_default_bounds_and_binaries() {
+    let pb: Problem = Problem {
+        name: "compact".to_string(),
+        sense: LpObjective::Maximize,
+        objective: StrExpression("a + b + c + d".to_string()),
+        variables: vec![
+            var("a", false, 0., f64::INFINITY),
+            var("b", true, 0., 1.),
+            var("c", true, 0., f64::INFINITY),
+            var("d", false, 0., 1.),
+        ],
+        constraints: vec![],
+    };
+    let options = LpFormatOptions {
+        skip_default_bounds: true,
+        binaries_section: true,
+        ..Default::default()
+    };
+    let expected_str = "\\ compact
+
+Maximize
+  obj: a + b + c + d
+
+Subject To
+
+Bounds
+  0.0 <= d <= 1.0
+
+Generals
+  c
+
+Binaries
+  b
+
+End
+";
+    let compact = pb.with_format_options(options);
+    assert_eq!(compact.display_lp().to_string(), expected_str);
+    let mut written = vec![];
+    compact.to_writer(&mut written).unwrap();
+    assert_eq!(String::from_utf8(written).unwrap(), expected_str);
+}
+
+fn var(name: &str, is_integer: bool, lower_bound: f64, upper_bound: f64) -> Variable {
+    Variable {
+        name: name.to_string(),
+        is_integer,
+        lower_bound,
+        upper_bound,
+    }
+}
+
+fn linear(terms: &[(&str, f64)]) -> LinearExpression {
+    LinearExpression {
+        coefficients: terms
+            .iter()
+            .map(|&(name, coefficient)| (name.to_string(), coefficient))
+            .collect(),
+    }
+}
+
+#[test]
+fn write_free_mps() {
+    let pb = Problem {
+        name: "mps".to_string(),
+        sense: LpObjective::Maximize,
+        objective: linear(&[("x", 1.), ("y", 2.)]),
+        variables: vec![
+            var("x", false, 0., f64::INFINITY),
+            var("y", true, 0., 10.),
+            var("z", false, f64::NEG_INFINITY, f64::INFINITY),
+            var("w", true, -1., f64::INFINITY),
+        ],
+        constraints: vec![
+            Constraint {
+                lhs: linear(&[("x", 1.), ("y", 1.), ("x", 0.5)]),
+                operator: Ordering::Less,
+                rhs: 4.,
+            },
+            Constraint {
+                lhs: linear(&[("y", -1.), ("u", 3.)]),
+                operator: Ordering::Equal,
+                rhs: 0.,
+            },
+        ],
+    };
+    let mut written = vec![];
+    pb.write_free_mps_to(&mut written).unwrap();
+    assert_eq!(
+        String::from_utf8(written).unwrap(),
+        "NAME mps
+OBJSENSE
+    MAX
+ROWS
+ N obj
+ L c0
+ E c1
+COLUMNS
+ x obj 1.0
+ x c0 1.5
+ MARKER 'MARKER' 'INTORG'
+ y obj 2.0
+ y c0 1.0
+ y c1 -1.0
+ MARKER 'MARKER' 'INTEND'
+ z obj 0.0
+ MARKER 'MARKER' 'INTORG'
+ w obj 0.0
+ MARKER 'MARKER' 'INTEND'
+ u c1 3.0
+RHS
+ RHS c0 4.0
+BOUNDS
+ LO BND y 0.0
+ UP BND y 10.0
+ FR BND z
+ LO BND w -1.0
+ PL BND w
+ENDATA
+"
+    );
+}
+
+#[test]
+fn named_objectives() {
+    let pb = Problem {
+        name: "objectives".to_string(),
+        sense: LpObjective::Minimize,
+        objective: linear(&[("x", 1.)]),
+        variables: vec![var("x", false, 0., 1.), var("y", false, 0., 1.)],
+        constraints: vec![Constraint {
+            lhs: linear(&[("x", 1.), ("y", 1.)]),
+            operator: Ordering::Greater,
+            rhs: 1.,
+        }],
+    };
+    let options = LpFormatOptions {
+        objective_name: Some("cost".to_string()),
+        other_objectives: vec![("risk".to_string(), linear(&[("y", 2.)]))],
+        ..LpFormatOptions::default()
+    };
+    let pb = pb.with_format_options(options);
+    let lp = pb.display_lp().to_string();
+    assert!(lp.contains("Minimize\n  cost: 1.0 x\n"), "{}", lp);
+    assert!(!lp.contains("risk"), "{}", lp);
+
+    let mut written = vec![];
+    pb.write_free_mps_to(&mut written).unwrap();
+    assert_eq!(
+        String::from_utf8(written).unwrap(),
+        "NAME objectives
+OBJNAME
+    cost
+ROWS
+ N cost
+ N risk
+ G c0
+COLUMNS
+ x cost 1.0
+ x c0 1.0
+ y risk 2.0
+ y c0 1.0
+RHS
+ RHS c0 1.0
+BOUNDS
+ UP BND x 1.0
+ UP BND y 1.0
+ENDATA
+"
+    );
+}
+
+#[test]
+fn constraint_tags() {
+    let pb = Problem {
+        name: "tags".to_string(),
+        sense: LpObjective::Minimize,
+        objective: linear(&[("x", 1.)]),
+        variables: vec![var("x", false, 0., 1.)],
+        constraints: vec![
+            Constraint {
+                lhs: linear(&[("x", 1.)]),
+                operator: Ordering::Greater,
+                rhs: 0.5,
+            },
+            Constraint {
+                lhs: linear(&[("x", 2.)]),
+                operator: Ordering::Less,
+                rhs: 1.,
+            },
+        ],
+    };
+    let mut tags = ConstraintTags::default();
+    tags.add(ConsId::from_index(1), "capacity");
+    tags.add(ConsId::from_index(1), "plant 7\nsouth");
+    tags.add(ConsId::from_index(1), "capacity");
+    let pb = pb.with_format_options(LpFormatOptions {
+        constraint_tags: tags,
+        ..LpFormatOptions::default()
+    });
+    let lp = pb.display_lp().to_string();
+    assert!(
+        lp.contains(
+            "Subject To
+  c0: 1.0 x >= 0.5
+  \\ tag: capacity
+  \\ tag: plant 7 south
+  c1: 2.0 x <= 1.0
+"
+        ),
+        "{}",
+        lp
+    );
+    let mut written = vec![];
+    pb.write_lp_to(&mut written).unwrap();
+    assert_eq!(String::from_utf8(written).unwrap(), lp);
+}
+
+#[test]
+fn metadata_round_trip() {
+    let pb: Problem<LinearExpression, Variable> = Problem {
+        name: "archived".to_string(),
+        sense: LpObjective::Minimize,
+        objective: linear(&[("x", 1.)]),
+        variables: vec![var("x", false, 0., 1.)],
+        constraints: vec![],
+    };
+    let time = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    let params = SolverParams::new()
+        .with_param("Threads", 4)
+        .with_param("MIPGap", 0.01);
+    let metadata = FileMetadata::new()
+        .with_model_version("3.1")
+        .with_git_hash("8f2c1e7")
+        .with_timestamp(time)
+        .with_parameters(&params)
+        .with("run id", "nightly\n42");
+    let pb = pb.with_format_options(LpFormatOptions {
+        metadata: metadata.clone(),
+        ..LpFormatOptions::default()
+    });
+
+    let mut lp = vec![];
+    pb.write_lp_to(&mut lp).unwrap();
+    let lp = String::from_utf8(lp).unwrap();
+    assert!(
+        lp.starts_with(
+            "\\ archived
+\\ model_version: 3.1
+\\ git_hash: 8f2c1e7
+\\ timestamp: 1700000000
+\\ param.Threads: 4
+\\ param.MIPGap: 0.01
+\\ run_id: nightly 42
+
+Minimize
+"
+        ),
+        "{}",
+        lp
+    );
+    assert!(pb
+        .display_lp()
+        .to_string()
+        .starts_with(&lp[..lp.find("Minimize").unwrap()]));
+
+    let mut mps = vec![];
+    pb.write_free_mps_to(&mut mps).unwrap();
+    let mps = String::from_utf8(mps).unwrap();
+    assert!(mps.starts_with("* model_version: 3.1\n"), "{}", mps);
+    assert!(
+        mps.contains("* run_id: nightly 42\nNAME archived\n"),
+        "{}",
+        mps
+    );
+
+    for file in [lp, mps] {
+        let read = FileMetadata::read(file.as_bytes()).unwrap();
+        assert_eq!(read, metadata);
+        assert_eq!(read.timestamp(), Some(time));
+        assert_eq!(read.parameters(), params);
+    }
+    assert_eq!(metadata.entries().last(), Some(("run_id", "nightly 42")));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn deserialized_metadata_stays_in_comments() {
+    let metadata: FileMetadata =
+        serde_json::from_str(r#"{"entries": [["run id", "nightly\nMaximize"]]}"#).unwrap();
+    assert_eq!(metadata.get("run_id"), Some("nightly Maximize"));
+    let mut lp = vec![];
+    lp_solvers::problem::ProblemBuilder::<f64>::new("archived", LpObjective::Minimize)
+        .build()
+        .with_format_options(LpFormatOptions {
+            metadata,
+            ..LpFormatOptions::default()
+        })
+        .write_lp_to(&mut lp)
+        .unwrap();
+    assert!(String::from_utf8(lp)
+        .unwrap()
+        .starts_with("\\ archived\n\\ run_id: nightly Maximize\n\nMinimize"));
+}
+
+#[test]
+fn deterministic_number_formatting() {
+    let pb = Problem {
+        name: "numbers".to_string(),
+        sense: LpObjective::Minimize,
+        objective: linear(&[("x", 0.1 + 0.2), ("y", -0.), ("z", 123_456_789.)]),
+        variables: vec![var("x", false, 1e-7, 2.5e300), var("y", false, -1., 1.)],
+        constraints: vec![Constraint {
+            lhs: linear(&[("x", 1e21), ("y", -1. / 3.)]),
+            operator: Ordering::Less,
+            rhs: 1e-300,
+        }],
+    };
+    let expected_str = "\\ numbers
+
+Minimize
+  obj: 0.30000000000000004 x - 0.0 y + 123456789.0 z
+
+Subject To
+  c0: 1e21 x - 0.3333333333333333 y <= 1e-300
+
+Bounds
+  1e-7 <= x <= 2.5e300
+  -1.0 <= y <= 1.0
+
+End
+";
+    assert_eq!(pb.display_lp().to_string(), expected_str);
+    let mut written = vec![];
+    pb.write_lp_to(&mut written).unwrap();
+    assert_eq!(String::from_utf8(written).unwrap(), expected_str);
+}
+
+#[test]
+fn mps_needs_linear_expressions() {
+    let pb: Problem = Problem {
+        name: "str".to_string(),
+        sense: LpObjective::Minimize,
+        objective: StrExpression("x".to_string()),
+        variables: vec![var("x", false, 0., 1.)],
+        constraints: vec![],
+    };
+    let err = pb.write_free_mps_to(&mut vec![]).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    let by_size = FormatSelection::BySize { mps_threshold: 0 };
+    let both = [ProblemFormat::Lp, ProblemFormat::FreeMps];
+    assert_eq!(by_size.select(&pb, &both), ProblemFormat::Lp);
+}
+
+#[test]
+fn format_selection() {
+    let pb: Problem<LinearExpression, Variable> = Problem {
+        name: "select".to_string(),
+        sense: LpObjective::Minimize,
+        objective: linear(&[("x", 1.)]),
+        variables: vec![var("x", false, 0., 1.), var("y", false, 0., 1.)],
+        constraints: vec![],
+    };
+    let both = [ProblemFormat::Lp, ProblemFormat::FreeMps];
+    let small = FormatSelection::BySize { mps_threshold: 3 };
+    let large = FormatSelection::BySize { mps_threshold: 2 };
+    assert_eq!(small.select(&pb, &both), ProblemFormat::Lp);
+    assert_eq!(large.select(&pb, &both), ProblemFormat::FreeMps);
+    assert_eq!(large.select(&pb, &[ProblemFormat::Lp]), ProblemFormat::Lp);
+    let fixed = FormatSelection::Fixed(ProblemFormat::FreeMps);
+    assert_eq!(fixed.select(&pb, &both), ProblemFormat::FreeMps);
+    assert_eq!(
+        LpFormatOptions::default().format.select(&pb, &both),
+        ProblemFormat::Lp
+    );
+    let file = pb.to_tmp_file_in(ProblemFormat::FreeMps).unwrap();
+    assert!(file.path().to_string_lossy().ends_with(".mps"));
+
+    // iterators whose size is not known in advance are counted
+    let filtered = Filtered(pb);
+    assert_eq!(large.select(&filtered, &both), ProblemFormat::FreeMps);
+    assert_eq!(small.select(&filtered, &both), ProblemFormat::Lp);
+
+    // a single quadratic constraint keeps the problem in the .lp format
+    let mixed: Problem<Mixed, Variable> = Problem {
+        name: "mixed".to_string(),
+        sense: LpObjective::Minimize,
+        objective: Mixed::Linear(linear(&[("x", 1.)])),
+        variables: vec![var("x", false, 0., 1.), var("y", false, 0., 1.)],
+        constraints: vec![Constraint {
+            lhs: Mixed::Quadratic("[ x ^ 2 ]".to_string()),
+            operator: Ordering::Less,
+            rhs: 1.,
+        }],
+    };
+    assert_eq!(large.select(&mixed, &both), ProblemFormat::Lp);
+}
+
+/// A problem whose variables are filtered, so that their number is unknown in advance
+struct Filtered(Problem<LinearExpression, Variable>);
+
+impl<'a> LpProblem<'a> for Filtered {
+    type Variable = &'a Variable;
+    type Expression = &'a LinearExpression;
+    type Float = f64;
+    type ConstraintIterator =
+        <Problem<LinearExpression, Variable> as LpProblem<'a>>::ConstraintIterator;
+    type VariableIterator = Box<dyn Iterator<Item = &'a Variable> + 'a>;
+
+    fn variables(&'a self) -> Self::VariableIterator {
+        Box::new(self.0.variables.iter().filter(|v| !v.name.is_empty()))
+    }
+
+    fn objective(&'a self) -> Self::Expression {
+        &self.0.objective
+    }
+
+    fn sense(&'a self) -> LpObjective {
+        self.0.sense
+    }
+
+    fn constraints(&'a self) -> Self::ConstraintIterator {
+        self.0.constraints()
+    }
+}
+
+/// A linear or a quadratic expression
+enum Mixed {
+    Linear(LinearExpression),
+    Quadratic(String),
+}
+
+impl WriteToLpFileFormat for Mixed {
+    fn to_lp_file_format(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Mixed::Linear(expression) => expression.to_lp_file_format(f),
+            Mixed::Quadratic(expression) => f.write_str(expression),
+        }
+    }
+
+    fn linear_terms(&self) -> Option<Vec<(&str, f64)>> {
+        match self {
+            Mixed::Linear(expression) => expression.linear_terms(),
+            Mixed::Quadratic(_) => None,
+        }
+    }
+}
+
+#[test]
+fn write_to_path() {
+    let pb: Problem = Problem {
+        name: "archived".to_string(),
+        sense: LpObjective::Maximize,
+        objective: StrExpression("x".to_string()),
+        variables: vec![Variable {
+            name: "x".to_string(),
+            is_integer: false,
+            lower_bound: 0.,
+            upper_bound: 1.,
+        }],
+        constraints: vec![],
+    };
+    let mut expected = vec![];
+    pb.to_writer(&mut expected).unwrap();
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("archived.lp");
+    std::fs::write(&path, "an older problem").unwrap();
+    pb.write_to_path(&path, ProblemFormat::Lp).unwrap();
+    assert_eq!(std::fs::read(&path).unwrap(), expected);
+    // the temporary file was renamed
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+
+    let compressed = pb.write_to_path(&dir.path().join("archived.lp.gz"), ProblemFormat::Lp);
+    #[cfg(feature = "gzip")]
+    {
+        use std::io::Read;
+
+        compressed.unwrap();
+        let file = std::fs::File::open(dir.path().join("archived.lp.gz")).unwrap();
+        let mut written = vec![];
+        flate2::read::GzDecoder::new(file)
+            .read_to_end(&mut written)
+            .unwrap();
+        assert_eq!(written, expected);
+    }
+    #[cfg(not(feature = "gzip"))]
+    assert!(compressed.is_err());
+}
+
+#[cfg(feature = "testing")]
+#[test]
+fn lp_snapshots() {
+    use lp_solvers::testing::{check_snapshot, lp_snapshot};
+
+    let pb: Problem<LinearExpression, Variable> = Problem {
+        name: "golden".to_string(),
+        sense: LpObjective::Minimize,
+        objective: linear(&[("x", 1.)]),
+        variables: vec![var("x", false, 0., 1.)],
+        constraints: vec![],
+    };
+    let snapshot = lp_snapshot(&pb);
+    assert!(snapshot.contains("obj: 1.0 x\n"), "{}", snapshot);
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("snapshots").join("golden.lp");
+    let missing = check_snapshot(&snapshot, &path, false).unwrap_err();
+    assert!(
+        missing.contains("LP_SOLVERS_UPDATE_SNAPSHOTS"),
+        "{}",
+        missing
+    );
+    check_snapshot(&snapshot, &path, true).unwrap();
+    check_snapshot(&snapshot, &path, false).unwrap();
+    // files checked out with Windows line endings
+    std::fs::write(&path, snapshot.replace('\n', "\r\n")).unwrap();
+    check_snapshot(&snapshot, &path, false).unwrap();
+
+    let changed = lp_snapshot(&Problem {
+        objective: linear(&[("x", 2.)]),
+        ..pb
+    });
+    let error = check_snapshot(&changed, &path, false).unwrap_err();
+    assert!(
+        error.contains("line 4: expected \"  obj: 1.0 x\", got \"  obj: 2.0 x\""),
+        "{}",
+        error
+    );
+
+    // only the final newline differs
+    let error = check_snapshot(snapshot.trim_end(), &path, false).unwrap_err();
+    assert!(
+        error.contains("expected a final newline, got no final newline"),
+        "{}",
+        error
+    );
+    let error = check_snapshot(&snapshot.replace('\n', "\r\n"), &path, false).unwrap_err();
+    assert!(error.contains("line endings"), "{}", error);
+}
@@ -0,0 +1,891 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use lp_solvers::lp_format::{ConsId, Constraint, LpObjective, LpProblem};
+use lp_solvers::matrix::MatrixProblem;
+use lp_solvers::presolve::{propagate_bounds, Infeasibility};
+use lp_solvers::problem::{
+    Column, LinearExpression, Problem, ProblemBuilder, Redundancy, Variable,
+};
+use lp_solvers::solvers::{
+    find_infeasible_subset, solve_with_cuts, FnSolver, PresolvingSolver, Relaxation, Solution,
+    SolverError, SolverTrait, Status, Warning,
+};
+use lp_solvers::template::ProblemTemplate;
+use lp_solvers::util::{validate_name, validate_variable_names, NamePolicy, UniqueNameGenerator};
+
+fn expr(terms: &[(&str, f64)]) -> LinearExpression {
+    LinearExpression {
+        coefficients: terms
+            .iter()
+            .map(|&(name, coefficient)| (name.to_string(), coefficient))
+            .collect(),
+    }
+}
+
+fn var(name: &str) -> Variable {
+    Variable {
+        name: name.to_string(),
+        is_integer: false,
+        lower_bound: 0.,
+        upper_bound: 10.,
+    }
+}
+
+#[test]
+fn linear_expression_format() {
+    let pb = Problem {
+        name: "linear".to_string(),
+        sense: LpObjective::Minimize,
+        objective: expr(&[("x", -2.), ("y", 1.5), ("z", -1.)]),
+        variables: vec![var("x"), var("y"), var("z")],
+        constraints: vec![Constraint {
+            lhs: expr(&[]),
+            operator: Ordering::Less,
+            rhs: 1.,
+        }],
+    };
+    let lp = pb.display_lp().to_string();
+    assert!(lp.contains("obj: -2.0 x + 1.5 y - 1.0 z\n"), "{}", lp);
+    assert!(lp.contains("c0: 0 <= 1.0\n"), "{}", lp);
+}
+
+#[test]
+fn merge_problems() {
+    let mut first = Problem {
+        name: "first".to_string(),
+        sense: LpObjective::Minimize,
+        objective: expr(&[("x", 1.), ("y", 2.)]),
+        variables: vec![var("x"), var("y")],
+        constraints: vec![Constraint {
+            lhs: expr(&[("x", 1.), ("y", 1.)]),
+            operator: Ordering::Greater,
+            rhs: 1.,
+        }],
+    };
+    let second = Problem {
+        name: "second".to_string(),
+        sense: LpObjective::Maximize,
+        objective: expr(&[("x", 3.), ("z", 1.)]),
+        variables: vec![var("x"), var("z")],
+        constraints: vec![Constraint {
+            lhs: expr(&[("x", 1.), ("z", -1.)]),
+            operator: Ordering::Equal,
+            rhs: 0.,
+        }],
+    };
+    let renames = first.merge(second, 1., 2.);
+
+    assert_eq!(renames.len(), 1);
+    assert_eq!(renames["x"], "x2");
+    let names: Vec<_> = first.variables.iter().map(|v| v.name.as_str()).collect();
+    assert_eq!(names, ["x", "y", "x2", "z"]);
+    assert_eq!(
+        first.objective,
+        expr(&[("x", 1.), ("y", 2.), ("x2", -6.), ("z", -2.)])
+    );
+    assert_eq!(first.constraints.len(), 2);
+    assert_eq!(first.constraints[1].lhs, expr(&[("x2", 1.), ("z", -1.)]));
+}
+
+#[test]
+fn single_precision_problem() {
+    let pb: Problem<LinearExpression<f32>, Variable<f32>, f32> = Problem {
+        name: "single".to_string(),
+        sense: LpObjective::Maximize,
+        objective: LinearExpression {
+            coefficients: vec![("x".to_string(), 0.1f32)],
+        },
+        variables: vec![Variable {
+            name: "x".to_string(),
+            is_integer: false,
+            lower_bound: 0.5f32,
+            upper_bound: f32::INFINITY,
+        }],
+        constraints: vec![Constraint {
+            lhs: LinearExpression {
+                coefficients: vec![("x".to_string(), 2.5f32)],
+            },
+            operator: Ordering::Less,
+            rhs: 0.1,
+        }],
+    };
+    let lp = pb.display_lp().to_string();
+    assert!(lp.contains("obj: 0.1 x\n"), "{}", lp);
+    // the right-hand side is written in single precision, not widened
+    assert!(lp.contains("c0: 2.5 x <= 0.1\n"), "{}", lp);
+    assert!(lp.contains("0.5 <= x\n"), "{}", lp);
+
+    let mut builder = ProblemBuilder::<f32>::new("single", LpObjective::Minimize);
+    let id = builder.add_constraint(LinearExpression::default(), Ordering::Greater, 0.3f32);
+    let constraint: &Constraint<LinearExpression<f32>, f32> = builder.constraint(id).unwrap();
+    assert_eq!(constraint.rhs, 0.3f32);
+    assert!(builder
+        .build()
+        .display_lp()
+        .to_string()
+        .contains("c0: 0 >= 0.3\n"));
+}
+
+#[test]
+fn builder_constraint_handles() {
+    let mut builder = ProblemBuilder::new("handles", LpObjective::Minimize);
+    builder.add_variable(var("x")).add_variable(var("y"));
+    builder.set_objective(expr(&[("x", 1.), ("y", 1.)]));
+    let first = builder.add_constraint(expr(&[("x", 1.)]), Ordering::Greater, 1.);
+    let second = builder.add_constraint(expr(&[("y", 1.)]), Ordering::Greater, 2.);
+    assert_eq!(builder.constraint(second).unwrap().rhs, 2.);
+
+    let lp = builder.build().display_lp().to_string();
+    assert!(
+        lp.contains(&format!("  {}: 1.0 x >= 1.0\n", first.name())),
+        "{}",
+        lp
+    );
+    assert!(
+        lp.contains(&format!("  {}: 1.0 y >= 2.0\n", second.name())),
+        "{}",
+        lp
+    );
+
+    let mut solution = Solution::new(Status::Optimal, HashMap::new());
+    solution.dual_values.insert("c1".to_string(), 1.);
+    assert_eq!(solution.dual(second), Some(1.));
+    assert_eq!(solution.dual(first), None);
+}
+
+#[test]
+fn builder_normalizes_expressions() {
+    let mut builder = ProblemBuilder::new("normalized", LpObjective::Minimize);
+    builder.add_variable(var("x")).add_variable(var("y"));
+    builder.normalize_expressions();
+    builder.set_objective(expr(&[("y", 1.), ("x", 0.), ("y", 2.)]));
+    builder.add_constraint(
+        expr(&[("y", 1.), ("x", 1.), ("y", -1.), ("x", 0.5)]),
+        Ordering::Less,
+        3.,
+    );
+    // the same constraint, with its terms in another order
+    builder.add_constraint(expr(&[("x", 1.5), ("y", 0.)]), Ordering::Less, 3.);
+    let problem = builder.build();
+    assert_eq!(problem.objective, expr(&[("y", 3.)]));
+    assert_eq!(problem.constraints[0].lhs, expr(&[("x", 1.5)]));
+    assert_eq!(problem.constraints[0].lhs, problem.constraints[1].lhs);
+
+    let mut cancelled = expr(&[("x", 1.), ("x", -1.)]);
+    cancelled.normalize();
+    assert!(cancelled.coefficients.is_empty());
+}
+
+#[test]
+fn remove_redundant_constraints() {
+    let mut problem = Problem {
+        name: "redundant".to_string(),
+        sense: LpObjective::Minimize,
+        objective: expr(&[("x", 1.)]),
+        variables: vec![var("x"), var("y")],
+        constraints: vec![
+            Constraint {
+                lhs: expr(&[("x", 1.), ("y", 1.)]),
+                operator: Ordering::Less,
+                rhs: 8.,
+            },
+            // within the bounds of x and y, between 0 and 10
+            Constraint {
+                lhs: expr(&[("x", 1.), ("y", -1.)]),
+                operator: Ordering::Less,
+                rhs: 10.,
+            },
+            // stronger than the first one
+            Constraint {
+                lhs: expr(&[("y", 1.), ("x", 1.)]),
+                operator: Ordering::Less,
+                rhs: 6.,
+            },
+            Constraint {
+                lhs: expr(&[("x", 2.)]),
+                operator: Ordering::Equal,
+                rhs: 4.,
+            },
+            Constraint {
+                lhs: expr(&[("x", 1.), ("x", 1.)]),
+                operator: Ordering::Equal,
+                rhs: 4.,
+            },
+            // an equality with another right-hand side is infeasible, not redundant
+            Constraint {
+                lhs: expr(&[("x", 2.)]),
+                operator: Ordering::Equal,
+                rhs: 5.,
+            },
+        ],
+    };
+    let report = problem.remove_redundant_constraints();
+    let removed: Vec<_> = report
+        .removed
+        .iter()
+        .map(|r| (r.constraint.index(), r.reason.clone()))
+        .collect();
+    assert_eq!(
+        removed,
+        vec![
+            (0, Redundancy::Dominated(ConsId::from_index(2))),
+            (1, Redundancy::ImpliedByBounds),
+            (4, Redundancy::Duplicate(ConsId::from_index(3))),
+        ]
+    );
+    let rhs: Vec<f64> = problem.constraints.iter().map(|c| c.rhs).collect();
+    assert_eq!(rhs, vec![6., 4., 5.]);
+    assert_eq!(
+        report.original_id(ConsId::from_index(1)),
+        Some(ConsId::from_index(3))
+    );
+    assert_eq!(report.original_id(ConsId::from_index(3)), None);
+}
+
+#[test]
+fn scale_badly_conditioned_problem() {
+    let mut integer = var("n");
+    integer.is_integer = true;
+    let original = Problem {
+        name: "badly_conditioned".to_string(),
+        sense: LpObjective::Minimize,
+        objective: expr(&[("x", 1.), ("y", 1.), ("n", 1.)]),
+        variables: vec![var("x"), var("y"), integer],
+        constraints: vec![
+            Constraint {
+                lhs: expr(&[("x", 1e9), ("y", 1e3)]),
+                operator: Ordering::Less,
+                rhs: 5e9,
+            },
+            Constraint {
+                lhs: expr(&[("x", 1e-6), ("n", 1e-6)]),
+                operator: Ordering::Greater,
+                rhs: 1e-6,
+            },
+        ],
+    };
+    let mut scaled = Problem {
+        name: original.name.clone(),
+        sense: original.sense,
+        objective: original.objective.clone(),
+        variables: original.variables.clone(),
+        constraints: original
+            .constraints
+            .iter()
+            .map(|c| Constraint {
+                lhs: c.lhs.clone(),
+                operator: c.operator,
+                rhs: c.rhs,
+            })
+            .collect(),
+    };
+    let scaling = scaled.scale();
+    let coefficients = scaled
+        .constraints
+        .iter()
+        .flat_map(|c| c.lhs.coefficients.iter().map(|(_, c)| c.abs()));
+    let largest = coefficients.clone().fold(0., f64::max);
+    let smallest = coefficients.fold(f64::INFINITY, f64::min);
+    assert!(largest / smallest < 1e6, "{} / {}", largest, smallest);
+    assert_eq!(scaling.column_factor("n"), 1.);
+    assert_eq!(scaled.variables[2], original.variables[2]);
+
+    // the same point, in both problems
+    let point = |name: &str| match name {
+        "x" => 2.,
+        "y" => 3.,
+        _ => 1.,
+    };
+    let scaled_point = |name: &str| point(name) / scaling.column_factor(name);
+    assert!(
+        (scaled.objective.evaluate_with(scaled_point) - original.objective.evaluate_with(point))
+            .abs()
+            < 1e-9
+    );
+    for (index, (scaled, original)) in scaled
+        .constraints
+        .iter()
+        .zip(&original.constraints)
+        .enumerate()
+    {
+        let row = scaling.row_factor(ConsId::from_index(index));
+        let lhs = original.lhs.evaluate_with(point);
+        assert!((scaled.lhs.evaluate_with(scaled_point) - row * lhs).abs() <= 1e-9 * row * lhs);
+        assert_eq!(scaled.rhs, row * original.rhs);
+    }
+
+    let mut solution = Solution::new(Status::Optimal, HashMap::new());
+    solution
+        .results
+        .insert("x".to_string(), scaled_point("x") as f32);
+    solution.dual_values.insert("c1".to_string(), 1.);
+    scaling.unscale(&mut solution);
+    assert_eq!(solution.results["x"], 2.);
+    assert_eq!(
+        solution.dual_values["c1"] as f64,
+        scaling.row_factor(ConsId::from_index(1))
+    );
+}
+
+#[test]
+fn evaluate_solution() {
+    let problem = Problem {
+        name: "evaluate".to_string(),
+        sense: LpObjective::Minimize,
+        objective: expr(&[("x", 2.), ("y", -1.), ("z", 4.)]),
+        variables: vec![var("x"), var("y"), var("z")],
+        constraints: vec![
+            Constraint {
+                lhs: expr(&[("x", 1.), ("y", 1.)]),
+                operator: Ordering::Less,
+                rhs: 4.,
+            },
+            Constraint {
+                lhs: expr(&[("z", 3.)]),
+                operator: Ordering::Greater,
+                rhs: 1.,
+            },
+        ],
+    };
+    // z is zero, and left out like solvers do
+    let mut solution = Solution::new(Status::Optimal, HashMap::new());
+    solution.results.insert("x".to_string(), 1.5);
+    solution.results.insert("y".to_string(), 0.25);
+    solution.objective = Some(100.);
+    assert_eq!(problem.evaluate(&solution), 2.75);
+    assert_eq!(problem.constraint_activities(&solution), vec![1.75, 0.]);
+    assert_eq!(
+        problem.constraint_activity(ConsId::from_index(1), &solution),
+        Some(0.)
+    );
+    assert_eq!(
+        problem.constraint_activity(ConsId::from_index(2), &solution),
+        None
+    );
+}
+
+#[test]
+fn column_generation() {
+    let mut builder = ProblemBuilder::new("master", LpObjective::Minimize);
+    builder.add_variable(var("x"));
+    builder.set_objective(expr(&[("x", 3.)]));
+    let small = builder.add_constraint(expr(&[("x", 1.)]), Ordering::Greater, 2.);
+    let large = builder.add_constraint(expr(&[]), Ordering::Greater, 5.);
+    let mut master = builder.build();
+
+    let column = |name: &str, objective: f64, coefficients: Vec<(ConsId, f64)>| Column {
+        variable: var(name),
+        objective,
+        coefficients,
+    };
+    master
+        .add_column(column("p", 1., vec![(small, 2.), (large, 1.)]))
+        .unwrap();
+    master
+        .add_column(column("q", 0., vec![(large, 3.)]))
+        .unwrap();
+    assert_eq!(master.objective, expr(&[("x", 3.), ("p", 1.)]));
+    assert_eq!(master.constraints[0].lhs, expr(&[("x", 1.), ("p", 2.)]));
+    assert_eq!(master.constraints[1].lhs, expr(&[("p", 1.), ("q", 3.)]));
+    assert_eq!(master.variables.len(), 3);
+
+    // rejected columns leave the problem unchanged
+    assert!(master.add_column(column("x", 1., vec![])).is_err());
+    let missing = master.add_column(column(
+        "r",
+        1.,
+        vec![(small, 1.), (ConsId::from_index(2), 1.)],
+    ));
+    assert!(missing.unwrap_err().contains("c2"));
+    assert_eq!(master.variables.len(), 3);
+    assert_eq!(master.constraints[0].lhs, expr(&[("x", 1.), ("p", 2.)]));
+}
+
+#[test]
+fn cuts_stop_without_solution() {
+    let mut problem = ProblemBuilder::new("cuts", LpObjective::Minimize).build();
+    let solver = FnSolver(|lp: &str| -> Result<Solution, SolverError> {
+        let status = if lp.contains(">= 1") {
+            Status::Infeasible
+        } else {
+            Status::Optimal
+        };
+        Ok(Solution::new(status, HashMap::new()))
+    });
+    let mut calls = 0;
+    let result = solve_with_cuts(&solver, &mut problem, |_: &Solution| {
+        calls += 1;
+        vec![Constraint {
+            lhs: expr(&[("x", 1.)]),
+            operator: Ordering::Greater,
+            rhs: 1.,
+        }]
+    })
+    .unwrap();
+    assert_eq!(calls, 1);
+    assert_eq!(result.rounds, 2);
+    assert_eq!(result.cuts, vec![ConsId::from_index(0)]);
+    assert_eq!(result.solution.status, Status::Infeasible);
+}
+
+#[test]
+fn infeasible_subset_budget() {
+    let mut builder = ProblemBuilder::new("subset", LpObjective::Minimize);
+    for rhs in 1..=4 {
+        builder.add_constraint(expr(&[("x", 1.)]), Ordering::Greater, rhs as f64);
+    }
+    builder.add_constraint(expr(&[("x", 1.)]), Ordering::Less, 0.);
+    let problem = builder.build();
+    let solver = FnSolver(|lp: &str| -> Result<Solution, SolverError> {
+        let status = if lp.contains(">= 4") && lp.contains("<= 0") {
+            Status::Infeasible
+        } else {
+            Status::Optimal
+        };
+        Ok(Solution::new(status, HashMap::new()))
+    });
+
+    let subset = find_infeasible_subset(&solver, &problem, None).unwrap();
+    let ids: Vec<usize> = subset.constraints.iter().map(|c| c.index()).collect();
+    assert_eq!(ids, vec![3, 4]);
+    assert!(subset.minimal);
+
+    // the first half is removed, then the budget runs out
+    let subset = find_infeasible_subset(&solver, &problem, Some(2)).unwrap();
+    let ids: Vec<usize> = subset.constraints.iter().map(|c| c.index()).collect();
+    assert_eq!(ids, vec![2, 3, 4]);
+    assert!(!subset.minimal);
+    assert_eq!(subset.solves, 2);
+
+    let feasible = ProblemBuilder::new("feasible", LpObjective::Minimize).build();
+    assert!(find_infeasible_subset(&solver, &feasible, None).is_err());
+}
+
+#[test]
+fn infeasible_subset_by_tag() {
+    let mut builder = ProblemBuilder::new("tagged", LpObjective::Minimize);
+    for plant in 0..3 {
+        let demand = builder.add_constraint(expr(&[("x", 1.)]), Ordering::Greater, 5.);
+        builder.tag_constraint(demand, "demand");
+        let capacity = builder.add_constraint(expr(&[("x", 1.)]), Ordering::Less, plant as f64);
+        builder
+            .tag_constraint(capacity, "capacity")
+            .tag_constraint(capacity, format!("plant {}", plant));
+    }
+    builder.add_constraint(expr(&[("x", 1.)]), Ordering::Greater, 0.);
+    let (problem, tags) = builder.build_with_tags();
+    assert_eq!(tags.tags(ConsId::from_index(3)), ["capacity", "plant 1"]);
+
+    let solver = FnSolver(|lp: &str| -> Result<Solution, SolverError> {
+        let status = if lp.contains(">= 5") && lp.contains("<= 1") {
+            Status::Infeasible
+        } else {
+            Status::Optimal
+        };
+        Ok(Solution::new(status, HashMap::new()))
+    });
+    let subset = find_infeasible_subset(&solver, &problem, None).unwrap();
+    let groups = subset.by_tag(&tags);
+    let names: Vec<&str> = groups.tagged.keys().map(String::as_str).collect();
+    assert_eq!(names, vec!["capacity", "demand", "plant 1"]);
+    assert_eq!(groups.tagged["plant 1"], vec![ConsId::from_index(3)]);
+    assert!(groups.untagged.is_empty());
+
+    let all = tags.group((0..problem.constraints.len()).map(ConsId::from_index));
+    assert_eq!(all.tagged["capacity"].len(), 3);
+    assert_eq!(all.untagged, vec![ConsId::from_index(6)]);
+}
+
+#[test]
+fn elastic_problem() {
+    let problem = Problem {
+        name: "elastic".to_string(),
+        sense: LpObjective::Maximize,
+        objective: expr(&[("x", 1.)]),
+        variables: vec![var("x"), var("slackAbove")],
+        constraints: vec![
+            Constraint {
+                lhs: expr(&[("x", 1.)]),
+                operator: Ordering::Equal,
+                rhs: 3.,
+            },
+            Constraint {
+                lhs: expr(&[("x", 1.), ("slackAbove", 1.)]),
+                operator: Ordering::Less,
+                rhs: 1.,
+            },
+        ],
+    };
+    let relaxation = Relaxation::new().with_constraint(ConsId::from_index(0), 5.);
+    let elastic = relaxation.elastic_problem(&problem);
+    assert_eq!(elastic.slacks.len(), 1);
+    let slack = &elastic.slacks[0];
+    assert_eq!(slack.above.as_deref(), Some("slackAbove2"));
+    assert_eq!(slack.below.as_deref(), Some("slackBelow"));
+    assert_eq!(elastic.problem.sense, LpObjective::Minimize);
+    assert_eq!(
+        elastic.problem.objective,
+        expr(&[("slackAbove2", 5.), ("slackBelow", 5.)])
+    );
+    assert_eq!(
+        elastic.problem.constraints[0].lhs,
+        expr(&[("x", 1.), ("slackAbove2", -1.), ("slackBelow", 1.)])
+    );
+    assert_eq!(
+        elastic.problem.constraints[1].lhs,
+        problem.constraints[1].lhs
+    );
+    assert_eq!(elastic.problem.variables.len(), 4);
+
+    let results = vec![("x".to_string(), 1.), ("slackBelow".to_string(), 2.)];
+    let mut solution = Solution::new(Status::Optimal, results.into_iter().collect());
+    let violations = elastic.violations(&solution);
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].violation, -2.);
+    elastic.remove_slacks(&mut solution);
+    assert_eq!(solution.results.len(), 1);
+}
+
+#[test]
+fn presolve_tightens_bounds_and_detects_infeasibility() {
+    let mut integer = var("n");
+    integer.is_integer = true;
+    let mut problem = Problem {
+        name: "presolve".to_string(),
+        sense: LpObjective::Maximize,
+        objective: expr(&[("x", 1.)]),
+        variables: vec![var("x"), var("y"), integer],
+        constraints: vec![
+            // x <= 7 - y
+            Constraint {
+                lhs: expr(&[("x", 1.), ("y", 1.)]),
+                operator: Ordering::Less,
+                rhs: 7.,
+            },
+            // y >= 3, so x <= 4
+            Constraint {
+                lhs: expr(&[("y", 2.)]),
+                operator: Ordering::Greater,
+                rhs: 6.,
+            },
+            // 2n <= 5, so n <= 2
+            Constraint {
+                lhs: expr(&[("n", 2.)]),
+                operator: Ordering::Less,
+                rhs: 5.,
+            },
+        ],
+    };
+    assert_eq!(problem.tighten_bounds(), Ok(4));
+    let bounds: Vec<(f64, f64)> = problem
+        .variables
+        .iter()
+        .map(|v| (v.lower_bound, v.upper_bound))
+        .collect();
+    assert_eq!(bounds, vec![(0., 4.), (3., 7.), (0., 2.)]);
+
+    // x >= 4: the bounds of x meet, and x is fixed
+    problem.constraints.push(Constraint {
+        lhs: expr(&[("x", 1.)]),
+        operator: Ordering::Greater,
+        rhs: 4.,
+    });
+    problem.tighten_bounds().unwrap();
+    assert_eq!(
+        (
+            problem.variables[0].lower_bound,
+            problem.variables[0].upper_bound
+        ),
+        (4., 4.)
+    );
+
+    // x + y >= 12 cannot hold with x <= 4 and y <= 7
+    problem.constraints.push(Constraint {
+        lhs: expr(&[("x", 1.), ("y", 1.)]),
+        operator: Ordering::Greater,
+        rhs: 12.,
+    });
+    assert_eq!(
+        propagate_bounds(&problem),
+        Err(Infeasibility::Constraint(ConsId::from_index(4)))
+    );
+
+    // 2n = 3 has no integer solution
+    problem.constraints.pop();
+    problem.constraints.push(Constraint {
+        lhs: expr(&[("n", 2.)]),
+        operator: Ordering::Equal,
+        rhs: 3.,
+    });
+    assert!(matches!(
+        propagate_bounds(&problem),
+        Err(Infeasibility::CrossingBounds { variable, .. }) if variable == "n"
+    ));
+
+    let solver = PresolvingSolver(FnSolver(|_: &str| -> Result<Solution, SolverError> {
+        panic!("the solver should not run on an infeasible problem")
+    }));
+    assert_eq!(solver.run(&problem).unwrap().status, Status::Infeasible);
+}
+
+#[test]
+fn presolve_tightens_infinite_bounds() {
+    let bounded = |name: &str, lower_bound, upper_bound| Variable {
+        name: name.to_string(),
+        is_integer: false,
+        lower_bound,
+        upper_bound,
+    };
+    let single = |name: &str, operator, rhs| Constraint {
+        lhs: expr(&[(name, 1.)]),
+        operator,
+        rhs,
+    };
+    let mut problem = Problem {
+        name: "free".to_string(),
+        sense: LpObjective::Minimize,
+        objective: expr(&[("x", 1.), ("y", 1.)]),
+        // x is free, and y is only bounded below
+        variables: vec![
+            bounded("x", f64::NEG_INFINITY, f64::INFINITY),
+            bounded("y", 2., f64::INFINITY),
+        ],
+        constraints: vec![
+            single("x", Ordering::Less, 4.),
+            single("x", Ordering::Greater, 1.),
+            // y <= 9 - x
+            Constraint {
+                lhs: expr(&[("x", 1.), ("y", 1.)]),
+                operator: Ordering::Less,
+                rhs: 9.,
+            },
+        ],
+    };
+    let bounds = propagate_bounds(&problem).unwrap();
+    assert_eq!(bounds["x"], (1., 4.));
+    assert_eq!(bounds["y"], (2., 8.));
+
+    // x >= 5 contradicts x <= 4
+    problem.constraints[1] = single("x", Ordering::Greater, 5.);
+    assert!(propagate_bounds(&problem).is_err());
+    let solver = PresolvingSolver(FnSolver(|_: &str| -> Result<Solution, SolverError> {
+        panic!("the solver should not run on an infeasible problem")
+    }));
+    assert_eq!(solver.run(&problem).unwrap().status, Status::Infeasible);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn problem_from_json() {
+    let definition = r#"{
+        "sense": "minimize",
+        "objective": [["x", 1], ["y", -1.5]],
+        "variables": [
+            {"name": "x", "lower_bound": -1, "upper_bound": null},
+            {"name": "y", "upper_bound": 4, "is_integer": true}
+        ],
+        "constraints": [
+            [[["x", 1], ["y", 1]], ">=", 2],
+            [[["x", 1]], "=", 1]
+        ]
+    }"#;
+    let pb = Problem::from_reader(definition.as_bytes()).unwrap();
+    let expected = "\\ lp_solvers_problem
+
+Minimize
+  obj: 1.0 x - 1.5 y
+
+Subject To
+  c0: 1.0 x + 1.0 y >= 2.0
+  c1: 1.0 x = 1.0
+
+Bounds
+  -1.0 <= x
+  y <= 4.0
+
+Generals
+  y
+
+End
+";
+    assert_eq!(pb.display_lp().to_string(), expected);
+
+    let invalid = r#"{"sense": "minimize", "constraints": [[[], "!=", 0]]}"#;
+    assert!(Problem::from_reader(invalid.as_bytes()).is_err());
+}
+
+#[cfg(feature = "yaml")]
+#[test]
+fn problem_from_yaml() {
+    let definition = "
+sense: maximize
+objective: [[x, 1]]
+variables:
+  - name: x
+    upper_bound: 3
+constraints: []
+";
+    let pb = Problem::from_yaml_reader(definition.as_bytes()).unwrap();
+    assert_eq!(pb.sense, LpObjective::Maximize);
+    assert_eq!(pb.variables[0].lower_bound, f64::NEG_INFINITY);
+}
+
+#[test]
+fn problem_from_sparse_rows() {
+    let rows = vec![vec![(0, 1.), (2, -1.)], vec![(1, 3.)]];
+    let pb = MatrixProblem::from_sparse_rows(
+        LpObjective::Minimize,
+        &[1., 0., 2.],
+        rows.clone(),
+        &[Ordering::Greater, Ordering::Equal],
+        &[1., 6.],
+    )
+    .unwrap();
+    assert_eq!(pb.objective, expr(&[("x0", 1.), ("x2", 2.)]));
+    assert_eq!(pb.variables.len(), 3);
+    assert_eq!(pb.variables[2].upper_bound, f64::INFINITY);
+    assert_eq!(pb.constraints[0].lhs, expr(&[("x0", 1.), ("x2", -1.)]));
+    assert_eq!(pb.constraints[1].operator, Ordering::Equal);
+    assert_eq!(pb.constraints[1].rhs, 6.);
+
+    let missing_rhs = MatrixProblem::from_sparse_rows(
+        LpObjective::Minimize,
+        &[1., 0., 2.],
+        rows.clone(),
+        &[Ordering::Greater],
+        &[1.],
+    );
+    assert!(missing_rhs.is_err());
+    let out_of_bounds = MatrixProblem::from_sparse_rows(
+        LpObjective::Minimize,
+        &[1., 0.],
+        rows,
+        &[Ordering::Greater, Ordering::Equal],
+        &[1., 6.],
+    );
+    assert!(out_of_bounds.err().unwrap().contains("Column 2 of row 0"));
+}
+
+#[test]
+fn template_writes_like_the_problem() {
+    let mut pb = MatrixProblem::from_sparse_rows(
+        LpObjective::Maximize,
+        &[1., 2.],
+        vec![vec![(0, 1.), (1, 1.)], vec![(0, 1.), (1, -1.)]],
+        &[Ordering::Less, Ordering::Greater],
+        &[4., 1.],
+    )
+    .unwrap();
+    pb.variables[1].is_integer = true;
+    let mut template = ProblemTemplate::new(&pb);
+    assert_eq!(template.rhs(ConsId::from_index(1)), Some(1.));
+
+    template.set_rhs(ConsId::from_index(0), 7.5).unwrap();
+    template.set_bounds("x1", -1., 3.).unwrap();
+    pb.constraints[0].rhs = 7.5;
+    pb.variables[1].lower_bound = -1.;
+    pb.variables[1].upper_bound = 3.;
+    let mut expected = vec![];
+    pb.write_lp_to(&mut expected).unwrap();
+    let mut written = vec![];
+    template.write_lp_to(&mut written).unwrap();
+    assert_eq!(
+        String::from_utf8(written).unwrap(),
+        String::from_utf8(expected).unwrap()
+    );
+
+    assert!(template.set_rhs(ConsId::from_index(2), 0.).is_err());
+    assert!(template.set_bounds("x2", 0., 1.).is_err());
+
+    // the linear terms are kept, so that the template can be written in the MPS format
+    let mut expected = vec![];
+    pb.write_free_mps_to(&mut expected).unwrap();
+    let mut written = vec![];
+    template.write_free_mps_to(&mut written).unwrap();
+    assert_eq!(
+        String::from_utf8(written).unwrap(),
+        String::from_utf8(expected).unwrap()
+    );
+}
+
+#[test]
+fn rename_problem() {
+    let mut pb = Problem {
+        name: "renamed".to_string(),
+        sense: LpObjective::Minimize,
+        objective: expr(&[("x[1]", 1.), ("x[2]", 1.)]),
+        variables: vec![var("x[1]"), var("x[2]"), var("free")],
+        constraints: vec![Constraint {
+            lhs: expr(&[("x[2]", 1.), ("free", 2.), ("undeclared", 1.)]),
+            operator: Ordering::Less,
+            rhs: 3.,
+        }],
+    };
+    let names = UniqueNameGenerator::default().rename_problem(&mut pb);
+    assert_eq!(names.len(), 4);
+    validate_variable_names(&pb).unwrap();
+    let renamed: Vec<&str> = pb.variables.iter().map(|v| v.name.as_str()).collect();
+    assert_eq!(renamed, ["x", "x2", "vfree"]);
+    assert_eq!(pb.objective, expr(&[("x", 1.), ("x2", 1.)]));
+    assert_eq!(
+        pb.constraints[0].lhs,
+        expr(&[("x2", 1.), ("vfree", 2.), ("undeclared", 1.)])
+    );
+    assert_eq!(names.sanitized("x[2]"), Some("x2"));
+    assert_eq!(names.original("vfree"), Some("free"));
+
+    let results: HashMap<String, f32> = vec![("x2".to_string(), 1.), ("vfree".to_string(), 2.)]
+        .into_iter()
+        .collect();
+    let restored = names.restore(Solution::new(Status::Optimal, results));
+    assert_eq!(restored.results["x[2]"], 1.);
+    assert_eq!(restored.results["free"], 2.);
+    assert_eq!(
+        restored.warnings,
+        vec![
+            Warning::Renamed {
+                original: "free".to_string(),
+                renamed: "vfree".to_string()
+            },
+            Warning::Renamed {
+                original: "x[2]".to_string(),
+                renamed: "x2".to_string()
+            },
+        ]
+    );
+}
+
+#[test]
+fn name_policies() {
+    let mut gen = UniqueNameGenerator::with_policy(NamePolicy::lp());
+    let names: Vec<String> = [
+        "x", "x", "x2", "3y", "stock[1]", "Energy", "E1x", "e", "bounds",
+    ]
+    .iter()
+    .map(|name| gen.add_variable(name).into_owned())
+    .collect();
+    assert_eq!(
+        names,
+        ["x", "x2", "x22", "v3y", "stock1", "Energy", "vE1x", "ve", "vbounds"]
+    );
+    for name in names.iter() {
+        assert!(gen.policy().accepts(name), "{}", name);
+        validate_name(name).unwrap();
+    }
+
+    let policy = NamePolicy {
+        max_len: 4,
+        forbidden_prefixes: vec!["tmp".to_string()],
+        ..NamePolicy::fixed_mps()
+    };
+    let mut gen = UniqueNameGenerator::with_policy(policy.clone());
+    let names: Vec<String> = ["tmp", "long_name", "long_name", "long"]
+        .iter()
+        .map(|name| gen.add_variable(name).into_owned())
+        .collect();
+    assert_eq!(names, ["vtmp", "long", "lon2", "lon3"]);
+    assert!(names.iter().all(|name| policy.accepts(name)));
+    assert!(!policy.accepts("tmpx"));
+    assert!(!policy.accepts("names"));
+}
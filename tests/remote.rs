@@ -0,0 +1,104 @@
+#![cfg(feature = "remote")]
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::thread;
+use std::time::Duration;
+
+use lp_solvers::lp_format::LpObjective;
+use lp_solvers::problem::{Problem, StrExpression, Variable};
+use lp_solvers::solvers::{RemoteSolver, SolverError, SolverTrait, Status};
+
+/// Answer each request with the given responses, in order, and return the requests
+fn serve(responses: Vec<(u16, &'static str)>) -> (String, thread::JoinHandle<Vec<String>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/solve", listener.local_addr().unwrap());
+    let handle = thread::spawn(move || {
+        let mut requests = vec![];
+        for (status, body) in responses {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut request = String::new();
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if let Some(length) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                    content_length = length.trim().parse().unwrap();
+                }
+                request.push_str(&line);
+                if line == "\r\n" {
+                    break;
+                }
+            }
+            let mut content = vec![0; content_length];
+            reader.read_exact(&mut content).unwrap();
+            request.push_str(&String::from_utf8(content).unwrap());
+            requests.push(request);
+            let response = format!(
+                "HTTP/1.1 {} X\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+            reader.get_mut().write_all(response.as_bytes()).unwrap();
+        }
+        requests
+    });
+    (url, handle)
+}
+
+fn problem() -> Problem {
+    Problem {
+        name: "remote".to_string(),
+        sense: LpObjective::Maximize,
+        objective: StrExpression("x".to_string()),
+        variables: vec![Variable {
+            name: "x".to_string(),
+            is_integer: false,
+            lower_bound: 0.,
+            upper_bound: 2.,
+        }],
+        constraints: vec![],
+    }
+}
+
+#[test]
+fn retries_unavailable_service() {
+    let (url, server) = serve(vec![
+        (502, "bad gateway"),
+        (200, r#"{"status": "Optimal", "results": {"x": 2.0}}"#),
+    ]);
+    let solver = RemoteSolver::new(url)
+        .with_bearer_token("secret")
+        .with_retries(1, Duration::from_millis(1));
+    let solution = solver.run(&problem()).unwrap();
+    assert_eq!(solution.status, Status::Optimal);
+    assert_eq!(solution.results["x"], 2.);
+    let requests = server.join().unwrap();
+    assert_eq!(requests.len(), 2);
+    assert!(requests[1].contains("Authorization: Bearer secret"));
+    assert!(requests[1].contains("Maximize"));
+}
+
+#[test]
+fn solver_errors_are_not_retried() {
+    let (url, server) = serve(vec![
+        (500, r#"{"error": "cbc exited with status 1"}"#),
+        (503, r#"{"error": "License error: expired"}"#),
+    ]);
+    let solver = RemoteSolver::new(url).with_retries(1, Duration::from_millis(1));
+    let error = solver.run(&problem()).unwrap_err();
+    assert!(
+        error.to_string().ends_with("cbc exited with status 1"),
+        "{}",
+        error
+    );
+    // licenses may be freed by other users, so license errors are retried when enabled
+    let error = solver
+        .with_retries(0, Duration::ZERO)
+        .run(&problem())
+        .unwrap_err();
+    assert!(matches!(error, SolverError::License(_)), "{}", error);
+    server.join().unwrap();
+}
@@ -0,0 +1,83 @@
+#![cfg(all(unix, feature = "server", feature = "cbc"))]
+
+use std::ffi::OsString;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use axum::body::{to_bytes, Bytes};
+use axum::extract::State;
+use axum::http::{header, HeaderMap, StatusCode};
+use lp_solvers::lp_format::LpProblem;
+use lp_solvers::server::solve;
+use lp_solvers::solvers::{
+    parse_cbc_solution, ParserMode, Solution, SolverProgram, SolverWithSolutionParsing,
+};
+
+/// Copies a cbc solution file instead of solving the problem
+struct CopySolver;
+
+impl SolverProgram for CopySolver {
+    fn command_name(&self) -> &str {
+        "cp"
+    }
+
+    fn arguments(&self, _lp_file: &Path, solution_file: &Path) -> Vec<OsString> {
+        let fixture = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("solution_files")
+            .join("cbc_optimal.sol");
+        vec![fixture.into(), solution_file.into()]
+    }
+}
+
+impl SolverWithSolutionParsing for CopySolver {
+    fn read_specific_solution<'a, P: LpProblem<'a>>(
+        &self,
+        f: &File,
+        _problem: Option<&'a P>,
+    ) -> Result<Solution, String> {
+        parse_cbc_solution(BufReader::new(f), ParserMode::Strict).map_err(String::from)
+    }
+}
+
+/// Send a request to the handler, and return the status and body of the response
+fn request(content_type: &str, body: &str) -> (StatusCode, serde_json::Value) {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    runtime.block_on(async {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::CONTENT_TYPE, content_type.parse().unwrap());
+        let body = Bytes::from(body.to_string());
+        let response = solve(State(Arc::new(CopySolver)), headers, body).await;
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    })
+}
+
+#[test]
+fn solve_json_problem() {
+    let problem = r#"{"sense": "maximize", "objective": [["x", 1]], "variables": [{"name": "x"}]}"#;
+    let (status, body) = request("application/json", problem);
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["status"], "Optimal");
+}
+
+#[test]
+fn solve_lp_problem() {
+    let (status, body) = request("text/plain", "Maximize\n obj: x\nEnd\n");
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["status"], "Optimal");
+}
+
+#[test]
+fn invalid_problems() {
+    let (status, body) = request("application/json", "{}");
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body["error"].as_str().unwrap().contains("Invalid problem"));
+    let (status, _) = request("text/plain", "\n");
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
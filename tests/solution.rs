@@ -0,0 +1,227 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use lp_solvers::lp_format::{ConsId, ConstraintNaming, LpObjective};
+use lp_solvers::problem::{Problem, StrExpression, Variable};
+use lp_solvers::solvers::{
+    FixedMipDualsSolver, FnSolver, Solution, SolverError, SolverTrait, Status, TypedValue,
+    VariableTable, DEFAULT_INTEGRALITY_TOLERANCE,
+};
+
+fn problem() -> Problem {
+    let var = |name: &str, is_integer| Variable {
+        name: name.to_string(),
+        is_integer,
+        lower_bound: 0.,
+        upper_bound: 10.,
+    };
+    Problem {
+        name: "pb".to_string(),
+        sense: LpObjective::Maximize,
+        objective: StrExpression("x + y + z".to_string()),
+        variables: vec![var("x", true), var("y", true), var("z", false)],
+        constraints: vec![],
+    }
+}
+
+fn solution(values: &[(&str, f32)]) -> Solution {
+    let results: HashMap<String, f32> = values
+        .iter()
+        .map(|&(name, value)| (name.to_string(), value))
+        .collect();
+    Solution::new(Status::Optimal, results)
+}
+
+#[test]
+fn snap_integers() {
+    let mut solution = solution(&[("x", 0.999_999_9), ("y", 2.5), ("z", 0.999_999_9)]);
+    let not_integral = solution.snap_integers(&problem(), DEFAULT_INTEGRALITY_TOLERANCE);
+    assert_eq!(not_integral, vec!["y".to_string()]);
+    assert_eq!(solution.results["x"], 1.);
+    assert_eq!(solution.results["y"], 2.5);
+    assert_eq!(solution.results["z"], 0.999_999_9);
+}
+
+#[test]
+fn typed_results() {
+    let mut problem = problem();
+    problem.variables[0].upper_bound = 1.;
+    let solution = solution(&[("x", 0.000_01), ("y", 6.999), ("z", 2.5), ("w", 1.)]);
+    let typed = solution.typed_results(&problem);
+    assert_eq!(
+        typed,
+        vec![
+            ("x", TypedValue::Bool(false)),
+            ("y", TypedValue::Int(7)),
+            ("z", TypedValue::Float(2.5))
+        ]
+    );
+    assert_eq!(typed[1].1.as_f64(), 7.);
+    assert_eq!(typed[0].1.to_string(), "false");
+}
+
+#[test]
+fn fixed_mip_duals() {
+    // the MIP solution, then the solution of the LP, which fixes x and y to 2 and 3
+    let solver = FixedMipDualsSolver(FnSolver(|lp: &str| -> Result<Solution, SolverError> {
+        if lp.contains("General") {
+            Ok(solution(&[("x", 1.999_999), ("y", 3.), ("z", 0.5)]))
+        } else {
+            assert!(
+                lp.contains("2.0 <= x <= 2.0") && lp.contains("3.0 <= y <= 3.0"),
+                "{}",
+                lp
+            );
+            let mut lp_solution = solution(&[("x", 2.), ("y", 3.), ("z", 0.5)]);
+            lp_solution.dual_values.insert("c0".to_string(), 1.5);
+            Ok(lp_solution)
+        }
+    }));
+    let solution = solver.run(&problem()).unwrap();
+    assert_eq!(solution.value("x"), Some(1.999_999_046_325_683_6));
+    assert_eq!(solution.dual(ConsId::from_index(0)), Some(1.5));
+    assert!(solution.warnings.is_empty());
+}
+
+#[test]
+fn duals_with_custom_constraint_naming() {
+    let mut solution = solution(&[]);
+    solution.constraint_naming = ConstraintNaming::new("r", 3);
+    solution.dual_values.insert("r001".to_string(), 2.5);
+    solution.dual_values.insert("c1".to_string(), 7.);
+    assert_eq!(solution.dual(ConsId::from_index(1)), Some(2.5));
+    assert_eq!(solution.dual(ConsId::from_index(0)), None);
+    let duals: Vec<_> = solution.constraint_duals().collect();
+    assert_eq!(duals, vec![(ConsId::from_index(1), 2.5)]);
+}
+
+#[test]
+fn fn_solver_receives_lp_text() {
+    let solver = FnSolver(|lp: &str| {
+        if lp.contains("obj: x + y + z") {
+            Ok(Solution::new(Status::Optimal, HashMap::new()))
+        } else {
+            Err(SolverError::Other(format!("unexpected problem: {}", lp)))
+        }
+    });
+    assert_eq!(solver.run(&problem()).unwrap().status, Status::Optimal);
+}
+
+#[test]
+fn sorted_results() {
+    let solution = solution(&[("z", 3.), ("x", 1.), ("y", 2.)]);
+    assert_eq!(
+        solution.sorted_results(),
+        vec![("x", 1.), ("y", 2.), ("z", 3.)]
+    );
+}
+
+#[test]
+fn shared_solutions_reuse_names() {
+    let pb = problem();
+    let table = VariableTable::from_problem(&pb);
+    assert_eq!(table.len(), 3);
+    let solutions: Vec<_> = (0..3)
+        .map(|i| solution(&[("x", i as f32), ("w", 1.)]).into_shared(&table))
+        .collect();
+    assert_eq!(solutions[2].value("x"), Some(2.));
+    assert_eq!(solutions[2].value("w"), Some(1.));
+    // the same value, in the same type, as the solution it was made from
+    let tenth = solution(&[("x", 0.1)]);
+    let value: Option<f64> = tenth.value("x");
+    assert_eq!(tenth.into_shared(&table).value("x"), value);
+    let x = table.get("x").unwrap();
+    for solution in &solutions {
+        let (name, _) = solution.results.get_key_value("x").unwrap();
+        assert!(Arc::ptr_eq(name, &x));
+    }
+}
+
+#[cfg(feature = "testing")]
+#[test]
+fn compare_solutions_with_tolerance() {
+    use lp_solvers::testing::{compare_solutions, Tolerance};
+
+    let mut expected = solution(&[("x", 1000.), ("y", 0.)]);
+    expected.objective = Some(1000.);
+    expected.dual_values.insert("c0".to_string(), 1.);
+    let mut actual = solution(&[("x", 1000.01), ("z", 1e-7)]);
+    actual.objective = Some(1000.01);
+    actual.dual_values.insert("c0".to_string(), 1.);
+    actual.dual_values.insert("c1".to_string(), 5.);
+
+    let strict = Tolerance::default();
+    assert_eq!(
+        compare_solutions(&actual, &expected, strict),
+        vec![
+            format!("x is {}, not 1000", f64::from(1000.01f32)),
+            "the objective is 1000.01, not 1000".to_string(),
+        ]
+    );
+    assert!(compare_solutions(&actual, &expected, Tolerance::new(1e-6, 1e-4)).is_empty());
+
+    actual.status = Status::SubOptimal;
+    actual.dual_values.remove("c0");
+    let differences = compare_solutions(&actual, &expected, Tolerance::new(0.1, 0.));
+    assert_eq!(
+        differences,
+        vec![
+            "the status is suboptimal, not optimal",
+            "the dual value of c0 is 0, not 1",
+        ]
+    );
+}
+
+#[cfg(feature = "certificate")]
+#[test]
+fn optimality_certificate() {
+    use lp_solvers::certificate::{CertificateViolation, OptimalityCertificate};
+    use lp_solvers::lp_format::Constraint;
+    use lp_solvers::problem::LinearExpression;
+
+    let expr = |terms: &[(&str, f64)]| LinearExpression {
+        coefficients: terms.iter().map(|&(n, c)| (n.to_string(), c)).collect(),
+    };
+    let var = |name: &str, upper_bound| Variable {
+        name: name.to_string(),
+        is_integer: false,
+        lower_bound: 0.,
+        upper_bound,
+    };
+    // maximize 3x + 2y subject to x + y <= 4, with x <= 3
+    let lp = Problem {
+        name: "lp".to_string(),
+        sense: LpObjective::Maximize,
+        objective: expr(&[("x", 3.), ("y", 2.)]),
+        variables: vec![var("x", 3.), var("y", f64::INFINITY)],
+        constraints: vec![Constraint {
+            lhs: expr(&[("x", 1.), ("y", 1.)]),
+            operator: std::cmp::Ordering::Less,
+            rhs: 4.,
+        }],
+    };
+    let mut optimal = solution(&[("x", 3.), ("y", 1.)]);
+    let constraint = ConstraintNaming::default().name(ConsId::from_index(0));
+    optimal.dual_values.insert(constraint, 2.);
+    let certificate = OptimalityCertificate::from_solution(&lp, &optimal).unwrap();
+    assert_eq!(certificate.duals, vec![2.]);
+    assert_eq!(certificate.verify(&lp, 0.), vec![]);
+
+    // without a dual value on the constraint, increasing y would increase the objective
+    let suboptimal = OptimalityCertificate {
+        values: vec![("x".to_string(), 3.), ("y".to_string(), 0.)]
+            .into_iter()
+            .collect(),
+        duals: vec![0.],
+    };
+    assert_eq!(
+        suboptimal.verify(&lp, 1e-9),
+        vec![CertificateViolation::ReducedCost {
+            variable: "y".to_string(),
+            reduced_cost: 2.
+        },]
+    );
+
+    optimal.dual_values.clear();
+    assert!(OptimalityCertificate::from_solution(&lp, &optimal).is_err());
+}
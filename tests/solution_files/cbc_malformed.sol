@@ -0,0 +1,5 @@
+Optimal - objective value -170.00000000
+      0 a                      5                    -10
+      1 b                      #IND                 -20
+
+      2 c                      3                      0
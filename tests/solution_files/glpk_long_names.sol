@@ -0,0 +1,26 @@
+Problem:    a_problem_with_a_name_longer_than_the_usual_offsets
+Rows:       2
+Columns:    2
+Non-zeros:  4
+Status:     OPTIMAL
+Objective:  obj = 10.5 (MAXimum)
+
+   No.   Row name   St   Activity     Lower bound   Upper bound    Marginal
+------ ------------ -- ------------- ------------- ------------- -------------
+     1 c0           NU             4                           4           2.5 
+     2 a_very_long_constraint_name
+                    B              2                           5 
+
+   No. Column name  St   Activity     Lower bound   Upper bound    Marginal
+------ ------------ -- ------------- ------------- ------------- -------------
+     1 x            B              2             0               
+     2 a_very_long_variable_name
+                    NL           1.5             0                       < eps
+
+Karush-Kuhn-Tucker optimality conditions:
+
+KKT.PE: max.abs.err = 0.00e+00 on row 0
+        max.rel.err = 0.00e+00 on row 0
+        High quality
+
+End of output
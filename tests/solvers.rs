@@ -1,9 +1,31 @@
+#![cfg(all(feature = "cbc", feature = "glpk", feature = "gurobi"))]
+
 extern crate lp_solvers;
 
-use std::path::PathBuf;
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 
-use lp_solvers::problem::Problem;
-use lp_solvers::solvers::{CbcSolver, GlpkSolver, Solution, SolverWithSolutionParsing, Status};
+use lp_solvers::lp_format::{ConsId, Constraint, ConstraintNaming, LpObjective, LpProblem};
+use lp_solvers::problem::{LinearExpression, Problem, StrExpression, Variable};
+use lp_solvers::solvers::mock::MockSolver;
+#[cfg(feature = "parallel")]
+use lp_solvers::solvers::solve_scenarios;
+#[cfg(feature = "process")]
+use lp_solvers::solvers::{
+    diagnose_solver, Diagnosis, Discovery, DiscoveryCache, LicenseCheck, SkipReason, SkippedSolver,
+    SolverError, SolverTuning,
+};
+use lp_solvers::solvers::{
+    parse_cbc_solution, parse_cbc_solution_bytes, parse_glpk_plain_solution_bytes,
+    parse_glpk_solution, parse_glpk_solution_bytes, parse_gurobi_solution,
+    parse_gurobi_solution_bytes, read_cbc_solution_iter, read_glpk_solution_iter,
+    write_cbc_solution, write_gurobi_solution, AllSolvers, ApplyOptions, AutoSolver, CbcSolver,
+    DisambiguatingSolver, GlpkSolver, GurobiSolver, MeteredSolver, MissingVariables, NoSolver,
+    ParseError, ParserMode, Scenario, Solution, SolveEvent, SolverCapabilities, SolverOptions,
+    SolverParams, SolverProgram, SolverTrait, SolverWithSolutionParsing, Status, Warning,
+    WithMipStart, WithMissingVariables, WithParserMode, WithStatusRules, RUN_ID_PLACEHOLDER,
+};
 
 fn sol_file(file: &str) -> PathBuf {
     PathBuf::from(env!("CARGO_MANIFEST_DIR"))
@@ -18,6 +40,7 @@ fn cbc_optimal() {
     let Solution {
         status,
         results: mut variables,
+        ..
     } = solver
         .read_solution_from_path::<Problem>(&sol_file("cbc_optimal.sol"), None)
         .unwrap();
@@ -119,3 +142,1431 @@ fn glpk_empty_col_bounds() {
     assert_eq!(1.0, *solution.get("a").unwrap());
     assert_eq!(0.0, *solution.get("b").unwrap());
 }
+
+#[test]
+fn glpk_long_names() {
+    let solver = GlpkSolver::new();
+    let solution = solver
+        .read_solution_from_path::<Problem>(&sol_file("glpk_long_names.sol"), None)
+        .unwrap();
+    assert_eq!(solution.status, Status::Optimal);
+    assert_eq!(solution.objective, Some(10.5));
+    assert_eq!(solution.results.len(), 2);
+    assert_eq!(solution.results["x"], 2.);
+    assert_eq!(solution.results["a_very_long_variable_name"], 1.5);
+    assert_eq!(solution.dual_values.get("c0"), Some(&2.5));
+    assert_eq!(
+        solution.dual_values.get("a_very_long_constraint_name"),
+        None
+    );
+}
+
+#[test]
+fn glpk_row_marginals() {
+    let solver = GlpkSolver::new();
+    let solution = solver
+        .read_solution_from_path::<Problem>(&sol_file("glpk_empty_col_bounds.sol"), None)
+        .unwrap();
+    assert_eq!(solution.objective, Some(1.));
+    assert_eq!(solution.dual_values.get("c1"), None);
+    assert_eq!(solution.dual_values.get("c2"), Some(&-1.));
+    assert_eq!(solution.dual_values.get("c3"), Some(&1.));
+}
+
+#[test]
+fn cbc_malformed_strict() {
+    let result =
+        CbcSolver::new().read_solution_from_path::<Problem>(&sol_file("cbc_malformed.sol"), None);
+    assert!(result.is_err());
+}
+
+#[test]
+fn cbc_malformed_lenient() {
+    let solution = CbcSolver::new()
+        .with_parser_mode(ParserMode::Lenient)
+        .read_solution_from_path::<Problem>(&sol_file("cbc_malformed.sol"), None)
+        .unwrap();
+    assert_eq!(solution.status, Status::Optimal);
+    assert_eq!(solution.results.get("a"), Some(&5.));
+    assert_eq!(solution.results.get("b"), None);
+    assert_eq!(solution.results.get("c"), Some(&3.));
+    assert_eq!(solution.warnings.len(), 2);
+    assert!(matches!(
+        solution.warnings[0],
+        Warning::SkippedLine { line: Some(3), .. }
+    ));
+    assert!(solution.warnings[0].to_string().starts_with("line 3:"));
+}
+
+#[test]
+fn missing_variables_policies() {
+    let mut problem = scenario_base();
+    problem.variables = ["a", "b", "c", "d", "e"]
+        .iter()
+        .map(|name| Variable {
+            name: name.to_string(),
+            is_integer: false,
+            lower_bound: 0.,
+            upper_bound: 10.,
+        })
+        .collect();
+    let cbc_file = sol_file("cbc_optimal.sol");
+    let solution = CbcSolver::new()
+        .read_solution_from_path(&cbc_file, Some(&problem))
+        .unwrap();
+    assert_eq!(solution.results.get("e"), Some(&0.));
+    // cbc always leaves out zeros: by default, they are filled in silently
+    assert!(solution.warnings.is_empty());
+
+    let cbc = CbcSolver::new().with_missing_variables(MissingVariables::ZeroWithWarning);
+    let solution = cbc
+        .read_solution_from_path(&cbc_file, Some(&problem))
+        .unwrap();
+    assert_eq!(solution.results.get("e"), Some(&0.));
+    assert_eq!(
+        solution.warnings,
+        vec![Warning::DefaultedToZero {
+            variables: vec!["d".to_string(), "e".to_string()]
+        }]
+    );
+
+    let cbc = CbcSolver::new().with_missing_variables(MissingVariables::Skip);
+    let solution = cbc
+        .read_solution_from_path(&cbc_file, Some(&problem))
+        .unwrap();
+    assert_eq!(solution.results.get("e"), None);
+    assert!(solution.warnings.is_empty());
+
+    let cbc = CbcSolver::new().with_missing_variables(MissingVariables::Error);
+    let error = cbc
+        .read_solution_from_path(&cbc_file, Some(&problem))
+        .unwrap_err();
+    assert!(error.contains("2 variables"), "{}", error);
+
+    // gurobi lists every variable, but the policy applies to it the same way
+    let mut gurobi_file = tempfile::NamedTempFile::new().unwrap();
+    std::io::Write::write_all(&mut gurobi_file, b"# Objective value = 3\na 1\nb 2\nc 0\n").unwrap();
+    let interesting: HashSet<String> = vec!["a".to_string(), "d".to_string()].into_iter().collect();
+    let solution = GurobiSolver::new()
+        .with_missing_variables(MissingVariables::ZeroWithWarning)
+        .read_solution_filtered(gurobi_file.path(), Some(&problem), &interesting)
+        .unwrap();
+    assert_eq!(solution.results.len(), 2);
+    assert_eq!(
+        solution.warnings,
+        vec![Warning::DefaultedToZero {
+            variables: vec!["d".to_string()]
+        }]
+    );
+    let gurobi = GurobiSolver::new().with_missing_variables(MissingVariables::Error);
+    assert!(gurobi
+        .read_solution_from_path(gurobi_file.path(), Some(&problem))
+        .is_err());
+}
+
+#[test]
+fn parse_from_memory() {
+    let sol =
+        "Optimal - objective value 3\n      0 x                      3                      1\n";
+    let solution = parse_cbc_solution(sol.as_bytes(), ParserMode::Strict).unwrap();
+    assert_eq!(solution.status, Status::Optimal);
+    assert_eq!(solution.results.get("x"), Some(&3.));
+
+    let sol = "# Objective value = 3\nx 3\n";
+    let solution = parse_gurobi_solution(sol.as_bytes(), ParserMode::Strict).unwrap();
+    assert_eq!(solution.results.get("x"), Some(&3.));
+
+    let glpk = std::fs::read(sol_file("glpk_optimal.sol")).unwrap();
+    let solution = parse_glpk_solution(&glpk[..], ParserMode::Strict).unwrap();
+    assert_eq!(solution.status, Status::Optimal);
+}
+
+#[test]
+fn iterate_over_solution_files() {
+    let values: Vec<_> = read_cbc_solution_iter(&sol_file("cbc_optimal.sol"))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(
+        values,
+        vec![
+            ("a".to_string(), 5.),
+            ("b".to_string(), 6.),
+            ("c".to_string(), 0.)
+        ]
+    );
+
+    let values: Vec<_> = read_glpk_solution_iter(&sol_file("glpk_long_names.sol"))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    let expected = GlpkSolver::new()
+        .read_solution_from_path::<Problem>(&sol_file("glpk_long_names.sol"), None)
+        .unwrap()
+        .results;
+    assert_eq!(values.len(), expected.len());
+    for (name, value) in values {
+        assert_eq!(expected[&name], value as f32);
+    }
+
+    let malformed: Vec<_> = read_cbc_solution_iter(&sol_file("cbc_malformed.sol"))
+        .unwrap()
+        .collect();
+    assert_eq!(malformed.iter().filter(|r| r.is_err()).count(), 2);
+}
+
+#[test]
+fn parse_bytes_like_readers() {
+    for file in [
+        "cbc_optimal.sol",
+        "cbc_infeasible.sol",
+        "cbc_infeasible_alternative_format.sol",
+        "cbc_unbounded.sol",
+        "cbc_malformed.sol",
+    ] {
+        let bytes = std::fs::read(sol_file(file)).unwrap();
+        let from_bytes = parse_cbc_solution_bytes(&bytes, ParserMode::Lenient).unwrap();
+        let from_reader = parse_cbc_solution(&bytes[..], ParserMode::Lenient).unwrap();
+        assert_eq!(from_bytes.status, from_reader.status, "{}", file);
+        assert_eq!(from_bytes.results, from_reader.results, "{}", file);
+        assert_eq!(from_bytes.warnings, from_reader.warnings, "{}", file);
+    }
+
+    let sol = b"# Objective value = 3\nx 3\n# comment\ny 1.5\nz\n";
+    let from_bytes = parse_gurobi_solution_bytes(sol, ParserMode::Lenient).unwrap();
+    let from_reader = parse_gurobi_solution(&sol[..], ParserMode::Lenient).unwrap();
+    assert_eq!(from_bytes.results, from_reader.results);
+    assert_eq!(from_bytes.warnings, from_reader.warnings);
+    assert_eq!(
+        from_bytes.warnings,
+        vec![Warning::SkippedLine {
+            line: Some(5),
+            message: "Incorrect solution format".to_string()
+        }]
+    );
+}
+
+#[test]
+fn truncated_solutions_are_errors() {
+    let naming = ConstraintNaming::default();
+    let columns = vec!["x".to_string(), "y".to_string()];
+    let plain = b"s bas 2 2 f f 3\ni 1 b 2 0\ni 2 u 1 1\nj 1 b 1 0\nj 2 l 1 -2\ne o f\n";
+    let mut files: Vec<Vec<u8>> = std::fs::read_dir(sol_file(""))
+        .unwrap()
+        .map(|entry| std::fs::read(entry.unwrap().path()).unwrap())
+        .collect();
+    files.push(plain.to_vec());
+    for file in files {
+        for end in 0..=file.len() {
+            let bytes = &file[..end];
+            for mode in [ParserMode::Strict, ParserMode::Lenient] {
+                let _ = parse_cbc_solution_bytes(bytes, mode);
+                let _ = parse_gurobi_solution_bytes(bytes, mode);
+                let _ = parse_glpk_solution_bytes(bytes, mode);
+                let _ = parse_glpk_plain_solution_bytes(bytes, &columns, &naming, mode);
+            }
+        }
+    }
+
+    let error =
+        parse_cbc_solution_bytes(b"Optimal - objective value 1\n 0 x\n", ParserMode::Strict)
+            .unwrap_err();
+    assert_eq!(error, ParseError::at(2, "Incorrect solution format"));
+    assert_eq!(error.to_string(), "line 2: Incorrect solution format");
+    // the parsers of readers return the same errors
+    let error = parse_cbc_solution(
+        &b"Optimal - objective value 1\n 0 x\n"[..],
+        ParserMode::Strict,
+    )
+    .unwrap_err();
+    assert_eq!(error, ParseError::at(2, "Incorrect solution format"));
+    let error = parse_gurobi_solution(&b"# header\nx\n"[..], ParserMode::Strict).unwrap_err();
+    assert_eq!(error, ParseError::at(2, "Incorrect solution format"));
+    assert_eq!(
+        parse_glpk_solution(&b""[..], ParserMode::Strict).unwrap_err(),
+        parse_glpk_solution_bytes(b"", ParserMode::Strict).unwrap_err()
+    );
+    assert_eq!(
+        parse_cbc_solution_bytes(b"", ParserMode::Strict)
+            .unwrap_err()
+            .line,
+        Some(1)
+    );
+    let error =
+        parse_glpk_plain_solution_bytes(&plain[..30], &columns, &naming, ParserMode::Strict)
+            .unwrap_err();
+    assert_eq!(error.line, Some(3));
+    let error = parse_glpk_plain_solution_bytes(plain, &columns[..1], &naming, ParserMode::Strict)
+        .unwrap_err();
+    assert_eq!(error.line, Some(1));
+    assert!(parse_glpk_solution_bytes(b"Status: OPTIMAL\n", ParserMode::Strict).is_err());
+}
+
+#[test]
+fn cbc_empty_file() {
+    let file = tempfile::NamedTempFile::new().unwrap();
+    let result = CbcSolver::new().read_solution_from_path::<Problem>(file.path(), None);
+    assert!(result.is_err());
+}
+
+#[test]
+fn parse_exponents_and_signs() {
+    let sol = "Optimal - objective value 3.00000000\n\
+      0 x              1e-07                      0\n\
+      1 y             -2.5E+03                    0\n\
+      2 z             +4.                         0\n";
+    for solution in [
+        parse_cbc_solution(sol.as_bytes(), ParserMode::Strict).unwrap(),
+        parse_cbc_solution_bytes(sol.as_bytes(), ParserMode::Strict).unwrap(),
+    ] {
+        assert_eq!(solution.results["x"], 1e-7);
+        assert_eq!(solution.results["y"], -2500.);
+        assert_eq!(solution.results["z"], 4.);
+    }
+}
+
+#[test]
+fn auto_solver_uses_first_available() {
+    let solution = Solution::new(Status::Optimal, Default::default());
+    let solver = AutoSolver::<NoSolver, NoSolver>::new()
+        .with_solver(MockSolver::new().with_solution(solution))
+        .with_solver(MockSolver::new());
+    assert!(solver.is_available());
+    let pb: Problem = Problem {
+        name: "auto".to_string(),
+        sense: LpObjective::Minimize,
+        objective: StrExpression("0".to_string()),
+        variables: vec![],
+        constraints: vec![],
+    };
+    assert_eq!(solver.run(&pb).unwrap().status, Status::Optimal);
+    assert!(!AutoSolver::<NoSolver, NoSolver>::new().is_available());
+}
+
+#[cfg(all(unix, feature = "process"))]
+#[test]
+fn auto_solver_reports_selection() {
+    let missing = || CbcSolver::new().command_name("lp_solvers_missing_solver".to_string());
+    let solution = Solution::new(Status::Optimal, Default::default());
+    let solver = AutoSolver::<NoSolver, NoSolver>::new()
+        .with_solver(MockSolver::new().with_solution(solution))
+        .with_solver(missing());
+    let selection = solver
+        .run(&scenario_base())
+        .unwrap()
+        .solver_selection
+        .unwrap();
+    assert_eq!(selection.solver, "MockSolver");
+    assert_eq!(selection.index, 1);
+    assert_eq!(
+        selection.skipped,
+        vec![SkippedSolver {
+            solver: "lp_solvers_missing_solver".to_string(),
+            reason: SkipReason::Unavailable,
+        }]
+    );
+    assert_eq!(
+        selection.to_string(),
+        "MockSolver (skipped lp_solvers_missing_solver: not available)"
+    );
+
+    let none = AutoSolver::<NoSolver, NoSolver>::new().with_solver(missing());
+    let error = none.run(&scenario_base()).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "No solver available, tried: lp_solvers_missing_solver"
+    );
+}
+
+#[cfg(all(unix, feature = "process"))]
+#[test]
+fn availability_is_checked_with_version_arguments() {
+    assert!(CbcSolver::new()
+        .command_name("true".to_string())
+        .is_available());
+    let missing = CbcSolver::new().command_name("lp_solvers_missing_solver".to_string());
+    assert!(!missing.is_available());
+}
+
+#[cfg(all(unix, feature = "process"))]
+#[test]
+fn discovery_cache() {
+    let dir = tempfile::tempdir().unwrap();
+    let solver = AutoSolver::<NoSolver, NoSolver>::new()
+        .with_solver(CbcSolver::new().command_name("true".to_string()))
+        .with_discovery_cache(DiscoveryCache::new(dir.path().join("cache.tsv")));
+    assert!(solver.is_available());
+    let found = Discovery {
+        available: true,
+        version: None,
+    };
+    assert_eq!(solver.cache.get("true"), Some(found));
+    // the cache is used instead of running the program again
+    let not_found = Discovery {
+        available: false,
+        version: Some("1.0".to_string()),
+    };
+    solver.cache.insert("true", &not_found).unwrap();
+    assert_eq!(solver.cache.get("true"), Some(not_found));
+    assert!(!solver.is_available());
+    solver.cache.clear().unwrap();
+    assert!(solver.is_available());
+}
+
+#[test]
+fn filtered_reading() {
+    let keep: HashSet<String> = vec!["b".to_string()].into_iter().collect();
+    let cbc = CbcSolver::new()
+        .read_solution_filtered::<Problem>(&sol_file("cbc_optimal.sol"), None, &keep)
+        .unwrap();
+    let glpk = GlpkSolver::new()
+        .read_solution_filtered::<Problem>(&sol_file("glpk_optimal.sol"), None, &keep)
+        .unwrap();
+    for (solution, b) in [(cbc, 6.), (glpk, 5.)] {
+        assert_eq!(solution.status, Status::Optimal);
+        assert_eq!(solution.results.len(), 1);
+        assert_eq!(solution.results.get("b"), Some(&b));
+    }
+}
+
+#[cfg(all(unix, feature = "process"))]
+#[test]
+fn diagnose_solvers() {
+    let missing = diagnose_solver(&CbcSolver::new().command_name("lp_solvers_missing".to_string()));
+    assert!(!missing.is_installed());
+    assert!(missing
+        .advice()
+        .unwrap()
+        .starts_with("install lp_solvers_missing"));
+    // `true` runs, but writes no solution
+    let broken = diagnose_solver(&CbcSolver::new().command_name("true".to_string()));
+    assert!(broken.is_installed());
+    assert!(!broken.is_usable());
+    assert_eq!(broken.license_error, None);
+    assert!(broken.solve_error.is_some());
+    let diagnosis = Diagnosis {
+        solvers: vec![missing, broken],
+    };
+    assert_eq!(diagnosis.usable().count(), 0);
+    assert!(diagnosis
+        .to_string()
+        .contains("true: true is installed but failed"));
+}
+
+/// A solver for purely linear problems
+struct LpOnly;
+
+impl SolverCapabilities for LpOnly {
+    fn supports_mip(&self) -> bool {
+        false
+    }
+
+    fn supports_quadratic(&self) -> bool {
+        false
+    }
+
+    fn supports_sos(&self) -> bool {
+        false
+    }
+
+    fn supports_indicators(&self) -> bool {
+        false
+    }
+
+    fn supports_warm_start(&self) -> bool {
+        false
+    }
+}
+
+#[test]
+fn capabilities() {
+    assert!(CbcSolver::new().supports_sos());
+    assert!(!GlpkSolver::new().supports_sos());
+    assert!(DisambiguatingSolver(GlpkSolver::new()).supports_mip());
+    let pb: Problem = Problem {
+        name: "mip".to_string(),
+        sense: LpObjective::Minimize,
+        objective: StrExpression("x".to_string()),
+        variables: vec![Variable {
+            name: "x".to_string(),
+            is_integer: true,
+            lower_bound: 0.,
+            upper_bound: 10.,
+        }],
+        constraints: vec![],
+    };
+    assert!(GlpkSolver::new().check_problem(&pb).is_ok());
+    let error = LpOnly.check_problem(&pb).unwrap_err();
+    assert!(error.contains("integer variables such as x"), "{}", error);
+}
+
+#[test]
+fn metered_solver() {
+    let events = std::sync::Mutex::new(vec![]);
+    let solution = Solution::new(Status::Infeasible, Default::default());
+    let record = |event: &SolveEvent<'_>| {
+        let status = event.status_label();
+        events
+            .lock()
+            .unwrap()
+            .push((event.solver.to_string(), status, event.variables));
+    };
+    let pb: Problem = Problem {
+        name: "metered".to_string(),
+        sense: LpObjective::Minimize,
+        objective: StrExpression("x".to_string()),
+        variables: vec![Variable {
+            name: "x".to_string(),
+            is_integer: false,
+            lower_bound: 0.,
+            upper_bound: 1.,
+        }],
+        constraints: vec![],
+    };
+    MeteredSolver::new("mock", MockSolver::new().with_solution(solution), &record)
+        .run(&pb)
+        .unwrap();
+    assert!(MeteredSolver::new("failing", MockSolver::new(), &record)
+        .run(&pb)
+        .is_err());
+    assert_eq!(
+        *events.lock().unwrap(),
+        vec![
+            ("mock".to_string(), "infeasible", 1),
+            ("failing".to_string(), "error", 1)
+        ]
+    );
+}
+
+fn scenario_base() -> Problem<StrExpression, Variable> {
+    Problem {
+        name: "base".to_string(),
+        sense: LpObjective::Minimize,
+        objective: StrExpression("x".to_string()),
+        variables: vec![Variable {
+            name: "x".to_string(),
+            is_integer: false,
+            lower_bound: 0.,
+            upper_bound: 10.,
+        }],
+        constraints: vec![Constraint {
+            lhs: StrExpression("x".to_string()),
+            operator: Ordering::Greater,
+            rhs: 1.,
+        }],
+    }
+}
+
+#[test]
+fn scenarios_change_the_base_problem() {
+    let base = scenario_base();
+    let scenario = Scenario::new()
+        .with_rhs(ConsId::from_index(0), 4.)
+        .with_bounds("x", 2., 3.);
+    let lp = scenario.apply_to(&base).unwrap().display_lp().to_string();
+    assert!(lp.contains("c0: x >= 4.0"), "{}", lp);
+    assert!(lp.contains("2.0 <= x <= 3.0"), "{}", lp);
+    assert!(base.display_lp().to_string().contains("c0: x >= 1.0"));
+    let unknown = Scenario::new().with_bounds("y", 0., 1.);
+    assert!(unknown.apply_to(&base).is_err());
+    let unknown = Scenario::new().with_rhs(ConsId::from_index(1), 0.);
+    assert!(unknown.apply_to(&base).is_err());
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn solve_scenarios_in_parallel() {
+    let base = scenario_base();
+    let solver =
+        MockSolver::new().with_solution(Solution::new(Status::Optimal, Default::default()));
+    let scenarios = (0..10).map(|i| Scenario::new().with_rhs(ConsId::from_index(0), i as f64));
+    let results = solve_scenarios(&solver, &base, scenarios, 3);
+    assert_eq!(results.len(), 10);
+    assert!(results.iter().all(|r| r.is_ok()));
+    let problems = solver.problems();
+    for i in 0..10 {
+        let line = format!("c0: x >= {}.0\n", i);
+        assert!(problems.iter().any(|p| p.contains(&line)), "{}", line);
+    }
+}
+
+fn written_solution(status: Status) -> Solution {
+    let results = vec![("a".to_string(), 5.), ("b".to_string(), -1.5)];
+    let mut solution = Solution::new(status, results.into_iter().collect());
+    solution.objective = Some(2.5);
+    solution
+}
+
+#[test]
+fn cbc_solution_roundtrip() {
+    for status in [
+        Status::Optimal,
+        Status::SubOptimal,
+        Status::Infeasible,
+        Status::Unbounded,
+        Status::NotSolved,
+    ] {
+        let solution = written_solution(status.clone());
+        let mut written = vec![];
+        write_cbc_solution(&solution, &mut written).unwrap();
+        let parsed = parse_cbc_solution(&written[..], ParserMode::Strict).unwrap();
+        assert_eq!(parsed.status, status);
+        assert_eq!(parsed.results, solution.results);
+    }
+}
+
+#[test]
+fn gurobi_solution_roundtrip() {
+    let solution = written_solution(Status::SubOptimal);
+    let mut written = vec![];
+    write_gurobi_solution(&solution, &mut written).unwrap();
+    assert!(String::from_utf8_lossy(&written).contains("# Objective value = 2.5"));
+    let parsed = parse_gurobi_solution(&written[..], ParserMode::Strict).unwrap();
+    assert_eq!(parsed.results, solution.results);
+    assert_eq!(parsed.objective, Some(2.5));
+}
+
+#[test]
+fn gurobi_solution_header() {
+    let sol = b"# Solution for model production\n# Objective value = 1.25e+01\nx 3\ny 1.5\n";
+    for solution in [
+        parse_gurobi_solution(&sol[..], ParserMode::Strict).unwrap(),
+        parse_gurobi_solution_bytes(sol, ParserMode::Strict).unwrap(),
+    ] {
+        assert_eq!(solution.objective, Some(12.5));
+        let report = solution.report.unwrap();
+        assert_eq!(report.model_name.as_deref(), Some("production"));
+        assert_eq!(solution.results.get("y"), Some(&1.5));
+    }
+
+    // older versions start with the objective value, without the model
+    let sol = b"# Objective value = -3\nx 3\n";
+    let solution = parse_gurobi_solution_bytes(sol, ParserMode::Strict).unwrap();
+    assert_eq!(solution.objective, Some(-3.));
+    assert_eq!(solution.report, None);
+
+    let sol = b"# Solution for model m\n# Objective value = abc\nx 3\n";
+    assert_eq!(
+        parse_gurobi_solution_bytes(sol, ParserMode::Strict).unwrap_err(),
+        ParseError::at(2, "Invalid objective value \"abc\"")
+    );
+    let solution = parse_gurobi_solution(&sol[..], ParserMode::Lenient).unwrap();
+    assert_eq!(solution.objective, None);
+    assert_eq!(solution.warnings.len(), 1);
+
+    // files start with a comment or a value, and anything else is not a gurobi solution
+    let solution = parse_gurobi_solution_bytes(b"x 3\n", ParserMode::Strict).unwrap();
+    assert_eq!(solution.results.get("x"), Some(&3.));
+    for sol in [&b""[..], b"\n", b"Optimal - objective value 3\nx 3\n"] {
+        let expected = ParseError::at(1, "Incorrect solution format");
+        let from_reader = parse_gurobi_solution(sol, ParserMode::Lenient).unwrap_err();
+        let from_bytes = parse_gurobi_solution_bytes(sol, ParserMode::Lenient).unwrap_err();
+        assert_eq!((from_reader, from_bytes), (expected.clone(), expected));
+    }
+}
+
+#[cfg(feature = "cplex")]
+#[test]
+fn cplex_solution_roundtrip() {
+    use lp_solvers::solvers::{
+        parse_cplex_solution, parse_cplex_solution_bytes, parse_cplex_solution_pool_bytes,
+        write_cplex_solution,
+    };
+
+    for status in [
+        Status::Optimal,
+        Status::SubOptimal,
+        Status::Infeasible,
+        Status::Unbounded,
+        Status::InfeasibleOrUnbounded,
+        Status::NotSolved,
+    ] {
+        let mut solution = written_solution(status.clone());
+        solution.results.insert("x_2".to_string(), 1.);
+        solution.dual_values.insert("c1".to_string(), 0.5);
+        let mut written = vec![];
+        write_cplex_solution(&solution, &mut written).unwrap();
+        let parsed = parse_cplex_solution(&written[..]).unwrap();
+        assert_eq!(parsed.status, status);
+        assert_eq!(parsed.objective, Some(2.5));
+        assert_eq!(parsed.results, solution.results);
+        assert_eq!(parsed.dual_values, solution.dual_values);
+        assert_eq!(
+            parse_cplex_solution_bytes(&written).unwrap().results,
+            parsed.results
+        );
+        for end in 0..written.len() {
+            let _ = parse_cplex_solution_bytes(&written[..end]);
+            let _ = parse_cplex_solution_pool_bytes(&written[..end]);
+        }
+    }
+}
+
+#[test]
+fn solver_params() {
+    let cplex = "CPLEX Parameter File Version 22.1.0\nCPXPARAM_MIP_Strategy_Search 1\nCPXPARAM_Emphasis_MIP  2\n";
+    let params = SolverParams::parse(cplex).unwrap();
+    assert_eq!(params.get("CPXPARAM_Emphasis_MIP"), Some("2"));
+    assert_eq!(params.to_string(), cplex.replace("  ", " "));
+    assert!(SolverParams::parse("MIPFocus").is_err());
+
+    let params = SolverParams::new()
+        .with_param("MIPFocus", 1)
+        .with_param("Cuts", 2)
+        .with_param("MIPFocus", 3);
+    let args = GurobiSolver::new()
+        .with_params(&params)
+        .arguments(Path::new("p.lp"), Path::new("p.sol"));
+    assert!(args.contains(&"MIPFocus=3".into()));
+    assert!(args.contains(&"Cuts=2".into()));
+}
+
+/// Write a shell script that runs `script` to `dir/name`
+#[cfg(all(unix, feature = "process"))]
+fn fake_program(dir: &Path, name: &str, script: &str) -> PathBuf {
+    use std::os::unix::fs::PermissionsExt;
+
+    let path = dir.join(name);
+    std::fs::write(&path, format!("#!/bin/sh\n{}\n", script)).unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    path
+}
+
+#[cfg(all(unix, feature = "process"))]
+#[test]
+fn gurobi_tuning() {
+    // a fake grbtune, next to the gurobi executable, that recommends a parameter
+    let dir = tempfile::tempdir().unwrap();
+    fake_program(
+        dir.path(),
+        "grbtune",
+        "printf '# Parameter settings\\nMIPFocus 1\\n' > tune0.prm",
+    );
+    let solver = GurobiSolver::new()
+        .command_name(dir.path().join("gurobi_cl").to_string_lossy().into_owned());
+    let problem: Problem = Problem {
+        name: "tuned".to_string(),
+        sense: LpObjective::Minimize,
+        objective: StrExpression("x".to_string()),
+        variables: vec![Variable {
+            name: "x".to_string(),
+            is_integer: true,
+            lower_bound: 0.,
+            upper_bound: 1.,
+        }],
+        constraints: vec![],
+    };
+    let params = solver.tune(&problem, None).unwrap();
+    assert_eq!(params, SolverParams::new().with_param("MIPFocus", 1));
+}
+
+#[cfg(all(unix, feature = "process"))]
+#[test]
+fn gurobi_license_check() {
+    let dir = tempfile::tempdir().unwrap();
+    let licensed = fake_program(
+        dir.path(),
+        "licensed",
+        "echo 'Using license file /opt/gurobi/gurobi.lic'\n\
+         echo 'Restricted license - for non-production use only - expires 2026-11-23'",
+    );
+    let license = GurobiSolver::new()
+        .command_name(licensed.to_string_lossy().into_owned())
+        .check_license()
+        .unwrap();
+    assert_eq!(license.expires.as_deref(), Some("2026-11-23"));
+    assert!(license.size_limited);
+    assert!(license
+        .to_string()
+        .starts_with("Restricted license - for non-production use only"));
+
+    let unlicensed = fake_program(
+        dir.path(),
+        "unlicensed",
+        "echo 'ERROR 10009: No Gurobi license found'; exit 1",
+    );
+    let error = GurobiSolver::new()
+        .command_name(unlicensed.to_string_lossy().into_owned())
+        .check_license()
+        .unwrap_err();
+    assert!(matches!(error, SolverError::License(_)));
+}
+
+#[cfg(all(unix, feature = "process"))]
+#[test]
+fn gurobi_result_files() {
+    // a fake gurobi_cl that finds the problem infeasible, and writes the result files
+    let dir = tempfile::tempdir().unwrap();
+    let ilp = dir.path().join("conflict.ilp");
+    let json = dir.path().join("details.json");
+    let gurobi = fake_program(
+        dir.path(),
+        "gurobi_cl",
+        &format!(
+            "printf 'Subject To\\n c1: x >= 12\\nBounds\\n x <= 10\\nEnd\\n' > {:?}\n\
+             printf '{{\"SolutionInfo\": {{\"Status\": 3, \"Runtime\": 0.5}}}}' > {:?}\n\
+             echo 'Model is infeasible'",
+            ilp, json
+        ),
+    );
+    let solver = GurobiSolver::new()
+        .command_name(gurobi.to_string_lossy().into_owned())
+        .with_result_file(&ilp)
+        .with_result_file(&json);
+    let problem = scenario_base();
+    let solution = solver.run(&problem).unwrap();
+    assert_eq!(solution.status, Status::Infeasible);
+    let report = solution.report.unwrap();
+    assert_eq!(report.output_files, vec![ilp, json]);
+    assert_eq!(report.infeasible_constraints, vec!["c1"]);
+    #[cfg(feature = "serde")]
+    assert_eq!(report.attributes.get("Runtime"), Some(&0.5));
+    #[cfg(not(feature = "serde"))]
+    assert_eq!(solution.warnings.len(), 1);
+}
+
+#[cfg(all(unix, feature = "process"))]
+#[test]
+fn resolving_solver_starts_from_the_previous_solution() {
+    use lp_solvers::solvers::ResolvingSolver;
+
+    // a fake cbc that logs its arguments, keeps its starting solution,
+    // and copies a solution to the last of its arguments
+    let dir = tempfile::tempdir().unwrap();
+    let log = dir.path().join("args.log");
+    let start = dir.path().join("start.sol");
+    let cbc = fake_program(
+        dir.path(),
+        "cbc",
+        &format!(
+            "echo \"$@\" >> {log:?}\n\
+             while [ $# -gt 1 ]; do\n\
+               if [ \"$1\" = mips ]; then cp \"$2\" {start:?}; fi\n\
+               shift\n\
+             done\n\
+             cp {fixture:?} \"$1\"",
+            log = log,
+            start = start,
+            fixture = sol_file("cbc_optimal.sol"),
+        ),
+    );
+    let solver = ResolvingSolver::new(CbcSolver::new().command_name(cbc.to_string_lossy().into()));
+    let mut problem: Problem = Problem {
+        name: "rolling".to_string(),
+        sense: LpObjective::Maximize,
+        objective: StrExpression("a".to_string()),
+        variables: vec![Variable {
+            name: "a".to_string(),
+            is_integer: true,
+            lower_bound: 0.,
+            upper_bound: 10.,
+        }],
+        constraints: vec![],
+    };
+    solver.run(&problem).unwrap();
+    problem.objective = StrExpression("a + b".to_string());
+    problem.variables.push(Variable {
+        name: "b".to_string(),
+        is_integer: false,
+        lower_bound: 0.,
+        upper_bound: 10.,
+    });
+    let solution = solver.run(&problem).unwrap();
+    assert_eq!(
+        solver.previous("rolling").unwrap().results,
+        solution.results
+    );
+
+    let log = std::fs::read_to_string(log).unwrap();
+    let runs: Vec<&str> = log.lines().collect();
+    assert_eq!(runs.len(), 2);
+    assert!(!runs[0].contains("mips"));
+    assert!(runs[1].contains("mips"));
+    // the start has the variables of the new problem only
+    let start = parse_cbc_solution(
+        std::io::BufReader::new(std::fs::File::open(start).unwrap()),
+        ParserMode::Strict,
+    )
+    .unwrap();
+    assert_eq!(start.results.len(), 2);
+    assert_eq!(start.results["a"], 5.);
+    assert!(!start.results.contains_key("c"));
+}
+
+#[cfg(all(unix, feature = "process"))]
+#[test]
+fn solution_files_are_namespaced_by_run() {
+    // a fake cbc that copies a solution to the last of its arguments
+    let dir = tempfile::tempdir().unwrap();
+    let cbc = fake_program(
+        dir.path(),
+        "cbc",
+        &format!(
+            "while [ $# -gt 1 ]; do shift; done\ncp {:?} \"$1\"",
+            sol_file("cbc_optimal.sol")
+        ),
+    );
+    let solution_file = dir.path().join(format!("{}.sol", RUN_ID_PLACEHOLDER));
+    let solver = CbcSolver::new()
+        .command_name(cbc.to_string_lossy().into())
+        .with_temp_solution_file(solution_file.to_string_lossy().into());
+    let problem = scenario_base();
+    let first = solver.run(&problem).unwrap().report.unwrap();
+    let second = solver.run(&problem).unwrap().report.unwrap();
+    assert_ne!(first.run_id, second.run_id);
+    for report in [first, second] {
+        let written = dir.path().join(format!("{}.sol", report.run_id));
+        assert!(written.exists());
+        assert_eq!(
+            report.command_line.last(),
+            Some(&written.to_string_lossy().into_owned())
+        );
+    }
+}
+
+#[cfg(all(unix, feature = "process"))]
+#[test]
+fn gurobi_restarts_from_its_solution_file() {
+    // a fake gurobi that writes a solution to its first ResultFile,
+    // after copying the starting solution it is given, if any
+    let dir = tempfile::tempdir().unwrap();
+    let gurobi = fake_program(
+        dir.path(),
+        "gurobi_cl",
+        &format!(
+            "for arg in \"$@\"; do case $arg in\n\
+             InputFile=*) cp \"${{arg#InputFile=}}\" {:?} ;;\n\
+             ResultFile=*) [ -z \"$out\" ] && out=${{arg#ResultFile=}} ;;\n\
+             esac; done\n\
+             printf '# Objective value = 4\\nx 4\\n' > \"$out\"\n\
+             echo 'Optimal solution found'",
+            dir.path().join("start.sol")
+        ),
+    );
+    let warm = dir.path().join("warm.sol");
+    let solver = GurobiSolver::new()
+        .command_name(gurobi.to_string_lossy().into_owned())
+        .with_temp_solution_file(warm.to_string_lossy().into_owned());
+    let problem = scenario_base();
+    let first = solver.run(&problem).unwrap();
+    assert_eq!(first.results["x"], 4.);
+    assert!(warm.exists());
+
+    let second = solver.with_mip_start(warm.clone()).run(&problem).unwrap();
+    assert_eq!(second.status, Status::Optimal);
+    let start = std::fs::read_to_string(dir.path().join("start.sol")).unwrap();
+    assert!(start.contains("x 4"), "{}", start);
+}
+
+#[test]
+fn stdout_status_rules() {
+    let solver = GurobiSolver::new();
+    let site_build = b"Model solved to optimality by the site build";
+    assert_eq!(solver.parse_stdout_status(site_build), None);
+    let rules = solver
+        .status_rules()
+        .clone()
+        .with_first_rule("solved to optimality", Status::Optimal);
+    let solver = solver.with_status_rules(rules);
+    assert_eq!(
+        solver.parse_stdout_status(site_build),
+        Some(Status::Optimal)
+    );
+    // the default rules still apply
+    assert_eq!(
+        solver.parse_stdout_status(b"Model is infeasible"),
+        Some(Status::Infeasible)
+    );
+    assert_eq!(
+        CbcSolver::new().parse_stdout_status(b"Model is infeasible"),
+        None
+    );
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn stdout_status_regex_rules() {
+    use lp_solvers::solvers::StdoutStatusRules;
+
+    let rules = StdoutStatusRules::new()
+        .with_regex_rule(
+            regex::bytes::Regex::new(r"(?m)^Status: [0-9]+ \(limit\)$").unwrap(),
+            Status::SubOptimal,
+        )
+        .with_first_regex_rule(
+            regex::bytes::Regex::new("(?i)no feasible").unwrap(),
+            Status::Infeasible,
+        );
+    assert_eq!(
+        rules.status(b"Status: 12 (limit)"),
+        Some(Status::SubOptimal)
+    );
+    assert_eq!(
+        rules.status(b"NO FEASIBLE point\nStatus: 12 (limit)"),
+        Some(Status::Infeasible)
+    );
+    assert_eq!(rules.status(b"Status: limit"), None);
+}
+
+fn command_line<S: SolverProgram>(solver: &S) -> String {
+    let args = solver.arguments(Path::new("p.lp"), Path::new("p.sol"));
+    let args: Vec<_> = args.iter().map(|arg| arg.to_string_lossy()).collect();
+    args.join(" ")
+}
+
+#[test]
+fn solver_options() {
+    let options = SolverOptions::new()
+        .with_time_limit(60)
+        .with_threads(4)
+        .with_mip_gap(0.01)
+        .with_seed(7);
+    let gurobi = GurobiSolver::new().apply_options(&options).unwrap();
+    assert!(command_line(&gurobi).contains("MIPGap=0.01 TimeLimit=60 Threads=4 Seed=7"));
+    let cbc = CbcSolver::new().apply_options(&options).unwrap();
+    assert!(command_line(&cbc).contains("ratiogap 0.01 seconds 60 threads 4 randomCbcSeed 7"));
+    // glpk has no threads: the option is ignored
+    let glpk = GlpkSolver::new().apply_options(&options).unwrap();
+    assert!(command_line(&glpk).contains("--tmlim 60 --mipgap 0.01"));
+    // all the solvers are configured, whichever one is available
+    let solvers = AllSolvers::new().apply_options(&options).unwrap();
+
+    let invalid = SolverOptions::new().with_mip_gap(-1.);
+    assert!(CbcSolver::new().apply_options(&invalid).is_err());
+    assert!(solvers.apply_options(&invalid).is_err());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn solver_options_from_config() {
+    let options: SolverOptions = serde_json::from_str(r#"{"time_limit": 30}"#).unwrap();
+    assert_eq!(options, SolverOptions::new().with_time_limit(30));
+}
+
+#[cfg(all(unix, feature = "process", feature = "serde"))]
+#[test]
+fn solver_from_config() {
+    use lp_solvers::solvers::{from_config, SolverConfig};
+
+    // a fake cbc that gives x the value of an environment variable of the configuration,
+    // and fails without it, like a solver whose license is only given in the configuration
+    let dir = tempfile::tempdir().unwrap();
+    let cbc = fake_program(
+        dir.path(),
+        "cbc",
+        "[ -n \"$X_VALUE\" ] || exit 1\n\
+         for last; do :; done\n\
+         printf 'Optimal - objective value 3\\n      0 x %s 0\\n' \"$X_VALUE\" > \"$last\"",
+    );
+    let temp_dir = dir.path().join("files");
+    std::fs::create_dir(&temp_dir).unwrap();
+    let config = format!(
+        r#"{{"solver": "cbc", "command": {:?}, "time_limit": 10,
+            "temp_dir": {:?}, "env": {{"X_VALUE": "3"}}}}"#,
+        cbc, temp_dir
+    );
+    let solver = from_config(&config).unwrap();
+    assert_eq!(solver.name(), "cbc");
+    assert!(solver.is_available());
+    let solution = solver.run(&scenario_base()).unwrap();
+    assert_eq!(solution.results["x"], 3.);
+    // the problem and solution files were written in the configured directory
+    assert!(std::fs::read_dir(&temp_dir).unwrap().next().is_some());
+    let config = format!(r#"{{"solver": "cbc", "command": {:?}}}"#, cbc);
+    assert!(!from_config(&config).unwrap().is_available());
+
+    assert!(from_config(r#"{"solver": "scip"}"#).is_err());
+    let config = SolverConfig::parse(r#"{"threads": 2, "params": {"MIPFocus": 1}}"#).unwrap();
+    assert_eq!(config.solver, None);
+    assert_eq!(config.options.threads, Some(2));
+}
+
+#[cfg(all(feature = "process", feature = "toml"))]
+#[test]
+fn solver_config_in_toml() {
+    use lp_solvers::solvers::SolverConfig;
+
+    let config = SolverConfig::parse(
+        "solver = \"gurobi\"\ntime_limit = 60\nmip_gap = 0.05\n\n[params]\nMIPFocus = 1\n",
+    )
+    .unwrap();
+    assert_eq!(config.solver.as_deref(), Some("gurobi"));
+    assert_eq!(
+        config.options,
+        SolverOptions::new().with_time_limit(60).with_mip_gap(0.05)
+    );
+    assert_eq!(config.build().unwrap().name(), "gurobi");
+}
+
+#[cfg(feature = "process")]
+#[test]
+fn solver_registry() {
+    use lp_solvers::solvers::{registry, BoxedSolver};
+
+    let mut solvers = registry();
+    assert!(solvers.contains("cbc") && solvers.contains("auto"));
+    let solution = Solution::new(Status::Optimal, [("x".to_string(), 1.)].into());
+    solvers.register("mock", move |_options: &SolverOptions| {
+        Ok(BoxedSolver::new(
+            MockSolver::new().with_solution(solution.clone()),
+        ))
+    });
+    let solver = solvers.build("mock", &SolverOptions::new()).unwrap();
+    assert_eq!(solver.run(&scenario_base()).unwrap().results["x"], 1.);
+
+    let error = solvers.build("scip", &SolverOptions::new()).unwrap_err();
+    assert!(error.starts_with("Unknown solver \"scip\", expected one of: auto, cbc"));
+    assert!(error.ends_with("gurobi, mock"), "{}", error);
+    let invalid = SolverOptions::new().with_mip_gap(-1.);
+    assert!(solvers.build("glpk", &invalid).is_err());
+}
+
+#[test]
+fn solution_accessors() {
+    let results = vec![
+        ("x".to_string(), 2.5),
+        ("n".to_string(), 3.000_001),
+        ("off".to_string(), 0.000_001),
+    ];
+    let solution = Solution::new(Status::SubOptimal, results.into_iter().collect());
+    assert_eq!(solution.value("x"), Some(2.5));
+    assert_eq!(solution.int_value("x"), None);
+    assert_eq!(solution.int_value("n"), Some(3));
+    assert!(!solution.is_one("off"));
+    assert!(!solution.is_one("missing"));
+    assert_eq!(solution.status.to_string(), "suboptimal");
+    assert_eq!(
+        Status::InfeasibleOrUnbounded.to_string(),
+        "infeasible or unbounded"
+    );
+}
+
+#[test]
+fn ordered_solution_results() {
+    use lp_solvers::solvers::OrderedSolver;
+
+    let mut problem = scenario_base();
+    for name in ["z", "a"].iter() {
+        let mut variable = problem.variables[0].clone();
+        variable.name = name.to_string();
+        problem.variables.push(variable);
+    }
+    let results = vec![
+        ("a".to_string(), 1.),
+        ("x".to_string(), 2.),
+        ("z".to_string(), 3.),
+        ("extra".to_string(), 4.),
+    ];
+    let solver = OrderedSolver(MockSolver::new().with_solution(Solution::new(
+        Status::Optimal,
+        results.into_iter().collect(),
+    )));
+    let solution = solver.run(&problem).unwrap();
+    assert_eq!(solution.variable_order, vec!["x", "z", "a"]);
+    assert_eq!(
+        solution.ordered_results(),
+        vec![("x", 2.), ("z", 3.), ("a", 1.), ("extra", 4.)]
+    );
+    #[cfg(feature = "serde")]
+    assert!(serde_json::to_string(&solution)
+        .unwrap()
+        .contains(r#""results":{"a":1.0,"extra":4.0,"x":2.0,"z":3.0}"#));
+}
+
+#[test]
+fn shared_solvers() {
+    use std::sync::Arc;
+
+    fn solve<S: SolverTrait>(solver: S) -> Result<Solution, lp_solvers::solvers::SolverError> {
+        solver.run(&scenario_base())
+    }
+
+    let solution = Solution::new(Status::Optimal, Default::default());
+    let solver = Arc::new(MockSolver::new().with_solution(solution));
+    let threads: Vec<_> = (0..2)
+        .map(|_| {
+            let solver = Arc::clone(&solver);
+            std::thread::spawn(move || solve(solver).unwrap().status)
+        })
+        .collect();
+    for thread in threads {
+        assert_eq!(thread.join().unwrap(), Status::Optimal);
+    }
+    assert_eq!(solver.problems().len(), 2);
+
+    // external solvers can also be borrowed or boxed
+    #[cfg(feature = "process")]
+    {
+        fn is_solver<S: SolverTrait>(_solver: &S) {}
+        let cbc = CbcSolver::new();
+        is_solver(&&cbc);
+        is_solver(&Box::new(cbc.clone()));
+        is_solver(&Arc::new(AllSolvers::new()));
+    }
+}
+
+#[cfg(all(unix, feature = "process"))]
+#[test]
+fn glpk_output_files_in_report() {
+    use lp_solvers::solvers::GlpkOutput;
+
+    // a fake glpsol that writes its log, but not the translated problem
+    let dir = tempfile::tempdir().unwrap();
+    let glpsol = fake_program(
+        dir.path(),
+        "glpsol",
+        "for last; do :; done\n\
+         echo 'INTEGER OPTIMAL SOLUTION FOUND' > \"$last\"\n\
+         cp \"$(dirname \"$0\")/solution\" \"$4\"",
+    );
+    std::fs::copy(sol_file("glpk_optimal.sol"), dir.path().join("solution")).unwrap();
+    let log = dir.path().join("glpk.log");
+    let solver = GlpkSolver::new()
+        .command_name(glpsol.to_string_lossy().into_owned())
+        .with_output_file(GlpkOutput::Lp, dir.path().join("translated.lp"))
+        .with_output_file(GlpkOutput::Log, &log);
+    let solution = solver.run(&scenario_base()).unwrap();
+    assert_eq!(solution.report.unwrap().output_files, vec![log]);
+}
+
+#[cfg(all(unix, feature = "process"))]
+#[test]
+fn glpk_plain_solution() {
+    use lp_solvers::solvers::{registry, BoxedSolver, GlpkSolutionFormat};
+
+    // a fake glpsol that writes a plain solution, where b is the first column
+    let dir = tempfile::tempdir().unwrap();
+    let glpsol = fake_program(
+        dir.path(),
+        "glpsol",
+        "[ \"$3\" = --write ] || exit 1\n\
+         printf 'c Status: OPTIMAL\\ns bas 1 2 f f 2.5\\ni 1 l 1 1.5\\nj 1 b 0.5 0\\nj 2 b 1 0\\ne o f\\n' > \"$4\"",
+    );
+    let linear = |terms: &[(&str, f64)]| LinearExpression {
+        coefficients: terms.iter().map(|&(n, c)| (n.to_string(), c)).collect(),
+    };
+    let problem = Problem {
+        name: "plain".to_string(),
+        sense: LpObjective::Minimize,
+        objective: linear(&[("b", 1.), ("a", 2.)]),
+        variables: ["a", "b"]
+            .iter()
+            .map(|name| Variable {
+                name: name.to_string(),
+                is_integer: false,
+                lower_bound: 0.,
+                upper_bound: 10.,
+            })
+            .collect(),
+        constraints: vec![Constraint {
+            lhs: linear(&[("a", 1.), ("b", 1.)]),
+            operator: Ordering::Greater,
+            rhs: 1.,
+        }],
+    };
+    let solver = GlpkSolver::new()
+        .command_name(glpsol.to_string_lossy().into_owned())
+        .with_solution_format(GlpkSolutionFormat::Plain);
+    let solution = solver.run(&problem).unwrap();
+    assert_eq!(solution.status, Status::Optimal);
+    assert_eq!(solution.objective, Some(2.5));
+    assert_eq!(solution.results["a"], 1.);
+    assert_eq!(solution.results["b"], 0.5);
+    assert_eq!(solution.dual(ConsId::from_index(0)), Some(1.5));
+
+    // the solvers of the registry keep the linear terms that number the columns
+    let mut solvers = registry();
+    let registered = solver.clone();
+    solvers.register("glpk-plain", move |_options: &SolverOptions| {
+        Ok(BoxedSolver::new(registered.clone()))
+    });
+    let boxed = solvers.build("glpk-plain", &SolverOptions::new()).unwrap();
+    let solution = boxed.run(&problem).unwrap();
+    assert_eq!(solution.results["a"], 1.);
+    assert_eq!(solution.results["b"], 0.5);
+
+    let solution_file = dir.path().join("plain.sol");
+    std::fs::write(&solution_file, "s mip 1 3 o 1\ne o f\n").unwrap();
+    let error = solver
+        .read_solution_from_path(&solution_file, Some(&problem))
+        .unwrap_err();
+    assert!(error.contains("3 columns"), "{}", error);
+    assert!(solver
+        .read_solution_from_path::<Problem>(&solution_file, None)
+        .is_err());
+    // the columns of a problem written from strings cannot be numbered
+    let error = solver
+        .read_solution_from_path(&solution_file, Some(&scenario_base()))
+        .unwrap_err();
+    assert!(
+        error.contains("order of the columns is unknown"),
+        "{}",
+        error
+    );
+}
+
+#[cfg(all(unix, feature = "process"))]
+#[test]
+fn gurobi_status_from_log_file() {
+    // a fake gurobi_cl that only writes to its log file, like with LogToConsole=0
+    let dir = tempfile::tempdir().unwrap();
+    let gurobi = fake_program(
+        dir.path(),
+        "gurobi_cl",
+        "for arg; do case \"$arg\" in\n\
+           ResultFile=*) result=\"${arg#ResultFile=}\" ;;\n\
+           LogFile=*) log=\"${arg#LogFile=}\" ;;\n\
+         esac; done\n\
+         printf 'Time limit reached\\nBest objective 1.2e+01, best bound 1.3e+01, gap 8.0000%%\\n' >> \"$log\"\n\
+         printf '# Objective value = 12\\nx 1\\n' > \"$result\"",
+    );
+    // the log of a previous solve, that must not be read again
+    let log = dir.path().join("gurobi.log");
+    std::fs::write(&log, "Optimal solution found (tolerance 1.00e-04)\n").unwrap();
+    let solver = GurobiSolver::new()
+        .command_name(gurobi.to_string_lossy().into_owned())
+        .with_log_file(&log);
+    let solution = solver.run(&scenario_base()).unwrap();
+    assert_eq!(solution.status, Status::SubOptimal);
+    assert_eq!(solution.mip_gap, Some(0.08));
+    assert_eq!(solution.results["x"], 1.);
+    assert_eq!(solution.report.unwrap().output_files, vec![log]);
+}
+
+#[cfg(all(unix, feature = "process", feature = "cplex"))]
+#[test]
+fn cplex_conflict_file_in_report() {
+    use lp_solvers::solvers::Cplex;
+
+    // a fake cplex that finds the problem infeasible, and writes its log and the conflict
+    let dir = tempfile::tempdir().unwrap();
+    let cplex = fake_program(
+        dir.path(),
+        "cplex",
+        "for arg; do case \"$arg\" in\n\
+           'set logfile '*) log=\"${arg#set logfile \\\"}\"; log=\"${log%\\\"}\" ;;\n\
+           'WRITE '*.clp*) clp=\"${arg#WRITE \\\"}\"; clp=\"${clp%\\\"}\" ;;\n\
+         esac; done\n\
+         echo 'MIP - Integer infeasible.' >> \"$log\"\n\
+         echo 'Minimize' > \"$clp\"\n\
+         echo 'No solution exists.'",
+    );
+    let log = dir.path().join("cplex.log");
+    let conflict = dir.path().join("conflict.clp");
+    let solver = Cplex::with_command(cplex.to_string_lossy().into_owned())
+        .with_log_file(&log)
+        .with_conflict_file(&conflict);
+    let solution = solver.run(&scenario_base()).unwrap();
+    assert_eq!(solution.status, Status::Infeasible);
+    assert_eq!(solution.report.unwrap().output_files, vec![log, conflict]);
+}
+
+#[test]
+fn solvers_can_be_shared_between_threads() {
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    assert_send_sync::<CbcSolver>();
+    assert_send_sync::<GlpkSolver>();
+    assert_send_sync::<GurobiSolver>();
+    #[cfg(feature = "cplex")]
+    assert_send_sync::<lp_solvers::solvers::Cplex>();
+    assert_send_sync::<AllSolvers>();
+    assert_send_sync::<AutoSolver<CbcSolver, NoSolver>>();
+    assert_send_sync::<MockSolver>();
+    assert_send_sync::<DisambiguatingSolver<CbcSolver>>();
+    assert_send_sync::<lp_solvers::solvers::CachedAutoSolver<AllSolvers>>();
+    assert_send_sync::<lp_solvers::solvers::DiscoveryCache>();
+    #[cfg(feature = "process")]
+    {
+        use lp_solvers::solvers::{
+            BoxedSolver, HookedSolver, PooledSolver, ResolvingSolver, SolverRegistry,
+        };
+        assert_send_sync::<BoxedSolver>();
+        assert_send_sync::<SolverRegistry>();
+        assert_send_sync::<HookedSolver<CbcSolver, ()>>();
+        assert_send_sync::<PooledSolver<CbcSolver>>();
+        assert_send_sync::<ResolvingSolver<CbcSolver>>();
+    }
+    #[cfg(all(feature = "process", feature = "serde"))]
+    assert_send_sync::<lp_solvers::solvers::ConfiguredSolver>();
+    #[cfg(feature = "remote")]
+    assert_send_sync::<lp_solvers::solvers::RemoteSolver>();
+}
+
+#[cfg(all(unix, feature = "process"))]
+#[test]
+fn fixed_solution_file_is_used_by_one_solve_at_a_time() {
+    // a fake cbc that fails when another solve is writing the same solution file
+    let dir = tempfile::tempdir().unwrap();
+    let cbc = fake_program(
+        dir.path(),
+        "cbc",
+        &format!(
+            "while [ $# -gt 1 ]; do shift; done\n\
+             if [ -e \"$1.busy\" ]; then exit 1; fi\n\
+             touch \"$1.busy\"\n\
+             sleep 0.1\n\
+             cp {:?} \"$1\"\n\
+             rm \"$1.busy\"",
+            sol_file("cbc_optimal.sol")
+        ),
+    );
+    let solver = CbcSolver::new()
+        .command_name(cbc.to_string_lossy().into())
+        .with_temp_solution_file(dir.path().join("fixed.sol").to_string_lossy().into());
+    let problem = scenario_base();
+    std::thread::scope(|scope| {
+        let solves: Vec<_> = (0..3)
+            .map(|_| scope.spawn(|| solver.run(&problem)))
+            .collect();
+        for solve in solves {
+            assert_eq!(solve.join().unwrap().unwrap().status, Status::Optimal);
+        }
+    });
+}
+
+#[cfg(all(unix, feature = "process"))]
+#[test]
+fn auto_solver_probe_limits() {
+    use lp_solvers::solvers::{CancellationToken, ProbeLimits};
+    use std::time::{Duration, Instant};
+
+    // a gurobi whose license server does not answer, and a cbc that works
+    let dir = tempfile::tempdir().unwrap();
+    let gurobi = fake_program(dir.path(), "gurobi_cl", "sleep 5");
+    let cbc = fake_program(
+        dir.path(),
+        "cbc",
+        &format!(
+            "while [ $# -gt 1 ]; do shift; done\n[ \"$1\" = -quit ] || cp {:?} \"$1\"",
+            sol_file("cbc_optimal.sol")
+        ),
+    );
+    let solvers = AutoSolver::<NoSolver, NoSolver>::new()
+        .with_solver(CbcSolver::new().command_name(cbc.to_string_lossy().into()))
+        .with_solver(GurobiSolver::new().command_name(gurobi.to_string_lossy().into()));
+
+    let start = Instant::now();
+    let limited = solvers
+        .clone()
+        .with_probe_limits(ProbeLimits::new().with_timeout(Duration::from_millis(300)));
+    let selection = limited
+        .run(&scenario_base())
+        .unwrap()
+        .solver_selection
+        .unwrap();
+    assert!(start.elapsed() < Duration::from_secs(3));
+    assert_eq!(selection.index, 1);
+    assert_eq!(selection.skipped[0].reason, SkipReason::TimedOut);
+    assert_eq!(
+        selection.skipped[0].reason.to_string(),
+        "did not answer in time"
+    );
+
+    let token = CancellationToken::new();
+    let cancelled = solvers.with_probe_limits(ProbeLimits::new().with_cancellation(token.clone()));
+    token.cancel();
+    let error = cancelled.run(&scenario_base()).unwrap_err();
+    assert!(error.to_string().contains("cancelled"));
+    assert!(start.elapsed() < Duration::from_secs(3));
+}
@@ -0,0 +1,181 @@
+#![cfg(all(unix, feature = "process", feature = "cbc"))]
+
+use std::ffi::OsString;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use lp_solvers::lp_format::{LpObjective, LpProblem};
+use lp_solvers::problem::{Problem, StrExpression, Variable};
+use lp_solvers::solvers::{
+    parse_cbc_solution, solve_file, AnonymousFileSolver, BoxedSolver, NoSolver, ParserMode,
+    PooledSolver, Solution, SolverProgram, SolverTrait, SolverWithSolutionParsing, Status,
+    TempFilePool, WrittenProblem,
+};
+
+/// Copies a cbc solution file instead of solving the problem
+struct CopySolver;
+
+impl SolverProgram for CopySolver {
+    fn command_name(&self) -> &str {
+        "cp"
+    }
+
+    fn arguments(&self, _lp_file: &Path, solution_file: &Path) -> Vec<OsString> {
+        let fixture = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("solution_files")
+            .join("cbc_optimal.sol");
+        vec![fixture.into(), solution_file.into()]
+    }
+}
+
+impl SolverWithSolutionParsing for CopySolver {
+    fn read_specific_solution<'a, P: LpProblem<'a>>(
+        &self,
+        f: &File,
+        _problem: Option<&'a P>,
+    ) -> Result<Solution, String> {
+        parse_cbc_solution(BufReader::new(f), ParserMode::Strict).map_err(String::from)
+    }
+}
+
+#[test]
+fn pool_reuses_files() {
+    let pool = TempFilePool::new();
+    let path = {
+        let file = pool.acquire(".lp").unwrap();
+        file.path().to_path_buf()
+    };
+    assert_eq!(pool.free_files(), 1);
+    let file = pool.acquire(".lp").unwrap();
+    assert_eq!(file.path(), path);
+    assert_eq!(file.as_file().metadata().unwrap().len(), 0);
+    let other = pool.acquire(".sol").unwrap();
+    assert_ne!(other.path(), path);
+}
+
+fn problem() -> Problem {
+    Problem {
+        name: "pooled".to_string(),
+        sense: LpObjective::Maximize,
+        objective: StrExpression("a".to_string()),
+        variables: vec![Variable {
+            name: "a".to_string(),
+            is_integer: false,
+            lower_bound: 0.,
+            upper_bound: 5.,
+        }],
+        constraints: vec![],
+    }
+}
+
+#[test]
+fn pooled_solver_reuses_files() {
+    let pb = problem();
+    let solver = PooledSolver::new(CopySolver);
+    for _ in 0..3 {
+        let solution = solver.run(&pb).unwrap();
+        assert_eq!(solution.status, Status::Optimal);
+        assert_eq!(solution.results.get("a"), Some(&5.));
+        // the problem file and the solution file
+        assert_eq!(solver.pool.free_files(), 2);
+    }
+}
+
+#[test]
+fn written_problem_is_shared_between_solves() {
+    let pb = problem();
+    let written = WrittenProblem::new(&pb).unwrap();
+    let path = written.path().to_path_buf();
+    let mut write_times = vec![];
+    for _ in 0..2 {
+        let solution = written.solve(&CopySolver).unwrap();
+        assert_eq!(solution.results.get("a"), Some(&5.));
+        let report = solution.report.unwrap();
+        assert!(report.problem_file_size > 0);
+        assert!(path.exists());
+        write_times.push(report.write_time);
+    }
+    // the problem was written only once
+    assert_eq!(write_times[0], write_times[1]);
+    drop(written);
+    assert!(!path.exists());
+}
+
+/// Copies the problem file, and returns a solution if it was a valid problem
+struct CopyProblemSolver;
+
+impl SolverProgram for CopyProblemSolver {
+    fn command_name(&self) -> &str {
+        "cp"
+    }
+
+    fn arguments(&self, lp_file: &Path, solution_file: &Path) -> Vec<OsString> {
+        vec![lp_file.into(), solution_file.into()]
+    }
+
+    fn reads_paths_without_extension(&self) -> bool {
+        true
+    }
+}
+
+impl SolverWithSolutionParsing for CopyProblemSolver {
+    fn read_specific_solution<'a, P: LpProblem<'a>>(
+        &self,
+        f: &File,
+        _problem: Option<&'a P>,
+    ) -> Result<Solution, String> {
+        let mut content = String::new();
+        BufReader::new(f)
+            .read_to_string(&mut content)
+            .map_err(|e| e.to_string())?;
+        assert!(content.starts_with("\\ pooled"));
+        Ok(Solution::new(Status::Optimal, Default::default()))
+    }
+}
+
+#[test]
+fn anonymous_problem_file() {
+    let solution = AnonymousFileSolver(CopyProblemSolver)
+        .run(&problem())
+        .unwrap();
+    assert_eq!(solution.status, Status::Optimal);
+    let problem_path = &solution.report.unwrap().command_line[1];
+    if cfg!(target_os = "linux") {
+        assert!(problem_path.starts_with("/proc/"), "{}", problem_path);
+    } else {
+        assert!(problem_path.ends_with(".lp"), "{}", problem_path);
+    }
+}
+
+#[test]
+fn solve_existing_file() {
+    let file = problem().to_tmp_file().unwrap();
+    let solution = solve_file(&CopyProblemSolver, file.path()).unwrap();
+    assert_eq!(solution.status, Status::Optimal);
+    let report = solution.report.unwrap();
+    assert!(report.problem_file_size > 0);
+    assert_eq!(report.command_line[1], file.path().to_string_lossy());
+    assert!(solve_file(&CopyProblemSolver, Path::new("/nonexistent.lp")).is_err());
+}
+
+#[test]
+fn run_existing_file() {
+    let file = problem().to_tmp_file().unwrap();
+    let solvers: Vec<BoxedSolver> = vec![
+        BoxedSolver::new(CopyProblemSolver),
+        BoxedSolver::new(PooledSolver::new(CopyProblemSolver)),
+        BoxedSolver::new(Arc::new(AnonymousFileSolver(CopyProblemSolver))),
+    ];
+    for solver in solvers {
+        let solution = solver.run_file(file.path()).unwrap();
+        assert_eq!(solution.status, Status::Optimal);
+        assert_eq!(
+            solution.report.unwrap().command_line[1],
+            file.path().to_string_lossy()
+        );
+    }
+    assert!(NoSolver.run_file(file.path()).is_err());
+}